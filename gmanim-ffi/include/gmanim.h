@@ -0,0 +1,69 @@
+/* the c abi of gmanim, link against libgmanim_ffi built from gmanim-ffi.
+ *
+ * a scene is not thread safe, use it from the thread that created it.
+ * positions are scene units from the center of the frame with y up, the frame
+ * is 9 units high. colors are 0xRRGGBBAA. */
+#ifndef GMANIM_H
+#define GMANIM_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define GM_OK 0
+/* a null scene, an unknown mobject id or an invalid string */
+#define GM_INVALID_ARGUMENT -1
+/* play or wait before gm_scene_render_to_file or gm_scene_render_to_buffer */
+#define GM_NO_OUTPUT -2
+/* the output couldn't be written, e.g. ffmpeg is missing or exited with an error */
+#define GM_OUTPUT_FAILED -3
+/* a bug in gmanim, the scene should not be used any more */
+#define GM_PANIC -4
+
+typedef struct GmScene GmScene;
+
+/* frame is gm_scene_frame_len bytes of premultiplied rgba pixels,
+ * only valid during the call */
+typedef void (*GmFrameCallback)(const uint8_t *rgba, size_t len, void *user_data);
+
+/* NULL for an empty size */
+GmScene *gm_scene_new(uint32_t width, uint32_t height, uint32_t framerate);
+/* also closes the output if gm_scene_finish wasn't called */
+void gm_scene_free(GmScene *scene);
+
+/* encode with ffmpeg, .mov for prores with alpha, .webm for vp9, h.264 otherwise.
+ * a path with {} writes numbered png files instead */
+int gm_scene_render_to_file(GmScene *scene, const char *path);
+int gm_scene_render_to_buffer(GmScene *scene, GmFrameCallback on_frame, void *user_data);
+size_t gm_scene_frame_len(GmScene *scene);
+/* close the output, for files this waits until ffmpeg has finished */
+int gm_scene_finish(GmScene *scene);
+
+/* return the id of the mobject, 0 on errors */
+uint32_t gm_scene_add_dot(GmScene *scene, float x, float y, float radius, uint32_t rgba);
+/* x, y is the center, filled when fill is not 0 and stroked otherwise */
+uint32_t gm_scene_add_rectangle(GmScene *scene, float x, float y, float width, float height,
+                                uint32_t rgba, float stroke_width, int fill);
+uint32_t gm_scene_add_line(GmScene *scene, float x0, float y0, float x1, float y1,
+                           uint32_t rgba, float stroke_width);
+/* utf-8 text with its baseline starting at x, y */
+uint32_t gm_scene_add_text(GmScene *scene, const char *text, float x, float y, float font_size,
+                           uint32_t rgba);
+int gm_scene_remove(GmScene *scene, uint32_t id);
+
+/* draw the frames of the animation into the output, run_time in seconds */
+int gm_scene_play_create(GmScene *scene, uint32_t id, float run_time);
+int gm_scene_play_grow(GmScene *scene, uint32_t id, float run_time);
+int gm_scene_play_move(GmScene *scene, uint32_t id, float dx, float dy, float run_time);
+/* scale up and tint yellow, then back */
+int gm_scene_play_indicate(GmScene *scene, uint32_t id, float run_time);
+int gm_scene_wait(GmScene *scene, float seconds);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
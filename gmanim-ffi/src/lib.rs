@@ -0,0 +1,548 @@
+// a c abi for driving gmanim from other languages, declared in include/gmanim.h.
+// a scene is built and played step by step like the scene functions of gmanim::cli:
+// every play and wait call draws its frames into the output set before it.
+// pointers from c are checked for null and otherwise trusted, like c functions do,
+// so every function taking a pointer is unsafe
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, c_void, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use gmanim::animation::creation::DrawBorderThenFill;
+use gmanim::animation::growing::GrowFromPoint;
+use gmanim::animation::indication::Indicate;
+use gmanim::animation::{
+    play, rate_functions, seconds_to_frames, Animation, AnimationConfig, SimpleMovement,
+};
+use gmanim::background::Background;
+use gmanim::mobjects::polygon::Polygon;
+use gmanim::mobjects::text::Text;
+use gmanim::mobjects::{Dot, DrawConfig, Mobject, SimpleLine};
+use gmanim::video_backend::{
+    BufferBackend, ColorOrder, FfmpegPipeBackend, FfmpegPipeEncoder, PngSequenceBackend,
+    ProResProfile, VideoBackend, VideoBackendType, VideoConfig,
+};
+use gmanim::{Color, Context, ContextType, GMFloat, GMPoint, Scene, SceneConfig};
+
+pub const GM_OK: c_int = 0;
+// a null scene, an unknown mobject id or an invalid string
+pub const GM_INVALID_ARGUMENT: c_int = -1;
+// play or wait before gm_scene_render_to_file or gm_scene_render_to_buffer
+pub const GM_NO_OUTPUT: c_int = -2;
+// the output couldn't be written, e.g. ffmpeg is missing or exited with an error
+pub const GM_OUTPUT_FAILED: c_int = -3;
+// a bug in gmanim, the scene should not be used any more
+pub const GM_PANIC: c_int = -4;
+
+pub type GmFrameCallback =
+    Option<unsafe extern "C" fn(rgba: *const u8, len: usize, user_data: *mut c_void)>;
+
+type MobjectRef = Rc<RefCell<Box<dyn Mobject>>>;
+
+pub struct GmScene {
+    scene: Rc<RefCell<Scene>>,
+    ctx: Rc<RefCell<Context>>,
+    // None until an output is set
+    video_backend: Option<VideoBackend>,
+    // indexed by mobject id - 1, None after gm_scene_remove
+    mobjects: Vec<Option<MobjectRef>>,
+}
+
+impl GmScene {
+    fn mobject(&self, id: u32) -> Option<MobjectRef> {
+        self.mobjects.get((id as usize).checked_sub(1)?)?.clone()
+    }
+
+    // add to the scene, returns the id
+    fn add(&mut self, mobject: Box<dyn Mobject>) -> u32 {
+        let mobject = Rc::new(RefCell::new(mobject));
+        self.scene.borrow_mut().add_ref(mobject.clone());
+        self.mobjects.push(Some(mobject));
+        self.mobjects.len() as u32
+    }
+
+    fn framerate(&self) -> u32 {
+        self.ctx.borrow().scene_config.framerate
+    }
+
+    fn video_config(&self, filename: String) -> VideoConfig {
+        let scene_config = &self.ctx.borrow().scene_config;
+        VideoConfig {
+            filename,
+            framerate: scene_config.video_framerate(),
+            output_width: scene_config.output_width,
+            output_height: scene_config.output_height,
+            color_order: ColorOrder::Rgba,
+        }
+    }
+}
+
+// 0xRRGGBBAA
+fn color(rgba: u32) -> Color {
+    let [r, g, b, a] = rgba.to_be_bytes();
+    Color::new(r, g, b, a)
+}
+
+fn point(x: f32, y: f32) -> GMPoint {
+    GMPoint::new(x as GMFloat, y as GMFloat, 0.0)
+}
+
+// run f with the scene behind a pointer of gm_scene_new, panics don't cross the c boundary.
+// scene must be null or a scene of gm_scene_new that wasn't freed
+unsafe fn with_scene<T>(scene: *mut GmScene, error: T, f: impl FnOnce(&mut GmScene) -> T) -> T {
+    // SAFETY: the caller passes null or a scene of gm_scene_new that wasn't freed
+    let Some(scene) = (unsafe { scene.as_mut() }) else {
+        return error;
+    };
+    catch_unwind(AssertUnwindSafe(|| f(scene))).unwrap_or(error)
+}
+
+// with_scene for the functions returning a status
+unsafe fn scene_status(scene: *mut GmScene, f: impl FnOnce(&mut GmScene) -> c_int) -> c_int {
+    if scene.is_null() {
+        return GM_INVALID_ARGUMENT;
+    }
+    with_scene(scene, GM_PANIC, f)
+}
+
+// play the animation built by f for the mobject id into the output
+unsafe fn play_animation<A: Animation>(
+    scene: *mut GmScene,
+    id: u32,
+    f: impl FnOnce(&GmScene, MobjectRef) -> A,
+) -> c_int {
+    scene_status(scene, |s| {
+        let Some(m) = s.mobject(id) else {
+            return GM_INVALID_ARGUMENT;
+        };
+        let animation = f(s, m);
+        let Some(video_backend) = &mut s.video_backend else {
+            return GM_NO_OUTPUT;
+        };
+        play(&s.scene, &s.ctx, video_backend, animation);
+        GM_OK
+    })
+}
+
+// a scene of width x height pixels showing 9 scene units vertically, null for an empty size
+#[no_mangle]
+pub extern "C" fn gm_scene_new(width: u32, height: u32, framerate: u32) -> *mut GmScene {
+    let mut scene_config = SceneConfig::for_output(width, height, 9.0);
+    scene_config.framerate = framerate.max(1);
+    let Some(pixmap) = tiny_skia::Pixmap::new(width, height) else {
+        return std::ptr::null_mut();
+    };
+    Box::into_raw(Box::new(GmScene {
+        scene: Rc::new(RefCell::new(Scene::new())),
+        ctx: Rc::new(RefCell::new(Context::new(
+            ContextType::TinySKIA(pixmap),
+            scene_config,
+        ))),
+        video_backend: None,
+        mobjects: vec![],
+    }))
+}
+
+/// also closes the output if gm_scene_finish wasn't called
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+/// it must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_free(scene: *mut GmScene) {
+    if scene.is_null() {
+        return;
+    }
+    gm_scene_finish(scene);
+    // SAFETY: scene came from Box::into_raw in gm_scene_new
+    drop(unsafe { Box::from_raw(scene) });
+}
+
+/// encode the frames with ffmpeg into path, the encoder follows the extension:
+/// .mov for prores with alpha, .webm for vp9, h.264 otherwise.
+/// a path with {} writes numbered png files instead
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+/// path must be null or a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_render_to_file(
+    scene: *mut GmScene,
+    path: *const c_char,
+) -> c_int {
+    if path.is_null() {
+        return GM_INVALID_ARGUMENT;
+    }
+    // SAFETY: the caller passes a nul terminated string
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return GM_INVALID_ARGUMENT;
+    };
+    if scene.is_null() {
+        return GM_INVALID_ARGUMENT;
+    }
+    // FfmpegPipeBackend panics when ffmpeg can't be started
+    with_scene(scene, GM_OUTPUT_FAILED, |s| {
+        let video_config = s.video_config(path.to_owned());
+        let encoder = if path.ends_with(".mov") {
+            FfmpegPipeEncoder::ProRes(ProResProfile::P4444)
+        } else if path.ends_with(".webm") {
+            let alpha = matches!(
+                s.ctx.borrow().scene_config.background,
+                Background::Transparent
+            );
+            FfmpegPipeEncoder::Vp9 { alpha }
+        } else {
+            FfmpegPipeEncoder::Libx264
+        };
+        let backend_type = if path.contains("{}") {
+            VideoBackendType::PngSequence(PngSequenceBackend::new(&video_config))
+        } else {
+            VideoBackendType::FfmpegPipe(FfmpegPipeBackend::new(&video_config, encoder, true))
+        };
+        s.video_backend = Some(VideoBackend { backend_type });
+        GM_OK
+    })
+}
+
+/// call on_frame with every frame, gm_scene_frame_len bytes of premultiplied rgba pixels.
+/// the pointer is only valid during the call
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+/// on_frame is called with user_data while the scene plays,
+/// user_data must stay valid until gm_scene_finish or gm_scene_free.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_render_to_buffer(
+    scene: *mut GmScene,
+    on_frame: GmFrameCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    let Some(on_frame) = on_frame else {
+        return GM_INVALID_ARGUMENT;
+    };
+    scene_status(scene, |s| {
+        let backend = BufferBackend::new(move |frame| {
+            // SAFETY: the caller's callback with the user data it passed
+            unsafe { on_frame(frame.as_ptr(), frame.len(), user_data) }
+        });
+        s.video_backend = Some(VideoBackend {
+            backend_type: VideoBackendType::Buffer(backend),
+        });
+        GM_OK
+    })
+}
+
+/// bytes of a frame
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_frame_len(scene: *mut GmScene) -> usize {
+    with_scene(scene, 0, |s| {
+        let scene_config = &s.ctx.borrow().scene_config;
+        scene_config.output_width as usize * scene_config.output_height as usize * 4
+    })
+}
+
+/// close the output, for files this waits until ffmpeg has finished
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_finish(scene: *mut GmScene) -> c_int {
+    scene_status(scene, |s| match s.video_backend.take() {
+        Some(mut video_backend) => match video_backend.close() {
+            Ok(()) => GM_OK,
+            Err(_) => GM_OUTPUT_FAILED,
+        },
+        None => GM_OK,
+    })
+}
+
+// the functions adding mobjects return their id, 0 for a null scene.
+// colors are 0xRRGGBBAA and positions are scene units from the center, y up
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_add_dot(
+    scene: *mut GmScene,
+    x: f32,
+    y: f32,
+    radius: f32,
+    rgba: u32,
+) -> u32 {
+    with_scene(scene, 0, |s| {
+        let draw_config = DrawConfig {
+            color: color(rgba),
+            ..Default::default()
+        };
+        s.add(Box::new(Dot::new(
+            point(x, y),
+            radius as GMFloat,
+            color(rgba),
+            draw_config,
+        )))
+    })
+}
+
+/// filled when fill is not 0, stroke_width is in scene units
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_add_rectangle(
+    scene: *mut GmScene,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    rgba: u32,
+    stroke_width: f32,
+    fill: c_int,
+) -> u32 {
+    with_scene(scene, 0, |s| {
+        let (w, h) = (width / 2.0, height / 2.0);
+        // a polygon, Rectangle is only stroked
+        let mut rectangle = Polygon::new(vec![
+            point(x - w, y - h),
+            point(x + w, y - h),
+            point(x + w, y + h),
+            point(x - w, y + h),
+        ]);
+        rectangle.set_draw_config(DrawConfig {
+            color: color(rgba),
+            stoke_width: stroke_width as GMFloat,
+            fill: fill != 0,
+            ..Default::default()
+        });
+        s.add(Box::new(rectangle))
+    })
+}
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_add_line(
+    scene: *mut GmScene,
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    rgba: u32,
+    stroke_width: f32,
+) -> u32 {
+    with_scene(scene, 0, |s| {
+        s.add(Box::new(SimpleLine {
+            p0: point(x0, y0),
+            p1: point(x1, y1),
+            draw_config: DrawConfig {
+                color: color(rgba),
+                stoke_width: stroke_width as GMFloat,
+                ..Default::default()
+            },
+        }))
+    })
+}
+
+/// utf-8 text with its baseline starting at x, y
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+/// text must be null or a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_add_text(
+    scene: *mut GmScene,
+    text: *const c_char,
+    x: f32,
+    y: f32,
+    font_size: f32,
+    rgba: u32,
+) -> u32 {
+    if text.is_null() {
+        return 0;
+    }
+    // SAFETY: the caller passes a nul terminated string
+    let Ok(text) = unsafe { CStr::from_ptr(text) }.to_str() else {
+        return 0;
+    };
+    with_scene(scene, 0, |s| {
+        let draw_config = DrawConfig {
+            color: color(rgba),
+            fill: true,
+            ..Default::default()
+        };
+        s.add(Box::new(Text::new(
+            text.to_owned(),
+            point(x, y),
+            font_size as GMFloat,
+            draw_config,
+        )))
+    })
+}
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_remove(scene: *mut GmScene, id: u32) -> c_int {
+    scene_status(scene, |s| {
+        let Some(m) = s.mobject(id) else {
+            return GM_INVALID_ARGUMENT;
+        };
+        s.scene.borrow_mut().remove(&m);
+        s.mobjects[id as usize - 1] = None;
+        GM_OK
+    })
+}
+
+// the animations below take run_time seconds with a smooth rate function
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_play_create(
+    scene: *mut GmScene,
+    id: u32,
+    run_time: f32,
+) -> c_int {
+    play_animation(scene, id, |s, m| {
+        DrawBorderThenFill::new(
+            s.scene.clone(),
+            s.ctx.clone(),
+            m,
+            AnimationConfig::from_run_time(
+                run_time as GMFloat,
+                s.framerate(),
+                rate_functions::smooth,
+            ),
+        )
+    })
+}
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_play_grow(scene: *mut GmScene, id: u32, run_time: f32) -> c_int {
+    play_animation(scene, id, |s, m| {
+        GrowFromPoint::from_center(
+            s.scene.clone(),
+            s.ctx.clone(),
+            m,
+            AnimationConfig::from_run_time(
+                run_time as GMFloat,
+                s.framerate(),
+                rate_functions::smooth,
+            ),
+        )
+    })
+}
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_play_move(
+    scene: *mut GmScene,
+    id: u32,
+    dx: f32,
+    dy: f32,
+    run_time: f32,
+) -> c_int {
+    play_animation(scene, id, |s, m| SimpleMovement {
+        displacement: nalgebra::Vector3::new(dx as GMFloat, dy as GMFloat, 0.0),
+        scene: s.scene.clone(),
+        ctx: s.ctx.clone(),
+        m,
+        animation_config: AnimationConfig::from_run_time(
+            run_time as GMFloat,
+            s.framerate(),
+            rate_functions::smooth,
+        ),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    })
+}
+
+/// scale up and tint yellow, then back
+///
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_play_indicate(
+    scene: *mut GmScene,
+    id: u32,
+    run_time: f32,
+) -> c_int {
+    play_animation(scene, id, |s, m| {
+        let total_frame = seconds_to_frames(run_time as GMFloat, s.framerate()).max(1);
+        Indicate::new(s.scene.clone(), s.ctx.clone(), m, total_frame)
+    })
+}
+
+/// # Safety
+///
+/// scene must be null or a scene of gm_scene_new that wasn't freed.
+#[no_mangle]
+pub unsafe extern "C" fn gm_scene_wait(scene: *mut GmScene, seconds: f32) -> c_int {
+    scene_status(scene, |s| {
+        let Some(video_backend) = &mut s.video_backend else {
+            return GM_NO_OUTPUT;
+        };
+        s.scene
+            .borrow_mut()
+            .wait(&mut s.ctx.borrow_mut(), video_backend, seconds as GMFloat);
+        GM_OK
+    })
+}
+
+#[test]
+fn test_ffi_scene() {
+    unsafe extern "C" fn count_red(rgba: *const u8, len: usize, user_data: *mut c_void) {
+        let frame = std::slice::from_raw_parts(rgba, len);
+        let red = frame
+            .chunks(4)
+            .filter(|p| p[0] > 0xc0 && p[1] < 0x40)
+            .count();
+        (*(user_data as *mut Vec<usize>)).push(red);
+    }
+    let mut red_pixels: Vec<usize> = vec![];
+    let user_data = &mut red_pixels as *mut Vec<usize> as *mut c_void;
+    // SAFETY: the scene of gm_scene_new until gm_scene_free, red_pixels outlives it
+    unsafe {
+        let scene = gm_scene_new(160, 90, 10);
+        assert_eq!(gm_scene_frame_len(scene), 160 * 90 * 4);
+        let rectangle = gm_scene_add_rectangle(scene, 0.0, 0.0, 2.0, 2.0, 0xff0000ff, 0.05, 1);
+        assert_eq!(rectangle, 1);
+        assert_eq!(gm_scene_wait(scene, 0.5), GM_NO_OUTPUT);
+        assert_eq!(
+            gm_scene_render_to_buffer(scene, Some(count_red), user_data),
+            GM_OK
+        );
+        assert_eq!(gm_scene_play_move(scene, rectangle, 2.0, 0.0, 1.0), GM_OK);
+        assert_eq!(gm_scene_wait(scene, 0.5), GM_OK);
+        assert_eq!(
+            gm_scene_play_move(scene, 7, 2.0, 0.0, 1.0),
+            GM_INVALID_ARGUMENT
+        );
+        assert_eq!(gm_scene_remove(scene, rectangle), GM_OK);
+        assert_eq!(gm_scene_remove(scene, rectangle), GM_INVALID_ARGUMENT);
+        assert_eq!(gm_scene_finish(scene), GM_OK);
+        gm_scene_free(scene);
+    }
+    // the 20 x 20 pixel square in every frame
+    assert_eq!(red_pixels.len(), 15);
+    assert!(red_pixels.iter().all(|&n| n > 300));
+}
@@ -0,0 +1,582 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{Point3, Vector3};
+
+use crate::{
+    mobjects::{Arrow, Dot, Mobject, SimpleLine},
+    Context, ContextType, GMFloat, Scene, SceneConfig,
+};
+
+use super::{
+    growing::GrowArrow, render_frame, seconds_to_frames, Animation, AnimationConfig, SimpleMovement,
+};
+
+// play animations one after another, the total frame is the sum of all children
+pub struct Succession {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub animations: Vec<Box<dyn Animation>>,
+    pub animation_config: AnimationConfig,
+}
+
+impl Succession {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        animations: Vec<Box<dyn Animation>>,
+    ) -> Self {
+        let total_frame = animations.iter().map(|a| a.total_frame()).sum();
+        Self {
+            scene,
+            ctx,
+            animations,
+            animation_config: AnimationConfig {
+                total_frame,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+        }
+    }
+}
+
+// local alpha of a child that starts at start_frame and lasts for total_frame
+fn child_alpha(frame: GMFloat, start_frame: GMFloat, total_frame: u32) -> GMFloat {
+    if total_frame == 0 {
+        return 1.0;
+    }
+    ((frame - start_frame) / total_frame as GMFloat).clamp(0.0, 1.0)
+}
+
+impl Iterator for Succession {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for Succession {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let frame = (self.animation_config.rate_function)(alpha)
+            * self.animation_config.total_frame as GMFloat;
+        let mut start_frame = 0.0;
+        for a in &mut self.animations {
+            // animations that have not started yet are left untouched,
+            // so they pick up the state left by the previous ones
+            if frame >= start_frame {
+                a.interpolate(child_alpha(frame, start_frame, a.total_frame()));
+            }
+            start_frame += a.total_frame() as GMFloat;
+        }
+    }
+}
+
+// start animations one by one, each one starts when the previous one has
+// finished lag_ratio of its frames. lag_ratio 0.0 plays all of them together,
+// lag_ratio 1.0 is the same as Succession
+pub struct LaggedStart {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub animations: Vec<Box<dyn Animation>>,
+    pub lag_ratio: GMFloat,
+    pub animation_config: AnimationConfig,
+    start_frames: Vec<GMFloat>,
+}
+
+impl LaggedStart {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        animations: Vec<Box<dyn Animation>>,
+        lag_ratio: GMFloat,
+    ) -> Self {
+        let mut start_frames = vec![];
+        let mut start_frame = 0.0;
+        let mut end_frame: GMFloat = 0.0;
+        for a in &animations {
+            start_frames.push(start_frame);
+            let total_frame = a.total_frame() as GMFloat;
+            end_frame = end_frame.max(start_frame + total_frame);
+            start_frame += total_frame * lag_ratio;
+        }
+        Self {
+            scene,
+            ctx,
+            animations,
+            lag_ratio,
+            animation_config: AnimationConfig {
+                total_frame: end_frame.ceil() as u32,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+            start_frames,
+        }
+    }
+}
+
+impl Iterator for LaggedStart {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for LaggedStart {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let frame = (self.animation_config.rate_function)(alpha)
+            * self.animation_config.total_frame as GMFloat;
+        for (a, start_frame) in self.animations.iter_mut().zip(&self.start_frames) {
+            if frame >= *start_frame {
+                a.interpolate(child_alpha(frame, *start_frame, a.total_frame()));
+            }
+        }
+    }
+}
+
+// play animations together, each one starts after its own delay in seconds
+pub struct AnimationGroup {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub animations: Vec<Box<dyn Animation>>,
+    pub animation_config: AnimationConfig,
+    start_frames: Vec<GMFloat>,
+}
+
+impl AnimationGroup {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        animations: Vec<(GMFloat, Box<dyn Animation>)>,
+    ) -> Self {
+        let framerate = ctx.borrow().scene_config.framerate;
+        let mut start_frames = vec![];
+        let mut end_frame = 0;
+        for (delay, a) in &animations {
+            let start_frame = seconds_to_frames(*delay, framerate);
+            start_frames.push(start_frame as GMFloat);
+            end_frame = end_frame.max(start_frame + a.total_frame());
+        }
+        Self {
+            scene,
+            ctx,
+            animations: animations.into_iter().map(|(_, a)| a).collect(),
+            animation_config: AnimationConfig {
+                total_frame: end_frame,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+            start_frames,
+        }
+    }
+}
+
+impl Iterator for AnimationGroup {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for AnimationGroup {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let frame = (self.animation_config.rate_function)(alpha)
+            * self.animation_config.total_frame as GMFloat;
+        for (a, start_frame) in self.animations.iter_mut().zip(&self.start_frames) {
+            if frame >= *start_frame {
+                a.interpolate(child_alpha(frame, *start_frame, a.total_frame()));
+            }
+        }
+    }
+}
+
+// play an animation backwards, alpha goes from 1.0 to 0.0.
+// the mobject is expected to be in the end state of the animation, e.g. a fully grown mobject
+// for a GrowFromPoint, it is left in the start state of the animation
+pub struct Reverse {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub animation: Box<dyn Animation>,
+    pub animation_config: AnimationConfig,
+    // the animation was moved to its end, see interpolate
+    pub started: bool,
+}
+
+impl Reverse {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        animation: Box<dyn Animation>,
+    ) -> Self {
+        let total_frame = animation.total_frame();
+        Self {
+            scene,
+            ctx,
+            animation,
+            animation_config: AnimationConfig {
+                total_frame,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+            started: false,
+        }
+    }
+}
+
+impl Iterator for Reverse {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for Reverse {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        // animations moving by the change since their last alpha, like SimpleMovement, start
+        // from their end. the mobjects of the scene are put back where they were
+        if !self.started {
+            self.started = true;
+            let snapshot = self.scene.borrow().snapshot();
+            self.animation.interpolate(1.0);
+            self.scene.borrow_mut().restore_snapshot(&snapshot);
+        }
+        let alpha = (self.animation_config.rate_function)(alpha);
+        self.animation.interpolate(1.0 - alpha);
+    }
+}
+
+// play an animation at speed times its normal speed, 0.25 renders four times as many frames.
+// updaters of the scene are slowed down or sped up the same way while it plays
+pub struct ChangeSpeed {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub animation: Box<dyn Animation>,
+    pub speed: GMFloat,
+    pub animation_config: AnimationConfig,
+}
+
+impl ChangeSpeed {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        animation: Box<dyn Animation>,
+        speed: GMFloat,
+    ) -> Self {
+        let total_frame = (animation.total_frame() as GMFloat / speed).round() as u32;
+        Self {
+            scene,
+            ctx,
+            animation,
+            speed,
+            animation_config: AnimationConfig {
+                total_frame,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+        }
+    }
+}
+
+impl Iterator for ChangeSpeed {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for ChangeSpeed {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    // the update of the frame at alpha, whoever draws it, runs at the changed speed
+    fn interpolate(&mut self, alpha: GMFloat) {
+        self.animation
+            .interpolate((self.animation_config.rate_function)(alpha));
+        self.scene.borrow_mut().frame_speed *= self.speed;
+    }
+}
+
+// play an animation in total_frame frames, faster or slower than its own run time.
+// unlike ChangeSpeed the updaters of the scene keep their speed, see crate::voiceover
+pub struct Stretch {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub animation: Box<dyn Animation>,
+    pub animation_config: AnimationConfig,
+}
+
+impl Stretch {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        animation: Box<dyn Animation>,
+        total_frame: u32,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            animation,
+            animation_config: AnimationConfig {
+                total_frame,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+        }
+    }
+}
+
+impl Iterator for Stretch {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for Stretch {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        self.animation
+            .interpolate((self.animation_config.rate_function)(alpha));
+    }
+}
+
+#[cfg(test)]
+fn small_context() -> Rc<RefCell<Context>> {
+    Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            framerate: 30,
+            ..Default::default()
+        },
+    )))
+}
+
+#[test]
+fn test_succession_total_frame() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let line: Box<dyn Mobject> = Box::new(SimpleLine {
+        p0: Point3::new(0.0, 0.0, 0.0),
+        p1: Point3::new(1.0, 1.0, 0.0),
+        draw_config: Default::default(),
+    });
+    let line_ref = Rc::new(RefCell::new(line));
+    scene.borrow_mut().add_ref(line_ref.clone());
+    let movement = |total_frame| -> Box<dyn Animation> {
+        Box::new(SimpleMovement {
+            displacement: Vector3::new(1.0, 0.0, 0.0),
+            scene: scene.clone(),
+            ctx: ctx.clone(),
+            m: line_ref.clone(),
+            animation_config: AnimationConfig {
+                total_frame,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+            last_progress: 0.0,
+            path_arc: 0.0,
+        })
+    };
+    let succession = Succession::new(
+        scene.clone(),
+        ctx.clone(),
+        vec![movement(3), movement(0), movement(2)],
+    );
+    assert_eq!(succession.total_frame(), 5);
+    assert_eq!(succession.count(), 5);
+}
+
+#[test]
+fn test_lagged_start_total_frame() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let movements = || -> Vec<Box<dyn Animation>> {
+        (0..4)
+            .map(|i| {
+                let dot: Box<dyn Mobject> = Box::new(Dot::new(
+                    Point3::new(i as GMFloat, 0.0, 0.0),
+                    0.1,
+                    Default::default(),
+                    Default::default(),
+                ));
+                let dot_ref = Rc::new(RefCell::new(dot));
+                scene.borrow_mut().add_ref(dot_ref.clone());
+                Box::new(SimpleMovement {
+                    displacement: Vector3::new(0.0, 1.0, 0.0),
+                    scene: scene.clone(),
+                    ctx: ctx.clone(),
+                    m: dot_ref,
+                    animation_config: AnimationConfig {
+                        total_frame: 10,
+                        current_frame: 0,
+                        rate_function: |x| x,
+                    },
+                    last_progress: 0.0,
+                    path_arc: 0.0,
+                }) as Box<dyn Animation>
+            })
+            .collect()
+    };
+    let lagged_start = LaggedStart::new(scene.clone(), ctx.clone(), movements(), 0.5);
+    assert_eq!(lagged_start.total_frame(), 25);
+    assert_eq!(lagged_start.count(), 25);
+    let together = LaggedStart::new(scene.clone(), ctx.clone(), movements(), 0.0);
+    assert_eq!(together.total_frame(), 10);
+}
+
+#[test]
+fn test_animation_group_run_time_and_delay() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = small_context();
+    let framerate = 30;
+    let dot: Box<dyn Mobject> = Box::new(Dot::default());
+    let dot_ref = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot_ref.clone());
+    let movement = |run_time| -> Box<dyn Animation> {
+        Box::new(SimpleMovement {
+            displacement: Vector3::new(1.0, 0.0, 0.0),
+            scene: scene.clone(),
+            ctx: ctx.clone(),
+            m: dot_ref.clone(),
+            animation_config: AnimationConfig::from_run_time(run_time, framerate, |x| x),
+            last_progress: 0.0,
+            path_arc: 0.0,
+        })
+    };
+    let group = AnimationGroup::new(
+        scene.clone(),
+        ctx.clone(),
+        vec![(0.0, movement(1.0)), (0.5, movement(2.0))],
+    );
+    // the second one starts at 0.5s and lasts 2s
+    assert_eq!(group.total_frame(), framerate * 5 / 2);
+    assert_eq!(group.count() as u32, framerate * 5 / 2);
+    assert!((dot_ref.borrow().get_center().x - 2.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_reverse_grow_arrow() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = small_context();
+    let arrow: Box<dyn Mobject> = Box::new(Arrow::new(
+        Point3::new(-1.0, 0.0, 0.0),
+        Point3::new(2.0, 0.0, 0.0),
+    ));
+    let arrow_ref = Rc::new(RefCell::new(arrow));
+    scene.borrow_mut().add_ref(arrow_ref.clone());
+    let grow = GrowArrow::new(
+        scene.clone(),
+        ctx.clone(),
+        arrow_ref.clone(),
+        AnimationConfig::from_run_time(0.5, 30, |x| x),
+    );
+    let reverse = Reverse::new(scene.clone(), ctx.clone(), Box::new(grow));
+    assert_eq!(reverse.count(), 15);
+    for p in arrow_ref.borrow().get_points() {
+        assert!((p - Point3::new(-1.0, 0.0, 0.0)).norm() < 1e-4);
+    }
+}
+
+#[test]
+fn test_reverse_simple_movement() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = small_context();
+    let mut dot: Box<dyn Mobject> = Box::new(Dot::default());
+    // the end of the movement
+    dot.shift(Vector3::new(2.0, 0.0, 0.0));
+    let dot_ref = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot_ref.clone());
+    let movement = Box::new(SimpleMovement {
+        displacement: Vector3::new(2.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot_ref.clone(),
+        animation_config: AnimationConfig::from_run_time(4.0, 1, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    });
+    let mut reverse = Reverse::new(scene.clone(), ctx.clone(), movement);
+    for (frame, x) in [1.5, 1.0, 0.5, 0.0].into_iter().enumerate() {
+        reverse.interpolate((frame + 1) as GMFloat / 4.0);
+        assert!(
+            (dot_ref.borrow().get_center().x - x).abs() < 1e-4,
+            "{frame}"
+        );
+    }
+}
+
+#[test]
+fn test_change_speed() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = small_context();
+    let dot: Box<dyn Mobject> = Box::new(Dot::default());
+    let dot_ref = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot_ref.clone());
+    let movement = Box::new(SimpleMovement {
+        displacement: Vector3::new(1.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot_ref.clone(),
+        animation_config: AnimationConfig::from_run_time(1.0, 30, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    });
+    let slow_motion = ChangeSpeed::new(scene.clone(), ctx.clone(), movement, 0.25);
+    assert_eq!(slow_motion.count(), 120);
+    // 4 seconds of video is 1 second in the scene
+    assert!((scene.borrow().time - 1.0).abs() < 1e-3);
+    assert_eq!(scene.borrow().playback_speed, 1.0);
+    assert!((dot_ref.borrow().get_center().x - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_change_speed_interpolate() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = small_context();
+    let dot: Box<dyn Mobject> = Box::new(Dot::default());
+    let dot_ref = Rc::new(RefCell::new(dot));
+    let movement = Box::new(SimpleMovement {
+        displacement: Vector3::new(1.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot_ref,
+        animation_config: AnimationConfig::from_run_time(1.0, 30, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    });
+    let slow = ChangeSpeed::new(scene.clone(), ctx.clone(), movement, 0.5);
+    // nested, interpolated the way a skipped play or another combinator does it
+    let mut slower = ChangeSpeed::new(scene.clone(), ctx, Box::new(slow), 0.5);
+    slower.interpolate(1.0);
+    let dt = scene.borrow().frame_dt(30);
+    assert!((dt - 0.25 / 30.0).abs() < 1e-6);
+    scene.borrow_mut().update(dt);
+    assert_eq!(scene.borrow().frame_dt(30), 1.0 / 30.0);
+}
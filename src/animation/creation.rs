@@ -0,0 +1,223 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    mobjects::{polygon::Polygon, text::Text, DrawConfig, Mobject},
+    Context, GMFloat, GMPoint, Scene,
+};
+
+use super::{render_frame, Animation, AnimationConfig};
+
+// length of the polyline through all points, it is never shorter than the real outline
+fn outline_length(points: &[GMPoint]) -> GMFloat {
+    points.windows(2).map(|w| (w[1] - w[0]).norm()).sum()
+}
+
+// stroke the outline in the first half, then fade the fill in during the second half.
+// the outline is drawn by a growing dash, so it works for every mobject using DrawConfig::get_stroke
+pub struct DrawBorderThenFill {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub animation_config: AnimationConfig,
+    // (final draw config, outline length)
+    start_state: Option<(DrawConfig, GMFloat)>,
+}
+
+impl DrawBorderThenFill {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            animation_config,
+            start_state: None,
+        }
+    }
+}
+
+impl Iterator for DrawBorderThenFill {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for DrawBorderThenFill {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let (final_draw_config, length) = *self
+            .start_state
+            .get_or_insert_with(|| (m.get_draw_config(), outline_length(&m.get_points())));
+        if progress >= 1.0 {
+            m.set_draw_config(final_draw_config);
+            return;
+        }
+
+        let mut draw_config = final_draw_config;
+        draw_config.outline = true;
+        if progress < 0.5 {
+            draw_config.fill = false;
+            // a dash as long as the partial outline followed by a gap longer than the rest
+            draw_config.dash = Some((length * progress * 2.0, length));
+            draw_config.dash_offset = 0.0;
+        } else {
+            draw_config.dash = None;
+            draw_config.fill_opacity = final_draw_config.fill_opacity * (progress * 2.0 - 1.0);
+        }
+        m.set_draw_config(draw_config);
+    }
+}
+
+// typewriter effect, the text is revealed glyph by glyph at chars_per_second.
+// the animation adds the text to the scene itself, m is the handle of the revealed text
+pub struct AddTextLetterByLetter {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub text: Text,
+    pub chars_per_second: GMFloat,
+    pub animation_config: AnimationConfig,
+    shown_glyphs: usize,
+}
+
+impl AddTextLetterByLetter {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        text: Text,
+        chars_per_second: GMFloat,
+    ) -> Self {
+        // the run time would be infinite or negative
+        assert!(
+            chars_per_second > 0.0,
+            "chars_per_second must be positive, got {chars_per_second}"
+        );
+        let framerate = ctx.borrow().scene_config.framerate;
+        let run_time = text.glyph_count() as GMFloat / chars_per_second;
+        let empty: Box<dyn Mobject> = Box::new(text.truncated(0));
+        let m = Rc::new(RefCell::new(empty));
+        scene.borrow_mut().add_ref(m.clone());
+        Self {
+            scene,
+            ctx,
+            m,
+            text,
+            chars_per_second,
+            animation_config: AnimationConfig {
+                total_frame: (run_time * framerate as GMFloat).ceil() as u32,
+                current_frame: 0,
+                rate_function: |x| x,
+            },
+            shown_glyphs: 0,
+        }
+    }
+}
+
+impl Iterator for AddTextLetterByLetter {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for AddTextLetterByLetter {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha).clamp(0.0, 1.0);
+        let glyphs = (progress * self.text.glyph_count() as GMFloat).floor() as usize;
+        if glyphs != self.shown_glyphs {
+            *self.m.borrow_mut() = Box::new(self.text.truncated(glyphs));
+            self.shown_glyphs = glyphs;
+        }
+    }
+}
+
+#[test]
+fn test_draw_border_then_fill() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let triangle = Polygon::new(vec![
+        GMPoint::new(-1.0, -1.0, 0.0),
+        GMPoint::new(1.0, -1.0, 0.0),
+        GMPoint::new(0.0, 1.0, 0.0),
+    ]);
+    let triangle: Box<dyn Mobject> = Box::new(triangle);
+    let triangle_ref = Rc::new(RefCell::new(triangle));
+    scene.borrow_mut().add_ref(triangle_ref.clone());
+    let mut draw_border_then_fill = DrawBorderThenFill::new(
+        scene.clone(),
+        ctx.clone(),
+        triangle_ref.clone(),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    draw_border_then_fill.interpolate(0.25);
+    let draw_config = triangle_ref.borrow().get_draw_config();
+    assert!(!draw_config.fill);
+    assert!(draw_config.dash.is_some());
+    draw_border_then_fill.interpolate(0.75);
+    let draw_config = triangle_ref.borrow().get_draw_config();
+    assert!(draw_config.fill);
+    assert!((draw_config.fill_opacity - 0.5).abs() < 1e-5);
+    assert_eq!(draw_border_then_fill.count(), 10);
+    let draw_config = triangle_ref.borrow().get_draw_config();
+    assert!(draw_config.fill && !draw_config.outline);
+}
+
+#[test]
+fn test_add_text_letter_by_letter() {
+    let sans = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+    if !std::path::Path::new(sans).exists() {
+        return;
+    }
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let text = Text::with_font(
+        "ab cd e".to_owned(),
+        GMPoint::new(0.0, 1.0, 0.0),
+        32.0,
+        &crate::mobjects::text::FontConfig::FontFile(sans.to_owned()),
+        DrawConfig::default(),
+    );
+    // the spaces are glyphs too, the truncated text keeps the chars of the shown glyphs
+    assert_eq!(text.glyph_count(), 7);
+    assert_eq!(text.truncated(3).text, "ab ");
+    assert_eq!(text.truncated(4).glyph_count(), 4);
+    let mut typewriter = AddTextLetterByLetter::new(scene.clone(), ctx.clone(), text, 14.0);
+    assert_eq!(typewriter.total_frame(), 30);
+    typewriter.interpolate(0.5);
+    assert!(!typewriter.m.borrow().get_points().is_empty());
+    assert_eq!(typewriter.count(), 30);
+}
+
+#[test]
+#[should_panic(expected = "chars_per_second must be positive")]
+fn test_add_text_letter_by_letter_rate() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let text = Text::new(
+        "a".to_owned(),
+        GMPoint::origin(),
+        32.0,
+        DrawConfig::default(),
+    );
+    AddTextLetterByLetter::new(scene, ctx, text, 0.0);
+}
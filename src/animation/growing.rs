@@ -0,0 +1,298 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::{Rotation3, Vector3};
+
+use crate::{
+    math_utils::constants::PI,
+    mobjects::{Arrow, Mobject, Rectangle},
+    Context, GMFloat, GMPoint, Scene,
+};
+
+use super::{render_frame, Animation, AnimationConfig};
+
+// grow an arrow out of its start point, the tip always sits at the moving end.
+// the mobject is expected to be an Arrow or anything else whose first and last points are start and end
+pub struct GrowArrow {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub animation_config: AnimationConfig,
+    target_points: Option<Vec<GMPoint>>,
+}
+
+impl GrowArrow {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            animation_config,
+            target_points: None,
+        }
+    }
+}
+
+impl Iterator for GrowArrow {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for GrowArrow {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let target_points = self.target_points.get_or_insert_with(|| m.get_points());
+        if target_points.is_empty() {
+            return;
+        }
+        let start = target_points[0];
+        let points: Vec<GMPoint> = target_points
+            .iter()
+            .map(|p| start + (p - start) * progress)
+            .collect();
+        m.set_points(&points);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum GrowAnchor {
+    Center,
+    Point(GMPoint),
+    // a point on the bounding box, e.g. (-1, 0, 0) is the middle of the left edge
+    // and (1, 1, 0) is the upper right corner
+    Edge(Vector3<GMFloat>),
+}
+
+impl GrowAnchor {
+    pub fn get_point(&self, m: &dyn Mobject) -> GMPoint {
+        match self {
+            GrowAnchor::Center => m.get_center(),
+            GrowAnchor::Point(p) => *p,
+            GrowAnchor::Edge(direction) => {
+                let (min, max) = m.get_bounding_box();
+                let center = nalgebra::center(&min, &max);
+                center + (max - min).component_mul(direction) / 2.0
+            }
+        }
+    }
+}
+
+// scale the mobject up from nothing at the anchor
+pub struct GrowFromPoint {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub anchor: GrowAnchor,
+    pub animation_config: AnimationConfig,
+    // (anchor point, target points)
+    target: Option<(GMPoint, Vec<GMPoint>)>,
+}
+
+impl GrowFromPoint {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        anchor: GrowAnchor,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            anchor,
+            animation_config,
+            target: None,
+        }
+    }
+
+    pub fn from_center(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self::new(scene, ctx, m, GrowAnchor::Center, animation_config)
+    }
+}
+
+impl Iterator for GrowFromPoint {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for GrowFromPoint {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let anchor = self.anchor;
+        let (anchor_point, target_points) = self
+            .target
+            .get_or_insert_with(|| (anchor.get_point(m.as_ref()), m.get_points()));
+        let points: Vec<GMPoint> = target_points
+            .iter()
+            .map(|p| *anchor_point + (p - *anchor_point) * progress)
+            .collect();
+        m.set_points(&points);
+    }
+}
+
+// scale up from nothing at the center while spinning about the z axis,
+// the mobject starts rotated by angle and ends unrotated
+pub struct SpinInFromNothing {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub angle: GMFloat,
+    pub animation_config: AnimationConfig,
+    target: Option<(GMPoint, Vec<GMPoint>)>,
+}
+
+impl SpinInFromNothing {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            angle: PI / 2.0,
+            animation_config,
+            target: None,
+        }
+    }
+}
+
+impl Iterator for SpinInFromNothing {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for SpinInFromNothing {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let (center, target_points) = self
+            .target
+            .get_or_insert_with(|| (m.get_center(), m.get_points()));
+        let rotation =
+            Rotation3::from_axis_angle(&Vector3::z_axis(), self.angle * (1.0 - progress));
+        let points: Vec<GMPoint> = target_points
+            .iter()
+            .map(|p| *center + rotation * (p - *center) * progress)
+            .collect();
+        m.set_points(&points);
+    }
+}
+
+#[test]
+fn test_grow_arrow() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let arrow: Box<dyn Mobject> = Box::new(Arrow::new(
+        GMPoint::new(-1.0, 0.0, 0.0),
+        GMPoint::new(1.0, 0.0, 0.0),
+    ));
+    let arrow_ref = Rc::new(RefCell::new(arrow));
+    scene.borrow_mut().add_ref(arrow_ref.clone());
+    let mut grow_arrow = GrowArrow::new(
+        scene.clone(),
+        ctx.clone(),
+        arrow_ref.clone(),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    grow_arrow.interpolate(0.25);
+    let points = arrow_ref.borrow().get_points();
+    assert!((points[0] - GMPoint::new(-1.0, 0.0, 0.0)).norm() < 1e-5);
+    assert!((points[1] - GMPoint::new(-0.5, 0.0, 0.0)).norm() < 1e-5);
+    assert_eq!(grow_arrow.count(), 10);
+    let points = arrow_ref.borrow().get_points();
+    assert!((points[1] - GMPoint::new(1.0, 0.0, 0.0)).norm() < 1e-5);
+}
+
+#[test]
+fn test_grow_from_edge() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    scene.borrow_mut().add_ref(rectangle_ref.clone());
+    let mut grow = GrowFromPoint::new(
+        scene.clone(),
+        ctx.clone(),
+        rectangle_ref.clone(),
+        GrowAnchor::Edge(Vector3::new(-1.0, 0.0, 0.0)),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    grow.interpolate(0.0);
+    let (min, max) = rectangle_ref.borrow().get_bounding_box();
+    assert!((min - GMPoint::new(0.0, 0.5, 0.0)).norm() < 1e-5);
+    assert!((max - GMPoint::new(0.0, 0.5, 0.0)).norm() < 1e-5);
+    assert_eq!(grow.count(), 10);
+    let (min, max) = rectangle_ref.borrow().get_bounding_box();
+    assert!((max - GMPoint::new(1.0, 1.0, 0.0)).norm() < 1e-5);
+}
+
+#[test]
+fn test_spin_in_from_nothing() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    scene.borrow_mut().add_ref(rectangle_ref.clone());
+    let mut spin = SpinInFromNothing::new(
+        scene.clone(),
+        ctx.clone(),
+        rectangle_ref.clone(),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    spin.interpolate(0.5);
+    // half size and rotated by 45 degree, the first corner is now straight below the center
+    let points = rectangle_ref.borrow().get_points();
+    let expected = GMPoint::new(0.5, 0.5 - (0.5 as GMFloat).sqrt() / 2.0, 0.0);
+    assert!((points[0] - expected).norm() < 1e-5);
+    assert_eq!(spin.count(), 10);
+    let points = rectangle_ref.borrow().get_points();
+    assert!((points[0] - GMPoint::origin()).norm() < 1e-5);
+}
@@ -0,0 +1,266 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use nalgebra::Point3;
+
+use crate::{
+    mobjects::{Dot, Mobject},
+    Context, ContextType, GMFloat, GMPoint, Scene, SceneConfig, ValueTracker,
+};
+
+use super::{
+    rate_functions::{linear, smooth},
+    render_frame, seconds_to_frames, Animation, AnimationConfig,
+};
+
+pub trait Interpolate {
+    fn interpolate(&self, other: &Self, t: GMFloat) -> Self;
+}
+
+impl Interpolate for GMFloat {
+    fn interpolate(&self, other: &Self, t: GMFloat) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for GMPoint {
+    fn interpolate(&self, other: &Self, t: GMFloat) -> Self {
+        self + (other - self) * t
+    }
+}
+
+pub struct Keyframe<T> {
+    // seconds from the start of the animation
+    pub time: GMFloat,
+    pub value: T,
+    // easing of the segment from the previous keyframe to this one
+    pub easing: fn(GMFloat) -> GMFloat,
+}
+
+// keyframes of one property, sorted by time
+pub struct Track<T> {
+    pub keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Self { keyframes: vec![] }
+    }
+}
+
+impl<T: Interpolate + Copy> Track<T> {
+    pub fn add(&mut self, time: GMFloat, value: T, easing: fn(GMFloat) -> GMFloat) {
+        let index = self.keyframes.partition_point(|k| k.time <= time);
+        self.keyframes.insert(
+            index,
+            Keyframe {
+                time,
+                value,
+                easing,
+            },
+        );
+    }
+
+    // holds the first value before the first keyframe and the last value after the last one
+    pub fn value_at(&self, time: GMFloat) -> Option<T> {
+        let index = self.keyframes.partition_point(|k| k.time <= time);
+        if index == 0 {
+            return self.keyframes.first().map(|k| k.value);
+        }
+        let previous = &self.keyframes[index - 1];
+        let Some(next) = self.keyframes.get(index) else {
+            return Some(previous.value);
+        };
+        let t = (time - previous.time) / (next.time - previous.time);
+        Some(previous.value.interpolate(&next.value, (next.easing)(t)))
+    }
+}
+
+// animate properties of a mobject by keyframes instead of chaining animations.
+// position is the center of the mobject, scale is relative to the mobject when the animation starts.
+// tracker values are plain shared floats which updaters or other code can read
+pub struct KeyframeAnimation {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub position: Track<GMPoint>,
+    pub scale: Track<GMFloat>,
+    pub opacity: Track<GMFloat>,
+    pub values: Vec<(ValueTracker, Track<GMFloat>)>,
+    pub animation_config: AnimationConfig,
+    // (center, points) when the animation starts
+    start_state: Option<(GMPoint, Vec<GMPoint>)>,
+}
+
+impl KeyframeAnimation {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            position: Track::default(),
+            scale: Track::default(),
+            opacity: Track::default(),
+            values: vec![],
+            animation_config: AnimationConfig {
+                total_frame: 0,
+                current_frame: 0,
+                rate_function: linear,
+            },
+            start_state: None,
+        }
+    }
+
+    pub fn position_at(
+        mut self,
+        time: GMFloat,
+        position: GMPoint,
+        easing: fn(GMFloat) -> GMFloat,
+    ) -> Self {
+        self.position.add(time, position, easing);
+        self.extend_to(time);
+        self
+    }
+
+    pub fn scale_at(
+        mut self,
+        time: GMFloat,
+        scale: GMFloat,
+        easing: fn(GMFloat) -> GMFloat,
+    ) -> Self {
+        self.scale.add(time, scale, easing);
+        self.extend_to(time);
+        self
+    }
+
+    pub fn opacity_at(
+        mut self,
+        time: GMFloat,
+        opacity: GMFloat,
+        easing: fn(GMFloat) -> GMFloat,
+    ) -> Self {
+        self.opacity.add(time, opacity, easing);
+        self.extend_to(time);
+        self
+    }
+
+    pub fn value_at(
+        mut self,
+        tracker: &ValueTracker,
+        time: GMFloat,
+        value: GMFloat,
+        easing: fn(GMFloat) -> GMFloat,
+    ) -> Self {
+        match self.values.iter_mut().find(|(t, _)| Rc::ptr_eq(t, tracker)) {
+            Some((_, track)) => track.add(time, value, easing),
+            None => {
+                let mut track = Track::default();
+                track.add(time, value, easing);
+                self.values.push((tracker.clone(), track));
+            }
+        }
+        self.extend_to(time);
+        self
+    }
+
+    // the animation lasts until the last keyframe of all tracks
+    fn extend_to(&mut self, time: GMFloat) {
+        let framerate = self.ctx.borrow().scene_config.framerate;
+        let total_frame = seconds_to_frames(time, framerate);
+        self.animation_config.total_frame = self.animation_config.total_frame.max(total_frame);
+    }
+}
+
+impl Iterator for KeyframeAnimation {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for KeyframeAnimation {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let framerate = self.ctx.borrow().scene_config.framerate;
+        let time = (self.animation_config.rate_function)(alpha)
+            * self.animation_config.total_frame as GMFloat
+            / framerate as GMFloat;
+        let mut m = self.m.borrow_mut();
+        let (start_center, start_points) = self
+            .start_state
+            .get_or_insert_with(|| (m.get_center(), m.get_points()));
+
+        if !self.position.keyframes.is_empty() || !self.scale.keyframes.is_empty() {
+            let center = self.position.value_at(time).unwrap_or(*start_center);
+            let scale = self.scale.value_at(time).unwrap_or(1.0);
+            let points: Vec<GMPoint> = start_points
+                .iter()
+                .map(|p| center + (p - *start_center) * scale)
+                .collect();
+            m.set_points(&points);
+        }
+        if let Some(opacity) = self.opacity.value_at(time) {
+            let mut draw_config = m.get_draw_config();
+            draw_config.opacity = opacity;
+            m.set_draw_config(draw_config);
+        }
+        for (tracker, track) in &self.values {
+            if let Some(value) = track.value_at(time) {
+                tracker.set(value);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_track_value_at() {
+    let mut track = Track::default();
+    track.add(2.0, 4.0, linear);
+    track.add(0.0, 0.0, linear);
+    track.add(3.0, 0.0, smooth);
+    assert_eq!(track.value_at(-1.0), Some(0.0));
+    assert_eq!(track.value_at(1.0), Some(2.0));
+    assert!((track.value_at(2.5).unwrap() - 2.0).abs() < 1e-5);
+    assert_eq!(track.value_at(5.0), Some(0.0));
+}
+
+#[test]
+fn test_keyframe_animation() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            framerate: 30,
+            ..Default::default()
+        },
+    )));
+    let dot: Box<dyn Mobject> = Box::new(Dot::default());
+    let dot_ref = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot_ref.clone());
+    let tracker = Rc::new(Cell::new(0.0));
+    let animation = KeyframeAnimation::new(scene.clone(), ctx.clone(), dot_ref.clone())
+        .position_at(0.0, Point3::new(0.0, 0.0, 0.0), linear)
+        .position_at(1.0, Point3::new(2.0, 1.0, 0.0), smooth)
+        .opacity_at(0.5, 1.0, linear)
+        .opacity_at(1.5, 0.2, linear)
+        .value_at(&tracker, 1.0, 5.0, linear);
+    assert_eq!(animation.total_frame(), 45);
+    assert_eq!(animation.count(), 45);
+    let dot = dot_ref.borrow();
+    assert!((dot.get_center() - Point3::new(2.0, 1.0, 0.0)).norm() < 1e-4);
+    assert!((dot.get_draw_config().opacity - 0.2).abs() < 1e-5);
+    assert_eq!(tracker.get(), 5.0);
+}
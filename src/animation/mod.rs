@@ -4,13 +4,28 @@ use nalgebra::{Point3, Vector3};
 use tiny_skia::Pixmap;
 
 use crate::{
+    hooks::{self, AnimationEvent},
+    math_utils::path_along_arc,
     mobjects::{text::Text, Mobject, MobjectClone, SimpleLine},
-    video_backend::{ffmpeg::FfmpegBackend, FfmpegPipeBackend, FfmpegPipeEncoder},
-    Context, GMFloat, Scene, SceneConfig,
+    video_backend::{FfmpegPipeBackend, FfmpegPipeEncoder, VideoBackend},
+    Context, ContextType, GMFloat, GMPoint, Scene, SceneConfig,
 };
 
-trait Animation: Iterator<Item = Vec<u8>> {
+pub mod composition;
+pub mod creation;
+pub mod growing;
+pub mod indication;
+pub mod keyframe;
+pub mod numbers;
+pub mod rate_functions;
+pub mod style;
+pub mod transform;
+
+pub trait Animation: Iterator<Item = Vec<u8>> {
     fn total_frame(&self) -> u32;
+    // move the animated mobjects to the state at alpha (0.0 ~ 1.0) without drawing anything,
+    // so that combinators can drive their children and draw the scene only once per frame
+    fn interpolate(&mut self, alpha: GMFloat);
 }
 
 pub struct AnimationConfig {
@@ -18,6 +33,169 @@ pub struct AnimationConfig {
     pub current_frame: u32,
     pub rate_function: fn(GMFloat) -> GMFloat,
 }
+
+impl AnimationConfig {
+    // run_time is in seconds, at least one frame is played
+    pub fn from_run_time(
+        run_time: GMFloat,
+        framerate: u32,
+        rate_function: fn(GMFloat) -> GMFloat,
+    ) -> Self {
+        Self {
+            total_frame: seconds_to_frames(run_time, framerate).max(1),
+            current_frame: 0,
+            rate_function,
+        }
+    }
+    // advance one frame, return None when the animation is over
+    pub fn next_alpha(&mut self) -> Option<GMFloat> {
+        self.current_frame += 1;
+        if self.current_frame > self.total_frame {
+            return None;
+        }
+        Some(self.current_frame as GMFloat / self.total_frame as GMFloat)
+    }
+}
+
+pub fn seconds_to_frames(seconds: GMFloat, framerate: u32) -> u32 {
+    (seconds * framerate as GMFloat).round().max(0.0) as u32
+}
+
+// run the updaters for one frame and draw the whole scene,
+// a frame outside the render range of the scene is not drawn and is empty
+pub fn render_frame(scene: &Rc<RefCell<Scene>>, ctx: &Rc<RefCell<Context>>) -> Vec<u8> {
+    advance_frame(scene, ctx, true)
+}
+
+// render_frame, without draw the frame already in ctx is used again
+fn advance_frame(scene: &Rc<RefCell<Scene>>, ctx: &Rc<RefCell<Context>>, draw: bool) -> Vec<u8> {
+    let mut ctx = ctx.borrow_mut();
+    let framerate = ctx.scene_config.framerate;
+    let dt = scene.borrow().frame_dt(framerate);
+    scene.borrow_mut().update(dt);
+    let index = scene.borrow().frame_index;
+    let frame = if scene.borrow().render_range(framerate).contains(&index) {
+        let _span = tracing::debug_span!("render_frame", frame = index).entered();
+        let start = Instant::now();
+        if draw {
+            scene.borrow().draw_frame(&mut ctx);
+        }
+        tracing::debug!(elapsed = ?start.elapsed(), "rendered frame");
+        let mut scene = scene.borrow_mut();
+        scene.export_svg_frame(&ctx.scene_config);
+        scene.export_lottie_frame(&ctx.scene_config);
+        let time = scene.hook_time();
+        scene.hooks.frame_rendered(time);
+        ctx.image_bytes().to_vec()
+    } else {
+        vec![]
+    };
+    scene.borrow_mut().frame_index += 1;
+    frame
+}
+// write every frame of the animation to the video backend.
+// in a skipped section or outside the render range of the scene nothing is drawn,
+// the animation jumps to its end and updaters still run
+pub fn play(
+    scene: &Rc<RefCell<Scene>>,
+    ctx: &Rc<RefCell<Context>>,
+    video_backend: &mut VideoBackend,
+    mut animation: impl Animation,
+) {
+    let framerate = ctx.borrow().scene_config.framerate;
+    let total_frame = animation.total_frame();
+    let skipping = scene.borrow().is_skipping();
+    let skipped = skipping
+        || scene
+            .borrow()
+            .frames_in_render_range(total_frame as u64, framerate)
+            == 0;
+    let _span = tracing::info_span!("play", total_frame, skipped).entered();
+    tracing::info!(frame = scene.borrow().frame_index, "animation started");
+    let event = |scene: &Scene| AnimationEvent {
+        time: scene.hook_time(),
+        total_frame,
+        skipped,
+    };
+    {
+        let mut scene = scene.borrow_mut();
+        let start = event(&scene);
+        scene.hooks.animation_start(start);
+    }
+    if skipped {
+        animation.interpolate(1.0);
+        let dt = scene.borrow().frame_dt(framerate);
+        for _ in 0..total_frame {
+            scene.borrow_mut().update(dt);
+        }
+        if !skipping {
+            scene.borrow_mut().frame_index += total_frame as u64;
+        }
+    } else if ctx.borrow().scene_config.sub_frames > 1 {
+        play_sub_frames(scene, ctx, video_backend, animation);
+    } else {
+        for frame in animation {
+            if frame.is_empty() {
+                continue;
+            }
+            video_backend.write_frame(&frame);
+            scene.borrow_mut().preview.after_frame(&ctx.borrow());
+        }
+    }
+    let mut scene = scene.borrow_mut();
+    let end = event(&scene);
+    scene.hooks.animation_end(end);
+}
+
+// play with SceneConfig::sub_frames, the animation is interpolated at every sub-frame
+// instead of being iterated, see crate::SubFrameWriter
+fn play_sub_frames(
+    scene: &Rc<RefCell<Scene>>,
+    ctx: &Rc<RefCell<Context>>,
+    video_backend: &mut VideoBackend,
+    mut animation: impl Animation,
+) {
+    let framerate = ctx.borrow().scene_config.framerate;
+    let mut writer = crate::SubFrameWriter::new(&ctx.borrow().scene_config);
+    let sub_frames = writer.sub_frames;
+    let total_frame = animation.total_frame();
+    for frame in 0..total_frame {
+        let index = scene.borrow().frame_index;
+        let in_range = scene.borrow().render_range(framerate).contains(&index);
+        for k in 1..=sub_frames {
+            let alpha =
+                (frame as GMFloat + k as GMFloat / sub_frames as GMFloat) / total_frame as GMFloat;
+            animation.interpolate(alpha);
+            // after interpolate, which may change the speed, see ChangeSpeed
+            let dt = scene.borrow().frame_dt(framerate) / sub_frames as GMFloat;
+            scene.borrow_mut().update(dt);
+            if in_range {
+                let mut ctx = ctx.borrow_mut();
+                scene.borrow().draw_frame(&mut ctx);
+                writer.add(ctx.image_bytes(), video_backend);
+            }
+        }
+        scene.borrow_mut().frame_index += 1;
+        if in_range {
+            tracing::debug!(frame = index, sub_frames, "rendered frame");
+            {
+                let scene_config = &ctx.borrow().scene_config;
+                let mut scene = scene.borrow_mut();
+                scene.export_svg_frame(scene_config);
+                scene.export_lottie_frame(scene_config);
+            }
+            writer.end_frame(video_backend);
+            let mut scene = scene.borrow_mut();
+            let time = hooks::HookTime {
+                frame_index: index,
+                scene_time: scene.time,
+            };
+            scene.hooks.frame_rendered(time);
+            scene.preview.after_frame(&ctx.borrow());
+        }
+    }
+}
+
 pub struct SimpleMovement {
     pub displacement: Vector3<GMFloat>,
     pub scene: Rc<RefCell<Scene>>,
@@ -25,6 +203,8 @@ pub struct SimpleMovement {
     pub m: Rc<RefCell<Box<dyn Mobject>>>,
     pub animation_config: AnimationConfig,
     pub last_progress: GMFloat,
+    // the mobject travels along an arc turning by this angle instead of a straight line
+    pub path_arc: GMFloat,
 }
 
 pub struct MovementPrecise {
@@ -39,38 +219,36 @@ pub struct MovementPrecise {
 impl Iterator for SimpleMovement {
     type Item = Vec<u8>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.animation_config.current_frame += 1;
-
-        let current_frame = self.animation_config.current_frame;
-        let total_frame = self.animation_config.total_frame;
-
-        if current_frame > total_frame {
-            return None;
-        }
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
 
-        let progress = (self.animation_config.rate_function)(
-            current_frame as GMFloat / total_frame as GMFloat,
-        );
+impl Animation for SimpleMovement {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
 
-        let delta_progress = progress - self.last_progress;
+        // the offset from the start is the same for every start point, so the arc is taken from the origin
+        let offset = |t: GMFloat| {
+            path_along_arc(
+                GMPoint::origin(),
+                GMPoint::from(self.displacement),
+                self.path_arc,
+                t,
+            )
+            .coords
+        };
+        let delta = offset(progress) - offset(self.last_progress);
         self.last_progress = progress;
 
-        let translation = nalgebra::Matrix4::new_translation(&(self.displacement * delta_progress));
+        let translation = nalgebra::Matrix4::new_translation(&delta);
         self.m
             .borrow_mut()
             .transform(nalgebra::Transform::from_matrix_unchecked(translation));
-        // Some()
-        for d in &self.scene.borrow().mobjects {
-            self.ctx.borrow_mut().clear_transparent();
-            d.borrow().draw(&mut *self.ctx.borrow_mut());
-        }
-        Some(self.ctx.borrow().image_bytes().to_vec())
-    }
-}
-
-impl Animation for SimpleMovement {
-    fn total_frame(&self) -> u32 {
-        return self.animation_config.total_frame;
     }
 }
 
@@ -91,30 +269,9 @@ pub struct SimpleRotate {
 impl Iterator for SimpleRotate {
     type Item = Vec<u8>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.animation_config.current_frame += 1;
-
-        let current_frame = self.animation_config.current_frame;
-        let total_frame = self.animation_config.total_frame;
-        if current_frame > total_frame {
-            return None;
-        }
-
-        let progress = (self.animation_config.rate_function)(
-            current_frame as GMFloat / total_frame as GMFloat,
-        );
-
-        let delta_progress = progress - self.last_progress;
-        self.last_progress = progress;
-        let rotation_matrix =
-            nalgebra::Matrix4::new_rotation_wrt_point(self.axisangle * delta_progress, self.point);
-        self.m
-            .borrow_mut()
-            .transform(nalgebra::Transform::from_matrix_unchecked(rotation_matrix));
-        for d in &self.scene.borrow().mobjects {
-            self.ctx.borrow_mut().clear_transparent();
-            d.borrow().draw(&mut *self.ctx.borrow_mut());
-        }
-        Some(self.ctx.borrow().image_bytes().to_vec())
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
     }
 }
 
@@ -142,6 +299,7 @@ fn test_simple_move() {
             rate_function: |x| x,
         },
         last_progress: 0.0,
+        path_arc: 0.0,
     };
     use crate::video_backend::{
         ColorOrder, FfmpegPipeBackend, FrameMessage, VideoBackend, VideoBackendController,
@@ -170,10 +328,49 @@ fn test_simple_move() {
     // video_backend_controller.end();
 }
 
+#[test]
+fn test_simple_move_path_arc() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let dot: Box<dyn Mobject> = Box::new(crate::mobjects::Dot::new(
+        GMPoint::new(1.0, 0.0, 0.5),
+        0.1,
+        Default::default(),
+        Default::default(),
+    ));
+    let dot = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot.clone());
+    let mut movement = SimpleMovement {
+        displacement: Vector3::new(-2.0, 0.0, 1.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot.clone(),
+        animation_config: AnimationConfig::from_run_time(1.0, 30, |x| x),
+        last_progress: 0.0,
+        path_arc: crate::math_utils::constants::PI,
+    };
+    // half a turn counterclockwise about the midpoint passes above it, z moves linearly
+    movement.interpolate(0.5);
+    assert!((dot.borrow().get_center() - GMPoint::new(0.0, 1.0, 1.0)).norm() < 1e-4);
+    movement.interpolate(1.0);
+    assert!((dot.borrow().get_center() - GMPoint::new(-1.0, 0.0, 1.5)).norm() < 1e-4);
+}
+
 impl Animation for SimpleRotate {
     fn total_frame(&self) -> u32 {
         self.animation_config.total_frame
     }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+
+        let delta_progress = progress - self.last_progress;
+        self.last_progress = progress;
+        let rotation_matrix =
+            nalgebra::Matrix4::new_rotation_wrt_point(self.axisangle * delta_progress, self.point);
+        self.m
+            .borrow_mut()
+            .transform(nalgebra::Transform::from_matrix_unchecked(rotation_matrix));
+    }
 }
 
 pub struct Wait {
@@ -186,20 +383,12 @@ pub struct Wait {
 impl Iterator for Wait {
     type Item = Vec<u8>;
     fn next(&mut self) -> Option<Self::Item> {
-        self.animation_config.current_frame += 1;
-        let current_frame = self.animation_config.current_frame;
-        let total_frame = self.animation_config.total_frame;
-        if current_frame > total_frame {
-            return None;
-        }
-        if self.is_first_frame {
-            for d in &self.scene.borrow().mobjects {
-                self.ctx.borrow_mut().clear_transparent();
-                d.borrow().draw(&mut *self.ctx.borrow_mut());
-            }
-            self.is_first_frame = false;
-        }
-        Some(self.ctx.borrow().image_bytes().to_vec())
+        self.animation_config.next_alpha()?;
+        let draw = self.is_first_frame || self.scene.borrow().is_time_dependent();
+        let frame = advance_frame(&self.scene, &self.ctx, draw);
+        // nothing is drawn before the render range, the first frame inside it is
+        self.is_first_frame &= frame.is_empty();
+        Some(frame)
     }
 }
 
@@ -207,22 +396,27 @@ impl Animation for Wait {
     fn total_frame(&self) -> u32 {
         self.animation_config.total_frame
     }
+    fn interpolate(&mut self, alpha: GMFloat) {}
 }
 
+#[cfg(feature = "ffmpeg")]
 #[test]
 fn test_simple_rotate() {
+    use crate::video_backend::ffmpeg::FfmpegBackend;
     let width: usize = 1920;
     let height: usize = 1080;
-    let mut ctx = Context {
-        ctx_type: crate::ContextType::TinySKIA(Pixmap::new(width as u32, height as u32).unwrap()),
-        scene_config: SceneConfig {
+    let mut ctx = Context::new(
+        crate::ContextType::TinySKIA(Pixmap::new(width as u32, height as u32).unwrap()),
+        SceneConfig {
             width: 16.0,
             height: 9.0,
             output_width: width as u32,
             output_height: height as u32,
             scale_factor: height as GMFloat / 16.0,
+            framerate: 60,
+            ..Default::default()
         },
-    };
+    );
     let mut scene = Scene::default();
     let mut line: Box<dyn Mobject> = Box::new(SimpleLine {
         p0: Point3::new(0.0, 0.0, 0.0),
@@ -283,5 +477,276 @@ fn test_simple_rotate() {
     for f in frames {
         video_backend_var.write_frame(&f);
     }
-    video_backend_var.close();
+    video_backend_var.close().unwrap();
+}
+
+#[test]
+fn test_skip_section() {
+    use crate::video_backend::{BgraRAWBackend, ColorOrder, VideoBackendType, VideoConfig};
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            framerate: 30,
+            ..Default::default()
+        },
+    )));
+    let line: Box<dyn Mobject> = Box::new(SimpleLine::default());
+    let line_ref = Rc::new(RefCell::new(line));
+    scene.borrow_mut().add_ref(line_ref.clone());
+    let video_config = VideoConfig {
+        filename: std::env::temp_dir()
+            .join("gmanim_sections.raw")
+            .to_string_lossy()
+            .into_owned(),
+        framerate: 30,
+        output_width: 160,
+        output_height: 90,
+        color_order: ColorOrder::Rgba,
+    };
+    let _ = std::fs::remove_file(&video_config.filename);
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+    };
+    let movement = |scene: &Rc<RefCell<Scene>>| SimpleMovement {
+        displacement: Vector3::new(1.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: line_ref.clone(),
+        animation_config: AnimationConfig::from_run_time(1.0, 30, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    };
+
+    scene.borrow_mut().next_section("intro", true);
+    play(&scene, &ctx, &mut video_backend, movement(&scene));
+    scene
+        .borrow_mut()
+        .wait(&mut ctx.borrow_mut(), &mut video_backend, 1.0);
+    assert!((scene.borrow().time - 2.0).abs() < 1e-4);
+    assert!((line_ref.borrow().get_points()[0].x - 1.0).abs() < 1e-5);
+
+    scene.borrow_mut().next_section("main", false);
+    play(&scene, &ctx, &mut video_backend, movement(&scene));
+    video_backend.close().unwrap();
+    assert!((line_ref.borrow().get_points()[0].x - 2.0).abs() < 1e-5);
+    let written = std::fs::metadata(&video_config.filename).unwrap().len();
+    assert_eq!(written, 30 * 160 * 90 * 4);
+}
+
+#[test]
+fn test_render_range() {
+    use crate::video_backend::{BgraRAWBackend, ColorOrder, VideoBackendType, VideoConfig};
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            framerate: 30,
+            ..Default::default()
+        },
+    )));
+    let line: Box<dyn Mobject> = Box::new(SimpleLine::default());
+    let line_ref = Rc::new(RefCell::new(line));
+    scene.borrow_mut().add_ref(line_ref.clone());
+    let video_config = VideoConfig {
+        filename: std::env::temp_dir()
+            .join("gmanim_render_range.raw")
+            .to_string_lossy()
+            .into_owned(),
+        framerate: 30,
+        output_width: 160,
+        output_height: 90,
+        color_order: ColorOrder::Rgba,
+    };
+    let _ = std::fs::remove_file(&video_config.filename);
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+    };
+    let movement = |scene: &Rc<RefCell<Scene>>| SimpleMovement {
+        displacement: Vector3::new(1.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: line_ref.clone(),
+        animation_config: AnimationConfig::from_run_time(1.0, 30, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    };
+
+    scene.borrow_mut().render_from = 0.5;
+    scene.borrow_mut().render_to = Some(1.5);
+    // the second half of the movement and the first half of the wait are written
+    play(&scene, &ctx, &mut video_backend, movement(&scene));
+    scene
+        .borrow_mut()
+        .wait(&mut ctx.borrow_mut(), &mut video_backend, 1.0);
+    play(&scene, &ctx, &mut video_backend, movement(&scene));
+    video_backend.close().unwrap();
+    assert_eq!(scene.borrow().frame_index, 90);
+    assert!((line_ref.borrow().get_points()[0].x - 2.0).abs() < 1e-5);
+    let written = std::fs::metadata(&video_config.filename).unwrap().len();
+    assert_eq!(written, 30 * 160 * 90 * 4);
+}
+
+#[test]
+fn test_wait_frame_index() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(Pixmap::new(16, 9).unwrap()),
+        SceneConfig {
+            output_width: 16,
+            output_height: 9,
+            scale_factor: 1.0,
+            framerate: 30,
+            ..Default::default()
+        },
+    )));
+    let line: Box<dyn Mobject> = Box::new(SimpleLine::default());
+    scene.borrow_mut().add(line);
+    scene.borrow_mut().render_from = 0.5;
+    let wait = Wait {
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        animation_config: AnimationConfig::from_run_time(1.0, 30, |x| x),
+        is_first_frame: true,
+    };
+    let frames: Vec<Vec<u8>> = wait.collect();
+    // the frames before the render range are empty, the held frame is drawn once inside it
+    assert!(frames[..15].iter().all(|f| f.is_empty()));
+    assert!(frames[15..].iter().all(|f| f.len() == 16 * 9 * 4));
+    assert_eq!(scene.borrow().frame_index, 30);
+    assert!((scene.borrow().time - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_motion_blur() {
+    use crate::mobjects::Dot;
+    use crate::video_backend::{BgraRAWBackend, ColorOrder, VideoBackendType, VideoConfig};
+    // red at the center of the frame after the dot jumped across it in one frame
+    let center_red = |sub_frames: u32| {
+        let scene = Rc::new(RefCell::new(Scene::default()));
+        let ctx = Rc::new(RefCell::new(Context::new(
+            ContextType::TinySKIA(Pixmap::new(160, 90).unwrap()),
+            SceneConfig {
+                output_width: 160,
+                output_height: 90,
+                scale_factor: 10.0,
+                framerate: 30,
+                sub_frames,
+                ..Default::default()
+            },
+        )));
+        let red = crate::Color::new(0xff, 0, 0, 0xff);
+        let dot = Dot::new(
+            Point3::new(-4.0, 0.0, 0.0),
+            0.5,
+            red,
+            crate::mobjects::DrawConfig {
+                color: red,
+                ..Default::default()
+            },
+        );
+        let dot: Box<dyn Mobject> = Box::new(dot);
+        let dot = Rc::new(RefCell::new(dot));
+        scene.borrow_mut().add_ref(dot.clone());
+        let video_config = VideoConfig {
+            filename: std::env::temp_dir()
+                .join(format!("gmanim_motion_blur_{sub_frames}.raw"))
+                .to_string_lossy()
+                .into_owned(),
+            framerate: 30,
+            output_width: 160,
+            output_height: 90,
+            color_order: ColorOrder::Rgba,
+        };
+        let _ = std::fs::remove_file(&video_config.filename);
+        let mut video_backend = VideoBackend {
+            backend_type: VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+        };
+        let movement = SimpleMovement {
+            displacement: Vector3::new(8.0, 0.0, 0.0),
+            scene: scene.clone(),
+            ctx: ctx.clone(),
+            m: dot.clone(),
+            animation_config: AnimationConfig::from_run_time(1.0 / 30.0, 30, |x| x),
+            last_progress: 0.0,
+            path_arc: 0.0,
+        };
+        play(&scene, &ctx, &mut video_backend, movement);
+        video_backend.close().unwrap();
+        assert!((dot.borrow().get_center().x - 4.0).abs() < 1e-5);
+        let frame = std::fs::read(&video_config.filename).unwrap();
+        assert_eq!(frame.len(), 160 * 90 * 4);
+        frame[(45 * 160 + 80) * 4]
+    };
+    assert_eq!(center_red(1), 0);
+    // the dot is at the center in one of the four sub-frames
+    let blurred = center_red(4);
+    assert!((0x38..0x48).contains(&blurred), "{blurred}");
+}
+
+#[test]
+fn test_emit_sub_frames() {
+    use crate::mobjects::Dot;
+    use crate::video_backend::{BgraRAWBackend, ColorOrder, VideoBackendType, VideoConfig};
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let scene_config = SceneConfig {
+        output_width: 32,
+        output_height: 18,
+        scale_factor: 2.0,
+        framerate: 30,
+        sub_frames: 4,
+        sub_frame_mode: crate::SubFrameMode::Emit,
+        ..Default::default()
+    };
+    assert_eq!(scene_config.video_framerate(), 120);
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(Pixmap::new(32, 18).unwrap()),
+        scene_config,
+    )));
+    let dot: Box<dyn Mobject> = Box::new(Dot::new(
+        Point3::new(-4.0, 0.0, 0.0),
+        0.5,
+        crate::Color::new(0xff, 0, 0, 0xff),
+        Default::default(),
+    ));
+    let dot = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot.clone());
+    let video_config = VideoConfig {
+        filename: std::env::temp_dir()
+            .join("gmanim_emit_sub_frames.raw")
+            .to_string_lossy()
+            .into_owned(),
+        framerate: 120,
+        output_width: 32,
+        output_height: 18,
+        color_order: ColorOrder::Rgba,
+    };
+    let _ = std::fs::remove_file(&video_config.filename);
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+    };
+    let movement = SimpleMovement {
+        displacement: Vector3::new(8.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot.clone(),
+        animation_config: AnimationConfig::from_run_time(0.1, 30, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    };
+    play(&scene, &ctx, &mut video_backend, movement);
+    scene
+        .borrow_mut()
+        .wait(&mut ctx.borrow_mut(), &mut video_backend, 0.1);
+    video_backend.close().unwrap();
+    // 3 animated and 3 still frames of 4 sub-frames each
+    assert_eq!(scene.borrow().frame_index, 6);
+    let written = std::fs::metadata(&video_config.filename).unwrap().len();
+    assert_eq!(written, 24 * 32 * 18 * 4);
 }
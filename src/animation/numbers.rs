@@ -0,0 +1,100 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use crate::{Context, GMFloat, Scene, ValueTracker};
+
+use super::{render_frame, Animation, AnimationConfig};
+
+// tween a value tracker from start_value to end_value, a DecimalNumber showing the tracker
+// counts along, e.g. ChangingDecimal::new(scene, ctx, decimal.tracker.clone(), 0.0, 100.0, config)
+pub struct ChangingDecimal {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub tracker: ValueTracker,
+    pub start_value: GMFloat,
+    pub end_value: GMFloat,
+    pub animation_config: AnimationConfig,
+}
+
+impl ChangingDecimal {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        tracker: ValueTracker,
+        start_value: GMFloat,
+        end_value: GMFloat,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            tracker,
+            start_value,
+            end_value,
+            animation_config,
+        }
+    }
+
+    // count from the current value of the tracker
+    pub fn count_up(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        tracker: ValueTracker,
+        end_value: GMFloat,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        let start_value = tracker.get();
+        Self::new(
+            scene,
+            ctx,
+            tracker,
+            start_value,
+            end_value,
+            animation_config,
+        )
+    }
+}
+
+impl Iterator for ChangingDecimal {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for ChangingDecimal {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        self.tracker
+            .set(self.start_value + (self.end_value - self.start_value) * progress);
+    }
+}
+
+#[test]
+fn test_changing_decimal() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let tracker = Rc::new(Cell::new(10.0));
+    let mut count_up = ChangingDecimal::count_up(
+        scene.clone(),
+        ctx.clone(),
+        tracker.clone(),
+        50.0,
+        AnimationConfig {
+            total_frame: 4,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    count_up.interpolate(0.25);
+    assert_eq!(tracker.get(), 20.0);
+    assert_eq!(count_up.count(), 4);
+    assert_eq!(tracker.get(), 50.0);
+}
@@ -0,0 +1,82 @@
+use crate::{math_utils::constants::PI, GMFloat};
+
+pub fn linear(t: GMFloat) -> GMFloat {
+    t
+}
+
+pub fn smooth(t: GMFloat) -> GMFloat {
+    (1.0 - (t * PI).cos()) / 2.0
+}
+
+// go from 0.0 to 1.0 in the first half and come back in the second half
+pub fn there_and_back(t: GMFloat) -> GMFloat {
+    split_there_and_back(smooth, t)
+}
+
+// oscillate between -1.0 and 1.0 for the given times while fading in and out
+pub fn wiggle(t: GMFloat, wiggles: GMFloat) -> GMFloat {
+    there_and_back(t) * (wiggles * PI * t).sin()
+}
+
+// combinators take the rate function as the first argument, a non capturing closure
+// such as |t| squish(smooth, 0.25, 0.75, t) can be used as a rate function of AnimationConfig
+
+// play the rate function backwards
+pub fn reverse(rate_function: fn(GMFloat) -> GMFloat, t: GMFloat) -> GMFloat {
+    rate_function(1.0 - t)
+}
+
+// keep overshooting rate functions inside 0.0 ~ 1.0
+pub fn clamp(rate_function: fn(GMFloat) -> GMFloat, t: GMFloat) -> GMFloat {
+    rate_function(t).clamp(0.0, 1.0)
+}
+
+// the whole rate function in the first half and backwards in the second half
+pub fn split_there_and_back(rate_function: fn(GMFloat) -> GMFloat, t: GMFloat) -> GMFloat {
+    let t = if t < 0.5 { 2.0 * t } else { 2.0 * (1.0 - t) };
+    rate_function(t)
+}
+
+// run the rate function within a..b only, holding its start value before and its end value after
+pub fn squish(
+    rate_function: fn(GMFloat) -> GMFloat,
+    a: GMFloat,
+    b: GMFloat,
+    t: GMFloat,
+) -> GMFloat {
+    if t <= a {
+        return rate_function(0.0);
+    }
+    if t >= b {
+        return rate_function(1.0);
+    }
+    rate_function((t - a) / (b - a))
+}
+
+// outer(inner(t))
+pub fn compose(
+    outer: fn(GMFloat) -> GMFloat,
+    inner: fn(GMFloat) -> GMFloat,
+    t: GMFloat,
+) -> GMFloat {
+    outer(inner(t))
+}
+
+#[test]
+fn test_there_and_back() {
+    assert_eq!(there_and_back(0.0), 0.0);
+    assert!((there_and_back(0.5) - 1.0).abs() < 1e-6);
+    assert!(there_and_back(1.0).abs() < 1e-6);
+}
+
+#[test]
+fn test_combinators() {
+    let squished: fn(GMFloat) -> GMFloat = |t| squish(linear, 0.25, 0.75, t);
+    assert_eq!(squished(0.1), 0.0);
+    assert_eq!(squished(0.5), 0.5);
+    assert_eq!(squished(0.9), 1.0);
+    assert_eq!(reverse(linear, 0.25), 0.75);
+    assert_eq!(clamp(|t| 2.0 * t - 0.5, 0.9), 1.0);
+    assert_eq!(split_there_and_back(linear, 0.75), 0.5);
+    assert!((compose(smooth, |t| squish(linear, 0.5, 1.0, t), 0.75) - 0.5).abs() < 1e-6);
+}
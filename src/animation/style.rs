@@ -0,0 +1,122 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    mobjects::{DrawConfig, Mobject, Rectangle},
+    Color, Context, GMFloat, Scene,
+};
+
+use super::{render_frame, Animation, AnimationConfig};
+
+// tween the draw config of a mobject to style_function(start draw config),
+// the start draw config is taken at the first frame
+pub struct SetStyle {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub style_function: Box<dyn Fn(DrawConfig) -> DrawConfig>,
+    pub animation_config: AnimationConfig,
+    start_end: Option<(DrawConfig, DrawConfig)>,
+}
+
+impl SetStyle {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        style_function: Box<dyn Fn(DrawConfig) -> DrawConfig>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            style_function,
+            animation_config,
+            start_end: None,
+        }
+    }
+
+    // tween to the given draw config as a whole
+    pub fn to_style(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        target: DrawConfig,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self::new(scene, ctx, m, Box::new(move |_| target), animation_config)
+    }
+
+    pub fn fade_to_color(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        color: Color,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self::new(
+            scene,
+            ctx,
+            m,
+            Box::new(move |mut draw_config| {
+                draw_config.color = color;
+                draw_config
+            }),
+            animation_config,
+        )
+    }
+}
+
+impl Iterator for SetStyle {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for SetStyle {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let style_function = &self.style_function;
+        let (start, end) = *self.start_end.get_or_insert_with(|| {
+            let start = m.get_draw_config();
+            (start, style_function(start))
+        });
+        m.set_draw_config(start.interpolate(&end, progress));
+    }
+}
+
+#[test]
+fn test_fade_to_color() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    scene.borrow_mut().add_ref(rectangle_ref.clone());
+    let red = Color::new(0xff, 0x00, 0x00, 0xff);
+    let mut fade = SetStyle::fade_to_color(
+        scene.clone(),
+        ctx.clone(),
+        rectangle_ref.clone(),
+        red,
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    fade.interpolate(0.5);
+    let start_color = DrawConfig::default().color;
+    let half = rectangle_ref.borrow().get_draw_config().color;
+    assert_eq!(half.r, start_color.interpolate(&red, 0.5).r);
+    assert_eq!(fade.count(), 10);
+    let draw_config = rectangle_ref.borrow().get_draw_config();
+    assert_eq!((draw_config.color.r, draw_config.color.b), (0xff, 0x00));
+    assert_eq!(draw_config.stoke_width, DrawConfig::default().stoke_width);
+}
@@ -0,0 +1,343 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    math_utils::{path_along_arc, resample_points},
+    mobjects::{DrawConfig, Mobject, Rectangle},
+    Color, Context, GMFloat, GMPoint, Scene,
+};
+
+use super::{render_frame, Animation, AnimationConfig};
+
+// move every point of a mobject from p to function(p), each point travels along a straight line
+pub struct ApplyPointwiseFunction {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub function: Box<dyn Fn(GMPoint) -> GMPoint>,
+    // points travel along arcs turning by this angle instead of straight lines
+    pub path_arc: GMFloat,
+    pub animation_config: AnimationConfig,
+    // (start, target) of every point, computed at the first frame
+    point_pairs: Option<Vec<(GMPoint, GMPoint)>>,
+}
+
+impl ApplyPointwiseFunction {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        function: Box<dyn Fn(GMPoint) -> GMPoint>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            function,
+            path_arc: 0.0,
+            animation_config,
+            point_pairs: None,
+        }
+    }
+}
+
+impl Iterator for ApplyPointwiseFunction {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for ApplyPointwiseFunction {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let function = &self.function;
+        let point_pairs = self.point_pairs.get_or_insert_with(|| {
+            m.get_points()
+                .into_iter()
+                .map(|p| (p, function(p)))
+                .collect()
+        });
+        let points: Vec<GMPoint> = point_pairs
+            .iter()
+            .map(|(start, target)| path_along_arc(*start, *target, self.path_arc, progress))
+            .collect();
+        m.set_points(&points);
+    }
+}
+
+// continuous deformation, every point p of the mobject is moved to homotopy(p.x, p.y, p.z, t)
+// where p is taken from the mobject at the first frame, homotopy(x, y, z, 0.0) is expected to be (x, y, z)
+pub struct Homotopy {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub homotopy: Box<dyn Fn(GMFloat, GMFloat, GMFloat, GMFloat) -> GMPoint>,
+    pub animation_config: AnimationConfig,
+    start_points: Option<Vec<GMPoint>>,
+}
+
+impl Homotopy {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        homotopy: Box<dyn Fn(GMFloat, GMFloat, GMFloat, GMFloat) -> GMPoint>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            homotopy,
+            animation_config,
+            start_points: None,
+        }
+    }
+}
+
+impl Iterator for Homotopy {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for Homotopy {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let t = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let start_points = self.start_points.get_or_insert_with(|| m.get_points());
+        let points: Vec<GMPoint> = start_points
+            .iter()
+            .map(|p| (self.homotopy)(p.x, p.y, p.z, t))
+            .collect();
+        m.set_points(&points);
+    }
+}
+
+// (start, target) of the points and the draw configs of m and target
+type MorphState = (Vec<(GMPoint, GMPoint)>, DrawConfig, DrawConfig);
+
+// morph a mobject into the shape and style of target, the target itself is left untouched.
+// every field of the draw config is interpolated, so differently styled mobjects don't snap at the end.
+// when the numbers of points differ the target points are resampled to the points of m
+pub struct TransformAnimation {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+    pub m: Rc<RefCell<Box<dyn Mobject>>>,
+    pub target: Rc<RefCell<Box<dyn Mobject>>>,
+    // points travel along arcs turning by this angle instead of straight lines,
+    // e.g. PI swaps two mobjects along a half circle
+    pub path_arc: GMFloat,
+    pub animation_config: AnimationConfig,
+    // (start, target) of the points and the draw config, computed at the first frame
+    start_state: Option<MorphState>,
+}
+
+impl TransformAnimation {
+    pub fn new(
+        scene: Rc<RefCell<Scene>>,
+        ctx: Rc<RefCell<Context>>,
+        m: Rc<RefCell<Box<dyn Mobject>>>,
+        target: Rc<RefCell<Box<dyn Mobject>>>,
+        animation_config: AnimationConfig,
+    ) -> Self {
+        Self {
+            scene,
+            ctx,
+            m,
+            target,
+            path_arc: 0.0,
+            animation_config,
+            start_state: None,
+        }
+    }
+}
+
+impl Iterator for TransformAnimation {
+    type Item = Vec<u8>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let alpha = self.animation_config.next_alpha()?;
+        self.interpolate(alpha);
+        Some(render_frame(&self.scene, &self.ctx))
+    }
+}
+
+impl Animation for TransformAnimation {
+    fn total_frame(&self) -> u32 {
+        self.animation_config.total_frame
+    }
+    fn interpolate(&mut self, alpha: GMFloat) {
+        let progress = (self.animation_config.rate_function)(alpha);
+        let mut m = self.m.borrow_mut();
+        let target = &self.target;
+        let (point_pairs, start_draw_config, target_draw_config) =
+            self.start_state.get_or_insert_with(|| {
+                let target = target.borrow();
+                let points = m.get_points();
+                let target_points = resample_points(&target.get_points(), points.len());
+                (
+                    points.into_iter().zip(target_points).collect(),
+                    m.get_draw_config(),
+                    target.get_draw_config(),
+                )
+            });
+        let points: Vec<GMPoint> = point_pairs
+            .iter()
+            .map(|(start, target)| path_along_arc(*start, *target, self.path_arc, progress))
+            .collect();
+        m.set_points(&points);
+        m.set_draw_config(start_draw_config.interpolate(target_draw_config, progress));
+    }
+}
+
+#[test]
+fn test_apply_pointwise_function() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    scene.borrow_mut().add_ref(rectangle_ref.clone());
+    let mut apply_function = ApplyPointwiseFunction::new(
+        scene.clone(),
+        ctx.clone(),
+        rectangle_ref.clone(),
+        Box::new(|p| GMPoint::new(p.x * p.x * 2.0, p.y + p.x, 0.0)),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    apply_function.interpolate(0.5);
+    assert!((rectangle_ref.borrow().get_points()[1] - GMPoint::new(1.5, 0.5, 0.0)).norm() < 1e-5);
+    assert_eq!(apply_function.count(), 10);
+    let points = rectangle_ref.borrow().get_points();
+    assert!((points[2] - GMPoint::new(2.0, 2.0, 0.0)).norm() < 1e-5);
+}
+
+#[test]
+fn test_homotopy() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    scene.borrow_mut().add_ref(rectangle_ref.clone());
+    // a wave that passes through and leaves the shape where it was
+    let homotopy = Homotopy::new(
+        scene.clone(),
+        ctx.clone(),
+        rectangle_ref.clone(),
+        Box::new(|x, y, z, t| {
+            let wave = (t * crate::math_utils::constants::PI).sin();
+            GMPoint::new(x, y + wave * x, z)
+        }),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    assert_eq!(homotopy.count(), 10);
+    let points = rectangle_ref.borrow().get_points();
+    assert!((points[1] - GMPoint::new(1.0, 0.0, 0.0)).norm() < 1e-5);
+}
+
+#[test]
+fn test_transform_style() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let thin: Box<dyn Mobject> = Box::new(Rectangle {
+        draw_config: DrawConfig {
+            stoke_width: 0.1,
+            opacity: 1.0,
+            dash: Some((0.2, 0.2)),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let thin_ref = Rc::new(RefCell::new(thin));
+    let wide: Box<dyn Mobject> = Box::new(Rectangle {
+        p2: GMPoint::new(3.0, 1.0, 0.0),
+        p1: GMPoint::new(3.0, 0.0, 0.0),
+        draw_config: DrawConfig {
+            stoke_width: 0.5,
+            color: Color::new(255, 0, 0, 255),
+            opacity: 0.5,
+            dash: Some((0.2, 0.2)),
+            dash_offset: 1.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    let wide_ref = Rc::new(RefCell::new(wide));
+    scene.borrow_mut().add_ref(thin_ref.clone());
+    let mut transform = TransformAnimation::new(
+        scene.clone(),
+        ctx.clone(),
+        thin_ref.clone(),
+        wide_ref.clone(),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    transform.interpolate(0.5);
+    let draw_config = thin_ref.borrow().get_draw_config();
+    assert!((draw_config.stoke_width - 0.3).abs() < 1e-5);
+    assert!((draw_config.opacity - 0.75).abs() < 1e-5);
+    assert!((draw_config.dash_offset - 0.5).abs() < 1e-5);
+    transform.interpolate(1.0);
+    let thin = thin_ref.borrow();
+    assert!((thin.get_points()[2] - GMPoint::new(3.0, 1.0, 0.0)).norm() < 1e-5);
+    assert_eq!(thin.get_draw_config().color.r, 255);
+}
+
+#[test]
+fn test_transform_path_arc() {
+    let scene = Rc::new(RefCell::new(Scene::default()));
+    let ctx = Rc::new(RefCell::new(Context::default()));
+    let left: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let left_ref = Rc::new(RefCell::new(left));
+    let right: Box<dyn Mobject> = Box::new(Rectangle {
+        p0: GMPoint::new(4.0, 0.0, 0.0),
+        p1: GMPoint::new(5.0, 0.0, 0.0),
+        p2: GMPoint::new(5.0, 1.0, 0.0),
+        p3: GMPoint::new(4.0, 1.0, 0.0),
+        draw_config: Default::default(),
+    });
+    let right_ref = Rc::new(RefCell::new(right));
+    scene.borrow_mut().add_ref(left_ref.clone());
+    let mut transform = TransformAnimation::new(
+        scene.clone(),
+        ctx.clone(),
+        left_ref.clone(),
+        right_ref.clone(),
+        AnimationConfig {
+            total_frame: 10,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+    );
+    transform.path_arc = crate::math_utils::constants::PI;
+    transform.interpolate(0.5);
+    // half way along a half circle the shape is 2.0 below the straight path
+    let center = left_ref.borrow().get_center();
+    assert!((center - GMPoint::new(2.5, -1.5, 0.0)).norm() < 1e-4);
+    transform.interpolate(1.0);
+    let center = left_ref.borrow().get_center();
+    assert!((center - GMPoint::new(4.5, 0.5, 0.0)).norm() < 1e-4);
+}
@@ -0,0 +1,261 @@
+use crate::sound::{self, SoundClip, CHANNELS, SAMPLE_RATE};
+use crate::GMFloat;
+
+// the samples of a clip, Samples are interleaved stereo at sound::SAMPLE_RATE
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioSource {
+    File(String),
+    Samples(Vec<f32>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioClip {
+    pub source: AudioSource,
+    pub at_time: GMFloat,
+    pub gain: GMFloat,
+}
+
+// sound effects at animation times, mixed into one stereo track:
+//     let mut timeline = AudioTimeline::new();
+//     timeline.add_file("click.wav", 1.5, 0.8);
+//     ffmpeg_backend.set_audio_timeline(timeline)?;
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioTimeline {
+    pub clips: Vec<AudioClip>,
+}
+
+impl AudioTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the sounds of a scene, see Scene::add_sound
+    pub fn from_sounds(sounds: &[SoundClip]) -> Self {
+        let mut timeline = Self::new();
+        for clip in sounds {
+            timeline.add_file(&clip.path, clip.at_time, clip.gain);
+        }
+        timeline
+    }
+
+    pub fn add_file(&mut self, path: &str, at_time: GMFloat, gain: GMFloat) {
+        self.clips.push(AudioClip {
+            source: AudioSource::File(path.to_owned()),
+            at_time,
+            gain,
+        });
+    }
+
+    pub fn add_samples(&mut self, samples: Vec<f32>, at_time: GMFloat, gain: GMFloat) {
+        self.clips.push(AudioClip {
+            source: AudioSource::Samples(samples),
+            at_time,
+            gain,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+    }
+
+    // the clips mixed into interleaved stereo at sound::SAMPLE_RATE, the files decoded by ffmpeg.
+    // times are moved by -offset e.g. for Scene::render_from
+    pub fn mix(&self, offset: GMFloat) -> Result<Vec<f32>, String> {
+        self.mix_with(offset, |path| sound::decode(path, SAMPLE_RATE))
+    }
+
+    // mix with decode turning a file into interleaved stereo at sound::SAMPLE_RATE
+    pub fn mix_with(
+        &self,
+        offset: GMFloat,
+        decode: impl Fn(&str) -> Result<Vec<f32>, String>,
+    ) -> Result<Vec<f32>, String> {
+        let mut track = vec![];
+        for clip in &self.clips {
+            let decoded;
+            let samples = match &clip.source {
+                AudioSource::File(path) => {
+                    decoded = decode(path)?;
+                    &decoded
+                }
+                AudioSource::Samples(samples) => samples,
+            };
+            sound::mix_into(
+                &mut track,
+                samples,
+                clip.at_time - offset,
+                SAMPLE_RATE,
+                clip.gain,
+            );
+        }
+        for s in &mut track {
+            *s = s.clamp(-1.0, 1.0);
+        }
+        Ok(track)
+    }
+
+    // seconds until the last clip ends, files are decoded to know their length
+    pub fn duration(&self) -> Result<GMFloat, String> {
+        let mut end: GMFloat = 0.0;
+        for clip in &self.clips {
+            let length = match &clip.source {
+                AudioSource::File(path) => sound::duration(path)?,
+                AudioSource::Samples(samples) => {
+                    samples.len() as GMFloat / (SAMPLE_RATE as usize * CHANNELS) as GMFloat
+                }
+            };
+            end = end.max(clip.at_time + length);
+        }
+        Ok(end)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    // white noise through a low pass at the frequency, e.g. for a whoosh
+    Noise,
+}
+
+// a synthesized sound, see Scene::play_sound. the volume ramps up over attack seconds
+// and down over the last release seconds, the frequency glides to end_frequency
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tone {
+    pub waveform: Waveform,
+    pub frequency: GMFloat,
+    pub end_frequency: GMFloat,
+    pub duration: GMFloat,
+    pub volume: GMFloat,
+    pub attack: GMFloat,
+    pub release: GMFloat,
+}
+
+impl Tone {
+    // a sine beep
+    pub fn new(frequency: GMFloat, duration: GMFloat) -> Self {
+        Self {
+            waveform: Waveform::Sine,
+            frequency,
+            end_frequency: frequency,
+            duration,
+            volume: 0.5,
+            attack: 0.01,
+            release: 0.05,
+        }
+    }
+
+    pub fn square(frequency: GMFloat, duration: GMFloat) -> Self {
+        Self {
+            waveform: Waveform::Square,
+            volume: 0.25,
+            ..Self::new(frequency, duration)
+        }
+    }
+
+    // noise swelling and fading while its pitch rises
+    pub fn whoosh(duration: GMFloat) -> Self {
+        Self {
+            waveform: Waveform::Noise,
+            end_frequency: 4000.0,
+            attack: duration / 2.0,
+            release: duration / 2.0,
+            ..Self::new(200.0, duration)
+        }
+    }
+
+    // interleaved stereo at sample_rate
+    pub fn samples(&self, sample_rate: u32) -> Vec<f32> {
+        let rate = sample_rate as GMFloat;
+        let count = (self.duration.max(0.0) * rate).round() as usize;
+        let mut samples = Vec::with_capacity(count * CHANNELS);
+        let mut phase: GMFloat = 0.0;
+        let mut seed: u32 = 0x9e37_79b9;
+        let mut low_pass: GMFloat = 0.0;
+        for i in 0..count {
+            let t = i as GMFloat / rate;
+            let progress = t / self.duration;
+            let frequency = self.frequency + (self.end_frequency - self.frequency) * progress;
+            let value = match self.waveform {
+                Waveform::Sine => (phase * std::f64::consts::TAU as GMFloat).sin(),
+                Waveform::Square if phase < 0.5 => 1.0,
+                Waveform::Square => -1.0,
+                Waveform::Noise => {
+                    // xorshift, the same noise every render
+                    seed ^= seed << 13;
+                    seed ^= seed >> 17;
+                    seed ^= seed << 5;
+                    let white = seed as GMFloat / u32::MAX as GMFloat * 2.0 - 1.0;
+                    let alpha = 1.0 - (-std::f64::consts::TAU as GMFloat * frequency / rate).exp();
+                    low_pass += alpha * (white - low_pass);
+                    low_pass
+                }
+            };
+            phase = (phase + frequency / rate).fract();
+            let envelope = [
+                1.0,
+                t / self.attack.max(GMFloat::EPSILON),
+                (self.duration - t) / self.release.max(GMFloat::EPSILON),
+            ]
+            .into_iter()
+            .fold(GMFloat::INFINITY, GMFloat::min)
+            .max(0.0);
+            let sample = (value * envelope * self.volume) as f32;
+            samples.extend([sample; CHANNELS]);
+        }
+        samples
+    }
+}
+
+#[test]
+fn test_audio_timeline() {
+    let second = SAMPLE_RATE as usize * CHANNELS;
+    let mut timeline = AudioTimeline::new();
+    timeline.add_samples(vec![0.5; second], 1.0, 1.0);
+    timeline.add_samples(vec![0.75; second / 2], 1.5, 0.5);
+    timeline.add_file("beep.wav", 0.0, 1.0);
+    assert_eq!(timeline.clips.len(), 3);
+
+    let track = timeline
+        .mix_with(0.0, |path| {
+            assert_eq!(path, "beep.wav");
+            Ok(vec![1.0; CHANNELS])
+        })
+        .unwrap();
+    assert_eq!(track.len(), 2 * second);
+    assert_eq!(track[..CHANNELS], [1.0; CHANNELS]);
+    assert_eq!(track[CHANNELS], 0.0);
+    assert_eq!(track[second], 0.5);
+    // overlapping clips add up
+    assert_eq!(track[second + second / 2], 0.875);
+
+    // moved by the offset, the first clip is cut
+    let track = timeline.mix_with(1.5, |_| Ok(vec![])).unwrap();
+    assert_eq!(track.len(), second / 2);
+    assert_eq!(track[0], 0.875);
+    assert!(timeline
+        .mix_with(0.0, |_| Err("no file".to_owned()))
+        .is_err());
+}
+
+#[test]
+fn test_tone() {
+    // 4 samples a period at a sample rate of 400
+    let mut tone = Tone::new(100.0, 1.0);
+    tone.attack = 0.0;
+    tone.release = 0.0;
+    let samples = tone.samples(400);
+    assert_eq!(samples.len(), 400 * CHANNELS);
+    assert_eq!(samples[0], samples[1]);
+    assert!((samples[CHANNELS] - 0.5).abs() < 1e-4);
+    assert!(samples[3 * CHANNELS] < -0.49);
+
+    // the envelope starts and ends silent
+    let beep = Tone::square(100.0, 1.0).samples(400);
+    assert_eq!(beep[0], 0.0);
+    assert!(beep[200 * CHANNELS].abs() == 0.25);
+    let whoosh = Tone::whoosh(1.0).samples(400);
+    assert_eq!(whoosh, Tone::whoosh(1.0).samples(400));
+    assert!(whoosh.iter().all(|s| s.abs() <= 0.5));
+    assert!(whoosh.iter().any(|s| *s != 0.0));
+}
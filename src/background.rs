@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use tiny_skia::{GradientStop, LinearGradient, Paint, Pixmap, PixmapPaint, Point, Rect, Transform};
+
+use crate::{Color, GMFloat, SceneConfig};
+
+// what the context is cleared to before the mobjects are drawn.
+// gradients and images cover the whole frame, width x height scene units
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Background {
+    Color(Color),
+    // from start to end across the frame, angle 0 goes from left to right
+    // and PI / 2 from bottom to top
+    LinearGradient {
+        start: Color,
+        end: Color,
+        angle: GMFloat,
+    },
+    // stretched over the frame
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Image(Arc<Pixmap>),
+    // nothing behind the mobjects, the alpha channel reaches the video backend,
+    // see video_backend::FfmpegPipeEncoder::has_alpha and PngSequenceBackend
+    Transparent,
+}
+
+// images are told apart by their allocation, not their pixels
+impl std::fmt::Debug for Background {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Background::Color(color) => f.debug_tuple("Color").field(color).finish(),
+            Background::LinearGradient { start, end, angle } => f
+                .debug_struct("LinearGradient")
+                .field("start", start)
+                .field("end", end)
+                .field("angle", angle)
+                .finish(),
+            Background::Image(pixmap) => {
+                f.debug_tuple("Image").field(&Arc::as_ptr(pixmap)).finish()
+            }
+            Background::Transparent => f.write_str("Transparent"),
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Color(Color::new(0, 0, 0, 0xff))
+    }
+}
+
+impl Background {
+    // a png file as background
+    pub fn image(file_path: &str) -> Option<Self> {
+        Pixmap::load_png(file_path)
+            .ok()
+            .map(|pixmap| Background::Image(Arc::new(pixmap)))
+    }
+
+    // the color for contexts that can only be cleared to a solid color
+    pub fn base_color(&self) -> Color {
+        match self {
+            Background::Color(color) => *color,
+            Background::LinearGradient { start, .. } => *start,
+            Background::Image(_) => Color::new(0, 0, 0, 0xff),
+            Background::Transparent => Color::new(0, 0, 0, 0),
+        }
+    }
+
+    pub fn fill(&self, pixmap: &mut Pixmap, scene_config: &SceneConfig) {
+        pixmap.fill(self.base_color().into());
+        // in output pixels
+        let (frame_width, frame_height) = (
+            (scene_config.width * scene_config.scale_factor) as f32,
+            (scene_config.height * scene_config.scale_factor) as f32,
+        );
+        let (offset_x, offset_y) = (
+            scene_config.output_offset.0 as f32,
+            scene_config.output_offset.1 as f32,
+        );
+        match self {
+            Background::Color(_) | Background::Transparent => {}
+            Background::LinearGradient { start, end, angle } => {
+                let (sin, cos) = (*angle as f32).sin_cos();
+                let half_length =
+                    (frame_width * cos).abs() / 2.0 + (frame_height * sin).abs() / 2.0;
+                let (center_x, center_y) =
+                    (frame_width / 2.0 - offset_x, frame_height / 2.0 - offset_y);
+                // pixel y grows downwards
+                let (dx, dy) = (cos * half_length, -sin * half_length);
+                let Some(shader) = LinearGradient::new(
+                    Point::from_xy(center_x - dx, center_y - dy),
+                    Point::from_xy(center_x + dx, center_y + dy),
+                    vec![
+                        GradientStop::new(0.0, (*start).into()),
+                        GradientStop::new(1.0, (*end).into()),
+                    ],
+                    tiny_skia::SpreadMode::Pad,
+                    Transform::identity(),
+                ) else {
+                    return;
+                };
+                let paint = Paint {
+                    shader,
+                    colorspace: scene_config.color_space(),
+                    ..Default::default()
+                };
+                if let Some(rect) =
+                    Rect::from_xywh(0.0, 0.0, pixmap.width() as f32, pixmap.height() as f32)
+                {
+                    pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+                }
+            }
+            Background::Image(image) => {
+                pixmap.draw_pixmap(
+                    0,
+                    0,
+                    image.as_ref().as_ref(),
+                    &PixmapPaint {
+                        quality: tiny_skia::FilterQuality::Bilinear,
+                        ..Default::default()
+                    },
+                    Transform::from_row(
+                        frame_width / image.width() as f32,
+                        0.0,
+                        0.0,
+                        frame_height / image.height() as f32,
+                        -offset_x,
+                        -offset_y,
+                    ),
+                    None,
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_gradient_background() {
+    let background = Background::LinearGradient {
+        start: Color::new(0, 0, 0, 0xff),
+        end: Color::new(0xff, 0, 0, 0xff),
+        angle: 0.0,
+    };
+    let scene_config = SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        ..Default::default()
+    };
+    let mut pixmap = Pixmap::new(160, 90).unwrap();
+    background.fill(&mut pixmap, &scene_config);
+    let red = |pixmap: &Pixmap, x: u32| pixmap.pixel(x, 45).unwrap().red();
+    assert!(red(&pixmap, 2) < 10 && red(&pixmap, 157) > 245);
+    assert!(red(&pixmap, 80).abs_diff(0x80) < 4);
+    // a region of the output continues the gradient of the whole frame
+    let rect = tiny_skia::IntRect::from_xywh(80, 0, 40, 90).unwrap();
+    let mut region = Pixmap::new(40, 90).unwrap();
+    background.fill(&mut region, &scene_config.cropped(rect));
+    assert!(red(&region, 0).abs_diff(red(&pixmap, 80)) <= 1);
+}
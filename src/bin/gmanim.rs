@@ -0,0 +1,63 @@
+// renders the demo scenes below, e.g. `gmanim square -q l`.
+// a crate with its own scenes registers them the same way and calls gmanim::cli::run
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gmanim::{
+    animation::{
+        creation::DrawBorderThenFill, play, rate_functions, AnimationConfig, SimpleMovement,
+    },
+    cli::{run, SceneRegistry},
+    color::palette,
+    mobjects::{DrawConfig, Mobject, Rectangle},
+    video_backend::VideoBackend,
+    Context, GMPoint, Scene,
+};
+use nalgebra::Vector3;
+
+fn square(
+    scene: &Rc<RefCell<Scene>>,
+    ctx: &Rc<RefCell<Context>>,
+    video_backend: &mut VideoBackend,
+) {
+    let framerate = ctx.borrow().scene_config.framerate;
+    let square: Box<dyn Mobject> = Box::new(Rectangle {
+        p0: GMPoint::new(-1.0, -1.0, 0.0),
+        p1: GMPoint::new(1.0, -1.0, 0.0),
+        p2: GMPoint::new(1.0, 1.0, 0.0),
+        p3: GMPoint::new(-1.0, 1.0, 0.0),
+        draw_config: DrawConfig {
+            color: palette::BLUE,
+            stoke_width: 0.05,
+            ..Default::default()
+        },
+    });
+    let square = Rc::new(RefCell::new(square));
+    scene.borrow_mut().add_ref(square.clone());
+    let draw = DrawBorderThenFill::new(
+        scene.clone(),
+        ctx.clone(),
+        square.clone(),
+        AnimationConfig::from_run_time(2.0, framerate, rate_functions::smooth),
+    );
+    play(scene, ctx, video_backend, draw);
+    let movement = SimpleMovement {
+        displacement: Vector3::new(3.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: square,
+        animation_config: AnimationConfig::from_run_time(1.0, framerate, rate_functions::smooth),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    };
+    play(scene, ctx, video_backend, movement);
+    scene
+        .borrow_mut()
+        .wait(&mut ctx.borrow_mut(), video_backend, 1.0);
+}
+
+fn main() {
+    let mut registry = SceneRegistry::new();
+    registry.register("square", square);
+    run(&registry);
+}
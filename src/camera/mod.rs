@@ -1,17 +1,72 @@
 use crate::math_utils::constants::PI;
 use crate::GMFloat;
-use nalgebra::{Isometry3, Matrix4, Perspective3, Point3, Vector3, Vector4};
+use nalgebra::{Isometry3, Matrix4, Perspective3, Point3, Rotation3, Unit, Vector3, Vector4};
 use usvg::tiny_skia_path::Scalar;
 
-#[derive(Debug)]
+pub mod projection;
+pub mod zoomed;
+pub use projection::CustomProjection;
+pub use zoomed::ZoomedDisplay;
+
+// the 2d camera of a scene, SceneConfig::convert_point looks through it.
+// the visible frame is centered at center, rotated counterclockwise by rotation
+// and zoom times smaller than the scene size
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameCamera {
+    pub center: Point3<GMFloat>,
+    pub zoom: GMFloat,
+    pub rotation: GMFloat,
+}
+
+impl Default for FrameCamera {
+    fn default() -> Self {
+        Self::new(Point3::origin(), 1.0, 0.0)
+    }
+}
+
+impl FrameCamera {
+    pub fn new(center: Point3<GMFloat>, zoom: GMFloat, rotation: GMFloat) -> Self {
+        Self {
+            center,
+            zoom,
+            rotation,
+        }
+    }
+    pub fn pan(&mut self, offset: Vector3<GMFloat>) {
+        self.center += offset;
+    }
+    pub fn zoom_by(&mut self, factor: GMFloat) {
+        self.zoom *= factor;
+    }
+    // size of the visible part of a scene with the given size
+    pub fn frame_size(&self, scene_width: GMFloat, scene_height: GMFloat) -> (GMFloat, GMFloat) {
+        (scene_width / self.zoom, scene_height / self.zoom)
+    }
+    // scene coordinates relative to the frame, the frame center becomes the origin
+    pub fn to_frame(&self, x: GMFloat, y: GMFloat) -> (GMFloat, GMFloat) {
+        let (sin, cos) = (-self.rotation).sin_cos();
+        let (dx, dy) = (x - self.center.x, y - self.center.y);
+        (
+            (dx * cos - dy * sin) * self.zoom,
+            (dx * sin + dy * cos) * self.zoom,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Camera {
     pub position: Point3<GMFloat>,
     look_at: Vector3<GMFloat>, // attention that this vector is assumed to be a unit vector
     up_direction: Vector3<GMFloat>,
     projection: Projection,
+    // radian per second about the up direction through the origin
+    pub ambient_rotation_rate: GMFloat,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Projection {
     Perspective(PerspectiveSetting),
     Orthographic(OrthographicSetting),
@@ -23,7 +78,8 @@ impl Default for Projection {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerspectiveSetting {
     near: GMFloat,
     far: GMFloat,
@@ -43,6 +99,14 @@ impl Default for PerspectiveSetting {
 }
 
 impl PerspectiveSetting {
+    pub fn new(fovy: GMFloat, aspect: GMFloat, near: GMFloat, far: GMFloat) -> Self {
+        Self {
+            near,
+            far,
+            fovy,
+            aspect,
+        }
+    }
     pub fn get_perspective_project_matrix(&self) -> Matrix4<GMFloat> {
         Perspective3::new(self.aspect, self.fovy, self.near, self.far)
             .as_matrix()
@@ -50,7 +114,8 @@ impl PerspectiveSetting {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OrthographicSetting {
     left: GMFloat,
     right: GMFloat,
@@ -129,6 +194,7 @@ impl Camera {
             look_at: look_at.normalize(),
             up_direction: up_direction.normalize(),
             projection,
+            ambient_rotation_rate: 0.0,
         }
     }
     pub fn set_look_at(&mut self, look_at: Vector3<GMFloat>) {
@@ -137,6 +203,24 @@ impl Camera {
     pub fn set_up_direction(&mut self, up_direction: Vector3<GMFloat>) {
         self.up_direction = up_direction.normalize();
     }
+    // orbit around the origin, the rotation axis is the up direction
+    pub fn rotate_about_origin(&mut self, angle: GMFloat) {
+        let rotation = Rotation3::from_axis_angle(&Unit::new_normalize(self.up_direction), angle);
+        self.position = rotation * self.position;
+        self.look_at = rotation * self.look_at;
+    }
+    // keep orbiting while time advances, until stop_ambient_rotation is called
+    pub fn begin_ambient_rotation(&mut self, rate: GMFloat) {
+        self.ambient_rotation_rate = rate;
+    }
+    pub fn stop_ambient_rotation(&mut self) {
+        self.ambient_rotation_rate = 0.0;
+    }
+    pub fn advance_time(&mut self, dt: GMFloat) {
+        if self.ambient_rotation_rate != 0.0 {
+            self.rotate_about_origin(self.ambient_rotation_rate * dt);
+        }
+    }
     pub fn get_camera_transform_matrix(&self) -> Matrix4<GMFloat> {
         Isometry3::look_at_rh(
             &self.position,
@@ -151,6 +235,82 @@ impl Camera {
             Projection::Orthographic(o) => o.get_orthographic_project_matrix(),
         }
     }
+
+    // a perspective camera at distance on the z axis looking at the origin,
+    // the z = 0 plane fills a width x height frame like it does without a camera
+    pub fn perspective_for_frame(width: GMFloat, height: GMFloat, distance: GMFloat) -> Self {
+        let fovy = 2.0 * (height / 2.0 / distance).atan();
+        Self::new(
+            Point3::new(0.0, 0.0, distance),
+            -Vector3::z(),
+            Vector3::y(),
+            Projection::Perspective(PerspectiveSetting::new(
+                fovy,
+                width / height,
+                distance / 100.0,
+                distance * 100.0,
+            )),
+        )
+    }
+    // the same view as perspective_for_frame without foreshortening,
+    // parallel lines stay parallel and sizes don't change with depth
+    pub fn orthographic_for_frame(width: GMFloat, height: GMFloat, distance: GMFloat) -> Self {
+        Self::new(
+            Point3::new(0.0, 0.0, distance),
+            -Vector3::z(),
+            Vector3::y(),
+            Projection::Orthographic(OrthographicSetting::new(
+                -width / 2.0,
+                width / 2.0,
+                -height / 2.0,
+                height / 2.0,
+                0.0,
+                distance * 2.0,
+            )),
+        )
+    }
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+    pub fn look_at_point(&mut self, target: Point3<GMFloat>) {
+        self.set_look_at(target - self.position);
+    }
+    pub fn set_fovy(&mut self, fovy: GMFloat) {
+        if let Projection::Perspective(p) = &mut self.projection {
+            p.fovy = fovy;
+        }
+    }
+
+    // normalized device coordinates of a scene point, x and y are in -1..1 when visible.
+    // points behind a perspective camera are not clipped
+    pub fn project(&self, p: &Point3<GMFloat>) -> Point3<GMFloat> {
+        let m = self.get_projection_transform_matrix() * self.get_camera_transform_matrix();
+        let clip = m * p.to_homogeneous();
+        Point3::new(clip.x / clip.w, clip.y / clip.w, clip.z / clip.w)
+    }
+    // distance of p in front of the camera along the view direction
+    pub fn depth(&self, p: &Point3<GMFloat>) -> GMFloat {
+        (p - self.position).dot(&self.look_at)
+    }
+    // painter's algorithm order, the farthest item is drawn first so nearer ones cover it.
+    // the sort is stable, items at the same depth keep their order
+    pub fn sort_back_to_front<T>(&self, items: &mut [T], position: impl Fn(&T) -> Point3<GMFloat>) {
+        items.sort_by(|a, b| {
+            self.depth(&position(b))
+                .partial_cmp(&self.depth(&position(a)))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    }
+    // how much lengths at p shrink or grow in normalized device coordinates, per scene unit
+    pub fn scale_at(&self, p: &Point3<GMFloat>) -> GMFloat {
+        match &self.projection {
+            Projection::Perspective(s) => {
+                let depth = self.depth(p);
+                1.0 / ((s.fovy / 2.0).tan() * depth.max(GMFloat::EPSILON))
+            }
+            Projection::Orthographic(o) => 2.0 / (o.top - o.bottom),
+        }
+    }
 }
 #[test]
 pub fn test_camera_transform() {
@@ -176,6 +336,84 @@ pub fn test_camera_transform() {
     );
 }
 
+#[test]
+pub fn test_frame_camera() {
+    let mut camera = FrameCamera::default();
+    assert_eq!(camera.to_frame(1.0, 2.0), (1.0, 2.0));
+    camera.pan(Vector3::new(1.0, 1.0, 0.0));
+    camera.zoom_by(2.0);
+    assert_eq!(camera.to_frame(2.0, 1.0), (2.0, 0.0));
+    assert_eq!(camera.frame_size(16.0, 9.0), (8.0, 4.5));
+    camera.rotation = PI / 2.0;
+    let (x, y) = camera.to_frame(2.0, 1.0);
+    assert!(x.abs() < 1e-5 && (y + 2.0).abs() < 1e-5);
+}
+
+#[test]
+pub fn test_perspective_for_frame() {
+    let camera = Camera::perspective_for_frame(16.0, 9.0, 10.0);
+    // the corner of the frame on the z = 0 plane is the corner of the view
+    let p = camera.project(&Point3::new(8.0, 4.5, 0.0));
+    assert!((p.x - 1.0).abs() < 1e-4 && (p.y - 1.0).abs() < 1e-4);
+    // closer points are further from the center
+    let near = camera.project(&Point3::new(8.0, 4.5, 5.0));
+    assert!((near.x - 2.0).abs() < 1e-4);
+    assert!((camera.scale_at(&Point3::new(0.0, 0.0, 5.0)) - 2.0 / 4.5).abs() < 1e-4);
+}
+
+#[test]
+pub fn test_orthographic_for_frame() {
+    let mut camera = Camera::orthographic_for_frame(16.0, 9.0, 10.0);
+    camera.position = Point3::new(10.0, 0.0, 10.0);
+    camera.look_at_point(Point3::origin());
+    // two parallel edges at different depths stay parallel on screen
+    let edge = |start: Point3<GMFloat>| {
+        let end = start + Vector3::new(0.0, 1.0, 1.0);
+        (camera.project(&end) - camera.project(&start)).xy()
+    };
+    let near_edge = edge(Point3::new(3.0, 0.0, 3.0));
+    let far_edge = edge(Point3::new(-3.0, 1.0, -3.0));
+    assert!((near_edge - far_edge).norm() < 1e-4);
+    assert_eq!(
+        camera.scale_at(&Point3::new(3.0, 0.0, 3.0)),
+        camera.scale_at(&Point3::origin())
+    );
+}
+
+#[test]
+pub fn test_sort_back_to_front() {
+    let camera = Camera::perspective_for_frame(16.0, 9.0, 10.0);
+    let mut points = vec![
+        Point3::new(0.0, 0.0, 1.0),
+        Point3::new(5.0, 0.0, -2.0),
+        Point3::new(0.0, 0.0, 3.0),
+        Point3::new(-5.0, 0.0, -2.0),
+    ];
+    camera.sort_back_to_front(&mut points, |p| *p);
+    assert_eq!(points[0], Point3::new(5.0, 0.0, -2.0));
+    assert_eq!(points[1], Point3::new(-5.0, 0.0, -2.0));
+    assert_eq!(points[3], Point3::new(0.0, 0.0, 3.0));
+}
+
+#[test]
+pub fn test_ambient_rotation() {
+    let mut camera = Camera::new(
+        Point3::new(0.0, 0.0, 5.0),
+        -Vector3::z(),
+        Vector3::y(),
+        Projection::default(),
+    );
+    camera.begin_ambient_rotation(PI / 2.0);
+    for _ in 0..60 {
+        camera.advance_time(1.0 / 60.0);
+    }
+    assert!((camera.position - Point3::new(5.0, 0.0, 0.0)).norm() < 1e-3);
+    assert!((camera.look_at - -Vector3::x()).norm() < 1e-3);
+    camera.stop_ambient_rotation();
+    camera.advance_time(1.0);
+    assert!((camera.position - Point3::new(5.0, 0.0, 0.0)).norm() < 1e-3);
+}
+
 // we may need to write 3d render ourself, e.g. cannot use tinyskia, since we need to handle z-buffer.
 
 // pipe line
@@ -0,0 +1,128 @@
+use std::rc::Rc;
+
+use crate::math_utils::constants::PI;
+use crate::{GMFloat, GMPoint};
+
+// a user mapping of scene points applied before the 3d and frame camera,
+// see SceneConfig::projection. only the points of mobjects are mapped and the lines
+// between them stay straight, so curved results need mobjects with dense points
+#[derive(Clone)]
+pub struct CustomProjection(pub Rc<dyn Fn(&GMPoint) -> GMPoint>);
+
+impl std::fmt::Debug for CustomProjection {
+    // the closure itself can't be printed, the address tells projections apart
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CustomProjection({:p})",
+            Rc::as_ptr(&self.0) as *const ()
+        )
+    }
+}
+
+impl CustomProjection {
+    pub fn new(projection: impl Fn(&GMPoint) -> GMPoint + 'static) -> Self {
+        Self(Rc::new(projection))
+    }
+
+    pub fn apply(&self, p: &GMPoint) -> GMPoint {
+        (self.0)(p)
+    }
+
+    // equidistant fisheye around the origin, the distance r from it becomes
+    // focal * atan(r / focal). smaller focal lengths bend more
+    pub fn fisheye(focal: GMFloat) -> Self {
+        Self::new(move |p| {
+            let r = (p.x * p.x + p.y * p.y).sqrt();
+            if r <= GMFloat::EPSILON {
+                return *p;
+            }
+            let scale = focal * (r / focal).atan() / r;
+            GMPoint::new(p.x * scale, p.y * scale, p.z)
+        })
+    }
+
+    // points of the sphere of radius around the origin projected from its north pole
+    // onto the plane z = 0, points of the plane stay where they are
+    pub fn stereographic(radius: GMFloat) -> Self {
+        Self::new(move |p| {
+            let scale = radius / (radius - p.z).max(GMFloat::EPSILON);
+            GMPoint::new(p.x * scale, p.y * scale, 0.0)
+        })
+    }
+
+    // longitude and latitude of the direction of a point from the origin, z is north,
+    // as x and y scaled by radius. a globe becomes a flat map
+    pub fn equirectangular(radius: GMFloat) -> Self {
+        Self::new(move |p| {
+            let (longitude, latitude) = longitude_latitude(p);
+            GMPoint::new(radius * longitude, radius * latitude, 0.0)
+        })
+    }
+
+    // like equirectangular with the latitudes stretched to keep angles,
+    // cut at 85 degrees where the stretch goes to infinity
+    pub fn mercator(radius: GMFloat) -> Self {
+        Self::new(move |p| {
+            let (longitude, latitude) = longitude_latitude(p);
+            let latitude = latitude.clamp(-85.0 / 180.0 * PI, 85.0 / 180.0 * PI);
+            GMPoint::new(
+                radius * longitude,
+                radius * (PI / 4.0 + latitude / 2.0).tan().ln(),
+                0.0,
+            )
+        })
+    }
+}
+
+// in radians, longitude from -pi to pi and latitude from -pi/2 to pi/2
+fn longitude_latitude(p: &GMPoint) -> (GMFloat, GMFloat) {
+    let r = p.coords.norm();
+    if r <= GMFloat::EPSILON {
+        return (0.0, 0.0);
+    }
+    (p.y.atan2(p.x), (p.z / r).clamp(-1.0, 1.0).asin())
+}
+
+#[test]
+fn test_projections() {
+    let close = |a: GMPoint, b: GMPoint| (a - b).norm() < 1e-4;
+    let fisheye = CustomProjection::fisheye(2.0);
+    assert!(close(
+        fisheye.apply(&GMPoint::new(0.01, 0.0, 0.0)),
+        GMPoint::new(0.01, 0.0, 0.0)
+    ));
+    let far = fisheye.apply(&GMPoint::new(100.0, 0.0, 1.0));
+    assert!(far.x < PI && far.z == 1.0);
+    // the south pole of the sphere lands on the origin and the equator stays
+    let stereographic = CustomProjection::stereographic(1.0);
+    assert!(close(
+        stereographic.apply(&GMPoint::new(0.0, 0.0, -1.0)),
+        GMPoint::origin()
+    ));
+    assert!(close(
+        stereographic.apply(&GMPoint::new(1.0, 0.0, 0.0)),
+        GMPoint::new(1.0, 0.0, 0.0)
+    ));
+    let map = CustomProjection::equirectangular(2.0);
+    assert!(close(
+        map.apply(&GMPoint::new(0.0, 3.0, 0.0)),
+        GMPoint::new(PI, 0.0, 0.0)
+    ));
+    assert!(close(
+        map.apply(&GMPoint::new(0.0, 0.0, 5.0)),
+        GMPoint::new(0.0, PI, 0.0)
+    ));
+    let mercator = CustomProjection::mercator(1.0);
+    let equator = mercator.apply(&GMPoint::new(1.0, 1.0, 0.0));
+    assert!(close(equator, GMPoint::new(PI / 4.0, 0.0, 0.0)));
+    // scene points go through the projection before the frame camera
+    let scene_config = crate::SceneConfig {
+        projection: Some(map),
+        ..Default::default()
+    };
+    let projected = scene_config.project(&GMPoint::new(0.0, 3.0, 0.0));
+    let (x, y) = scene_config.convert_point(PI, 0.0);
+    assert!((projected.x as GMFloat - x).abs() < 1e-2 && (projected.y as GMFloat - y).abs() < 1e-2);
+    assert!(scene_config.fixed_in_frame().projection.is_none());
+}
@@ -0,0 +1,162 @@
+use crate::mobjects::{Draw, DrawConfig, Rectangle};
+use crate::{Color, Context, GMFloat, GMPoint, SceneConfig};
+use nalgebra::Point3;
+
+// a magnified view of the region around target drawn into an inset of the frame,
+// like the zoomed camera of manim's ZoomedScene, see Scene::zoomed_displays
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoomedDisplay {
+    // the scene point in the middle of the region, seen through the cameras of the scene
+    pub target: GMPoint,
+    pub magnification: GMFloat,
+    // center and size of the inset in frame coordinates like a mobject fixed in the frame,
+    // the region is the size of the inset divided by magnification
+    pub center: (GMFloat, GMFloat),
+    pub width: GMFloat,
+    pub height: GMFloat,
+    // stroke around the region and the inset, None draws no borders
+    pub border: Option<DrawConfig>,
+}
+
+impl ZoomedDisplay {
+    pub fn new(
+        target: GMPoint,
+        magnification: GMFloat,
+        center: (GMFloat, GMFloat),
+        width: GMFloat,
+        height: GMFloat,
+    ) -> Self {
+        Self {
+            target,
+            magnification,
+            center,
+            width,
+            height,
+            border: Some(DrawConfig {
+                color: Color::new(0xff, 0xff, 0xff, 0xff),
+                stoke_width: 0.03,
+                ..Default::default()
+            }),
+        }
+    }
+
+    // pixels covered by the inset
+    pub fn display_rect(&self, scene_config: &SceneConfig) -> Option<tiny_skia::IntRect> {
+        let fixed = scene_config.fixed_in_frame();
+        let (x, y) = fixed.convert_point(
+            self.center.0 - self.width / 2.0,
+            self.center.1 + self.height / 2.0,
+        );
+        tiny_skia::IntRect::from_xywh(
+            x.round() as i32,
+            y.round() as i32,
+            (self.width * scene_config.scale_factor).round() as u32,
+            (self.height * scene_config.scale_factor).round() as u32,
+        )
+    }
+
+    // the config to draw the scene with into a pixmap of the display rect size:
+    // the whole frame magnified, cropped to the rect around the magnified target
+    pub fn zoomed_config(&self, scene_config: &SceneConfig) -> Option<SceneConfig> {
+        let display = self.display_rect(scene_config)?;
+        let target = scene_config.project(&self.target);
+        let mut magnified = scene_config.clone();
+        magnified.scale_factor *= self.magnification;
+        magnified.pixel_density *= self.magnification;
+        magnified.output_offset = (
+            (scene_config.output_offset.0 as GMFloat * self.magnification) as i32,
+            (scene_config.output_offset.1 as GMFloat * self.magnification) as i32,
+        );
+        magnified.supersampling = 1;
+        magnified.raster_tiles = 1;
+        // filtered with the main frame
+        magnified.post_filters = vec![];
+        let rect = tiny_skia::IntRect::from_xywh(
+            (target.x as GMFloat * self.magnification - display.width() as GMFloat / 2.0).round()
+                as i32,
+            (target.y as GMFloat * self.magnification - display.height() as GMFloat / 2.0).round()
+                as i32,
+            display.width(),
+            display.height(),
+        )?;
+        Some(magnified.cropped(rect))
+    }
+
+    // outline the region around the target and the inset
+    pub fn draw_borders(&self, ctx: &mut Context) {
+        let Some(border) = self.border else {
+            return;
+        };
+        let fixed = ctx.scene_config.fixed_in_frame();
+        // the target in frame coordinates
+        let target = ctx.scene_config.project(&self.target);
+        let (offset_x, offset_y) = ctx.scene_config.output_offset;
+        let region_center = (
+            (target.x as GMFloat + offset_x as GMFloat) / fixed.scale_factor - fixed.width / 2.0,
+            fixed.height / 2.0 - (target.y as GMFloat + offset_y as GMFloat) / fixed.scale_factor,
+        );
+        let rectangle = |(x, y): (GMFloat, GMFloat), width: GMFloat, height: GMFloat| {
+            let (w, h) = (width / 2.0, height / 2.0);
+            Rectangle {
+                p0: Point3::new(x - w, y - h, 0.0),
+                p1: Point3::new(x + w, y - h, 0.0),
+                p2: Point3::new(x + w, y + h, 0.0),
+                p3: Point3::new(x - w, y + h, 0.0),
+                draw_config: DrawConfig {
+                    fill: false,
+                    ..border
+                },
+            }
+        };
+        let region = rectangle(
+            region_center,
+            self.width / self.magnification,
+            self.height / self.magnification,
+        );
+        let display = rectangle(self.center, self.width, self.height);
+        let scene_config = std::mem::replace(&mut ctx.scene_config, fixed);
+        region.draw(ctx);
+        display.draw(ctx);
+        ctx.scene_config = scene_config;
+    }
+}
+
+#[test]
+fn test_zoomed_display() {
+    use crate::mobjects::Dot;
+    use crate::{ContextType, Scene};
+    let red = Color::new(0xff, 0, 0, 0xff);
+    let mut scene = Scene::new();
+    scene.add(Box::new(Dot::new(
+        Point3::new(2.0, 1.0, 0.0),
+        0.1,
+        red,
+        DrawConfig {
+            color: red,
+            ..Default::default()
+        },
+    )));
+    let mut display = ZoomedDisplay::new(Point3::new(2.0, 1.0, 0.0), 4.0, (-5.0, 2.5), 4.0, 2.5);
+    display.border = None;
+    scene.zoomed_displays.push(display);
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            ..Default::default()
+        },
+    );
+    let rect = scene.zoomed_displays[0].display_rect(&ctx.scene_config);
+    assert_eq!(rect, tiny_skia::IntRect::from_xywh(10, 8, 40, 25));
+    scene.draw(&mut ctx);
+    let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+        panic!("not a tiny-skia context");
+    };
+    let red_at = |x, y| pixmap.pixel(x, y).unwrap().red() > 0xc0;
+    // the dot of one pixel radius covers four pixels around the center of the inset
+    assert!(red_at(100, 35) && !red_at(103, 35));
+    assert!(red_at(30, 20) && red_at(33, 20) && !red_at(36, 20));
+}
@@ -0,0 +1,872 @@
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+pub mod batch;
+pub mod watch;
+
+use crate::{
+    background::Background,
+    debug_overlay::DebugOverlay,
+    log_utils::{self, Verbosity},
+    plugin, sound, subtitles,
+    video_backend::{
+        detect_best_encoder, open_in_player, stream_format, BgraRAWBackend, ColorOrder,
+        FfmpegPipeBackend, FfmpegPipeEncoder, PngSequenceBackend, ProResProfile, VideoBackend,
+        VideoBackendType, VideoConfig,
+    },
+    Context, ContextType, GMFloat, Scene, SceneConfig, SubFrameMode,
+};
+
+// adds mobjects to the scene and plays its animations into the video backend,
+// the scene, context and backend are set up by render from the command line flags
+pub type SceneFn = fn(&Rc<RefCell<Scene>>, &Rc<RefCell<Context>>, &mut VideoBackend);
+
+// the scenes a binary can render by name, see run
+#[derive(Default)]
+pub struct SceneRegistry {
+    pub scenes: Vec<(String, SceneFn)>,
+}
+
+impl SceneRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: &str, scene: SceneFn) -> &mut Self {
+        self.scenes.push((name.to_owned(), scene));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<SceneFn> {
+        self.scenes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, scene)| *scene)
+    }
+}
+
+// resolution and framerate presets like the -ql .. -qk flags of manim
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quality {
+    Low,
+    Medium,
+    High,
+    Production,
+}
+
+impl Quality {
+    pub fn resolution(&self) -> (u32, u32) {
+        match self {
+            Quality::Low => (854, 480),
+            Quality::Medium => (1280, 720),
+            Quality::High => (1920, 1080),
+            Quality::Production => (3840, 2160),
+        }
+    }
+
+    pub fn framerate(&self) -> u32 {
+        match self {
+            Quality::Low => 15,
+            Quality::Medium => 30,
+            Quality::High | Quality::Production => 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    // an ffmpeg child process, see FfmpegPipeBackend
+    FfmpegPipe(FfmpegPipeEncoder),
+    PngSequence,
+    Raw,
+    // a video sink registered by a plugin under this name, see crate::plugin
+    Plugin(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    pub scenes: Vec<String>,
+    // render every registered scene instead of scenes
+    pub all: bool,
+    // render the scenes in this many worker processes at a time, see batch
+    pub jobs: usize,
+    // defaults to the scene name with the extension of the backend,
+    // only for a single scene
+    pub output: Option<String>,
+    pub quality: Quality,
+    // override the resolution and framerate of quality
+    pub resolution: Option<(u32, u32)>,
+    pub framerate: Option<u32>,
+    pub backend: Backend,
+    // see Scene::render_from
+    pub render_from: GMFloat,
+    pub render_to: Option<GMFloat>,
+    // see SceneConfig::sub_frames and sub_frame_mode
+    pub sub_frames: u32,
+    pub sub_frame_mode: SubFrameMode,
+    // play the emitted sub-frames at the scene framerate instead of the raised one
+    pub slow_motion: bool,
+    pub transparent: bool,
+    // see SceneConfig::debug_overlay
+    pub debug: bool,
+    // add the srt file of the voiceover captions as a subtitle track of the video
+    pub embed_subtitles: bool,
+    // open the output with the default player of the system after rendering
+    pub preview: bool,
+    // files or directories of the scene definitions, see watch::watch
+    pub watch: Vec<std::path::PathBuf>,
+    // what run reports on stderr, see log_utils::setup_tracing
+    pub verbosity: Verbosity,
+    // show the scene in a window first, see interactive::WindowSink
+    pub interactive: bool,
+    // draw with the full skia library instead of tiny-skia, see Context::skia
+    pub skia: bool,
+}
+
+pub const USAGE: &str = "usage: gmanim <scene>... [options]
+  -a, --all               render every registered scene
+  -j, --jobs <n>          render n scenes at a time in worker processes, 0 for one per cpu
+  -o, --output <path>     output file, for png the pattern frames/frame_{}.png, the frame
+                          index goes before the extension without {}. an rtmp:// or
+                          srt:// url streams live, in real time and without sounds or subtitles
+  -q, --quality <l|m|h|k> 480p15, 720p30, 1080p60 (default) or 2160p60
+  -r, --resolution <WxH>  output size in pixels
+      --fps <n>           frames per second
+  -b, --backend <name>    ffmpeg (default), png, raw or a backend of a plugin
+  -e, --encoder <name>    libx264 (default), libx265, hevc_nvenc, hevc_vaapi, hevc_qsv,
+                          hevc_videotoolbox, prores_ks, libvpx-vp9, libaom-av1 or libsvtav1.
+                          auto picks the fastest hardware encoder that works, else libx264
+      --prores <profile>  prores_ks with proxy, lt, 422, hq or 4444 (the default of prores_ks)
+      --from <seconds>    first second of the video to render
+      --to <seconds>      last second of the video to render
+      --motion-blur <n>   average n sub-frames for every frame
+      --sub-frames <n>    render n sub-frames for every frame at n times the framerate
+      --slow-motion <n>   render n sub-frames for every frame, played n times slower
+  -t, --transparent       transparent background, libvpx-vp9 unless prores_ks 4444 or png is chosen
+  -d, --debug             draw the coordinate grid, bounding boxes, anchor points and safe areas
+  -p, --preview           open the output in the default player when done
+      --embed-subtitles   add the voiceover captions to the video, they are always written as .srt
+  -i, --interactive       show the scene in a window with playback keys first, w there renders
+                          to the output once the window is closed. needs the window feature
+      --skia              draw with the full skia library instead of tiny-skia. needs the skia feature
+  -v, --verbose           report animations, -vv every frame, -vvv encoding and the encoder queue
+      --quiet             only report errors
+  -w, --watch <path>      render in low quality again whenever a file in path changes,
+                          run through cargo from the crate of the scenes
+  -l, --list              list the registered scenes
+      --list-plugins      list the registered plugins with their mobjects, animations and backends
+a scene can also be the path of a .json, .ron or .yaml scene file with the scene_files feature";
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            scenes: vec![],
+            all: false,
+            jobs: 1,
+            output: None,
+            quality: Quality::High,
+            resolution: None,
+            framerate: None,
+            backend: Backend::FfmpegPipe(FfmpegPipeEncoder::Libx264),
+            render_from: 0.0,
+            render_to: None,
+            sub_frames: 1,
+            sub_frame_mode: SubFrameMode::Average,
+            slow_motion: false,
+            transparent: false,
+            debug: false,
+            embed_subtitles: false,
+            preview: false,
+            watch: vec![],
+            verbosity: Verbosity::Normal,
+            interactive: false,
+            skia: false,
+        }
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing value for {flag}"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {flag}: {value}"))
+}
+
+impl RenderOptions {
+    // the arguments without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut encoder = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-o" | "--output" => options.output = Some(parse_value(&arg, args.next())?),
+                "-q" | "--quality" => {
+                    options.quality = match parse_value::<String>(&arg, args.next())?.as_str() {
+                        "l" | "low" => Quality::Low,
+                        "m" | "medium" => Quality::Medium,
+                        "h" | "high" => Quality::High,
+                        "k" | "production" => Quality::Production,
+                        other => return Err(format!("unknown quality {other}")),
+                    }
+                }
+                "-r" | "--resolution" => {
+                    let value: String = parse_value(&arg, args.next())?;
+                    let (w, h) = value
+                        .split_once('x')
+                        .ok_or_else(|| format!("resolution {value} is not WxH"))?;
+                    options.resolution = Some((
+                        parse_value(&arg, Some(w.to_owned()))?,
+                        parse_value(&arg, Some(h.to_owned()))?,
+                    ));
+                }
+                "--fps" => options.framerate = Some(parse_value(&arg, args.next())?),
+                "-b" | "--backend" => {
+                    options.backend = match parse_value::<String>(&arg, args.next())?.as_str() {
+                        "ffmpeg" => Backend::FfmpegPipe(FfmpegPipeEncoder::Libx264),
+                        "png" => Backend::PngSequence,
+                        "raw" => Backend::Raw,
+                        other if plugin::video_sink_extension(other).is_some() => {
+                            Backend::Plugin(other.to_owned())
+                        }
+                        other => return Err(format!("unknown backend {other}")),
+                    }
+                }
+                "-e" | "--encoder" => {
+                    let name: String = parse_value(&arg, args.next())?;
+                    encoder = Some(match name.as_str() {
+                        "auto" => detect_best_encoder(),
+                        _ => FfmpegPipeEncoder::from_name(&name)
+                            .ok_or_else(|| format!("unknown encoder {name}"))?,
+                    });
+                }
+                "--prores" => {
+                    let name: String = parse_value(&arg, args.next())?;
+                    encoder = Some(FfmpegPipeEncoder::ProRes(
+                        ProResProfile::from_name(&name)
+                            .ok_or_else(|| format!("unknown prores profile {name}"))?,
+                    ));
+                }
+                "--from" => options.render_from = parse_value(&arg, args.next())?,
+                "--to" => options.render_to = Some(parse_value(&arg, args.next())?),
+                "--motion-blur" => {
+                    options.sub_frames = parse_value(&arg, args.next())?;
+                    options.sub_frame_mode = SubFrameMode::Average;
+                }
+                "--sub-frames" | "--slow-motion" => {
+                    options.sub_frames = parse_value(&arg, args.next())?;
+                    options.sub_frame_mode = SubFrameMode::Emit;
+                    options.slow_motion = arg == "--slow-motion";
+                }
+                "-t" | "--transparent" => options.transparent = true,
+                "-d" | "--debug" => options.debug = true,
+                "-p" | "--preview" => options.preview = true,
+                "--embed-subtitles" => options.embed_subtitles = true,
+                "-i" | "--interactive" => options.interactive = true,
+                "--skia" => options.skia = true,
+                "-v" | "--verbose" => options.verbosity = options.verbosity.more(),
+                "-vv" => options.verbosity = Verbosity::Debug,
+                "-vvv" => options.verbosity = Verbosity::Trace,
+                "--quiet" => options.verbosity = Verbosity::Quiet,
+                "-w" | "--watch" => options.watch.push(parse_value(&arg, args.next())?),
+                "-a" | "--all" => options.all = true,
+                "-j" | "--jobs" => {
+                    options.jobs = match parse_value(&arg, args.next())? {
+                        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+                        jobs => jobs,
+                    }
+                }
+                _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+                _ => options.scenes.push(arg),
+            }
+        }
+        if options.scenes.is_empty() && !options.all {
+            return Err("no scene given".to_owned());
+        }
+        if options.output.is_some() && (options.scenes.len() > 1 || options.all) {
+            return Err("--output needs a single scene".to_owned());
+        }
+        if options.embed_subtitles && !matches!(options.backend, Backend::FfmpegPipe(_)) {
+            return Err("--embed-subtitles needs the ffmpeg backend".to_owned());
+        }
+        if options.interactive && !cfg!(feature = "window") {
+            return Err("--interactive needs the window feature".to_owned());
+        }
+        if options.skia && !cfg!(feature = "skia") {
+            return Err("--skia needs the skia feature".to_owned());
+        }
+        if options.interactive && (options.scenes.len() > 1 || options.all) {
+            return Err("--interactive shows a single scene".to_owned());
+        }
+        if let Some(encoder) = encoder {
+            if !matches!(options.backend, Backend::FfmpegPipe(_)) {
+                return Err("--encoder is only used by the ffmpeg backend".to_owned());
+            }
+            options.backend = Backend::FfmpegPipe(encoder);
+        }
+        // transparent videos go to webm with alpha unless an encoder was chosen,
+        // vp9 only carries the alpha channel for transparent videos
+        if let (true, Backend::FfmpegPipe(chosen)) = (options.transparent, &mut options.backend) {
+            match chosen {
+                FfmpegPipeEncoder::Vp9 { alpha } => *alpha = true,
+                _ if chosen.has_alpha() => {}
+                _ if encoder.is_some() => {
+                    return Err(format!(
+                        "--transparent needs prores_ks 4444 or libvpx-vp9, {} drops the alpha channel",
+                        chosen.get_encoder_name()
+                    ));
+                }
+                _ => *chosen = FfmpegPipeEncoder::Vp9 { alpha: true },
+            }
+        }
+        Ok(options)
+    }
+
+    // the flags parse reads back into these options, without the scenes, --all and --jobs.
+    // the command line of the batch workers
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![];
+        let mut flag = |name: &str, value: Option<String>| {
+            args.push(name.to_owned());
+            args.extend(value);
+        };
+        if let Some(output) = &self.output {
+            flag("-o", Some(output.clone()));
+        }
+        let quality = match self.quality {
+            Quality::Low => "l",
+            Quality::Medium => "m",
+            Quality::High => "h",
+            Quality::Production => "k",
+        };
+        flag("-q", Some(quality.to_owned()));
+        if let Some((width, height)) = self.resolution {
+            flag("-r", Some(format!("{width}x{height}")));
+        }
+        if let Some(framerate) = self.framerate {
+            flag("--fps", Some(framerate.to_string()));
+        }
+        match &self.backend {
+            Backend::FfmpegPipe(FfmpegPipeEncoder::ProRes(profile)) => {
+                flag("--prores", Some(profile.name().to_owned()))
+            }
+            Backend::FfmpegPipe(encoder) => flag("-e", Some(encoder.get_encoder_name().to_owned())),
+            Backend::PngSequence => flag("-b", Some("png".to_owned())),
+            Backend::Raw => flag("-b", Some("raw".to_owned())),
+            Backend::Plugin(name) => flag("-b", Some(name.clone())),
+        }
+        if self.render_from != 0.0 {
+            flag("--from", Some(self.render_from.to_string()));
+        }
+        if let Some(render_to) = self.render_to {
+            flag("--to", Some(render_to.to_string()));
+        }
+        let sub_frames = Some(self.sub_frames.to_string());
+        match (self.sub_frame_mode, self.slow_motion) {
+            (SubFrameMode::Average, _) if self.sub_frames == 1 => {}
+            (SubFrameMode::Average, _) => flag("--motion-blur", sub_frames),
+            (SubFrameMode::Emit, false) => flag("--sub-frames", sub_frames),
+            (SubFrameMode::Emit, true) => flag("--slow-motion", sub_frames),
+        }
+        for (set, name) in [
+            (self.transparent, "-t"),
+            (self.debug, "-d"),
+            (self.preview, "-p"),
+            (self.embed_subtitles, "--embed-subtitles"),
+            (self.interactive, "-i"),
+            (self.skia, "--skia"),
+        ] {
+            if set {
+                flag(name, None);
+            }
+        }
+        match self.verbosity {
+            Verbosity::Quiet => flag("--quiet", None),
+            Verbosity::Normal => {}
+            Verbosity::Verbose => flag("-v", None),
+            Verbosity::Debug => flag("-vv", None),
+            Verbosity::Trace => flag("-vvv", None),
+        }
+        for path in &self.watch {
+            flag("-w", Some(path.to_string_lossy().into_owned()));
+        }
+        args
+    }
+
+    pub fn resolution(&self) -> (u32, u32) {
+        self.resolution.unwrap_or(self.quality.resolution())
+    }
+
+    pub fn framerate(&self) -> u32 {
+        self.framerate.unwrap_or(self.quality.framerate())
+    }
+
+    // the scenes to render, the whole registry with --all
+    pub fn scene_names(&self, registry: &SceneRegistry) -> Vec<String> {
+        if self.all {
+            return registry
+                .scenes
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect();
+        }
+        self.scenes.clone()
+    }
+
+    pub fn output_path(&self, scene: &str) -> String {
+        if let Some(output) = &self.output {
+            return output.clone();
+        }
+        // the output of a scene file is named after the file
+        let scene = match Path::new(scene).file_stem() {
+            Some(stem) if is_scene_file(scene) => stem.to_str().unwrap_or(scene),
+            _ => scene,
+        };
+        match &self.backend {
+            Backend::FfmpegPipe(FfmpegPipeEncoder::ProRes(_)) => format!("{scene}.mov"),
+            Backend::FfmpegPipe(encoder) if encoder.is_webm() => format!("{scene}.webm"),
+            Backend::FfmpegPipe(_) => format!("{scene}.mp4"),
+            Backend::PngSequence => format!("{scene}/frame_{{}}.png"),
+            Backend::Raw => format!("{scene}.raw"),
+            Backend::Plugin(name) => {
+                let extension = plugin::video_sink_extension(name).unwrap_or_default();
+                format!("{scene}.{extension}")
+            }
+        }
+    }
+
+    // a frame 9 scene units high like the presets of SceneConfig
+    pub fn scene_config(&self) -> SceneConfig {
+        let (width, height) = self.resolution();
+        let mut scene_config = SceneConfig::for_output(width, height, 9.0);
+        scene_config.framerate = self.framerate();
+        scene_config.sub_frames = self.sub_frames;
+        scene_config.sub_frame_mode = self.sub_frame_mode;
+        if self.transparent {
+            scene_config.background = Background::Transparent;
+        }
+        if self.debug {
+            scene_config.debug_overlay = Some(DebugOverlay::default());
+        }
+        scene_config
+    }
+
+    // the context the scenes are drawn into, tiny-skia unless --skia
+    pub fn context(&self) -> Result<Context, String> {
+        let scene_config = self.scene_config();
+        #[cfg(feature = "skia")]
+        if self.skia {
+            return Ok(Context::skia(scene_config));
+        }
+        let pixmap = tiny_skia::Pixmap::new(scene_config.output_width, scene_config.output_height)
+            .ok_or("invalid resolution")?;
+        Ok(Context::new(ContextType::TinySKIA(pixmap), scene_config))
+    }
+
+    pub fn video_config(&self, scene: &str) -> VideoConfig {
+        let (output_width, output_height) = self.resolution();
+        VideoConfig {
+            filename: self.output_path(scene),
+            framerate: if self.slow_motion {
+                self.framerate()
+            } else {
+                self.scene_config().video_framerate()
+            },
+            output_width,
+            output_height,
+            color_order: ColorOrder::Rgba,
+        }
+    }
+
+    pub fn video_backend(&self, scene: &str) -> VideoBackend {
+        let video_config = self.video_config(scene);
+        let backend_type =
+            match &self.backend {
+                Backend::FfmpegPipe(encoder) => VideoBackendType::FfmpegPipe(
+                    FfmpegPipeBackend::new(&video_config, *encoder, self.quality != Quality::Low),
+                ),
+                Backend::PngSequence => {
+                    if let Some(directory) = std::path::Path::new(&video_config.filename).parent() {
+                        let _ = std::fs::create_dir_all(directory);
+                    }
+                    VideoBackendType::PngSequence(PngSequenceBackend::new(&video_config))
+                }
+                Backend::Raw => VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+                // parse only accepts registered names
+                Backend::Plugin(name) => VideoBackendType::Plugin(
+                    plugin::build_video_sink(name, &video_config)
+                        .unwrap_or_else(|| panic!("no plugin backend named {name}")),
+                ),
+            };
+        VideoBackend { backend_type }
+    }
+}
+
+// a scene given by the path of a document of crate::scene_file instead of a registered name
+pub fn is_scene_file(scene: &str) -> bool {
+    cfg!(feature = "scene_files")
+        && matches!(
+            Path::new(scene).extension().and_then(|e| e.to_str()),
+            Some("json" | "ron" | "yaml" | "yml")
+        )
+}
+
+// render one scene with the settings of options, returns the output path
+pub fn render(
+    registry: &SceneRegistry,
+    options: &RenderOptions,
+    scene_name: &str,
+) -> Result<String, String> {
+    if let Some(scene_fn) = registry.get(scene_name) {
+        let play = |scene: &Rc<RefCell<Scene>>,
+                    ctx: &Rc<RefCell<Context>>,
+                    video_backend: &mut VideoBackend| {
+            scene_fn(scene, ctx, video_backend);
+            Ok(())
+        };
+        #[cfg(feature = "window")]
+        if options.interactive && !show_in_window(options, scene_name, play)? {
+            return Ok("a window".to_owned());
+        }
+        return render_with(options, scene_name, play);
+    }
+    #[cfg(feature = "scene_files")]
+    if is_scene_file(scene_name) {
+        let scene_file = crate::scene_file::SceneFile::load(Path::new(scene_name))?;
+        let play = |scene: &Rc<RefCell<Scene>>,
+                    ctx: &Rc<RefCell<Context>>,
+                    video_backend: &mut VideoBackend| {
+            scene_file.play(scene, ctx, video_backend)
+        };
+        #[cfg(feature = "window")]
+        if options.interactive && !show_in_window(options, scene_name, play)? {
+            return Ok("a window".to_owned());
+        }
+        return render_with(options, scene_name, play);
+    }
+    Err(format!("no scene named {scene_name}"))
+}
+
+// a fresh scene played into video_backend, which is closed after it
+fn play_into(
+    options: &RenderOptions,
+    scene_name: &str,
+    mut video_backend: VideoBackend,
+    scene_fn: impl FnOnce(
+        &Rc<RefCell<Scene>>,
+        &Rc<RefCell<Context>>,
+        &mut VideoBackend,
+    ) -> Result<(), String>,
+) -> Result<Rc<RefCell<Scene>>, String> {
+    let ctx = Rc::new(RefCell::new(options.context()?));
+    let scene = Rc::new(RefCell::new(Scene::new()));
+    scene.borrow_mut().render_from = options.render_from;
+    scene.borrow_mut().render_to = options.render_to;
+    let output = options.output_path(scene_name);
+    let _span = tracing::info_span!("scene", scene = scene_name).entered();
+    tracing::info!(output = %output, backend = video_backend.name(), "rendering");
+    let played = scene_fn(&scene, &ctx, &mut video_backend);
+    video_backend
+        .close()
+        .map_err(|e| format!("can't finish {output}: {e}"))?;
+    played?;
+    Ok(scene)
+}
+
+// play the scene in a window, true when w asked for the file afterwards
+#[cfg(feature = "window")]
+fn show_in_window(
+    options: &RenderOptions,
+    scene_name: &str,
+    scene_fn: impl FnOnce(
+        &Rc<RefCell<Scene>>,
+        &Rc<RefCell<Context>>,
+        &mut VideoBackend,
+    ) -> Result<(), String>,
+) -> Result<bool, String> {
+    let (sink, write_requested) =
+        crate::interactive::WindowSink::new(scene_name, &options.video_config(scene_name))?;
+    let video_backend = VideoBackend {
+        backend_type: VideoBackendType::Plugin(Box::new(sink)),
+    };
+    play_into(options, scene_name, video_backend, scene_fn)?;
+    Ok(write_requested.get())
+}
+
+pub(crate) fn render_with(
+    options: &RenderOptions,
+    scene_name: &str,
+    scene_fn: impl FnOnce(
+        &Rc<RefCell<Scene>>,
+        &Rc<RefCell<Context>>,
+        &mut VideoBackend,
+    ) -> Result<(), String>,
+) -> Result<String, String> {
+    let output = options.output_path(scene_name);
+    let scene = play_into(
+        options,
+        scene_name,
+        options.video_backend(scene_name),
+        scene_fn,
+    )?;
+    // a live stream has no file to add them to or to open
+    if stream_format(&output).is_some() {
+        return Ok(output);
+    }
+    add_soundtrack(&scene.borrow(), options, &output)?;
+    write_subtitles(&scene.borrow(), options, &output)?;
+    if options.preview {
+        // a png sequence is shown as its directory
+        let path = match &options.backend {
+            Backend::PngSequence => std::path::Path::new(&output)
+                .parent()
+                .map(|p| p.to_string_lossy().into_owned())
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| ".".to_owned()),
+            _ => output.clone(),
+        };
+        open_in_player(&path).map_err(|e| format!("can't open {path}: {e}"))?;
+    }
+    Ok(output)
+}
+
+// mux the sounds and tones of the scene into the video, frame outputs get an audio file
+// next to them
+fn add_soundtrack(scene: &Scene, options: &RenderOptions, output: &str) -> Result<(), String> {
+    let timeline = scene.audio_timeline(options.framerate());
+    if timeline.is_empty() {
+        return Ok(());
+    }
+    let track = timeline.mix(scene.render_from)?;
+    match &options.backend {
+        Backend::FfmpegPipe(_) => sound::mux(output, &track, sound::SAMPLE_RATE),
+        Backend::PngSequence => {
+            // frames/frame_{}.png gets frames.wav
+            let directory = Path::new(output).parent().unwrap_or(Path::new(""));
+            let path = match directory.file_name() {
+                Some(_) => directory.with_extension("wav"),
+                None => Path::new("soundtrack.wav").to_path_buf(),
+            };
+            sound::write_audio(&path.to_string_lossy(), &track, sound::SAMPLE_RATE)
+        }
+        Backend::Raw | Backend::Plugin(_) => {
+            let path = Path::new(output).with_extension("wav");
+            sound::write_audio(&path.to_string_lossy(), &track, sound::SAMPLE_RATE)
+        }
+    }
+    .map_err(|e| format!("can't add the soundtrack to {output}: {e}"))
+}
+
+// the captions of the voiceovers as an srt file named after the output
+fn write_subtitles(scene: &Scene, options: &RenderOptions, output: &str) -> Result<(), String> {
+    if scene.subtitles.is_empty() {
+        return Ok(());
+    }
+    let path = match &options.backend {
+        Backend::PngSequence => match Path::new(output).parent() {
+            Some(directory) if directory.file_name().is_some() => directory.with_extension("srt"),
+            _ => Path::new("subtitles.srt").to_path_buf(),
+        },
+        _ => Path::new(output).with_extension("srt"),
+    };
+    std::fs::write(
+        &path,
+        subtitles::to_srt(&scene.subtitles, scene.render_from),
+    )
+    .map_err(|e| format!("can't write {}: {e}", path.display()))?;
+    if options.embed_subtitles {
+        subtitles::mux(output, &path.to_string_lossy())
+            .map_err(|e| format!("can't add the subtitles to {output}: {e}"))?;
+    }
+    Ok(())
+}
+
+// entry point for a binary with its own scenes, parses the command line and renders.
+// exits with status 2 for bad arguments and 1 when rendering failed
+pub fn run(registry: &SceneRegistry) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-l" || a == "--list") {
+        for (name, _) in &registry.scenes {
+            println!("{name}");
+        }
+        return;
+    }
+    if args.iter().any(|a| a == "--list-plugins") {
+        println!("{}", plugin::with_plugins(|plugins| plugins.describe()));
+        return;
+    }
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{USAGE}");
+        return;
+    }
+    let options = match RenderOptions::parse(args.clone()) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = log_utils::setup_tracing(options.verbosity) {
+        eprintln!("{e}");
+    }
+    if !options.watch.is_empty() {
+        watch::watch(options.watch, &args);
+    }
+    let scenes = options.scene_names(registry);
+    if let Some(missing) = scenes
+        .iter()
+        .find(|name| registry.get(name).is_none() && !is_scene_file(name))
+    {
+        eprintln!("no scene named {missing}");
+        std::process::exit(2);
+    }
+    if options.jobs > 1 && scenes.len() > 1 {
+        let result = std::env::current_exe()
+            .map_err(|e| format!("can't find the current binary: {e}"))
+            .and_then(|exe| batch::render_in_workers(&exe, &options, &scenes));
+        if let Err(e) = result {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    // a failed scene doesn't stop the ones after it
+    let mut failed = false;
+    for scene in &scenes {
+        match render(registry, &options, scene) {
+            Ok(output) => println!("rendered {scene} to {output}"),
+            Err(e) => {
+                eprintln!("{scene}: {e}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+#[test]
+fn test_parse_options() {
+    let args = |s: &str| s.split_whitespace().map(str::to_owned).collect::<Vec<_>>();
+    let options =
+        RenderOptions::parse(args("intro -q l --fps 24 --to 2.5 -p -d --motion-blur 4")).unwrap();
+    assert!(options.preview);
+    assert!(options.scene_config().debug_overlay.is_some());
+    assert_eq!(options.scene_config().sub_frames, 4);
+    assert_eq!(options.video_config("intro").framerate, 24);
+    assert_eq!(options.scenes, ["intro"]);
+    assert_eq!(
+        (options.resolution(), options.framerate()),
+        ((854, 480), 24)
+    );
+    assert_eq!(options.render_to, Some(2.5));
+    assert_eq!(options.output_path("intro"), "intro.mp4");
+    let options = RenderOptions::parse(args("intro -r 640x360 -e libvpx-vp9 -t")).unwrap();
+    assert_eq!(options.resolution(), (640, 360));
+    assert_eq!(
+        options.backend,
+        Backend::FfmpegPipe(FfmpegPipeEncoder::Vp9 { alpha: true })
+    );
+    assert_eq!(options.output_path("intro"), "intro.webm");
+    assert!(matches!(
+        options.scene_config().background,
+        Background::Transparent
+    ));
+    let options = RenderOptions::parse(args("intro -e libvpx-vp9")).unwrap();
+    assert_eq!(
+        options.backend,
+        Backend::FfmpegPipe(FfmpegPipeEncoder::Vp9 { alpha: false })
+    );
+    let options = RenderOptions::parse(args("intro -e libsvtav1")).unwrap();
+    assert_eq!(options.output_path("intro"), "intro.webm");
+    let options = RenderOptions::parse(args("intro --prores lt")).unwrap();
+    assert_eq!(
+        options.backend,
+        Backend::FfmpegPipe(FfmpegPipeEncoder::ProRes(ProResProfile::Lt))
+    );
+    assert_eq!(options.output_path("intro"), "intro.mov");
+    assert!(RenderOptions::parse(args("intro --prores 4445")).is_err());
+    let options = RenderOptions::parse(args("intro -t")).unwrap();
+    assert_eq!(options.output_path("intro"), "intro.webm");
+    assert!(RenderOptions::parse(args("intro -t --prores hq")).is_err());
+    assert!(RenderOptions::parse(args("intro -t -b png")).is_ok());
+    let options = RenderOptions::parse(args("intro -q l --sub-frames 4")).unwrap();
+    assert_eq!(options.video_config("intro").framerate, 60);
+    let options = RenderOptions::parse(args("intro -q l --slow-motion 4")).unwrap();
+    assert_eq!(options.scene_config().sub_frame_mode, SubFrameMode::Emit);
+    assert_eq!(options.video_config("intro").framerate, 15);
+    assert!(RenderOptions::parse(args("intro -q x")).is_err());
+    assert!(RenderOptions::parse(args("-b png")).is_err());
+    assert!(RenderOptions::parse(args("intro -b png -e libx264")).is_err());
+    assert!(RenderOptions::parse(args("intro outro -o a.mp4")).is_err());
+    assert!(RenderOptions::parse(args("intro -b png --embed-subtitles")).is_err());
+    assert_eq!(
+        RenderOptions::parse(args("intro -i")).is_ok(),
+        cfg!(feature = "window")
+    );
+    assert!(RenderOptions::parse(args("intro outro --interactive")).is_err());
+    assert_eq!(
+        RenderOptions::parse(args("intro --skia")).is_ok(),
+        cfg!(feature = "skia")
+    );
+    assert_eq!(
+        RenderOptions::parse(args("intro -v -v")).unwrap().verbosity,
+        Verbosity::Debug
+    );
+}
+
+#[test]
+fn test_render_registered_scene() {
+    let mut registry = SceneRegistry::new();
+    registry.register("empty", |scene, ctx, video_backend| {
+        scene
+            .borrow_mut()
+            .wait(&mut ctx.borrow_mut(), video_backend, 1.0);
+    });
+    let output = std::env::temp_dir()
+        .join("gmanim_cli_empty.raw")
+        .to_string_lossy()
+        .into_owned();
+    let options = RenderOptions::parse(
+        [
+            "empty", "-b", "raw", "-r", "16x9", "--fps", "10", "-o", &output,
+        ]
+        .map(str::to_owned),
+    )
+    .unwrap();
+    let _ = std::fs::remove_file(&output);
+    assert_eq!(render(&registry, &options, "empty").unwrap(), output);
+    let written = std::fs::metadata(&output).unwrap().len();
+    assert_eq!(written, 10 * 16 * 9 * 4);
+    assert!(render(&registry, &options, "missing").is_err());
+}
+
+#[test]
+fn test_render_several_scenes() {
+    let mut registry = SceneRegistry::new();
+    registry
+        .register("first", |scene, ctx, video_backend| {
+            scene
+                .borrow_mut()
+                .wait(&mut ctx.borrow_mut(), video_backend, 1.0);
+        })
+        .register("second", |scene, ctx, video_backend| {
+            scene
+                .borrow_mut()
+                .wait(&mut ctx.borrow_mut(), video_backend, 2.0);
+        });
+    let args = "-a -j 2 -b raw -r 16x9 --fps 10".split_whitespace();
+    let options = RenderOptions::parse(args.map(str::to_owned)).unwrap();
+    assert_eq!(options.jobs, 2);
+    let scenes = options.scene_names(&registry);
+    assert_eq!(scenes, ["first", "second"]);
+    for (scene, seconds) in scenes.iter().zip([1, 2]) {
+        let output = render(&registry, &options, scene).unwrap();
+        assert_eq!(output, format!("{scene}.raw"));
+        let written = std::fs::metadata(&output).unwrap().len();
+        assert_eq!(written, seconds * 10 * 16 * 9 * 4);
+        let _ = std::fs::remove_file(output);
+    }
+}
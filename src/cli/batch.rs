@@ -0,0 +1,89 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::process::{Child, Command};
+
+use super::RenderOptions;
+
+// the command line of a worker rendering only scene with the other options
+fn worker_args(options: &RenderOptions, scene: &str) -> Vec<String> {
+    let mut worker = vec![scene.to_owned()];
+    worker.extend(options.to_args());
+    worker
+}
+
+// render every scene by running program with worker_args, at most jobs of them at a time.
+// the workers print to the same terminal, the error names the scenes that failed
+pub fn render_in_workers(
+    program: &Path,
+    options: &RenderOptions,
+    scenes: &[String],
+) -> Result<(), String> {
+    let jobs = options.jobs;
+    let succeeded = |mut child: Child| child.wait().is_ok_and(|status| status.success());
+    let mut running: VecDeque<(&String, Child)> = VecDeque::new();
+    let mut failed = vec![];
+    for scene in scenes {
+        if running.len() >= jobs.max(1) {
+            let (scene, child) = running.pop_front().unwrap();
+            if !succeeded(child) {
+                failed.push(scene.clone());
+            }
+        }
+        match Command::new(program)
+            .args(worker_args(options, scene))
+            .spawn()
+        {
+            Ok(child) => running.push_back((scene, child)),
+            Err(e) => {
+                eprintln!("can't start a worker for {scene}: {e}");
+                failed.push(scene.clone());
+            }
+        }
+    }
+    for (scene, child) in running {
+        if !succeeded(child) {
+            failed.push(scene.clone());
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("rendering failed for {}", failed.join(", ")))
+    }
+}
+
+#[test]
+fn test_render_in_workers() {
+    let args = |s: &str| s.split_whitespace().map(str::to_owned).collect::<Vec<_>>();
+    let options = RenderOptions::parse(args("intro outro -j 4 -q l -p -a")).unwrap();
+    assert_eq!(
+        worker_args(&options, "intro"),
+        args("intro -q l -e libx264 -p")
+    );
+    let scenes = ["intro", "outro", "credits"].map(str::to_owned);
+    assert!(render_in_workers(Path::new("true"), &options, &scenes).is_ok());
+    assert_eq!(
+        render_in_workers(Path::new("false"), &options, &scenes[..2]),
+        Err("rendering failed for intro, outro".to_owned())
+    );
+}
+
+#[test]
+fn test_worker_args() {
+    let args = |s: &str| s.split_whitespace().map(str::to_owned).collect::<Vec<_>>();
+    // every flag with a value, a worker parses them back into the same options
+    for flags in [
+        "-o out.mov -q m -r 640x360 --fps 24 --prores hq --from 1.5 --to 2.25 --motion-blur 4 -w src",
+        "--output a.webm --quality k --resolution 32x18 -e libvpx-vp9 -t --sub-frames 2 -vv",
+        "-b png --slow-motion 3 -d --quiet",
+        "--backend raw -p",
+        "--encoder libaom-av1 --embed-subtitles -v",
+    ] {
+        let options = RenderOptions::parse(args(&format!("intro {flags}"))).unwrap();
+        let worker = RenderOptions::parse(worker_args(&options, "intro")).unwrap();
+        assert_eq!(worker, options, "{flags}");
+    }
+    let options = RenderOptions::parse(args("a b -j 2 -q l")).unwrap();
+    let worker = RenderOptions::parse(worker_args(&options, "b")).unwrap();
+    assert_eq!((worker.scenes, worker.jobs), (vec!["b".to_owned()], 1));
+}
@@ -0,0 +1,153 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+// file change notifications of the os for paths, a directory is watched with everything
+// inside it
+pub struct Watcher {
+    pub paths: Vec<PathBuf>,
+    // how long the files have to stay unchanged before wait_for_change returns
+    pub settle: Duration,
+    events: Receiver<notify::Result<notify::Event>>,
+    // no more events once it is dropped
+    _watcher: RecommendedWatcher,
+}
+
+// hidden files and build output like .git and target, relative to the watched path
+fn is_ignored(paths: &[PathBuf], path: &Path) -> bool {
+    let relative = paths
+        .iter()
+        .find_map(|p| path.strip_prefix(p).ok())
+        .unwrap_or(path);
+    relative.components().any(|c| match c {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || name == "target"
+        }
+        _ => false,
+    })
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Result<Self, String> {
+        let (sender, events) = channel();
+        let mut watcher =
+            notify::recommended_watcher(sender).map_err(|e| format!("can't watch files: {e}"))?;
+        for path in &paths {
+            watcher
+                .watch(path, RecursiveMode::Recursive)
+                .map_err(|e| format!("can't watch {}: {e}", path.display()))?;
+        }
+        Ok(Self {
+            paths,
+            settle: Duration::from_millis(300),
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    // reads are reported too, cargo reading the sources must not trigger another render
+    fn is_change(&self, event: notify::Result<notify::Event>) -> bool {
+        match event {
+            Ok(event) => {
+                !matches!(event.kind, EventKind::Access(_))
+                    && event.paths.iter().any(|p| !is_ignored(&self.paths, p))
+            }
+            Err(_) => false,
+        }
+    }
+
+    // whether anything was modified since the last call or new
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            changed |= self.is_change(event);
+        }
+        changed
+    }
+
+    // block until a file changed, editors often write a file in several steps
+    // so this also waits until there were no changes for settle
+    pub fn wait_for_change(&mut self) {
+        while let Ok(event) = self.events.recv() {
+            if self.is_change(event) {
+                break;
+            }
+        }
+        while self.events.recv_timeout(self.settle).is_ok() {}
+    }
+}
+
+// the command line arguments without the watch flags and with preview quality
+fn preview_args(args: &[String]) -> Vec<String> {
+    let mut preview = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-w" | "--watch" | "-q" | "--quality" => {
+                args.next();
+            }
+            _ => preview.push(arg.clone()),
+        }
+    }
+    preview.extend(["-q".to_owned(), "l".to_owned()]);
+    preview
+}
+
+// rebuild and rerun the current binary with cargo every time one of paths changes.
+// scenes are compiled into the binary, so it has to run from the crate that defines them
+pub fn watch(paths: Vec<PathBuf>, args: &[String]) -> ! {
+    let binary = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "gmanim".to_owned());
+    let args = preview_args(args);
+    let mut watcher = Watcher::new(paths).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    loop {
+        let status = std::process::Command::new("cargo")
+            .args(["run", "--quiet", "--bin", &binary, "--"])
+            .args(&args)
+            .status();
+        match status {
+            Ok(status) if !status.success() => eprintln!("render failed with {status}"),
+            Err(e) => eprintln!("can't run cargo: {e}"),
+            _ => {}
+        }
+        println!("watching for changes");
+        watcher.wait_for_change();
+    }
+}
+
+#[test]
+fn test_watcher() {
+    let directory = std::env::temp_dir().join("gmanim_watch_test");
+    let _ = std::fs::remove_dir_all(&directory);
+    for sub in ["scenes", ".git", "target"] {
+        std::fs::create_dir_all(directory.join(sub)).unwrap();
+    }
+    let file = directory.join("scenes").join("intro.rs");
+    std::fs::write(&file, "fn intro() {}").unwrap();
+    let mut watcher = Watcher::new(vec![directory.clone()]).unwrap();
+    // the events arrive from another thread
+    let changed_soon = |watcher: &mut Watcher| {
+        (0..100).any(|_| {
+            std::thread::sleep(Duration::from_millis(20));
+            watcher.changed()
+        })
+    };
+    assert!(!watcher.changed());
+    std::fs::write(directory.join(".git").join("index"), "").unwrap();
+    std::fs::write(directory.join("target").join("gmanim"), "").unwrap();
+    std::fs::read_to_string(&file).unwrap();
+    assert!(!changed_soon(&mut watcher));
+    std::fs::write(&file, "fn intro() { wait(1.0); }").unwrap();
+    assert!(changed_soon(&mut watcher));
+    let args = ["intro", "-q", "h", "--watch", "src", "-o", "a.mp4"].map(str::to_owned);
+    assert_eq!(preview_args(&args), ["intro", "-o", "a.mp4", "-q", "l"]);
+    let _ = std::fs::remove_dir_all(&directory);
+}
@@ -0,0 +1,259 @@
+use crate::GMFloat;
+
+pub mod colormap;
+pub mod palette;
+
+pub use colormap::ColorMap;
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+    // linear interpolation of every channel, t = 0.0 gives self and t = 1.0 gives other
+    pub fn interpolate(&self, other: &Color, t: GMFloat) -> Color {
+        let lerp = |a: u8, b: u8| (a as GMFloat + (b as GMFloat - a as GMFloat) * t).round() as u8;
+        Color {
+            r: lerp(self.r, other.r),
+            g: lerp(self.g, other.g),
+            b: lerp(self.b, other.b),
+            a: lerp(self.a, other.a),
+        }
+    }
+
+    // interpolation in oklab, the hue and lightness change evenly to the eye
+    // and the middle of two saturated colors doesn't turn gray
+    pub fn interpolate_oklab(&self, other: &Color, t: GMFloat) -> Color {
+        let (l0, a0, b0) = self.to_oklab();
+        let (l1, a1, b1) = other.to_oklab();
+        let lerp = |a: GMFloat, b: GMFloat| a + (b - a) * t;
+        let alpha = (self.a as GMFloat + (other.a as GMFloat - self.a as GMFloat) * t).round();
+        Color::from_oklab(lerp(l0, l1), lerp(a0, a1), lerp(b0, b1)).with_alpha(alpha as u8)
+    }
+
+    pub fn with_alpha(&self, a: u8) -> Color {
+        Color { a, ..*self }
+    }
+
+    // "#rgb", "#rrggbb" or "#rrggbbaa", the # is optional
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        match hex.len() {
+            3 => {
+                let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+                Some(Color::new(digit(0)?, digit(1)?, digit(2)?, 0xff))
+            }
+            6 => Some(Color::new(byte(0)?, byte(2)?, byte(4)?, 0xff)),
+            8 => Some(Color::new(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+            _ => None,
+        }
+    }
+
+    // "#rrggbb", with the alpha appended when it is not opaque
+    pub fn to_hex(&self) -> String {
+        if self.a == 0xff {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    fn from_unit_rgb(r: GMFloat, g: GMFloat, b: GMFloat) -> Color {
+        let to_u8 = |c: GMFloat| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Color::new(to_u8(r), to_u8(g), to_u8(b), 0xff)
+    }
+
+    fn unit_rgb(&self) -> (GMFloat, GMFloat, GMFloat) {
+        (
+            self.r as GMFloat / 255.0,
+            self.g as GMFloat / 255.0,
+            self.b as GMFloat / 255.0,
+        )
+    }
+
+    // hue in degrees, saturation and value in 0..1
+    pub fn from_hsv(h: GMFloat, s: GMFloat, v: GMFloat) -> Color {
+        let c = v * s;
+        let (r, g, b) = hue_to_rgb(h, c);
+        let m = v - c;
+        Color::from_unit_rgb(r + m, g + m, b + m)
+    }
+
+    pub fn to_hsv(&self) -> (GMFloat, GMFloat, GMFloat) {
+        let (h, max, min) = self.hue();
+        let s = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (h, s, max)
+    }
+
+    // hue in degrees, saturation and lightness in 0..1
+    pub fn from_hsl(h: GMFloat, s: GMFloat, l: GMFloat) -> Color {
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue_to_rgb(h, c);
+        let m = l - c / 2.0;
+        Color::from_unit_rgb(r + m, g + m, b + m)
+    }
+
+    pub fn to_hsl(&self) -> (GMFloat, GMFloat, GMFloat) {
+        let (h, max, min) = self.hue();
+        let l = (max + min) / 2.0;
+        let s = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        (h, s, l)
+    }
+
+    // (hue in degrees, largest channel, smallest channel)
+    fn hue(&self) -> (GMFloat, GMFloat, GMFloat) {
+        let (r, g, b) = self.unit_rgb();
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let delta = max - min;
+        let h = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (h, max, min)
+    }
+
+    // https://bottosson.github.io/posts/oklab/, l in 0..1
+    #[allow(clippy::excessive_precision)]
+    pub fn from_oklab(l: GMFloat, a: GMFloat, b: GMFloat) -> Color {
+        let l_ = l + 0.3963377774 * a + 0.2158037573 * b;
+        let m_ = l - 0.1055613458 * a - 0.0638541728 * b;
+        let s_ = l - 0.0894841775 * a - 1.2914855480 * b;
+        let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+        let encode = |c: GMFloat| linear_to_srgb(c.clamp(0.0, 1.0) as f32) as GMFloat;
+        Color::from_unit_rgb(
+            encode(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            encode(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            encode(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+        )
+    }
+
+    #[allow(clippy::excessive_precision)]
+    pub fn to_oklab(&self) -> (GMFloat, GMFloat, GMFloat) {
+        let decode = |c: u8| srgb_to_linear(c as f32 / 255.0) as GMFloat;
+        let (r, g, b) = (decode(self.r), decode(self.g), decode(self.b));
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        (
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        )
+    }
+
+    // amount in 0..1 moves the oklab lightness towards white, keeping the hue
+    pub fn lighten(&self, amount: GMFloat) -> Color {
+        let (l, a, b) = self.to_oklab();
+        Color::from_oklab(l + (1.0 - l) * amount, a, b).with_alpha(self.a)
+    }
+
+    // amount in 0..1 moves the oklab lightness towards black, keeping the hue
+    pub fn darken(&self, amount: GMFloat) -> Color {
+        let (l, a, b) = self.to_oklab();
+        Color::from_oklab(l * (1.0 - amount), a, b).with_alpha(self.a)
+    }
+}
+
+// rgb of a hue with chroma c, before adding the lightness offset
+fn hue_to_rgb(h: GMFloat, c: GMFloat) -> (GMFloat, GMFloat, GMFloat) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    }
+}
+
+// the sRGB transfer function, channels in 0..1
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl From<Color> for tiny_skia::Color {
+    fn from(value: Color) -> Self {
+        Self::from_rgba8(value.r, value.g, value.b, value.a)
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self {
+        Self {
+            r: 0x33,
+            g: 0xcc,
+            b: 0xff,
+            a: 0xff,
+        }
+    }
+}
+
+#[test]
+fn test_color_conversions() {
+    let blue = Color::from_hex("#1f77b4").unwrap();
+    assert_eq!((blue.r, blue.g, blue.b, blue.a), (0x1f, 0x77, 0xb4, 0xff));
+    assert_eq!(blue.to_hex(), "#1f77b4");
+    assert_eq!(Color::from_hex("f0a").unwrap().to_hex(), "#ff00aa");
+    assert_eq!(Color::from_hex("#11223380").unwrap().a, 0x80);
+    assert!(Color::from_hex("#12345").is_none());
+    let same = |a: Color, b: Color| {
+        a.r.abs_diff(b.r) <= 1 && a.g.abs_diff(b.g) <= 1 && a.b.abs_diff(b.b) <= 1
+    };
+    let (h, s, l) = blue.to_hsl();
+    assert!(same(Color::from_hsl(h, s, l), blue));
+    let (h, s, v) = blue.to_hsv();
+    assert!(same(Color::from_hsv(h, s, v), blue));
+    let (l, a, b) = blue.to_oklab();
+    assert!(same(Color::from_oklab(l, a, b), blue));
+    assert!(same(
+        Color::from_hsl(120.0, 1.0, 0.5),
+        Color::new(0, 0xff, 0, 0xff)
+    ));
+    let white = Color::new(0xff, 0xff, 0xff, 0xff);
+    assert!((white.to_oklab().0 - 1.0).abs() < 1e-3);
+    assert!(blue.lighten(0.5).to_oklab().0 > l && blue.darken(0.5).to_oklab().0 < l);
+    // the oklab middle of black and white sits halfway in perceived lightness
+    let mid = palette::BLACK.interpolate_oklab(&palette::WHITE, 0.5);
+    assert!((mid.to_oklab().0 - 0.5).abs() < 0.01);
+    assert!(
+        palette::BLACK
+            .interpolate(&palette::WHITE, 0.5)
+            .to_oklab()
+            .0
+            > 0.55
+    );
+}
@@ -0,0 +1,108 @@
+use crate::GMFloat;
+
+use super::Color;
+
+// maps a value in 0..1 to a color by interpolating between stops in oklab
+#[derive(Clone, Debug)]
+pub struct ColorMap {
+    // (position in 0..1, color), sorted by position
+    pub stops: Vec<(GMFloat, Color)>,
+}
+
+impl ColorMap {
+    pub fn new(mut stops: Vec<(GMFloat, Color)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    // colors spread evenly over 0..1
+    pub fn from_colors(colors: &[Color]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as GMFloat;
+        Self::new(
+            colors
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i as GMFloat / last, *c))
+                .collect(),
+        )
+    }
+
+    fn from_hex(colors: &[&str]) -> Self {
+        let colors: Vec<_> = colors.iter().filter_map(|c| Color::from_hex(c)).collect();
+        Self::from_colors(&colors)
+    }
+
+    pub fn viridis() -> Self {
+        Self::from_hex(&[
+            "440154", "472c7a", "3b518b", "2c718e", "21908d", "27ad81", "5cc863", "aadc32",
+            "fde725",
+        ])
+    }
+
+    pub fn plasma() -> Self {
+        Self::from_hex(&[
+            "0d0887", "4c02a1", "7e03a8", "a82296", "cb4679", "e56b5d", "f89441", "fdc328",
+            "f0f921",
+        ])
+    }
+
+    // diverging, blue below 0.5 and red above
+    pub fn coolwarm() -> Self {
+        Self::from_hex(&[
+            "3b4cc0", "6282ea", "8db0fe", "b8d0f9", "dddddd", "f5c4ad", "f49a7b", "de604d",
+            "b40426",
+        ])
+    }
+
+    pub fn reversed(&self) -> Self {
+        Self::new(self.stops.iter().map(|(t, c)| (1.0 - t, *c)).collect())
+    }
+
+    // t is clamped to 0..1, an empty map gives the default color
+    pub fn sample(&self, t: GMFloat) -> Color {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let Some(first) = self.stops.first() else {
+            return Color::default();
+        };
+        if t <= first.0 {
+            return first.1;
+        }
+        for pair in self.stops.windows(2) {
+            let ((t0, c0), (t1, c1)) = (pair[0], pair[1]);
+            if t <= t1 {
+                let s = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+                return c0.interpolate_oklab(&c1, s);
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+
+    // sample value mapped from range.0..range.1 to 0..1
+    pub fn sample_range(&self, value: GMFloat, range: (GMFloat, GMFloat)) -> Color {
+        let span = range.1 - range.0;
+        let t = if span != 0.0 {
+            (value - range.0) / span
+        } else {
+            0.5
+        };
+        self.sample(t)
+    }
+}
+
+#[test]
+fn test_color_map() {
+    let map = ColorMap::viridis();
+    assert_eq!(map.sample(0.0).to_hex(), "#440154");
+    assert_eq!(map.sample(1.0).to_hex(), "#fde725");
+    assert_eq!(map.sample(2.0).to_hex(), "#fde725");
+    assert_eq!(map.sample(0.5).to_hex(), "#21908d");
+    assert_eq!(map.reversed().sample(0.0).to_hex(), "#fde725");
+    let custom = ColorMap::new(vec![
+        (1.0, Color::new(0xff, 0xff, 0xff, 0xff)),
+        (0.0, Color::new(0, 0, 0, 0xff)),
+    ]);
+    assert_eq!(custom.sample(0.0).to_hex(), "#000000");
+    assert_eq!(custom.sample_range(10.0, (0.0, 10.0)).to_hex(), "#ffffff");
+    let mid = custom.sample(0.5).to_oklab().0;
+    assert!((mid - 0.5).abs() < 0.01);
+}
@@ -0,0 +1,69 @@
+// the named colors of manim, the _C shade is the plain one (BLUE is BLUE_C)
+use super::Color;
+
+pub const WHITE: Color = Color::new(0xff, 0xff, 0xff, 0xff);
+pub const GRAY_A: Color = Color::new(0xdd, 0xdd, 0xdd, 0xff);
+pub const GRAY_B: Color = Color::new(0xbb, 0xbb, 0xbb, 0xff);
+pub const GRAY_C: Color = Color::new(0x88, 0x88, 0x88, 0xff);
+pub const GRAY_D: Color = Color::new(0x44, 0x44, 0x44, 0xff);
+pub const GRAY_E: Color = Color::new(0x22, 0x22, 0x22, 0xff);
+pub const BLACK: Color = Color::new(0x00, 0x00, 0x00, 0xff);
+pub const BLUE_A: Color = Color::new(0xc7, 0xe9, 0xf1, 0xff);
+pub const BLUE_B: Color = Color::new(0x9c, 0xdc, 0xeb, 0xff);
+pub const BLUE_C: Color = Color::new(0x58, 0xc4, 0xdd, 0xff);
+pub const BLUE_D: Color = Color::new(0x29, 0xab, 0xca, 0xff);
+pub const BLUE_E: Color = Color::new(0x23, 0x6b, 0x8e, 0xff);
+pub const TEAL_A: Color = Color::new(0xac, 0xea, 0xd7, 0xff);
+pub const TEAL_B: Color = Color::new(0x76, 0xdd, 0xc0, 0xff);
+pub const TEAL_C: Color = Color::new(0x5c, 0xd0, 0xb3, 0xff);
+pub const TEAL_D: Color = Color::new(0x55, 0xc1, 0xa7, 0xff);
+pub const TEAL_E: Color = Color::new(0x49, 0xa8, 0x8f, 0xff);
+pub const GREEN_A: Color = Color::new(0xc9, 0xe2, 0xae, 0xff);
+pub const GREEN_B: Color = Color::new(0xa6, 0xcf, 0x8c, 0xff);
+pub const GREEN_C: Color = Color::new(0x83, 0xc1, 0x67, 0xff);
+pub const GREEN_D: Color = Color::new(0x77, 0xb0, 0x5d, 0xff);
+pub const GREEN_E: Color = Color::new(0x69, 0x9c, 0x52, 0xff);
+pub const YELLOW_A: Color = Color::new(0xff, 0xf1, 0xb6, 0xff);
+pub const YELLOW_B: Color = Color::new(0xff, 0xea, 0x94, 0xff);
+pub const YELLOW_C: Color = Color::new(0xff, 0xff, 0x00, 0xff);
+pub const YELLOW_D: Color = Color::new(0xf4, 0xd3, 0x45, 0xff);
+pub const YELLOW_E: Color = Color::new(0xe8, 0xc1, 0x1c, 0xff);
+pub const GOLD_A: Color = Color::new(0xf7, 0xc7, 0x97, 0xff);
+pub const GOLD_B: Color = Color::new(0xf9, 0xb7, 0x75, 0xff);
+pub const GOLD_C: Color = Color::new(0xf0, 0xac, 0x5f, 0xff);
+pub const GOLD_D: Color = Color::new(0xe1, 0xa1, 0x58, 0xff);
+pub const GOLD_E: Color = Color::new(0xc7, 0x8d, 0x46, 0xff);
+pub const RED_A: Color = Color::new(0xf7, 0xa1, 0xa3, 0xff);
+pub const RED_B: Color = Color::new(0xff, 0x80, 0x80, 0xff);
+pub const RED_C: Color = Color::new(0xfc, 0x62, 0x55, 0xff);
+pub const RED_D: Color = Color::new(0xe6, 0x5a, 0x4c, 0xff);
+pub const RED_E: Color = Color::new(0xcf, 0x50, 0x44, 0xff);
+pub const MAROON_A: Color = Color::new(0xec, 0xab, 0xc1, 0xff);
+pub const MAROON_B: Color = Color::new(0xec, 0x92, 0xab, 0xff);
+pub const MAROON_C: Color = Color::new(0xc5, 0x5f, 0x73, 0xff);
+pub const MAROON_D: Color = Color::new(0xa2, 0x4d, 0x61, 0xff);
+pub const MAROON_E: Color = Color::new(0x94, 0x42, 0x4f, 0xff);
+pub const PURPLE_A: Color = Color::new(0xca, 0xa3, 0xe8, 0xff);
+pub const PURPLE_B: Color = Color::new(0xb1, 0x89, 0xc6, 0xff);
+pub const PURPLE_C: Color = Color::new(0x9a, 0x72, 0xac, 0xff);
+pub const PURPLE_D: Color = Color::new(0x71, 0x55, 0x82, 0xff);
+pub const PURPLE_E: Color = Color::new(0x64, 0x41, 0x72, 0xff);
+pub const PINK: Color = Color::new(0xd1, 0x47, 0xbd, 0xff);
+pub const LIGHT_PINK: Color = Color::new(0xdc, 0x75, 0xcd, 0xff);
+pub const ORANGE: Color = Color::new(0xff, 0x86, 0x2f, 0xff);
+pub const LIGHT_BROWN: Color = Color::new(0xcd, 0x85, 0x3f, 0xff);
+pub const DARK_BROWN: Color = Color::new(0x8b, 0x45, 0x13, 0xff);
+pub const GRAY_BROWN: Color = Color::new(0x73, 0x63, 0x57, 0xff);
+pub const PURE_RED: Color = Color::new(0xff, 0x00, 0x00, 0xff);
+pub const PURE_GREEN: Color = Color::new(0x00, 0xff, 0x00, 0xff);
+pub const PURE_BLUE: Color = Color::new(0x00, 0x00, 0xff, 0xff);
+
+pub const BLUE: Color = BLUE_C;
+pub const TEAL: Color = TEAL_C;
+pub const GREEN: Color = GREEN_C;
+pub const YELLOW: Color = YELLOW_C;
+pub const GOLD: Color = GOLD_C;
+pub const RED: Color = RED_C;
+pub const MAROON: Color = MAROON_C;
+pub const PURPLE: Color = PURPLE_C;
+pub const GRAY: Color = GRAY_C;
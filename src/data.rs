@@ -0,0 +1,248 @@
+use std::path::Path;
+
+use crate::mobjects::{Heatmap, PolyLine};
+use crate::{ColorMap, GMFloat, GMPoint};
+
+// a column is numbers when every non-empty field of it parses as one, empty fields are NaN
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Numbers(Vec<GMFloat>),
+    Text(Vec<String>),
+}
+
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Numbers(values) => values.len(),
+            Column::Text(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// csv or tsv data with a header row, for data driven scenes:
+//     let table = Table::load("data/population.csv")?;
+//     let line = table.polyline("year", "people", (min, max))?;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub names: Vec<String>,
+    pub columns: Vec<Column>,
+}
+
+// fields of one line, quotes may hold the delimiter and "" is a quote
+fn split_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+impl Table {
+    // the first line names the columns, short rows are filled with empty fields
+    pub fn parse(text: &str, delimiter: char) -> Result<Self, String> {
+        let mut lines = text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.trim().is_empty());
+        let names: Vec<String> = split_line(lines.next().ok_or("no header row")?, delimiter)
+            .into_iter()
+            .map(|name| name.trim().to_owned())
+            .collect();
+        let mut fields: Vec<Vec<String>> = vec![vec![]; names.len()];
+        for (row, line) in lines.enumerate() {
+            let values = split_line(line, delimiter);
+            if values.len() > names.len() {
+                return Err(format!(
+                    "row {} has {} fields but there are {} columns",
+                    row + 1,
+                    values.len(),
+                    names.len()
+                ));
+            }
+            for (i, column) in fields.iter_mut().enumerate() {
+                column.push(values.get(i).map_or("", |v| v.trim()).to_owned());
+            }
+        }
+        let columns = fields
+            .into_iter()
+            .map(|values| {
+                let numbers: Option<Vec<GMFloat>> = values
+                    .iter()
+                    .map(|v| match v.as_str() {
+                        "" => Some(GMFloat::NAN),
+                        v => v.parse().ok(),
+                    })
+                    .collect();
+                match numbers {
+                    Some(numbers) if values.iter().any(|v| !v.is_empty()) => {
+                        Column::Numbers(numbers)
+                    }
+                    _ => Column::Text(values),
+                }
+            })
+            .collect();
+        Ok(Self { names, columns })
+    }
+
+    // tab separated for .tsv and .tab files, comma separated otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("can't read {}: {e}", path.display()))?;
+        let delimiter = match path.extension().and_then(|e| e.to_str()) {
+            Some("tsv" | "tab") => '\t',
+            _ => ',',
+        };
+        Self::parse(&text, delimiter).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn rows(&self) -> usize {
+        self.columns.first().map_or(0, Column::len)
+    }
+
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        let i = self.names.iter().position(|n| n == name)?;
+        self.columns.get(i)
+    }
+
+    pub fn numbers(&self, name: &str) -> Result<&[GMFloat], String> {
+        match self.column(name) {
+            Some(Column::Numbers(values)) => Ok(values),
+            Some(Column::Text(_)) => Err(format!("column {name} is not numeric")),
+            None => Err(format!("no column named {name}")),
+        }
+    }
+
+    pub fn text(&self, name: &str) -> Result<Vec<String>, String> {
+        match self.column(name) {
+            Some(Column::Text(values)) => Ok(values.clone()),
+            Some(Column::Numbers(values)) => Ok(values.iter().map(|v| v.to_string()).collect()),
+            None => Err(format!("no column named {name}")),
+        }
+    }
+
+    // (x, y) of the rows where both are given, in data units
+    pub fn points(&self, x: &str, y: &str) -> Result<Vec<(GMFloat, GMFloat)>, String> {
+        let (xs, ys) = (self.numbers(x)?, self.numbers(y)?);
+        Ok(xs
+            .iter()
+            .zip(ys)
+            .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+            .map(|(x, y)| (*x, *y))
+            .collect())
+    }
+
+    // the rows of the numeric columns, e.g. for a Heatmap
+    pub fn matrix(&self, names: &[&str]) -> Result<Vec<Vec<GMFloat>>, String> {
+        let columns = names
+            .iter()
+            .map(|name| self.numbers(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((0..self.rows())
+            .map(|row| columns.iter().map(|column| column[row]).collect())
+            .collect())
+    }
+
+    // the points of two columns stretched from the smallest to the largest values onto the
+    // scene rectangle from min to max, in row order
+    pub fn polyline(
+        &self,
+        x: &str,
+        y: &str,
+        (min, max): (GMPoint, GMPoint),
+    ) -> Result<PolyLine, String> {
+        let points = self.points(x, y)?;
+        if points.is_empty() {
+            return Err(format!("no rows with both {x} and {y}"));
+        }
+        let range = |values: &mut dyn Iterator<Item = GMFloat>| {
+            values.fold((GMFloat::INFINITY, GMFloat::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            })
+        };
+        let (x_range, y_range) = (
+            range(&mut points.iter().map(|p| p.0)),
+            range(&mut points.iter().map(|p| p.1)),
+        );
+        let scale = |v: GMFloat, (lo, hi): (GMFloat, GMFloat), to: (GMFloat, GMFloat)| {
+            // a single value sits in the middle
+            let t = if hi > lo { (v - lo) / (hi - lo) } else { 0.5 };
+            to.0 + t * (to.1 - to.0)
+        };
+        Ok(PolyLine {
+            points: points
+                .iter()
+                .map(|&(px, py)| {
+                    GMPoint::new(
+                        scale(px, x_range, (min.x, max.x)),
+                        scale(py, y_range, (min.y, max.y)),
+                        min.z,
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    // the numeric columns given by names as the columns of a heatmap, the first row at the top
+    pub fn heatmap(
+        &self,
+        names: &[&str],
+        color_map: ColorMap,
+        min: GMPoint,
+        max: GMPoint,
+    ) -> Result<Heatmap, String> {
+        Ok(Heatmap::new(self.matrix(names)?, color_map, min, max))
+    }
+}
+
+#[test]
+fn test_table() {
+    let table = Table::parse(
+        "year,name,value\n2020,\"a, b\",1.5\n2021,\"say \"\"hi\"\"\",\n2022,c,3\n",
+        ',',
+    )
+    .unwrap();
+    assert_eq!(table.rows(), 3);
+    assert_eq!(table.text("name").unwrap(), ["a, b", "say \"hi\"", "c"]);
+    assert_eq!(table.numbers("year").unwrap(), [2020.0, 2021.0, 2022.0]);
+    assert!(table.numbers("name").is_err());
+    // the empty value is skipped
+    assert_eq!(
+        table.points("year", "value").unwrap(),
+        [(2020.0, 1.5), (2022.0, 3.0)]
+    );
+    let line = table
+        .polyline(
+            "year",
+            "value",
+            (GMPoint::new(-1.0, 0.0, 0.0), GMPoint::new(1.0, 2.0, 0.0)),
+        )
+        .unwrap();
+    assert_eq!(
+        line.points,
+        [GMPoint::new(-1.0, 0.0, 0.0), GMPoint::new(1.0, 2.0, 0.0)]
+    );
+    let tsv = Table::parse("a\tb\n1\t2\n3\t4", '\t').unwrap();
+    assert_eq!(
+        tsv.matrix(&["b", "a"]).unwrap(),
+        [vec![2.0, 1.0], vec![4.0, 3.0]]
+    );
+    assert!(Table::parse("a,b\n1,2,3", ',').is_err());
+}
@@ -0,0 +1,210 @@
+use tiny_skia::{FillRule, PathBuilder};
+
+use crate::mobjects::{DrawConfig, StrokeUnits};
+use crate::{Color, Context, GMFloat, GMPoint, Scene, SceneConfig};
+
+// diagnostic drawing over every frame to check layouts, see SceneConfig::debug_overlay.
+// line widths and dot sizes are in output pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugOverlay {
+    // lines of the scene coordinate grid every grid_step units, brighter axes.
+    // 0 draws no grid
+    pub grid_step: GMFloat,
+    // the rectangle around the projected points of every mobject
+    pub bounding_boxes: bool,
+    pub anchor_points: bool,
+    // the action safe (90%) and title safe (80%) rectangles of the frame
+    pub safe_areas: bool,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self {
+            grid_step: 1.0,
+            bounding_boxes: true,
+            anchor_points: true,
+            safe_areas: true,
+        }
+    }
+}
+
+// grid lines are split into segments so they follow a custom projection
+const GRID_SEGMENTS: u32 = 16;
+// more lines than this on each side of the center draw no grid
+const MAX_GRID_LINES: GMFloat = 200.0;
+
+fn pixel_stroke(color: Color, width: GMFloat) -> DrawConfig {
+    DrawConfig {
+        color,
+        stoke_width: width,
+        stroke_units: StrokeUnits::Pixels,
+        ..Default::default()
+    }
+}
+
+impl DebugOverlay {
+    // called after the mobjects of a frame are drawn
+    pub fn draw(&self, scene: &Scene, ctx: &mut Context) {
+        if self.grid_step > 0.0 {
+            self.draw_grid(ctx);
+        }
+        if self.bounding_boxes || self.anchor_points {
+            let fixed_config = ctx.scene_config.fixed_in_frame();
+            for m in &scene.mobjects {
+                let scene_config = if scene.is_fixed_in_frame(m) {
+                    fixed_config.clone()
+                } else {
+                    ctx.scene_config.clone()
+                };
+                self.draw_mobject(ctx, &scene_config, &m.borrow().get_points());
+            }
+        }
+        if self.safe_areas {
+            self.draw_safe_areas(ctx);
+        }
+    }
+
+    fn draw_grid(&self, ctx: &mut Context) {
+        let scene_config = &ctx.scene_config;
+        // a square around the frame center that covers the frame at any camera rotation
+        let center = scene_config.camera.center;
+        let radius = (scene_config.width.hypot(scene_config.height)
+            / 2.0
+            / scene_config.camera.zoom
+            / self.grid_step)
+            .ceil();
+        if radius > MAX_GRID_LINES {
+            return;
+        }
+        let (first_x, first_y) = (
+            (center.x / self.grid_step).round() - radius,
+            (center.y / self.grid_step).round() - radius,
+        );
+        let line = |pb: &mut PathBuilder, from: GMPoint, to: GMPoint| {
+            for i in 0..=GRID_SEGMENTS {
+                let p =
+                    scene_config.project(&from.lerp(&to, i as GMFloat / GRID_SEGMENTS as GMFloat));
+                if i == 0 {
+                    pb.move_to(p.x, p.y);
+                } else {
+                    pb.line_to(p.x, p.y);
+                }
+            }
+        };
+        let mut grid = PathBuilder::new();
+        let mut axes = PathBuilder::new();
+        let (min_x, max_x) = (
+            first_x * self.grid_step,
+            (first_x + 2.0 * radius) * self.grid_step,
+        );
+        let (min_y, max_y) = (
+            first_y * self.grid_step,
+            (first_y + 2.0 * radius) * self.grid_step,
+        );
+        for i in 0..=(2.0 * radius) as u32 {
+            let x = (first_x + i as GMFloat) * self.grid_step;
+            let pb = if x == 0.0 { &mut axes } else { &mut grid };
+            line(pb, GMPoint::new(x, min_y, 0.0), GMPoint::new(x, max_y, 0.0));
+            let y = (first_y + i as GMFloat) * self.grid_step;
+            let pb = if y == 0.0 { &mut axes } else { &mut grid };
+            line(pb, GMPoint::new(min_x, y, 0.0), GMPoint::new(max_x, y, 0.0));
+        }
+        let grid_config = pixel_stroke(Color::new(0x80, 0x80, 0x80, 0x60), 1.0);
+        let axes_config = pixel_stroke(Color::new(0xc0, 0xc0, 0xc0, 0xc0), 1.5);
+        if let Some(path) = grid.finish() {
+            ctx.stroke_path(&path, &grid_config);
+        }
+        if let Some(path) = axes.finish() {
+            ctx.stroke_path(&path, &axes_config);
+        }
+    }
+
+    fn draw_mobject(&self, ctx: &mut Context, scene_config: &SceneConfig, points: &[GMPoint]) {
+        let points: Vec<_> = points.iter().map(|p| scene_config.project(p)).collect();
+        let Some(first) = points.first() else {
+            return;
+        };
+        if self.bounding_boxes {
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+            for p in &points {
+                (min_x, min_y) = (min_x.min(p.x), min_y.min(p.y));
+                (max_x, max_y) = (max_x.max(p.x), max_y.max(p.y));
+            }
+            let rect = tiny_skia::Rect::from_ltrb(
+                min_x,
+                min_y,
+                max_x.max(min_x + 1.0),
+                max_y.max(min_y + 1.0),
+            );
+            if let Some(rect) = rect {
+                let path = PathBuilder::from_rect(rect);
+                let config = pixel_stroke(Color::new(0xff, 0xd7, 0x00, 0xc0), 1.0);
+                ctx.stroke_path(&path, &config);
+            }
+        }
+        if self.anchor_points {
+            let mut pb = PathBuilder::new();
+            for p in &points {
+                pb.push_circle(p.x, p.y, 1.5 * scene_config.pixel_density as f32);
+            }
+            if let Some(path) = pb.finish() {
+                let paint = pixel_stroke(Color::new(0xff, 0x40, 0x40, 0xff), 0.0).get_paint();
+                ctx.fill_path(&path, &paint, FillRule::Winding);
+            }
+        }
+    }
+
+    fn draw_safe_areas(&self, ctx: &mut Context) {
+        let fixed = ctx.scene_config.fixed_in_frame();
+        let (width, height) = (fixed.width, fixed.height);
+        let config = DrawConfig {
+            dash: Some((6.0, 4.0)),
+            ..pixel_stroke(Color::new(0x00, 0xd0, 0xff, 0xa0), 1.0)
+        };
+        for fraction in [0.9, 0.8] {
+            let (x0, y0) = fixed.convert_point(-width / 2.0 * fraction, height / 2.0 * fraction);
+            let (x1, y1) = fixed.convert_point(width / 2.0 * fraction, -height / 2.0 * fraction);
+            if let Some(rect) =
+                tiny_skia::Rect::from_ltrb(x0 as f32, y0 as f32, x1 as f32, y1 as f32)
+            {
+                ctx.stroke_path(&PathBuilder::from_rect(rect), &config);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_debug_overlay() {
+    use crate::mobjects::Rectangle;
+    use crate::ContextType;
+    let mut scene = Scene::new();
+    scene.add(Box::new(Rectangle {
+        p0: GMPoint::new(1.0, 1.0, 0.0),
+        p1: GMPoint::new(3.0, 1.0, 0.0),
+        p2: GMPoint::new(3.0, 2.5, 0.0),
+        p3: GMPoint::new(1.0, 2.5, 0.0),
+        ..Default::default()
+    }));
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            debug_overlay: Some(DebugOverlay::default()),
+            ..Default::default()
+        },
+    );
+    scene.draw(&mut ctx);
+    let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+        panic!("not a tiny-skia context");
+    };
+    let pixel = |x, y| pixmap.pixel(x, y).unwrap();
+    // an anchor point in red, the y axis and a grid line between the cells
+    let anchor = pixel(90, 35);
+    assert!(anchor.red() > 0xc0 && anchor.green() < 0x80);
+    assert!(pixel(80, 20).red() > 0x20);
+    assert!(pixel(70, 20).red() > 0x08);
+    assert_eq!(pixel(75, 20).red(), 0);
+}
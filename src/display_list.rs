@@ -0,0 +1,304 @@
+use tiny_skia::{
+    BlendMode, ColorSpace, FillRule, Mask, Paint, Path, PathBuilder, PathSegment, Pixmap,
+    PixmapMut, PixmapPaint, Point, Stroke, Transform,
+};
+
+use crate::{mobjects::DrawConfig, GMFloat};
+
+// clip holds the clip paths of the context when the command was recorded, see Context::push_clip
+pub enum DrawCommand {
+    Fill {
+        path: Path,
+        paint: Paint<'static>,
+        fill_rule: FillRule,
+        clip: Vec<Path>,
+    },
+    Pixmap {
+        x: i32,
+        y: i32,
+        pixmap: Pixmap,
+        blend_mode: BlendMode,
+        clip: Vec<Path>,
+    },
+}
+
+// draw calls of a frame recorded by ContextType::DisplayList.
+// mobjects can't leave the thread they live on, a recorded frame can be rasterized anywhere.
+// strokes are recorded as filled outlines and curves as lines, tiny-skia subdivides curves
+// differently when they are cut by the border of a tile, lines give the same pixels in every tile
+#[derive(Default)]
+pub struct DisplayList {
+    pub commands: Vec<DrawCommand>,
+}
+
+impl DisplayList {
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule, clip: &[Path]) {
+        let recorded = Paint {
+            shader: match &paint.shader {
+                tiny_skia::Shader::SolidColor(color) => tiny_skia::Shader::SolidColor(*color),
+                // only solid colors are used by the mobjects
+                _ => return,
+            },
+            anti_alias: paint.anti_alias,
+            blend_mode: paint.blend_mode,
+            colorspace: paint.colorspace,
+            force_hq_pipeline: paint.force_hq_pipeline,
+        };
+        let Some(path) = flatten(path) else {
+            return;
+        };
+        self.commands.push(DrawCommand::Fill {
+            path,
+            paint: recorded,
+            fill_rule,
+            clip: clip.to_vec(),
+        });
+    }
+
+    pub fn stroke_path(
+        &mut self,
+        path: &Path,
+        draw_config: &DrawConfig,
+        scale_factor: GMFloat,
+        anti_alias: bool,
+        color_space: ColorSpace,
+        clip: &[Path],
+    ) {
+        let mut paint = draw_config.get_paint();
+        paint.anti_alias = anti_alias;
+        paint.colorspace = color_space;
+        let stroke = draw_config.get_stroke(scale_factor);
+        let dashed;
+        let path = match &stroke.dash {
+            Some(dash) => match path.dash(dash, 1.0) {
+                Some(path) => {
+                    dashed = path;
+                    &dashed
+                }
+                None => return,
+            },
+            None => path,
+        };
+        let Some(outline) = path.stroke(&stroke, 1.0).as_ref().and_then(flatten) else {
+            return;
+        };
+        self.commands.push(DrawCommand::Fill {
+            path: outline,
+            paint,
+            fill_rule: FillRule::Winding,
+            clip: clip.to_vec(),
+        });
+    }
+
+    pub fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: &Pixmap,
+        blend_mode: BlendMode,
+        clip: &[Path],
+    ) {
+        self.commands.push(DrawCommand::Pixmap {
+            x,
+            y,
+            pixmap: pixmap.clone(),
+            blend_mode,
+            clip: clip.to_vec(),
+        });
+    }
+
+    // draw every command onto target, transform moves the recorded output coordinates
+    pub fn replay(&self, target: &mut PixmapMut, transform: Transform) {
+        for command in &self.commands {
+            match command {
+                DrawCommand::Fill {
+                    path,
+                    paint,
+                    fill_rule,
+                    clip,
+                } => {
+                    let mask = clip_mask(clip, target, transform, paint.anti_alias);
+                    target.fill_path(path, paint, *fill_rule, transform, mask.as_ref())
+                }
+                DrawCommand::Pixmap {
+                    x,
+                    y,
+                    pixmap,
+                    blend_mode,
+                    clip,
+                } => {
+                    let mask = clip_mask(clip, target, transform, true);
+                    target.draw_pixmap(
+                        *x,
+                        *y,
+                        pixmap.as_ref(),
+                        &PixmapPaint {
+                            blend_mode: *blend_mode,
+                            ..Default::default()
+                        },
+                        transform,
+                        mask.as_ref(),
+                    )
+                }
+            }
+        }
+    }
+
+    // split target into tile_count horizontal tiles and replay the commands on each of them,
+    // in parallel with the parallel feature.
+    // every tile is drawn with a few rows more on both sides, so the rows it keeps are not
+    // next to where tiny-skia cuts the edges
+    pub fn rasterize_tiles(&self, target: &mut Pixmap, tile_count: u32) {
+        const MARGIN: u32 = 4;
+        let (width, height) = (target.width(), target.height());
+        let tile_height = height.div_ceil(tile_count.max(1));
+        let row_bytes = width as usize * 4;
+        let draw_tile = |(i, tile): (usize, &mut [u8])| {
+            let rows = (tile.len() / row_bytes) as u32;
+            let start = i as u32 * tile_height;
+            let top = MARGIN.min(start);
+            let bottom = MARGIN.min(height - start - rows);
+            let Some(mut pixmap) = Pixmap::new(width, top + rows + bottom) else {
+                return;
+            };
+            // the target is cleared to the background before
+            pixmap.data_mut()[top as usize * row_bytes..][..tile.len()].copy_from_slice(tile);
+            self.replay(
+                &mut pixmap.as_mut(),
+                Transform::from_translate(0.0, -((start - top) as f32)),
+            );
+            tile.copy_from_slice(&pixmap.data()[top as usize * row_bytes..][..tile.len()]);
+        };
+        let tile_bytes = (width * tile_height * 4) as usize;
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "parallel")] {
+                use rayon::prelude::*;
+                target
+                    .data_mut()
+                    .par_chunks_mut(tile_bytes)
+                    .enumerate()
+                    .for_each(draw_tile);
+            } else {
+                target
+                    .data_mut()
+                    .chunks_mut(tile_bytes)
+                    .enumerate()
+                    .for_each(draw_tile);
+            }
+        }
+    }
+}
+
+fn clip_mask(
+    clip: &[Path],
+    target: &PixmapMut,
+    transform: Transform,
+    anti_alias: bool,
+) -> Option<Mask> {
+    let (first, rest) = clip.split_first()?;
+    let mut mask = Mask::new(target.width(), target.height())?;
+    mask.fill_path(first, FillRule::Winding, anti_alias, transform);
+    for path in rest {
+        mask.intersect_path(path, FillRule::Winding, anti_alias, transform);
+    }
+    Some(mask)
+}
+
+// maximum distance in pixels between a curve and the lines replacing it
+const FLATTEN_TOLERANCE: f32 = 0.1;
+
+fn flatten(path: &Path) -> Option<Path> {
+    let mut pb = PathBuilder::new();
+    let (mut start, mut last) = (Point::zero(), Point::zero());
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                pb.move_to(p.x, p.y);
+                (start, last) = (p, p);
+            }
+            PathSegment::LineTo(p) => {
+                pb.line_to(p.x, p.y);
+                last = p;
+            }
+            PathSegment::QuadTo(p1, p) => {
+                let n = segment_count(0.25, second_difference(last, p1, p));
+                for i in 1..=n {
+                    let t = i as f32 / n as f32;
+                    let s = 1.0 - t;
+                    let (a, b, c) = (s * s, 2.0 * s * t, t * t);
+                    pb.line_to(
+                        a * last.x + b * p1.x + c * p.x,
+                        a * last.y + b * p1.y + c * p.y,
+                    );
+                }
+                last = p;
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                let dd = second_difference(last, p1, p2).max(second_difference(p1, p2, p));
+                let n = segment_count(0.75, dd);
+                for i in 1..=n {
+                    let t = i as f32 / n as f32;
+                    let s = 1.0 - t;
+                    let (a, b, c, d) = (s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t);
+                    pb.line_to(
+                        a * last.x + b * p1.x + c * p2.x + d * p.x,
+                        a * last.y + b * p1.y + c * p2.y + d * p.y,
+                    );
+                }
+                last = p;
+            }
+            PathSegment::Close => {
+                pb.close();
+                last = start;
+            }
+        }
+    }
+    pb.finish()
+}
+
+fn second_difference(p0: Point, p1: Point, p2: Point) -> f32 {
+    let (x, y) = (p0.x - 2.0 * p1.x + p2.x, p0.y - 2.0 * p1.y + p2.y);
+    (x * x + y * y).sqrt()
+}
+
+// wang's formula, factor is degree * (degree - 1) / 8
+fn segment_count(factor: f32, second_difference: f32) -> u32 {
+    ((factor * second_difference / FLATTEN_TOLERANCE)
+        .sqrt()
+        .ceil() as u32)
+        .clamp(1, 100)
+}
+
+#[test]
+fn test_rasterize_tiles() {
+    let mut list = DisplayList::default();
+    let path = tiny_skia::PathBuilder::from_circle(40.0, 30.0, 20.0).unwrap();
+    list.fill_path(
+        &path,
+        &DrawConfig::default().get_paint(),
+        FillRule::Winding,
+        &[],
+    );
+    list.stroke_path(
+        &path,
+        &DrawConfig::default(),
+        10.0,
+        true,
+        ColorSpace::Linear,
+        &[],
+    );
+    let mut single = Pixmap::new(80, 60).unwrap();
+    list.replay(&mut single.as_mut(), Transform::identity());
+    // 7 tiles don't divide the height, the last one is shorter
+    let mut tiled = Pixmap::new(80, 60).unwrap();
+    list.rasterize_tiles(&mut tiled, 7);
+    assert!(single
+        .data()
+        .iter()
+        .zip(tiled.data())
+        .all(|(a, b)| a.abs_diff(*b) <= 1));
+}
@@ -0,0 +1,116 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::video_backend::{BufferBackend, VideoBackend, VideoBackendType};
+use crate::{Context, ContextType, GMFloat, Scene, SceneConfig};
+
+// renders a scene for an application that presents the frames itself, like a gui or
+// a game engine. frames are output_width x output_height premultiplied rgba pixels,
+// see video_backend::demultiply for straight alpha
+pub struct FrameRenderer {
+    pub scene: Rc<RefCell<Scene>>,
+    pub ctx: Rc<RefCell<Context>>,
+}
+
+impl FrameRenderer {
+    // None for an empty output size
+    pub fn new(scene_config: SceneConfig) -> Option<Self> {
+        let pixmap = tiny_skia::Pixmap::new(scene_config.output_width, scene_config.output_height)?;
+        Some(Self {
+            scene: Rc::new(RefCell::new(Scene::new())),
+            ctx: Rc::new(RefCell::new(Context::new(
+                ContextType::TinySKIA(pixmap),
+                scene_config,
+            ))),
+        })
+    }
+
+    // bytes of a frame
+    pub fn frame_len(&self) -> usize {
+        let scene_config = &self.ctx.borrow().scene_config;
+        scene_config.output_width as usize * scene_config.output_height as usize * 4
+    }
+
+    // advance the scene by dt seconds and draw it into buffer of frame_len bytes,
+    // for scenes driven by the loop of the application through updaters and value trackers
+    pub fn render_into(&mut self, dt: GMFloat, buffer: &mut [u8]) {
+        let mut scene = self.scene.borrow_mut();
+        let mut ctx = self.ctx.borrow_mut();
+        scene.update(dt);
+        scene.draw_frame(&mut ctx);
+        let time = scene.hook_time();
+        scene.hooks.frame_rendered(time);
+        scene.frame_index += 1;
+        buffer.copy_from_slice(ctx.image_bytes());
+    }
+
+    // run a scene function like the ones of cli::SceneRegistry, every frame its animations
+    // and waits write is passed to on_frame as soon as it is drawn
+    pub fn play(
+        &mut self,
+        scene_fn: impl FnOnce(&Rc<RefCell<Scene>>, &Rc<RefCell<Context>>, &mut VideoBackend),
+        on_frame: impl FnMut(&[u8]) + 'static,
+    ) {
+        let mut video_backend = VideoBackend {
+            backend_type: VideoBackendType::Buffer(BufferBackend::new(on_frame)),
+        };
+        scene_fn(&self.scene, &self.ctx, &mut video_backend);
+    }
+
+    // the frames of scene_fn in order, all of them are rendered before the first is returned.
+    // animations are iterators of frames themselves for rendering one lazily
+    pub fn frames(
+        &mut self,
+        scene_fn: impl FnOnce(&Rc<RefCell<Scene>>, &Rc<RefCell<Context>>, &mut VideoBackend),
+    ) -> std::vec::IntoIter<Vec<u8>> {
+        let frames = Rc::new(RefCell::new(vec![]));
+        let sink = frames.clone();
+        self.play(scene_fn, move |frame| {
+            sink.borrow_mut().push(frame.to_vec())
+        });
+        frames.take().into_iter()
+    }
+}
+
+#[test]
+fn test_frame_renderer() {
+    use crate::mobjects::{Dot, Mobject};
+    use crate::Color;
+    let mut renderer = FrameRenderer::new(SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        framerate: 10,
+        ..Default::default()
+    })
+    .unwrap();
+    let red = Color::new(0xff, 0, 0, 0xff);
+    let dot: Box<dyn Mobject> = Box::new(Dot::new(
+        nalgebra::Point3::origin(),
+        1.0,
+        red,
+        crate::mobjects::DrawConfig {
+            color: red,
+            ..Default::default()
+        },
+    ));
+    let dot = Rc::new(RefCell::new(dot));
+    renderer.scene.borrow_mut().add_ref(dot.clone());
+    renderer.scene.borrow_mut().add_updater_fn(dot, |m, dt| {
+        m.move_this(nalgebra::Vector3::new(dt * 4.0, 0.0, 0.0))
+    });
+    let mut buffer = vec![0; renderer.frame_len()];
+    renderer.render_into(0.5, &mut buffer);
+    // the dot moved two units to the right
+    let red_at = |buffer: &[u8], x: usize| buffer[(45 * 160 + x) * 4] > 0xc0;
+    assert!(red_at(&buffer, 100) && !red_at(&buffer, 80));
+    let frames: Vec<_> = renderer
+        .frames(|scene, ctx, video_backend| {
+            scene
+                .borrow_mut()
+                .wait(&mut ctx.borrow_mut(), video_backend, 0.5);
+        })
+        .collect();
+    assert_eq!(frames.len(), 5);
+    assert!(red_at(&frames[4], 120));
+}
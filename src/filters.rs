@@ -0,0 +1,366 @@
+use std::sync::Arc;
+
+use tiny_skia::Pixmap;
+
+use crate::{GMFloat, SceneConfig};
+
+// effects on the whole rasterized frame, applied in order by apply_post_filters
+// before the frame reaches the video backend. lengths are in scene units
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PostFilter {
+    // darken towards the corners, from radius (a fraction of the half diagonal) outwards.
+    // strength 1 makes the corners black
+    Vignette {
+        strength: GMFloat,
+        radius: GMFloat,
+    },
+    Blur {
+        sigma: GMFloat,
+    },
+    // the pixels brighter than threshold (0..1) blurred and added on top
+    Bloom {
+        threshold: GMFloat,
+        sigma: GMFloat,
+        intensity: GMFloat,
+    },
+    // red and blue are moved apart, by offset at the corners and not at all in the center
+    ChromaticAberration {
+        offset: GMFloat,
+    },
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Lut(Arc<Lut>),
+}
+
+// gaussian blur with standard deviation sigma in pixels, approximated by three box blurs.
+// pixels outside of the pixmap count as transparent
+pub fn gaussian_blur(pixmap: &mut Pixmap, sigma: f32) {
+    if sigma < 0.5 {
+        return;
+    }
+    let (width, height) = (pixmap.width() as usize, pixmap.height() as usize);
+    let mut buffer = vec![0u8; pixmap.data().len()];
+    for radius in box_radii(sigma) {
+        box_blur(
+            pixmap.data_mut(),
+            &mut buffer,
+            width,
+            height,
+            4,
+            width * 4,
+            radius,
+        );
+        box_blur(
+            &buffer,
+            pixmap.data_mut(),
+            height,
+            width,
+            width * 4,
+            4,
+            radius,
+        );
+    }
+}
+
+// radii of three box blurs whose sequence is close to a gaussian of sigma
+fn box_radii(sigma: f32) -> [usize; 3] {
+    let n = 3.0;
+    let ideal = (12.0 * sigma * sigma / n + 1.0).sqrt();
+    let mut lower = ideal.floor() as i32;
+    if lower % 2 == 0 {
+        lower -= 1;
+    }
+    let l = lower as f32;
+    let lower_count = ((12.0 * sigma * sigma - n * l * l - 4.0 * n * l - 3.0 * n)
+        / (-4.0 * l - 4.0))
+        .round() as usize;
+    let size = |i: usize| if i < lower_count { lower } else { lower + 2 };
+    [0, 1, 2].map(|i| (size(i).max(1) as usize - 1) / 2)
+}
+
+// blur the lines of src into dst along one axis, step is the byte distance of neighbouring
+// pixels on a line and line_step the one of neighbouring lines
+fn box_blur(
+    src: &[u8],
+    dst: &mut [u8],
+    length: usize,
+    lines: usize,
+    step: usize,
+    line_step: usize,
+    radius: usize,
+) {
+    let size = (2 * radius + 1) as u32;
+    for line in 0..lines {
+        let base = line * line_step;
+        for channel in 0..4 {
+            let at = |i: usize| src[base + i * step + channel] as u32;
+            let mut sum: u32 = (0..radius.min(length)).map(at).sum();
+            for i in 0..length {
+                if i + radius < length {
+                    sum += at(i + radius);
+                }
+                dst[base + i * step + channel] = ((sum + size / 2) / size) as u8;
+                if i >= radius {
+                    sum -= at(i - radius);
+                }
+            }
+        }
+    }
+}
+
+// 3d color lookup table, table[r + g * size + b * size * size] is the color for the
+// input (r, g, b) / (size - 1)
+pub struct Lut {
+    pub size: usize,
+    pub table: Vec<[f32; 3]>,
+    // tells tables apart in the frame cache without comparing them
+    checksum: u64,
+}
+
+impl std::fmt::Debug for Lut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lut")
+            .field("size", &self.size)
+            .field("checksum", &self.checksum)
+            .finish()
+    }
+}
+
+impl Lut {
+    pub fn new(size: usize, table: Vec<[f32; 3]>) -> Option<Self> {
+        if size < 2 || table.len() != size * size * size {
+            return None;
+        }
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for c in &table {
+            c.map(f32::to_bits).hash(&mut hasher);
+        }
+        Some(Self {
+            size,
+            table,
+            checksum: hasher.finish(),
+        })
+    }
+
+    // the .cube format of grading tools, only LUT_3D_SIZE tables with the default domain
+    pub fn parse_cube(text: &str) -> Option<Self> {
+        let mut size = 0;
+        let mut table = vec![];
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("LUT_3D_SIZE") {
+                size = value.trim().parse().ok()?;
+                continue;
+            }
+            if line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            let values: Vec<f32> = line
+                .split_whitespace()
+                .map(|v| v.parse().ok())
+                .collect::<Option<_>>()?;
+            table.push(<[f32; 3]>::try_from(values).ok()?);
+        }
+        Self::new(size, table)
+    }
+
+    pub fn load_cube(file_path: &str) -> Option<Self> {
+        Self::parse_cube(&std::fs::read_to_string(file_path).ok()?)
+    }
+
+    // trilinear interpolation of the table, channels in 0..1
+    pub fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let max = (self.size - 1) as f32;
+        let [r, g, b] = rgb.map(|c| c.clamp(0.0, 1.0) * max);
+        let (r0, g0, b0) = (r.floor() as usize, g.floor() as usize, b.floor() as usize);
+        let (r1, g1, b1) = (
+            (r0 + 1).min(self.size - 1),
+            (g0 + 1).min(self.size - 1),
+            (b0 + 1).min(self.size - 1),
+        );
+        let (tr, tg, tb) = (r - r0 as f32, g - g0 as f32, b - b0 as f32);
+        let at = |r: usize, g: usize, b: usize| {
+            self.table[r + g * self.size + b * self.size * self.size]
+        };
+        let lerp = |a: [f32; 3], b: [f32; 3], t: f32| [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t);
+        let g_0 = lerp(
+            lerp(at(r0, g0, b0), at(r1, g0, b0), tr),
+            lerp(at(r0, g1, b0), at(r1, g1, b0), tr),
+            tg,
+        );
+        let g_1 = lerp(
+            lerp(at(r0, g0, b1), at(r1, g0, b1), tr),
+            lerp(at(r0, g1, b1), at(r1, g1, b1), tr),
+            tg,
+        );
+        lerp(g_0, g_1, tb)
+    }
+}
+
+// the post filters of the scene config, called once per frame on the finished pixmap
+pub fn apply_post_filters(pixmap: &mut Pixmap, scene_config: &SceneConfig) {
+    let scale = scene_config.pixel_scale() as f32;
+    for filter in &scene_config.post_filters {
+        match filter {
+            PostFilter::Vignette { strength, radius } => {
+                vignette(pixmap, *strength as f32, *radius as f32)
+            }
+            PostFilter::Blur { sigma } => gaussian_blur(pixmap, *sigma as f32 * scale),
+            PostFilter::Bloom {
+                threshold,
+                sigma,
+                intensity,
+            } => bloom(
+                pixmap,
+                *threshold as f32,
+                *sigma as f32 * scale,
+                *intensity as f32,
+            ),
+            PostFilter::ChromaticAberration { offset } => {
+                chromatic_aberration(pixmap, *offset as f32 * scale)
+            }
+            PostFilter::Lut(lut) => apply_lut(pixmap, lut),
+        }
+    }
+}
+
+// distance of every pixel from the center as a fraction of the half diagonal,
+// with the direction away from the center
+fn for_each_pixel(pixmap: &Pixmap, mut f: impl FnMut(usize, f32, (f32, f32))) {
+    let (width, height) = (pixmap.width() as usize, pixmap.height() as usize);
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let half_diagonal = (cx * cx + cy * cy).sqrt().max(1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (
+                (x as f32 + 0.5 - cx) / half_diagonal,
+                (y as f32 + 0.5 - cy) / half_diagonal,
+            );
+            f(y * width + x, (dx * dx + dy * dy).sqrt(), (dx, dy));
+        }
+    }
+}
+
+fn vignette(pixmap: &mut Pixmap, strength: f32, radius: f32) {
+    let mut factors = vec![1.0; (pixmap.width() * pixmap.height()) as usize];
+    for_each_pixel(pixmap, |i, d, _| {
+        let t = ((d - radius) / (1.0 - radius).max(f32::EPSILON)).clamp(0.0, 1.0);
+        factors[i] = 1.0 - strength.clamp(0.0, 1.0) * t * t * (3.0 - 2.0 * t);
+    });
+    for (p, factor) in pixmap.data_mut().chunks_exact_mut(4).zip(factors) {
+        for c in &mut p[..3] {
+            *c = (*c as f32 * factor).round() as u8;
+        }
+    }
+}
+
+fn bloom(pixmap: &mut Pixmap, threshold: f32, sigma: f32, intensity: f32) {
+    let mut bright = pixmap.clone();
+    for p in bright.data_mut().chunks_exact_mut(4) {
+        let luminance =
+            (0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32) / 255.0;
+        if luminance < threshold {
+            p.fill(0);
+        }
+    }
+    gaussian_blur(&mut bright, sigma);
+    for (p, b) in pixmap
+        .data_mut()
+        .chunks_exact_mut(4)
+        .zip(bright.data().chunks_exact(4))
+    {
+        let add = |a: u8, b: u8| (a as f32 + b as f32 * intensity).min(255.0) as u8;
+        p[3] = add(p[3], b[3]);
+        for i in 0..3 {
+            // premultiplied colors can't exceed the alpha
+            p[i] = add(p[i], b[i]).min(p[3]);
+        }
+    }
+}
+
+fn chromatic_aberration(pixmap: &mut Pixmap, offset: f32) {
+    let source = pixmap.clone();
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let src = source.data();
+    let mut shifted = vec![];
+    for_each_pixel(pixmap, |i, _, (dx, dy)| {
+        let (x, y) = ((i as i32) % width, (i as i32) / width);
+        let at = |sign: f32, channel: usize| {
+            let sx = (x + (sign * dx * offset).round() as i32).clamp(0, width - 1);
+            let sy = (y + (sign * dy * offset).round() as i32).clamp(0, height - 1);
+            src[(sy * width + sx) as usize * 4 + channel]
+        };
+        shifted.push((at(-1.0, 0), at(1.0, 2)));
+    });
+    for (p, (r, b)) in pixmap.data_mut().chunks_exact_mut(4).zip(shifted) {
+        p[0] = r.min(p[3]);
+        p[2] = b.min(p[3]);
+    }
+}
+
+fn apply_lut(pixmap: &mut Pixmap, lut: &Lut) {
+    for p in pixmap.pixels_mut() {
+        let color = p.demultiply();
+        let rgb = [color.red(), color.green(), color.blue()].map(|c| c as f32 / 255.0);
+        let [r, g, b] = lut
+            .sample(rgb)
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        *p = tiny_skia::ColorU8::from_rgba(r, g, b, color.alpha()).premultiply();
+    }
+}
+
+#[test]
+fn test_gaussian_blur() {
+    let mut flat = Pixmap::new(41, 41).unwrap();
+    flat.fill(tiny_skia::Color::WHITE);
+    gaussian_blur(&mut flat, 3.0);
+    // a flat area stays flat, only the border fades
+    assert_eq!(flat.pixel(20, 20).unwrap().alpha(), 0xff);
+    assert!(flat.pixel(0, 20).unwrap().alpha() < 0xc0);
+
+    let mut pixmap = Pixmap::new(41, 41).unwrap();
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color_rgba8(0xff, 0xff, 0xff, 0xff);
+    pixmap.fill_rect(
+        tiny_skia::Rect::from_xywh(15.0, 15.0, 11.0, 11.0).unwrap(),
+        &paint,
+        tiny_skia::Transform::identity(),
+        None,
+    );
+    gaussian_blur(&mut pixmap, 2.0);
+    let alpha = |x: u32| pixmap.pixel(x, 20).unwrap().alpha();
+    // symmetric and falling off away from the square
+    assert_eq!(alpha(12), alpha(28));
+    assert!(alpha(20) > alpha(15) && alpha(15) > alpha(12));
+    assert_eq!(alpha(2), 0);
+}
+
+#[test]
+fn test_post_filters() {
+    let mut scene_config = SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        ..Default::default()
+    };
+    let mut pixmap = Pixmap::new(160, 90).unwrap();
+    pixmap.fill(tiny_skia::Color::from_rgba8(0x80, 0x80, 0x80, 0xff));
+    scene_config.post_filters = vec![PostFilter::Vignette {
+        strength: 1.0,
+        radius: 0.5,
+    }];
+    apply_post_filters(&mut pixmap, &scene_config);
+    let red = |pixmap: &Pixmap, x: u32, y: u32| pixmap.pixel(x, y).unwrap().red();
+    assert_eq!(red(&pixmap, 80, 45), 0x80);
+    assert!(red(&pixmap, 0, 0) < 0x08);
+    // an inverting table applied to the vignetted frame
+    let invert =
+        Lut::parse_cube("LUT_3D_SIZE 2\n1 1 1\n0 1 1\n1 0 1\n0 0 1\n1 1 0\n0 1 0\n1 0 0\n0 0 0\n");
+    scene_config.post_filters = vec![PostFilter::Lut(Arc::new(invert.unwrap()))];
+    apply_post_filters(&mut pixmap, &scene_config);
+    assert_eq!(red(&pixmap, 80, 45), 0x7f);
+    assert!(red(&pixmap, 0, 0) > 0xf7);
+}
@@ -0,0 +1,205 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use log::warn;
+use rusttype::Font;
+
+use crate::mobjects::text::FontConfig;
+
+// families tried in order for glyphs the font of a text doesn't have,
+// the first installed one is also the font of FontConfig::Default
+pub const DEFAULT_FALLBACK: &[&str] = &[
+    "Noto Sans",
+    "DejaVu Sans",
+    "Arial",
+    "Helvetica",
+    "Noto Sans CJK SC",
+    "Source Han Sans SC",
+    "Microsoft YaHei",
+    "PingFang SC",
+    "Noto Sans Arabic",
+    "Noto Sans Hebrew",
+    "Noto Sans Devanagari",
+    "Noto Sans Symbols",
+    "Noto Sans Symbols 2",
+    "Noto Sans Math",
+    "Noto Color Emoji",
+    "Apple Color Emoji",
+    "Segoe UI Emoji",
+];
+
+// a face parsed by rusttype for its outlines, data is kept for shaping with rustybuzz
+pub struct LoadedFont {
+    pub data: Vec<u8>,
+    pub index: u32,
+    pub font: Font<'static>,
+}
+
+// the fonts text is laid out with: the system fonts, fonts registered by the application
+// and the fallback chain. glyphs are looked up per char, a char missing from the chain
+// is looked up in every known font before it is left out
+pub struct FontBook {
+    pub db: fontdb::Database,
+    pub fallback: Vec<String>,
+    loaded: HashMap<fontdb::ID, LoadedFont>,
+    files: HashMap<String, Vec<fontdb::ID>>,
+    last_resort: HashMap<char, Option<fontdb::ID>>,
+}
+
+impl Default for FontBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FontBook {
+    // without any fonts, see with_system_fonts
+    pub fn new() -> Self {
+        Self {
+            db: fontdb::Database::new(),
+            fallback: DEFAULT_FALLBACK.iter().map(|f| f.to_string()).collect(),
+            loaded: HashMap::new(),
+            files: HashMap::new(),
+            last_resort: HashMap::new(),
+        }
+    }
+
+    pub fn with_system_fonts() -> Self {
+        let mut book = Self::new();
+        book.db.load_system_fonts();
+        book
+    }
+
+    // a font bundled with the application, e.g. from include_bytes!.
+    // returns the faces of the font, a collection has several
+    pub fn register_font_data(&mut self, data: Vec<u8>) -> Vec<fontdb::ID> {
+        self.last_resort.clear();
+        self.db
+            .load_font_source(fontdb::Source::Binary(Arc::new(data)))
+            .to_vec()
+    }
+
+    // a file is only loaded once
+    pub fn register_font_file(&mut self, path: &str) -> Result<Vec<fontdb::ID>, String> {
+        if let Some(ids) = self.files.get(path) {
+            return Ok(ids.clone());
+        }
+        let data = std::fs::read(path).map_err(|e| format!("can't read {path}: {e}"))?;
+        let ids = self.register_font_data(data);
+        if ids.is_empty() {
+            return Err(format!("{path} is not a font"));
+        }
+        self.files.insert(path.to_owned(), ids.clone());
+        Ok(ids)
+    }
+
+    // the regular face of a family
+    pub fn family(&self, name: &str) -> Option<fontdb::ID> {
+        self.db.query(&fontdb::Query {
+            families: &[fontdb::Family::Name(name)],
+            ..Default::default()
+        })
+    }
+
+    // the font of a text, None when there is no such font or no font at all
+    pub fn primary(&mut self, font: &FontConfig) -> Option<fontdb::ID> {
+        match font {
+            FontConfig::Default => self
+                .fallback
+                .iter()
+                .find_map(|name| self.family(name))
+                .or_else(|| self.db.faces().next().map(|f| f.id)),
+            FontConfig::FontName(name) => self.family(name),
+            FontConfig::FontFile(path) => match self.register_font_file(path) {
+                Ok(ids) => ids.first().copied(),
+                Err(e) => {
+                    warn!("{e}");
+                    None
+                }
+            },
+        }
+    }
+
+    pub fn loaded(&mut self, id: fontdb::ID) -> Option<&LoadedFont> {
+        if !self.loaded.contains_key(&id) {
+            let loaded = self.db.with_face_data(id, |data, index| {
+                Some(LoadedFont {
+                    data: data.to_vec(),
+                    index,
+                    font: Font::try_from_vec_and_index(data.to_vec(), index)?,
+                })
+            })??;
+            self.loaded.insert(id, loaded);
+        }
+        self.loaded.get(&id)
+    }
+
+    pub fn font(&mut self, id: fontdb::ID) -> Option<&Font<'static>> {
+        self.loaded(id).map(|l| &l.font)
+    }
+
+    pub fn has_glyph(&mut self, id: fontdb::ID, c: char) -> bool {
+        self.font(id).is_some_and(|f| f.glyph(c).id().0 != 0)
+    }
+
+    // primary if it has the char, else the first font of the fallback chain with it
+    pub fn font_for_char(&mut self, primary: Option<fontdb::ID>, c: char) -> Option<fontdb::ID> {
+        if let Some(id) = primary.filter(|id| self.has_glyph(*id, c)) {
+            return Some(id);
+        }
+        let chain: Vec<_> = self
+            .fallback
+            .iter()
+            .filter_map(|name| self.family(name))
+            .collect();
+        if let Some(id) = chain.into_iter().find(|id| self.has_glyph(*id, c)) {
+            return Some(id);
+        }
+        if let Some(id) = self.last_resort.get(&c) {
+            return *id;
+        }
+        // the faces are only parsed to look for the glyph, not kept loaded
+        let id = self.db.faces().map(|f| f.id).find(|id| {
+            self.db
+                .with_face_data(*id, |data, index| {
+                    Font::try_from_bytes_and_index(data, index)
+                        .is_some_and(|f| f.glyph(c).id().0 != 0)
+                })
+                .unwrap_or(false)
+        });
+        self.last_resort.insert(c, id);
+        id
+    }
+}
+
+thread_local! {
+    static FONT_BOOK: RefCell<FontBook> = RefCell::new(FontBook::with_system_fonts());
+}
+
+// the font book Text uses, the system fonts are loaded on first use.
+// register bundled fonts or change the fallback chain here before creating texts
+pub fn with_font_book<T>(f: impl FnOnce(&mut FontBook) -> T) -> T {
+    FONT_BOOK.with(|book| f(&mut book.borrow_mut()))
+}
+
+#[test]
+fn test_fallback_chain() {
+    let sans = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+    let mono = "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf";
+    if !std::path::Path::new(sans).exists() || !std::path::Path::new(mono).exists() {
+        return;
+    }
+    let mut book = FontBook::new();
+    assert_eq!(book.primary(&FontConfig::Default), None);
+    let sans = book.register_font_file(sans).unwrap()[0];
+    let mono = book.primary(&FontConfig::FontFile(mono.to_owned()));
+    assert!(mono.is_some());
+    assert_eq!(book.primary(&FontConfig::Default), Some(sans));
+    assert_eq!(book.font_for_char(mono, 'a'), mono);
+    // in dejavu sans but not in its mono variant
+    assert_eq!(book.font_for_char(mono, 'Ǆ'), Some(sans));
+    assert_eq!(book.font_for_char(mono, '你'), None);
+    book.fallback.clear();
+    assert_eq!(book.font_for_char(mono, 'Ǆ'), Some(sans));
+}
@@ -0,0 +1,248 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::video_backend::{BufferBackend, VideoBackend, VideoBackendType};
+use crate::{Color, Context, ContextType, GMFloat, Scene, SceneConfig};
+
+// regression tests against reference images, for the crate and for crates with scenes:
+//     let frame = golden::render_frame(scene_config, 30, my_scene)?;
+//     golden::assert_golden("tests/golden/my_scene_30.png", &frame, &Default::default());
+// a missing reference is written by the first run, outside of ci. run with
+// GMANIM_UPDATE_GOLDEN=1 to replace the references after an intended change
+
+// how different a frame may be from its reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    // per pixel distance in oklab, alpha counts as a fourth axis. 0.02 is about
+    // the smallest difference that can be seen, anti-aliasing noise is below it
+    pub max_distance: GMFloat,
+    // share of the pixels that may be further apart than max_distance
+    pub max_mismatched: GMFloat,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            max_distance: 0.02,
+            max_mismatched: 0.001,
+        }
+    }
+}
+
+impl Tolerance {
+    pub fn exact() -> Self {
+        Self {
+            max_distance: 0.0,
+            max_mismatched: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub mismatched: usize,
+    pub pixels: usize,
+    pub max_distance: GMFloat,
+    // indices of the pixels further apart than the tolerance
+    mismatches: Vec<usize>,
+}
+
+impl Comparison {
+    pub fn passes(&self, tolerance: &Tolerance) -> bool {
+        self.mismatched as GMFloat <= tolerance.max_mismatched * self.pixels as GMFloat
+    }
+}
+
+// the video frame with the given index of a scene function like the ones of
+// cli::SceneRegistry. only that frame is drawn, the ones before are stepped through
+pub fn render_frame(
+    scene_config: SceneConfig,
+    frame: u64,
+    scene_fn: impl FnOnce(&Rc<RefCell<Scene>>, &Rc<RefCell<Context>>, &mut VideoBackend),
+) -> Result<tiny_skia::Pixmap, String> {
+    let (width, height) = (scene_config.output_width, scene_config.output_height);
+    let pixmap = tiny_skia::Pixmap::new(width, height).ok_or("invalid resolution")?;
+    let framerate = scene_config.framerate as GMFloat;
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(pixmap),
+        scene_config,
+    )));
+    let scene = Rc::new(RefCell::new(Scene::new()));
+    scene.borrow_mut().render_from = frame as GMFloat / framerate;
+    scene.borrow_mut().render_to = Some((frame + 1) as GMFloat / framerate);
+    let written = Rc::new(RefCell::new(None));
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::Buffer(BufferBackend::new({
+            let written = written.clone();
+            move |data| *written.borrow_mut() = Some(data.to_vec())
+        })),
+    };
+    scene_fn(&scene, &ctx, &mut video_backend);
+    let data = written
+        .take()
+        .ok_or_else(|| format!("the scene ends before frame {frame}"))?;
+    tiny_skia::IntSize::from_wh(width, height)
+        .and_then(|size| tiny_skia::Pixmap::from_vec(data, size))
+        .ok_or_else(|| "the frame has the wrong size".to_owned())
+}
+
+fn pixel_color(p: tiny_skia::PremultipliedColorU8) -> Color {
+    let p = p.demultiply();
+    Color::new(p.red(), p.green(), p.blue(), p.alpha())
+}
+
+// per pixel distance in oklab of two images of the same size
+pub fn compare(
+    actual: &tiny_skia::Pixmap,
+    expected: &tiny_skia::Pixmap,
+    tolerance: &Tolerance,
+) -> Result<Comparison, String> {
+    if (actual.width(), actual.height()) != (expected.width(), expected.height()) {
+        return Err(format!(
+            "the frame is {}x{} but the reference is {}x{}",
+            actual.width(),
+            actual.height(),
+            expected.width(),
+            expected.height()
+        ));
+    }
+    let mut comparison = Comparison {
+        mismatched: 0,
+        pixels: actual.pixels().len(),
+        max_distance: 0.0,
+        mismatches: vec![],
+    };
+    for (i, (a, e)) in actual.pixels().iter().zip(expected.pixels()).enumerate() {
+        if a == e {
+            continue;
+        }
+        let (a, e) = (pixel_color(*a), pixel_color(*e));
+        let (al, aa, ab) = a.to_oklab();
+        let (el, ea, eb) = e.to_oklab();
+        let alpha = (a.a as GMFloat - e.a as GMFloat) / 255.0;
+        let distance =
+            ((al - el).powi(2) + (aa - ea).powi(2) + (ab - eb).powi(2) + alpha.powi(2)).sqrt();
+        comparison.max_distance = comparison.max_distance.max(distance);
+        if distance > tolerance.max_distance {
+            comparison.mismatched += 1;
+            comparison.mismatches.push(i);
+        }
+    }
+    Ok(comparison)
+}
+
+// the reference faded out with the mismatched pixels in red
+fn diff_image(expected: &tiny_skia::Pixmap, comparison: &Comparison) -> tiny_skia::Pixmap {
+    let mut diff = expected.clone();
+    for p in diff.pixels_mut() {
+        let c = p.demultiply();
+        let gray = ((c.red() as u16 + c.green() as u16 + c.blue() as u16) / 3 / 4) as u8;
+        *p = tiny_skia::ColorU8::from_rgba(gray, gray, gray, 255).premultiply();
+    }
+    for &i in &comparison.mismatches {
+        diff.pixels_mut()[i] = tiny_skia::ColorU8::from_rgba(255, 0, 0, 255).premultiply();
+    }
+    diff
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{suffix}.png"))
+}
+
+fn update_requested() -> bool {
+    std::env::var_os("GMANIM_UPDATE_GOLDEN").is_some_and(|v| v != "0")
+}
+
+// compare frame with the png at reference. on a mismatch <name>.actual.png and
+// <name>.diff.png are written next to it for inspecting the failure
+pub fn check_golden(
+    reference: impl AsRef<Path>,
+    frame: &tiny_skia::Pixmap,
+    tolerance: &Tolerance,
+) -> Result<(), String> {
+    let reference = reference.as_ref();
+    let write = |path: &Path, pixmap: &tiny_skia::Pixmap| {
+        if let Some(directory) = path.parent() {
+            let _ = std::fs::create_dir_all(directory);
+        }
+        pixmap
+            .save_png(path)
+            .map_err(|e| format!("can't write {}: {e}", path.display()))
+    };
+    if update_requested() || (!reference.exists() && std::env::var_os("CI").is_none()) {
+        return write(reference, frame);
+    }
+    let expected = tiny_skia::Pixmap::load_png(reference)
+        .map_err(|e| format!("can't read the reference {}: {e}", reference.display()))?;
+    let comparison = compare(frame, &expected, tolerance)?;
+    if comparison.passes(tolerance) {
+        let _ = std::fs::remove_file(sibling(reference, "actual"));
+        let _ = std::fs::remove_file(sibling(reference, "diff"));
+        return Ok(());
+    }
+    write(&sibling(reference, "actual"), frame)?;
+    write(
+        &sibling(reference, "diff"),
+        &diff_image(&expected, &comparison),
+    )?;
+    Err(format!(
+        "{} of {} pixels differ from {} by more than {} (at most {}), see {}",
+        comparison.mismatched,
+        comparison.pixels,
+        reference.display(),
+        tolerance.max_distance,
+        comparison.max_distance,
+        sibling(reference, "diff").display()
+    ))
+}
+
+pub fn assert_golden(
+    reference: impl AsRef<Path>,
+    frame: &tiny_skia::Pixmap,
+    tolerance: &Tolerance,
+) {
+    if let Err(e) = check_golden(reference, frame, tolerance) {
+        panic!("{e}");
+    }
+}
+
+#[test]
+fn test_golden() {
+    use crate::mobjects::Dot;
+    use crate::GMPoint;
+
+    let scene_config = SceneConfig {
+        framerate: 10,
+        ..SceneConfig::for_output(32, 18, 9.0)
+    };
+    let dot_scene = |radius: GMFloat| {
+        move |scene: &Rc<RefCell<Scene>>, ctx: &Rc<RefCell<Context>>, vb: &mut VideoBackend| {
+            scene.borrow_mut().add(Box::new(Dot::new(
+                GMPoint::origin(),
+                radius,
+                Color::new(0xff, 0, 0, 0xff),
+                Default::default(),
+            )));
+            scene.borrow_mut().wait(&mut ctx.borrow_mut(), vb, 1.0);
+        }
+    };
+    let frame = render_frame(scene_config.clone(), 5, dot_scene(2.0)).unwrap();
+    assert!(render_frame(scene_config.clone(), 10, dot_scene(2.0)).is_err());
+    let comparison = compare(&frame, &frame, &Tolerance::exact()).unwrap();
+    assert!(comparison.passes(&Tolerance::exact()) && comparison.max_distance == 0.0);
+
+    let reference =
+        std::env::temp_dir().join(format!("gmanim_golden_{}/dot.png", std::process::id()));
+    let _ = std::fs::remove_file(&reference);
+    if std::env::var_os("CI").is_some() || update_requested() {
+        return;
+    }
+    check_golden(&reference, &frame, &Tolerance::default()).unwrap();
+    check_golden(&reference, &frame, &Tolerance::default()).unwrap();
+    let bigger = render_frame(scene_config, 5, dot_scene(3.0)).unwrap();
+    assert!(check_golden(&reference, &bigger, &Tolerance::default()).is_err());
+    assert!(sibling(&reference, "diff").exists());
+    let _ = std::fs::remove_dir_all(reference.parent().unwrap());
+}
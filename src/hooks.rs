@@ -0,0 +1,160 @@
+use crate::{GMFloat, Section};
+
+// when a hook is called: the index of the video frame, counting the frames before
+// Scene::render_from, and the seconds of Scene::time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HookTime {
+    pub frame_index: u64,
+    pub scene_time: GMFloat,
+}
+
+impl HookTime {
+    // seconds into the video, see Scene::video_time
+    pub fn video_time(&self, framerate: u32) -> GMFloat {
+        self.frame_index as GMFloat / framerate as GMFloat
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationEvent {
+    pub time: HookTime,
+    pub total_frame: u32,
+    // in a skipped section or outside the render range, no frames are written for it
+    pub skipped: bool,
+}
+
+type Hooks<T> = Vec<Box<dyn FnMut(&T)>>;
+
+// callbacks of the render loop, e.g. for a progress bar, triggering audio or logging.
+// they run while the scene is borrowed, so they get the time instead of the scene
+#[derive(Default)]
+pub struct SceneHooks {
+    frame_rendered: Hooks<HookTime>,
+    animation_start: Hooks<AnimationEvent>,
+    animation_end: Hooks<AnimationEvent>,
+    section_change: Hooks<(HookTime, Section)>,
+}
+
+impl SceneHooks {
+    // after a video frame is written, frames that are not drawn don't call it
+    pub fn on_frame_rendered(&mut self, hook: impl FnMut(&HookTime) + 'static) {
+        self.frame_rendered.push(Box::new(hook));
+    }
+
+    // when animation::play starts and finishes an animation, combinators count as one
+    pub fn on_animation_start(&mut self, hook: impl FnMut(&AnimationEvent) + 'static) {
+        self.animation_start.push(Box::new(hook));
+    }
+
+    pub fn on_animation_end(&mut self, hook: impl FnMut(&AnimationEvent) + 'static) {
+        self.animation_end.push(Box::new(hook));
+    }
+
+    // when Scene::next_section starts a section
+    pub fn on_section_change(&mut self, hook: impl FnMut(&(HookTime, Section)) + 'static) {
+        self.section_change.push(Box::new(hook));
+    }
+
+    pub fn frame_rendered(&mut self, time: HookTime) {
+        self.frame_rendered.iter_mut().for_each(|hook| hook(&time));
+    }
+
+    pub fn animation_start(&mut self, event: AnimationEvent) {
+        self.animation_start
+            .iter_mut()
+            .for_each(|hook| hook(&event));
+    }
+
+    pub fn animation_end(&mut self, event: AnimationEvent) {
+        self.animation_end.iter_mut().for_each(|hook| hook(&event));
+    }
+
+    pub fn section_change(&mut self, time: HookTime, section: Section) {
+        let event = (time, section);
+        self.section_change.iter_mut().for_each(|hook| hook(&event));
+    }
+}
+
+#[test]
+fn test_scene_hooks() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::animation::{play, AnimationConfig, SimpleMovement};
+    use crate::mobjects::{Dot, Mobject};
+    use crate::video_backend::{BufferBackend, VideoBackend, VideoBackendType};
+    use crate::{Context, ContextType, GMPoint, Scene, SceneConfig};
+
+    let scene = Rc::new(RefCell::new(Scene::new()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(16, 9).unwrap()),
+        SceneConfig::for_output(16, 9, 9.0),
+    )));
+    let framerate = ctx.borrow().scene_config.framerate;
+    let dot: Box<dyn Mobject> = Box::new(Dot::new(
+        GMPoint::origin(),
+        0.5,
+        Default::default(),
+        Default::default(),
+    ));
+    let dot = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot.clone());
+    let log = Rc::new(RefCell::new(vec![]));
+    {
+        let hooks = &mut scene.borrow_mut().hooks;
+        let l = log.clone();
+        hooks.on_frame_rendered(move |t| l.borrow_mut().push(format!("frame {}", t.frame_index)));
+        let l = log.clone();
+        hooks.on_animation_start(move |e| {
+            let l = &mut l.borrow_mut();
+            l.push(format!("start {} {}", e.time.frame_index, e.total_frame));
+        });
+        let l = log.clone();
+        hooks.on_animation_end(move |e| l.borrow_mut().push(format!("end {}", e.skipped)));
+        let l = log.clone();
+        hooks.on_section_change(move |(t, section)| {
+            let l = &mut l.borrow_mut();
+            l.push(format!("section {} {}", section.name, t.frame_index));
+        });
+    }
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::Buffer(BufferBackend::new(|_| {})),
+    };
+    let movement = |frames| SimpleMovement {
+        displacement: nalgebra::Vector3::new(1.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot.clone(),
+        animation_config: AnimationConfig {
+            total_frame: frames,
+            current_frame: 0,
+            rate_function: |x| x,
+        },
+        last_progress: 0.0,
+        path_arc: 0.0,
+    };
+    play(&scene, &ctx, &mut video_backend, movement(2));
+    scene.borrow_mut().next_section("intro", true);
+    play(&scene, &ctx, &mut video_backend, movement(3));
+    scene.borrow_mut().next_section("main", false);
+    scene.borrow_mut().wait(
+        &mut ctx.borrow_mut(),
+        &mut video_backend,
+        2.0 / framerate as GMFloat,
+    );
+    assert_eq!(
+        *log.borrow(),
+        [
+            "start 0 2",
+            "frame 0",
+            "frame 1",
+            "end false",
+            "section intro 2",
+            "start 2 3",
+            "end true",
+            "section main 2",
+            "frame 2",
+            "frame 3",
+        ]
+    );
+}
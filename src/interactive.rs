@@ -0,0 +1,315 @@
+use std::collections::VecDeque;
+
+// what the keys of the interactive window do, see WindowSink for the key bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    TogglePause,
+    // one frame forward, rendering the next one when the latest is shown
+    StepForward,
+    StepBack,
+    // one second
+    SkipForward,
+    SkipBack,
+    First,
+    Latest,
+    // render the scene to the output file once the window is closed
+    WriteFile,
+    Quit,
+}
+
+// the frames shown so far and the one on screen. they are kept to scrub back while
+// paused, the oldest are dropped past max_frames
+pub struct Timeline {
+    frames: VecDeque<Vec<u8>>,
+    // video frame index of frames[0]
+    first_frame: u64,
+    pub max_frames: usize,
+    // index into frames
+    shown: usize,
+    pub framerate: u32,
+    pub paused: bool,
+    // the next rendered frame is shown paused
+    step_pending: bool,
+    pub write_requested: bool,
+    pub closed: bool,
+}
+
+impl Timeline {
+    // keeps about a gigabyte of frames of frame_size bytes
+    pub fn new(framerate: u32, frame_size: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            first_frame: 0,
+            max_frames: ((1 << 30) / frame_size.max(1)).max(1),
+            shown: 0,
+            framerate,
+            paused: false,
+            step_pending: false,
+            write_requested: false,
+            closed: false,
+        }
+    }
+
+    pub fn push(&mut self, frame: Vec<u8>) {
+        let playing_latest = self.shown + 1 >= self.frames.len();
+        self.frames.push_back(frame);
+        while self.frames.len() > self.max_frames {
+            self.frames.pop_front();
+            self.first_frame += 1;
+            self.shown = self.shown.saturating_sub(1);
+        }
+        if self.step_pending || (!self.paused && playing_latest) {
+            self.shown = self.frames.len() - 1;
+        }
+        self.step_pending = false;
+    }
+
+    fn latest(&self) -> usize {
+        self.frames.len().saturating_sub(1)
+    }
+
+    pub fn apply(&mut self, command: Command) {
+        match command {
+            Command::TogglePause => self.paused = !self.paused,
+            Command::StepForward => {
+                self.paused = true;
+                if self.shown < self.latest() {
+                    self.shown += 1;
+                } else {
+                    self.step_pending = true;
+                }
+            }
+            Command::StepBack => {
+                self.paused = true;
+                self.shown = self.shown.saturating_sub(1);
+            }
+            Command::SkipForward => {
+                self.shown = (self.shown + self.framerate as usize).min(self.latest())
+            }
+            Command::SkipBack => self.shown = self.shown.saturating_sub(self.framerate as usize),
+            Command::First => self.shown = 0,
+            Command::Latest => self.shown = self.latest(),
+            Command::WriteFile => self.write_requested = true,
+            Command::Quit => self.closed = true,
+        }
+    }
+
+    // called once per frame duration, replays kept frames after scrubbing back
+    pub fn tick(&mut self) {
+        if !self.paused && self.shown < self.latest() {
+            self.shown += 1;
+        }
+    }
+
+    // whether the scene may render its next frame, it waits while this is false
+    pub fn wants_frame(&self) -> bool {
+        self.closed || self.step_pending || (!self.paused && self.shown >= self.latest())
+    }
+
+    pub fn current(&self) -> Option<&[u8]> {
+        self.frames.get(self.shown).map(Vec::as_slice)
+    }
+
+    // the video frame index of the one on screen
+    pub fn current_index(&self) -> u64 {
+        self.first_frame + self.shown as u64
+    }
+}
+
+// a window showing the frames as they are rendered, for choreographing timing:
+//   space      pause and resume, resuming replays the frames after the shown one
+//   right, .   next frame, renders one more frame at the latest one
+//   left, ,    previous frame
+//   up, down   one second forward and back
+//   home, end  first and latest frame
+//   w          render to the output file after the window is closed
+//   q, escape  close, the rest of the scene is rendered without being shown
+// the window stays open after the last frame until it is closed
+#[cfg(feature = "window")]
+pub struct WindowSink {
+    window: minifb::Window,
+    timeline: Timeline,
+    title: String,
+    width: usize,
+    height: usize,
+    color_order: crate::video_backend::ColorOrder,
+    buffer: Vec<u32>,
+    frame_duration: std::time::Duration,
+    last_shown: std::time::Instant,
+    write_requested: std::rc::Rc<std::cell::Cell<bool>>,
+}
+
+#[cfg(feature = "window")]
+impl WindowSink {
+    // the flag is set when the window was closed with a file requested by w
+    pub fn new(
+        title: &str,
+        video_config: &crate::video_backend::VideoConfig,
+    ) -> Result<(Self, std::rc::Rc<std::cell::Cell<bool>>), String> {
+        let (width, height) = (
+            video_config.output_width as usize,
+            video_config.output_height as usize,
+        );
+        let window = minifb::Window::new(
+            title,
+            width,
+            height,
+            minifb::WindowOptions {
+                resize: true,
+                scale: minifb::Scale::FitScreen,
+                scale_mode: minifb::ScaleMode::AspectRatioStretch,
+                ..Default::default()
+            },
+        )
+        .map_err(|e| format!("can't open a window: {e}"))?;
+        let write_requested = std::rc::Rc::new(std::cell::Cell::new(false));
+        let sink = Self {
+            window,
+            timeline: Timeline::new(video_config.framerate, width * height * 4),
+            title: title.to_owned(),
+            width,
+            height,
+            color_order: video_config.color_order,
+            buffer: vec![0; width * height],
+            frame_duration: std::time::Duration::from_secs_f64(
+                1.0 / video_config.framerate.max(1) as f64,
+            ),
+            last_shown: std::time::Instant::now(),
+            write_requested: write_requested.clone(),
+        };
+        Ok((sink, write_requested))
+    }
+
+    fn poll(&mut self) {
+        use minifb::{Key, KeyRepeat};
+        if !self.window.is_open() {
+            self.timeline.closed = true;
+            return;
+        }
+        for key in self.window.get_keys_pressed(KeyRepeat::Yes) {
+            let command = match key {
+                Key::Space => Command::TogglePause,
+                Key::Right | Key::Period => Command::StepForward,
+                Key::Left | Key::Comma => Command::StepBack,
+                Key::Up => Command::SkipForward,
+                Key::Down => Command::SkipBack,
+                Key::Home => Command::First,
+                Key::End => Command::Latest,
+                Key::W => Command::WriteFile,
+                Key::Q | Key::Escape => Command::Quit,
+                _ => continue,
+            };
+            self.timeline.apply(command);
+        }
+        self.write_requested.set(self.timeline.write_requested);
+    }
+
+    // frames are premultiplied, so their color channels are the frame over black
+    fn show(&mut self) {
+        use crate::video_backend::ColorOrder;
+        let Some(frame) = self.timeline.current() else {
+            self.window.update();
+            return;
+        };
+        for (pixel, rgba) in self.buffer.iter_mut().zip(frame.chunks_exact(4)) {
+            let (r, g, b) = match self.color_order {
+                ColorOrder::Rgba => (rgba[0], rgba[1], rgba[2]),
+                ColorOrder::Bgra => (rgba[2], rgba[1], rgba[0]),
+            };
+            *pixel = (r as u32) << 16 | (g as u32) << 8 | b as u32;
+        }
+        let state = if self.timeline.paused { ", paused" } else { "" };
+        let write = if self.timeline.write_requested {
+            ", writes the file when closed"
+        } else {
+            ""
+        };
+        self.window.set_title(&format!(
+            "{} - frame {}{state}{write}",
+            self.title,
+            self.timeline.current_index()
+        ));
+        let _ = self
+            .window
+            .update_with_buffer(&self.buffer, self.width, self.height);
+    }
+
+    // show frames at the framerate until the scene may render again, or until the
+    // window is closed when finished
+    fn run_until(&mut self, finished: bool) {
+        loop {
+            self.poll();
+            if self.timeline.closed || (!finished && self.timeline.wants_frame()) {
+                return;
+            }
+            let now = std::time::Instant::now();
+            if now >= self.last_shown + self.frame_duration {
+                self.timeline.tick();
+                self.last_shown = now;
+            }
+            self.show();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+}
+
+#[cfg(feature = "window")]
+impl crate::video_backend::VideoSink for WindowSink {
+    fn write_frame(&mut self, frame_data: &[u8]) {
+        if self.timeline.closed {
+            return;
+        }
+        self.timeline.push(frame_data.to_vec());
+        // real time, unless rendering is slower than that
+        let due = self.last_shown + self.frame_duration;
+        if let Some(wait) = due.checked_duration_since(std::time::Instant::now()) {
+            std::thread::sleep(wait);
+        }
+        self.last_shown = std::time::Instant::now();
+        self.show();
+        self.run_until(false);
+    }
+
+    fn close(&mut self) -> std::io::Result<()> {
+        self.run_until(true);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_timeline() {
+    let mut timeline = Timeline::new(2, 1);
+    for i in 0..4 {
+        assert!(timeline.wants_frame());
+        timeline.push(vec![i]);
+    }
+    assert_eq!(timeline.current(), Some(&[3][..]));
+    timeline.apply(Command::TogglePause);
+    assert!(!timeline.wants_frame());
+    // scrubbing stays within the kept frames
+    timeline.apply(Command::SkipBack);
+    timeline.apply(Command::StepBack);
+    assert_eq!(timeline.current_index(), 0);
+    timeline.apply(Command::StepForward);
+    assert_eq!(timeline.current(), Some(&[1][..]));
+    // resuming replays up to the latest frame before rendering again
+    timeline.apply(Command::TogglePause);
+    timeline.tick();
+    assert!(!timeline.wants_frame());
+    timeline.tick();
+    assert!(timeline.wants_frame());
+    // a step at the latest frame renders exactly one more
+    timeline.apply(Command::StepForward);
+    assert!(timeline.paused && timeline.wants_frame());
+    timeline.push(vec![4]);
+    assert_eq!(timeline.current(), Some(&[4][..]));
+    assert!(!timeline.wants_frame());
+    // old frames are dropped past max_frames
+    timeline.max_frames = 3;
+    timeline.push(vec![5]);
+    assert_eq!(timeline.current_index(), 4);
+    timeline.apply(Command::First);
+    assert_eq!(timeline.current_index(), 3);
+    timeline.apply(Command::Quit);
+    assert!(timeline.wants_frame());
+}
@@ -1,4 +1,6 @@
 #![allow(unused)]
+// GMFloat to f32 casts at the rasterizer are no-ops unless gmfloat_f64 is enabled
+#![allow(clippy::unnecessary_cast)]
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -6,19 +8,53 @@ use std::rc::Rc;
 use mobjects::{coordinate_change_x, coordinate_change_y};
 
 pub mod camera;
+pub mod cli;
+pub mod color;
+pub mod data;
+pub mod debug_overlay;
+pub use color::{linear_to_srgb, srgb_to_linear, Color, ColorMap};
 use nalgebra::Point3;
 
 pub mod animation;
+pub mod audio;
+pub mod background;
+pub mod display_list;
+pub mod embed;
+pub mod filters;
+pub mod fonts;
+pub mod golden;
+pub mod hooks;
+pub mod interactive;
 pub mod log_utils;
+pub mod lottie_export;
 pub mod math_utils;
 pub mod mobjects;
+pub mod notebook;
+pub mod plugin;
+pub mod preview;
+#[cfg(feature = "scene_files")]
+pub mod scene_file;
+#[cfg(feature = "serde")]
+pub mod scene_state;
+#[cfg(feature = "skia")]
+pub mod skia_backend;
+pub mod snapshot;
+pub mod sound;
+pub mod subtitles;
+pub mod svg_export;
+pub mod tex;
+pub mod updaters;
 pub mod video_backend;
+pub mod voiceover;
+#[cfg(feature = "web")]
+pub mod web;
 
+// the precision of scene coordinates. f64 keeps deep zooms and long simulations
+// accurate, f32 is faster for many points. gmfloat_f32 is a default feature, so
+// gmfloat_f64 wins when both are enabled. rasterizing is always done in f32
 cfg_if::cfg_if! {
     if #[cfg(feature = "gmfloat_f16")]{
         pub type GMFloat = f16;
-    }else if #[cfg(feature = "gmfloat_f32")]{
-        pub type GMFloat = f32;
     }else if #[cfg(feature = "gmfloat_f64")]{
         pub type GMFloat = f64;
     }else{
@@ -27,63 +63,334 @@ cfg_if::cfg_if! {
 }
 
 pub type GMPoint = Point3<GMFloat>;
-#[derive(Clone, Copy, Debug)]
-pub struct Color {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-    pub a: u8,
-}
-
-impl Color {
-    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
-        Self { r, g, b, a }
-    }
-}
-
-impl From<Color> for tiny_skia::Color {
-    fn from(value: Color) -> Self {
-        Self::from_rgba8(value.r, value.g, value.b, value.a)
-    }
-}
-
-impl Default for Color {
-    fn default() -> Self {
-        Self {
-            r: 0x33,
-            g: 0xcc,
-            b: 0xff,
-            a: 0xff,
-        }
-    }
-}
-
+// a shared number, animations write it and mobjects or updaters read it
+pub type ValueTracker = Rc<std::cell::Cell<GMFloat>>;
 pub enum ContextType {
     TinySKIA(tiny_skia::Pixmap), // we always have cairo as a fallback
+    Svg(svg_export::SvgRecorder),
+    Lottie(lottie_export::LottieFrame),
+    DisplayList(display_list::DisplayList),
+    #[cfg(feature = "skia")]
+    Skia(skia_backend::SkiaCanvas),
     VULKAN,
     CUDA,
     HIP,
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SceneConfig {
     pub width: GMFloat,
     pub height: GMFloat,
     pub output_width: u32,
     pub output_height: u32,
     pub scale_factor: GMFloat,
+    pub framerate: u32,
+    // which part of the scene is shown, see convert_point
+    pub camera: camera::FrameCamera,
+    // when set, mobject points are projected by this camera before the frame camera,
+    // see camera::Camera::perspective_for_frame and orthographic_for_frame
+    pub three_d_camera: Option<camera::Camera>,
+    // maps mobject points before both cameras, like a fisheye or a map projection of a globe.
+    // a closure, left out when the config is serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub projection: Option<camera::CustomProjection>,
+    // per paint anti-aliasing of fills and strokes
+    pub anti_alias: bool,
+    // render at supersampling times the output size and downscale before encoding,
+    // 1 renders directly at the output size. only used by the tiny-skia context
+    pub supersampling: u32,
+    // pixels drawn per pixel of the output video, raised for supersampling and zoomed
+    // displays. strokes in StrokeUnits::Pixels are scaled by it
+    pub pixel_density: GMFloat,
+    // temporal supersampling, every frame is rendered as this many sub-frames spread over
+    // the time since the previous frame. 1 renders one sharp frame per video frame
+    pub sub_frames: u32,
+    // whether the sub-frames are averaged into motion blur or all written to the video
+    pub sub_frame_mode: SubFrameMode,
+    // rasterize the tiny-skia context in this many horizontal tiles,
+    // in parallel with the parallel feature. 1 draws the mobjects directly
+    pub raster_tiles: u32,
+    pub background: background::Background,
+    // pixel position of the output inside the whole frame,
+    // not zero for the regions of it drawn separately, see cropped
+    pub output_offset: (i32, i32),
+    // applied to the tiny-skia pixmap after every frame, see filters::apply_post_filters.
+    // frames with post filters are always drawn whole
+    pub post_filters: Vec<filters::PostFilter>,
+    // blend and interpolate gradients in linear light and encode the result as sRGB,
+    // instead of mixing the sRGB values directly. only used by the tiny-skia context
+    pub linear_light: bool,
+    // seed of Context::rng, renders with the same seed draw the same random values
+    pub seed: u64,
+    // grid, bounding boxes, anchor points and safe areas drawn over every frame
+    pub debug_overlay: Option<debug_overlay::DebugOverlay>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SubFrameMode {
+    // one motion blurred frame per video frame
+    Average,
+    // every sub-frame is a video frame, written at video_framerate for a smooth high
+    // framerate video or at framerate for slow motion
+    Emit,
 }
 
 pub struct Context {
     pub ctx_type: ContextType,
     pub scene_config: SceneConfig,
+    // see push_clip
+    pub clip: ClipStack,
+    // see draw_stroke_passes
+    pub pass: DrawPass,
+    // for randomized mobjects and animations, Scene::draw reseeds it from scene_config.seed
+    // and the frame index before drawing so every frame gets the same values in every run.
+    // a mobject drawing with it should be time dependent, or its last frame is reused
+    pub rng: math_utils::Rng,
+    // the context of the last supersampled frame, its pixmap is reused by the next one
+    supersampled: Option<Box<Context>>,
+}
+
+// the part of the shapes the draw calls of a context go through.
+// strokes with DrawConfig::stroke_behind are drawn in the background pass, everything else
+// in the foreground pass, so they stay behind the fills of all mobjects drawn in the same passes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DrawPass {
+    #[default]
+    All,
+    BackgroundStrokes,
+    Foreground,
+}
+
+// clip paths in output pixels, drawing is limited to the intersection of all of them
+#[derive(Default)]
+pub struct ClipStack {
+    pub paths: Vec<tiny_skia::Path>,
+    // the intersection as a mask, for the tiny-skia context
+    mask: Option<tiny_skia::Mask>,
+}
+
+impl ClipStack {
+    pub fn mask(&self) -> Option<&tiny_skia::Mask> {
+        self.mask.as_ref()
+    }
+
+    fn update_mask(&mut self, width: u32, height: u32, anti_alias: bool) {
+        self.mask = None;
+        for path in &self.paths {
+            let transform = tiny_skia::Transform::identity();
+            match &mut self.mask {
+                Some(mask) => {
+                    mask.intersect_path(path, tiny_skia::FillRule::Winding, anti_alias, transform)
+                }
+                None => {
+                    self.mask = tiny_skia::Mask::new(width, height).map(|mut mask| {
+                        mask.fill_path(path, tiny_skia::FillRule::Winding, anti_alias, transform);
+                        mask
+                    })
+                }
+            }
+        }
+    }
 }
 
 impl SceneConfig {
-    pub fn convert_coord_x(&self, x: GMFloat) -> GMFloat {
-        coordinate_change_x(x, self.width) * self.scale_factor
+    // framerate of the video for a realtime playback, emitted sub-frames raise it
+    pub fn video_framerate(&self) -> u32 {
+        match self.sub_frame_mode {
+            SubFrameMode::Average => self.framerate,
+            SubFrameMode::Emit => self.framerate * self.sub_frames.max(1),
+        }
+    }
+    // scene coordinates to output pixels as seen through the camera
+    pub fn convert_point(&self, x: GMFloat, y: GMFloat) -> (GMFloat, GMFloat) {
+        let (x, y) = self.camera.to_frame(x, y);
+        (
+            coordinate_change_x(x, self.width) * self.scale_factor,
+            coordinate_change_y(y, self.height) * self.scale_factor,
+        )
+    }
+    // scene points to output pixels in homogeneous coordinates: the 3d camera, when set,
+    // maps the scene to normalized device coordinates which cover the frame,
+    // then the frame camera maps the frame to the output like convert_point
+    pub fn projection_matrix(&self) -> nalgebra::Matrix4<GMFloat> {
+        let frame = nalgebra::Matrix4::new_translation(&nalgebra::Vector3::new(
+            self.width / 2.0 * self.scale_factor,
+            self.height / 2.0 * self.scale_factor,
+            0.0,
+        )) * nalgebra::Matrix4::new_nonuniform_scaling(&nalgebra::Vector3::new(
+            self.pixel_scale(),
+            -self.pixel_scale(),
+            1.0,
+        )) * nalgebra::Rotation3::from_axis_angle(
+            &nalgebra::Vector3::z_axis(),
+            -self.camera.rotation,
+        )
+        .to_homogeneous()
+            * nalgebra::Matrix4::new_translation(&nalgebra::Vector3::new(
+                -self.camera.center.x,
+                -self.camera.center.y,
+                0.0,
+            ));
+        match &self.three_d_camera {
+            Some(camera) => {
+                frame
+                    * nalgebra::Matrix4::new_nonuniform_scaling(&nalgebra::Vector3::new(
+                        self.width / 2.0,
+                        self.height / 2.0,
+                        1.0,
+                    ))
+                    * camera.get_projection_transform_matrix()
+                    * camera.get_camera_transform_matrix()
+            }
+            None => frame,
+        }
+    }
+    // the output pixel of a scene point, mobjects draw their points through this.
+    // it builds the projection matrix for the one point, use project_with for many points
+    pub fn project(&self, p: &GMPoint) -> tiny_skia::Point {
+        self.project_with(&self.projection_matrix(), p)
+    }
+    // project with projection_matrix computed once by the caller for all points of a draw
+    pub fn project_with(
+        &self,
+        projection_matrix: &nalgebra::Matrix4<GMFloat>,
+        p: &GMPoint,
+    ) -> tiny_skia::Point {
+        let h = projection_matrix * self.apply_projection(p).to_homogeneous();
+        tiny_skia::Point::from_xy((h.x / h.w) as f32, (h.y / h.w) as f32)
+    }
+    // output pixels covered by a mobject, with room for its stroke and anti-aliasing.
+    // None when it is empty or outside of the output
+    pub fn pixel_rect(&self, m: &dyn mobjects::Mobject) -> Option<tiny_skia::IntRect> {
+        let points = m.get_points();
+        let (first, rest) = points.split_first()?;
+        let projection_matrix = self.projection_matrix();
+        let first = self.project_with(&projection_matrix, first);
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+        for p in rest {
+            let p = self.project_with(&projection_matrix, p);
+            (min_x, min_y) = (min_x.min(p.x), min_y.min(p.y));
+            (max_x, max_y) = (max_x.max(p.x), max_y.max(p.y));
+        }
+        let draw_config = m.get_draw_config().resolve_stroke_units(self);
+        let pad = ((draw_config.stoke_width / 2.0 + draw_config.effect_extent())
+            * self.pixel_scale_at(&m.get_center())
+            + 2.0) as f32;
+        let rect = tiny_skia::IntRect::from_ltrb(
+            (min_x - pad).floor() as i32,
+            (min_y - pad).floor() as i32,
+            (max_x + pad).ceil() as i32,
+            (max_y + pad).ceil() as i32,
+        )?;
+        rect.intersect(&tiny_skia::IntRect::from_xywh(
+            0,
+            0,
+            self.output_width,
+            self.output_height,
+        )?)
+    }
+    // the config of mobjects fixed in the frame, see Scene::fixed_in_frame. without the
+    // custom projection and 3d camera, and with a frame camera that only follows output_offset
+    pub fn fixed_in_frame(&self) -> SceneConfig {
+        let mut scene_config = self.clone();
+        scene_config.three_d_camera = None;
+        scene_config.projection = None;
+        scene_config.camera = camera::FrameCamera::default();
+        scene_config.camera.center.x += self.output_offset.0 as GMFloat / self.scale_factor;
+        scene_config.camera.center.y -= self.output_offset.1 as GMFloat / self.scale_factor;
+        scene_config
+    }
+    // the config of the part of the output inside rect, drawing with it gives
+    // the same pixels as drawing the whole output and cutting out rect
+    pub fn cropped(&self, rect: tiny_skia::IntRect) -> SceneConfig {
+        let mut scene_config = self.clone();
+        scene_config.output_width = rect.width();
+        scene_config.output_height = rect.height();
+        scene_config.output_offset = (
+            self.output_offset.0 + rect.x(),
+            self.output_offset.1 + rect.y(),
+        );
+        // moving the frame camera by the rect offset in frame coordinates
+        let (dx, dy) = (
+            rect.x() as GMFloat / self.scale_factor / self.camera.zoom,
+            -rect.y() as GMFloat / self.scale_factor / self.camera.zoom,
+        );
+        let (sin, cos) = self.camera.rotation.sin_cos();
+        scene_config.camera.center.x += dx * cos - dy * sin;
+        scene_config.camera.center.y += dx * sin + dy * cos;
+        scene_config
+    }
+    // p mapped by the custom projection, p itself without one
+    pub fn apply_projection(&self, p: &GMPoint) -> GMPoint {
+        match &self.projection {
+            Some(projection) => projection.apply(p),
+            None => *p,
+        }
+    }
+    // sort items by the depth of their position under the 3d camera, the farthest first.
+    // without a 3d camera the order is kept
+    pub fn sort_back_to_front<T>(&self, items: &mut [T], position: impl Fn(&T) -> GMPoint) {
+        if let Some(camera) = &self.three_d_camera {
+            camera.sort_back_to_front(items, |item| self.apply_projection(&position(item)));
+        }
+    }
+    // output pixels per scene unit at p, lengths shrink with depth under a perspective camera
+    pub fn pixel_scale_at(&self, p: &GMPoint) -> GMFloat {
+        match &self.three_d_camera {
+            Some(camera) => {
+                self.pixel_scale() * camera.scale_at(&self.apply_projection(p)) * self.height / 2.0
+            }
+            None => self.pixel_scale(),
+        }
     }
-    pub fn convert_coord_y(&self, y: GMFloat) -> GMFloat {
-        coordinate_change_y(y, self.height) * self.scale_factor
+    // the blending color space of tiny-skia paints
+    pub fn color_space(&self) -> tiny_skia::ColorSpace {
+        if self.linear_light {
+            tiny_skia::ColorSpace::FullSRGBGamma
+        } else {
+            tiny_skia::ColorSpace::Linear
+        }
+    }
+    // output pixels per scene unit, for lengths like stroke widths and radii
+    pub fn pixel_scale(&self) -> GMFloat {
+        self.scale_factor * self.camera.zoom
+    }
+
+    // a frame of frame_height scene units rendered to output_width x output_height pixels,
+    // the frame width follows the aspect ratio of the output
+    pub fn for_output(output_width: u32, output_height: u32, frame_height: GMFloat) -> Self {
+        let scale_factor = output_height as GMFloat / frame_height;
+        SceneConfig {
+            width: output_width as GMFloat / scale_factor,
+            height: frame_height,
+            output_width,
+            output_height,
+            scale_factor,
+            ..Default::default()
+        }
+    }
+
+    // presets keep the 9 unit frame height of the default, so the same scene fits every one of them
+    pub fn hd_1080p() -> Self {
+        Self::for_output(1920, 1080, 9.0)
+    }
+
+    pub fn qhd_1440p() -> Self {
+        Self::for_output(2560, 1440, 9.0)
+    }
+
+    pub fn uhd_4k() -> Self {
+        Self::for_output(3840, 2160, 9.0)
+    }
+
+    pub fn vertical_1080x1920() -> Self {
+        Self::for_output(1080, 1920, 9.0)
+    }
+
+    pub fn square_1080() -> Self {
+        Self::for_output(1080, 1080, 9.0)
     }
 }
 
@@ -95,6 +402,22 @@ impl Default for SceneConfig {
             output_width: 1920,
             output_height: 1080,
             scale_factor: 1920.0 / 16.0,
+            framerate: 60,
+            camera: Default::default(),
+            three_d_camera: None,
+            projection: None,
+            anti_alias: true,
+            supersampling: 1,
+            pixel_density: 1.0,
+            sub_frames: 1,
+            sub_frame_mode: SubFrameMode::Average,
+            raster_tiles: 1,
+            background: Default::default(),
+            output_offset: (0, 0),
+            post_filters: vec![],
+            linear_light: false,
+            seed: 0,
+            debug_overlay: None,
         }
     }
 }
@@ -104,19 +427,339 @@ impl Default for Context {
         let scene_config = SceneConfig::default();
         let pixmap =
             tiny_skia::Pixmap::new(scene_config.output_width, scene_config.output_height).unwrap();
-        Self {
-            ctx_type: ContextType::TinySKIA(pixmap),
-            scene_config,
+        Self::new(ContextType::TinySKIA(pixmap), scene_config)
+    }
+}
+
+// paths are given in output pixel coordinates, mobjects draw through these
+// so that every context type gets the same draw calls
+impl ContextType {
+    pub fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        fill_rule: tiny_skia::FillRule,
+        clip: &ClipStack,
+    ) {
+        match self {
+            ContextType::TinySKIA(pixmap) => {
+                pixmap.fill_path(
+                    path,
+                    paint,
+                    fill_rule,
+                    tiny_skia::Transform::identity(),
+                    clip.mask(),
+                );
+            }
+            ContextType::Svg(recorder) => recorder.fill_path(path, paint, fill_rule),
+            ContextType::Lottie(frame) => frame.fill_path(path, paint, fill_rule),
+            ContextType::DisplayList(list) => list.fill_path(path, paint, fill_rule, &clip.paths),
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => canvas.fill_path(path, paint, fill_rule),
+            _ => {}
+        }
+    }
+    pub fn stroke_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        draw_config: &mobjects::DrawConfig,
+        scale_factor: GMFloat,
+        anti_alias: bool,
+        color_space: tiny_skia::ColorSpace,
+        clip: &ClipStack,
+    ) {
+        match self {
+            ContextType::TinySKIA(pixmap) => {
+                let mut paint = draw_config.get_paint();
+                paint.anti_alias = anti_alias;
+                paint.colorspace = color_space;
+                pixmap.stroke_path(
+                    path,
+                    &paint,
+                    &draw_config.get_stroke(scale_factor),
+                    tiny_skia::Transform::identity(),
+                    clip.mask(),
+                );
+            }
+            ContextType::Svg(recorder) => recorder.stroke_path(path, draw_config, scale_factor),
+            ContextType::Lottie(frame) => frame.stroke_path(path, draw_config, scale_factor),
+            ContextType::DisplayList(list) => list.stroke_path(
+                path,
+                draw_config,
+                scale_factor,
+                anti_alias,
+                color_space,
+                &clip.paths,
+            ),
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => {
+                canvas.stroke_path(path, draw_config, scale_factor, anti_alias)
+            }
+            _ => {}
         }
     }
 }
 
 impl Context {
+    pub fn new(ctx_type: ContextType, scene_config: SceneConfig) -> Self {
+        Self {
+            ctx_type,
+            clip: Default::default(),
+            pass: DrawPass::All,
+            rng: math_utils::Rng::new(scene_config.seed),
+            scene_config,
+            supersampled: None,
+        }
+    }
+
+    // a full skia raster surface of the output size of scene_config, see skia_backend
+    #[cfg(feature = "skia")]
+    pub fn skia(scene_config: SceneConfig) -> Self {
+        let canvas =
+            skia_backend::SkiaCanvas::new(scene_config.output_width, scene_config.output_height);
+        Self::new(ContextType::Skia(canvas), scene_config)
+    }
+
+    // whether draw calls of the background (or else foreground) part are drawn in the current pass
+    fn in_pass(&self, background: bool) -> bool {
+        match self.pass {
+            DrawPass::All => true,
+            DrawPass::BackgroundStrokes => background,
+            DrawPass::Foreground => !background,
+        }
+    }
+
+    // call draw once for the strokes drawn behind the fills and once for the rest,
+    // used by mobjects made of several shapes so that no stroke covers a neighbour's fill.
+    // without stroke_behind or inside another pass draw is only called once
+    pub fn draw_stroke_passes(&mut self, stroke_behind: bool, draw: impl Fn(&mut Context)) {
+        if !stroke_behind || self.pass != DrawPass::All {
+            draw(self);
+            return;
+        }
+        self.pass = DrawPass::BackgroundStrokes;
+        draw(self);
+        self.pass = DrawPass::Foreground;
+        draw(self);
+        self.pass = DrawPass::All;
+    }
+
+    // limit the following draw calls to the inside of path, given in output pixels,
+    // until the matching pop_clip. nested clips intersect
+    pub fn push_clip(&mut self, path: &tiny_skia::Path) {
+        self.clip.paths.push(path.clone());
+        self.clip_changed();
+        match &mut self.ctx_type {
+            ContextType::Svg(recorder) => recorder.push_clip(path),
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => canvas.push_clip(path, self.scene_config.anti_alias),
+            _ => {}
+        }
+    }
+    pub fn pop_clip(&mut self) {
+        if self.clip.paths.pop().is_none() {
+            return;
+        }
+        self.clip_changed();
+        match &mut self.ctx_type {
+            ContextType::Svg(recorder) => recorder.pop_clip(),
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => canvas.pop_clip(),
+            _ => {}
+        }
+    }
+    fn clip_changed(&mut self) {
+        if let ContextType::TinySKIA(pixmap) = &self.ctx_type {
+            let (width, height) = (pixmap.width(), pixmap.height());
+            self.clip
+                .update_mask(width, height, self.scene_config.anti_alias);
+        }
+    }
+
+    // draw calls of the mobjects, applying the anti-aliasing setting of the scene config
+    pub fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        fill_rule: tiny_skia::FillRule,
+    ) {
+        if !self.in_pass(false) {
+            return;
+        }
+        let mut paint = paint.clone();
+        paint.anti_alias = paint.anti_alias && self.scene_config.anti_alias;
+        paint.colorspace = self.scene_config.color_space();
+        self.ctx_type.fill_path(path, &paint, fill_rule, &self.clip);
+    }
+    pub fn stroke_path(&mut self, path: &tiny_skia::Path, draw_config: &mobjects::DrawConfig) {
+        if !self.in_pass(draw_config.stroke_behind) {
+            return;
+        }
+        self.draw_effects(path, draw_config, None, true);
+        self.stroke_path_without_effects(path, draw_config);
+    }
+    // for shapes whose effects were drawn together with their fill, see DrawConfig::fill_and_stroke
+    pub fn stroke_path_without_effects(
+        &mut self,
+        path: &tiny_skia::Path,
+        draw_config: &mobjects::DrawConfig,
+    ) {
+        if !self.in_pass(draw_config.stroke_behind) {
+            return;
+        }
+        self.ctx_type.stroke_path(
+            path,
+            &draw_config.resolve_stroke_units(&self.scene_config),
+            self.scene_config.pixel_scale(),
+            self.scene_config.anti_alias,
+            self.scene_config.color_space(),
+            &self.clip,
+        );
+    }
+
+    // the glow and shadow of draw_config behind a shape, drawn before the shape itself.
+    // only the raster contexts draw them
+    pub fn draw_effects(
+        &mut self,
+        path: &tiny_skia::Path,
+        draw_config: &mobjects::DrawConfig,
+        fill: Option<tiny_skia::FillRule>,
+        stroke: bool,
+    ) {
+        if draw_config.glow.is_none() && draw_config.shadow.is_none() {
+            return;
+        }
+        // effects go with the part of the shape drawn first
+        if !self.in_pass(stroke && draw_config.stroke_behind) {
+            return;
+        }
+        if !matches!(
+            self.ctx_type,
+            ContextType::TinySKIA(_) | ContextType::DisplayList(_)
+        ) {
+            return;
+        }
+        let Some(output_rect) = tiny_skia::IntRect::from_xywh(
+            0,
+            0,
+            self.scene_config.output_width,
+            self.scene_config.output_height,
+        ) else {
+            return;
+        };
+        let draw_config = draw_config.resolve_stroke_units(&self.scene_config);
+        for (x, y, layer) in draw_config.effect_layers(
+            path,
+            fill,
+            stroke,
+            self.scene_config.pixel_scale(),
+            output_rect,
+        ) {
+            self.draw_pixmap_blended(x, y, &layer, draw_config.blend_mode);
+        }
+    }
+
+    // a pixmap rendered by the mobject itself, e.g. ThreeDViewport
+    pub fn draw_pixmap(&mut self, x: i32, y: i32, pixmap: &tiny_skia::Pixmap) {
+        if !self.in_pass(false) {
+            return;
+        }
+        self.draw_pixmap_blended(x, y, pixmap, tiny_skia::BlendMode::SourceOver);
+    }
+    pub fn draw_pixmap_blended(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: &tiny_skia::Pixmap,
+        blend_mode: tiny_skia::BlendMode,
+    ) {
+        match &mut self.ctx_type {
+            ContextType::TinySKIA(target) => {
+                target.draw_pixmap(
+                    x,
+                    y,
+                    pixmap.as_ref(),
+                    &tiny_skia::PixmapPaint {
+                        blend_mode,
+                        ..Default::default()
+                    },
+                    tiny_skia::Transform::identity(),
+                    self.clip.mask(),
+                );
+            }
+            ContextType::DisplayList(list) => {
+                list.draw_pixmap(x, y, pixmap, blend_mode, &self.clip.paths)
+            }
+            _ => {}
+        }
+    }
+
+    // a tiny-skia context with supersampling times the size and scale factor of this one.
+    // the pixmap of the previous frame is taken again unless the size or the factor changed,
+    // give the context back with put_supersampled after drawing
+    fn take_supersampled(&mut self) -> Option<Box<Context>> {
+        let factor = self.scene_config.supersampling;
+        if factor <= 1 || !matches!(self.ctx_type, ContextType::TinySKIA(_)) {
+            self.supersampled = None;
+            return None;
+        }
+        let mut scene_config = self.scene_config.clone();
+        scene_config.output_width *= factor;
+        scene_config.output_height *= factor;
+        scene_config.scale_factor *= factor as GMFloat;
+        scene_config.pixel_density *= factor as GMFloat;
+        scene_config.output_offset = (
+            self.scene_config.output_offset.0 * factor as i32,
+            self.scene_config.output_offset.1 * factor as i32,
+        );
+        scene_config.supersampling = 1;
+        // the downsampled frame is filtered
+        scene_config.post_filters = vec![];
+        if let Some(mut cached) = self.supersampled.take() {
+            if let ContextType::TinySKIA(pixmap) = &cached.ctx_type {
+                if (pixmap.width(), pixmap.height())
+                    == (scene_config.output_width, scene_config.output_height)
+                {
+                    cached.scene_config = scene_config;
+                    return Some(cached);
+                }
+            }
+        }
+        let pixmap = tiny_skia::Pixmap::new(scene_config.output_width, scene_config.output_height)?;
+        Some(Box::new(Context::new(
+            ContextType::TinySKIA(pixmap),
+            scene_config,
+        )))
+    }
+    fn put_supersampled(&mut self, supersampled: Box<Context>) {
+        self.supersampled = Some(supersampled);
+    }
+
+    // clear to the background of the scene config
     fn clear_transparent(&mut self) {
+        let background = &self.scene_config.background;
+        match &mut self.ctx_type {
+            ContextType::TinySKIA(pixmap) => background.fill(pixmap, &self.scene_config),
+            ContextType::Svg(recorder) => {
+                recorder.clear();
+                recorder.set_background(background);
+            }
+            ContextType::DisplayList(list) => list.clear(),
+            ContextType::Lottie(frame) => frame.clear(),
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => canvas.clear(background.base_color()),
+            _ => {}
+        }
+    }
+
+    // called after all mobjects of a frame are drawn
+    fn finish_frame(&mut self) {
         match &mut self.ctx_type {
             ContextType::TinySKIA(pixmap) => {
-                pixmap.fill(tiny_skia::Color::from_rgba8(0, 0, 0, 0xff));
+                filters::apply_post_filters(pixmap, &self.scene_config)
             }
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => canvas.finish_frame(),
             _ => {}
         }
     }
@@ -124,26 +767,474 @@ impl Context {
     fn image_bytes(&self) -> &[u8] {
         match &self.ctx_type {
             ContextType::TinySKIA(pixmap) => pixmap.data(),
+            #[cfg(feature = "skia")]
+            ContextType::Skia(canvas) => canvas.pixels(),
             _ => &[],
         }
     }
 }
 
-#[derive(Default)]
+// an updater and the mobject it runs on, see Scene::add_updater
+pub type MobjectUpdater = (
+    Rc<RefCell<Box<dyn mobjects::Mobject>>>,
+    Box<dyn updaters::Updater>,
+);
+
 pub struct Scene {
     pub mobjects: Vec<Rc<RefCell<Box<dyn mobjects::Mobject>>>>,
+    pub updaters: Vec<MobjectUpdater>,
+    // seconds elapsed in the scene, advanced by update
+    pub time: GMFloat,
+    // scene seconds per video second, 0.25 is slow motion and 2.0 is fast forward.
+    // it applies to updaters, see animation::ChangeSpeed for animations
+    pub playback_speed: GMFloat,
+    // multiplies playback_speed until the next update, set by animation::ChangeSpeed
+    // for the frame it interpolated
+    pub frame_speed: GMFloat,
+    pub sections: Vec<Section>,
+    pub preview: preview::Preview,
+    // also write rendered frames as svg files when set
+    pub svg_export: Option<svg_export::SvgExport>,
+    // also collect rendered frames as a lottie animation when set, see LottieExport::save
+    pub lottie_export: Option<lottie_export::LottieExport>,
+    // index of the next video frame, frames of skipped sections are not counted
+    pub frame_index: u64,
+    // only the video frames from render_from to render_to seconds are drawn and written,
+    // the others are stepped through like a skipped section. None renders to the end
+    pub render_from: GMFloat,
+    pub render_to: Option<GMFloat>,
+    // the mobjects drawn in screen space on top of the others, like titles
+    // and legends of a 3d scene. the frame and 3d camera don't move them
+    pub fixed_in_frame: Vec<Rc<RefCell<Box<dyn mobjects::Mobject>>>>,
+    // magnified insets, drawn over the mobjects and under the ones fixed in the frame
+    pub zoomed_displays: Vec<camera::ZoomedDisplay>,
+    // audio clips mixed into the soundtrack of the output by cli::render
+    pub sounds: Vec<sound::SoundClip>,
+    // tones of play_sound and the video frames they start at
+    pub tones: Vec<(u64, audio::Tone)>,
+    // captions written next to the output as an srt file by cli::render
+    pub subtitles: Vec<subtitles::SubtitleCue>,
+    pub hooks: hooks::SceneHooks,
+    // see save_state and restore
+    saved_state: Option<snapshot::SceneSnapshot>,
+    // what draw_frame left in the context, any other draw resets it
+    last_frame: RefCell<Option<FrameState>>,
+}
+
+// box filter of factor x factor blocks, averaged in linear light when linear is set
+fn downsample(src: &tiny_skia::Pixmap, dst: &mut tiny_skia::Pixmap, factor: u32, linear: bool) {
+    let src_width = src.width() as usize;
+    let (width, height) = (dst.width() as usize, dst.height() as usize);
+    let factor = factor as usize;
+    let block = (factor * factor) as u32;
+    let src_data = src.data();
+    let dst_data = dst.data_mut();
+    if linear {
+        let decode: Vec<f32> = (0..=255)
+            .map(|c| srgb_to_linear(c as f32 / 255.0))
+            .collect();
+        for y in 0..height {
+            for x in 0..width {
+                // premultiplied linear channels and alpha
+                let mut sum = [0f32; 4];
+                for sy in y * factor..(y + 1) * factor {
+                    let row = sy * src_width;
+                    for sx in x * factor..(x + 1) * factor {
+                        let p = src.pixels()[row + sx].demultiply();
+                        let a = p.alpha() as f32 / 255.0;
+                        sum[0] += decode[p.red() as usize] * a;
+                        sum[1] += decode[p.green() as usize] * a;
+                        sum[2] += decode[p.blue() as usize] * a;
+                        sum[3] += a;
+                    }
+                }
+                let a = sum[3] / block as f32;
+                let i = (y * width + x) * 4;
+                for c in 0..3 {
+                    let straight = if sum[3] > 0.0 { sum[c] / sum[3] } else { 0.0 };
+                    dst_data[i + c] = (linear_to_srgb(straight) * a * 255.0).round() as u8;
+                }
+                dst_data[i + 3] = (a * 255.0).round() as u8;
+            }
+        }
+        return;
+    }
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0u32; 4];
+            for sy in y * factor..(y + 1) * factor {
+                let row = sy * src_width;
+                for sx in x * factor..(x + 1) * factor {
+                    let i = (row + sx) * 4;
+                    for (sum, value) in sum.iter_mut().zip(&src_data[i..i + 4]) {
+                        *sum += *value as u32;
+                    }
+                }
+            }
+            let i = (y * width + x) * 4;
+            for (value, sum) in dst_data[i..i + 4].iter_mut().zip(sum) {
+                *value = ((sum + block / 2) / block) as u8;
+            }
+        }
+    }
+}
+
+// sums frames of the same size to average them, e.g. the sub-frames of motion blur
+#[derive(Default)]
+pub struct FrameAccumulator {
+    sum: Vec<u32>,
+    count: u32,
+}
+
+impl FrameAccumulator {
+    pub fn add(&mut self, frame: &[u8]) {
+        if self.sum.len() != frame.len() {
+            self.sum = vec![0; frame.len()];
+            self.count = 0;
+        }
+        for (sum, value) in self.sum.iter_mut().zip(frame) {
+            *sum += *value as u32;
+        }
+        self.count += 1;
+    }
+    // the rounded average of the added frames, the accumulator starts over
+    pub fn take_average(&mut self) -> Vec<u8> {
+        let count = self.count.max(1);
+        let average = self
+            .sum
+            .iter()
+            .map(|sum| ((sum + count / 2) / count) as u8)
+            .collect();
+        self.sum.iter_mut().for_each(|sum| *sum = 0);
+        self.count = 0;
+        average
+    }
+}
+
+// writes the sub-frames of SceneConfig::sub_frames to the video, averaged or one by one
+pub struct SubFrameWriter {
+    pub mode: SubFrameMode,
+    pub sub_frames: u32,
+    accumulator: FrameAccumulator,
+}
+
+impl SubFrameWriter {
+    pub fn new(scene_config: &SceneConfig) -> Self {
+        Self {
+            mode: scene_config.sub_frame_mode,
+            sub_frames: scene_config.sub_frames.max(1),
+            accumulator: FrameAccumulator::default(),
+        }
+    }
+    pub fn add(&mut self, frame: &[u8], video_backend: &mut video_backend::VideoBackend) {
+        if self.mode == SubFrameMode::Emit || self.sub_frames == 1 {
+            video_backend.write_frame(frame);
+        } else {
+            self.accumulator.add(frame);
+        }
+    }
+    // called after the last sub-frame of a video frame
+    pub fn end_frame(&mut self, video_backend: &mut video_backend::VideoBackend) {
+        if self.mode == SubFrameMode::Average && self.sub_frames > 1 {
+            video_backend.write_frame(&self.accumulator.take_average());
+        }
+    }
+    // how many video frames a still frame is repeated for
+    pub fn repeats(&self, frames: u64) -> u64 {
+        match self.mode {
+            SubFrameMode::Average => frames,
+            SubFrameMode::Emit => frames * self.sub_frames as u64,
+        }
+    }
+}
+
+// mobjects of a frame drawn by Scene::draw_frame as (identity, signature, pixel rect)
+struct FrameState {
+    config_signature: u64,
+    mobjects: Vec<(usize, Option<u64>, Option<tiny_skia::IntRect>)>,
+}
+
+impl FrameState {
+    // regions to redraw to get from this frame to next, None when everything has to be redrawn.
+    // a time dependent mobject can draw outside of the rect of its points, so it redraws everything
+    fn dirty_rects(&self, next: &FrameState) -> Option<Vec<tiny_skia::IntRect>> {
+        if self.config_signature != next.config_signature
+            || self.mobjects.len() != next.mobjects.len()
+        {
+            return None;
+        }
+        let mut rects = vec![];
+        for (last, next) in self.mobjects.iter().zip(&next.mobjects) {
+            if last.0 != next.0 || last.1.is_none() || next.1.is_none() {
+                return None;
+            }
+            if last.1 == next.1 {
+                continue;
+            }
+            rects.extend(last.2);
+            rects.extend(next.2);
+        }
+        Some(rects)
+    }
+}
+
+// a named part of the scene, frames of a skipped section are not rendered
+// but mobjects are still moved to where the section leaves them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub skip: bool,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Scene {
     pub fn new() -> Self {
-        Scene { mobjects: vec![] }
+        Scene {
+            mobjects: vec![],
+            updaters: vec![],
+            time: 0.0,
+            playback_speed: 1.0,
+            frame_speed: 1.0,
+            sections: vec![],
+            preview: Default::default(),
+            svg_export: None,
+            lottie_export: None,
+            frame_index: 0,
+            render_from: 0.0,
+            render_to: None,
+            fixed_in_frame: vec![],
+            zoomed_displays: vec![],
+            sounds: vec![],
+            tones: vec![],
+            subtitles: vec![],
+            hooks: Default::default(),
+            saved_state: None,
+            last_frame: Default::default(),
+        }
     }
-    pub fn save_png(&self, ctx: &mut Context, file_path: &str) {
+    // clear the context and draw every mobject of the scene
+    pub fn draw(&self, ctx: &mut Context) {
+        self.last_frame.replace(None);
+        ctx.rng = math_utils::Rng::new(ctx.scene_config.seed).fork(self.frame_index);
+        if let Some(mut supersampled) = ctx.take_supersampled() {
+            self.draw(&mut supersampled);
+            if let (ContextType::TinySKIA(src), ContextType::TinySKIA(dst)) =
+                (&supersampled.ctx_type, &mut ctx.ctx_type)
+            {
+                downsample(
+                    src,
+                    dst,
+                    ctx.scene_config.supersampling,
+                    ctx.scene_config.linear_light,
+                );
+            }
+            ctx.put_supersampled(supersampled);
+            ctx.finish_frame();
+            return;
+        }
+        if ctx.scene_config.raster_tiles > 1 && matches!(ctx.ctx_type, ContextType::TinySKIA(_)) {
+            let mut recording = Context::new(
+                ContextType::DisplayList(Default::default()),
+                ctx.scene_config.clone(),
+            );
+            self.draw(&mut recording);
+            ctx.clear_transparent();
+            if let (ContextType::DisplayList(list), ContextType::TinySKIA(pixmap)) =
+                (&recording.ctx_type, &mut ctx.ctx_type)
+            {
+                list.rasterize_tiles(pixmap, ctx.scene_config.raster_tiles);
+            }
+            ctx.finish_frame();
+            return;
+        }
         ctx.clear_transparent();
+        self.draw_mobjects(ctx);
+        for display in &self.zoomed_displays {
+            self.draw_zoomed_display(ctx, display);
+        }
+        let fixed: Vec<_> = self
+            .mobjects
+            .iter()
+            .filter(|m| self.is_fixed_in_frame(m))
+            .collect();
+        if !fixed.is_empty() {
+            let fixed_config = ctx.scene_config.fixed_in_frame();
+            let scene_config = std::mem::replace(&mut ctx.scene_config, fixed_config);
+            for m in fixed {
+                m.borrow().draw(ctx);
+            }
+            ctx.scene_config = scene_config;
+        }
+        if let Some(overlay) = ctx.scene_config.debug_overlay {
+            overlay.draw(self, ctx);
+        }
+        ctx.finish_frame();
+    }
 
-        for m in self.mobjects.iter() {
+    // the mobjects that are not fixed in the frame, back to front for a 3d camera
+    fn draw_mobjects(&self, ctx: &mut Context) {
+        let mut mobjects: Vec<_> = self
+            .mobjects
+            .iter()
+            .filter(|m| !self.is_fixed_in_frame(m))
+            .collect();
+        ctx.scene_config
+            .sort_back_to_front(&mut mobjects, |m| m.borrow().get_center());
+        for m in mobjects {
             m.borrow().draw(ctx);
         }
+    }
+    fn draw_zoomed_display(&self, ctx: &mut Context, display: &camera::ZoomedDisplay) {
+        let (Some(rect), Some(scene_config)) = (
+            display.display_rect(&ctx.scene_config),
+            display.zoomed_config(&ctx.scene_config),
+        ) else {
+            return;
+        };
+        let Some(pixmap) = tiny_skia::Pixmap::new(rect.width(), rect.height()) else {
+            return;
+        };
+        let mut zoomed = Context::new(ContextType::TinySKIA(pixmap), scene_config);
+        zoomed.rng = ctx.rng.clone();
+        zoomed.clear_transparent();
+        self.draw_mobjects(&mut zoomed);
+        if let ContextType::TinySKIA(pixmap) = &zoomed.ctx_type {
+            ctx.draw_pixmap(rect.x(), rect.y(), pixmap);
+        }
+        display.draw_borders(ctx);
+    }
+
+    // summary of a mobject for comparing frames, None when it has to be redrawn every frame
+    // because of time dependent state outside of its points
+    fn mobject_signature(m: &dyn mobjects::Mobject, fixed_in_frame: bool) -> Option<u64> {
+        use std::hash::{Hash, Hasher};
+        if m.is_time_dependent() {
+            return None;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        m.signature(&mut hasher);
+        // drawn with another camera
+        fixed_in_frame.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+    fn config_signature(scene_config: &SceneConfig) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        // every field that changes the pixels of a frame
+        format!(
+            "{} {} {} {:?} {:?} {:?} {}x{} {:?} {} {} {} {} {:?} {:?} {:?} {} {} {:?}",
+            scene_config.width,
+            scene_config.height,
+            scene_config.scale_factor,
+            scene_config.camera,
+            scene_config.three_d_camera,
+            scene_config.projection,
+            scene_config.output_width,
+            scene_config.output_height,
+            scene_config.output_offset,
+            scene_config.anti_alias,
+            scene_config.supersampling,
+            scene_config.pixel_density,
+            scene_config.raster_tiles,
+            scene_config.background,
+            scene_config.post_filters,
+            scene_config.sub_frame_mode,
+            scene_config.linear_light,
+            scene_config.seed,
+            scene_config.debug_overlay
+        )
+        .hash(&mut hasher);
+        hasher.finish()
+    }
+    fn frame_state(&self, scene_config: &SceneConfig) -> FrameState {
+        let fixed_config = scene_config.fixed_in_frame();
+        FrameState {
+            config_signature: Self::config_signature(scene_config),
+            mobjects: self
+                .mobjects
+                .iter()
+                .map(|m| {
+                    let b = m.borrow();
+                    let fixed_in_frame = self.is_fixed_in_frame(m);
+                    let config = if fixed_in_frame {
+                        &fixed_config
+                    } else {
+                        scene_config
+                    };
+                    (
+                        Rc::as_ptr(m) as *const () as usize,
+                        Self::mobject_signature(b.as_ref(), fixed_in_frame),
+                        config.pixel_rect(b.as_ref()),
+                    )
+                })
+                .collect(),
+        }
+    }
+    // draw for consecutive video frames into the same context.
+    // only the regions of mobjects that changed since the previous frame are redrawn,
+    // the whole frame when mobjects were added, removed, the camera moved or
+    // a mobject is time dependent
+    pub fn draw_frame(&self, ctx: &mut Context) {
+        // an inset can show any part of the scene, so it isn't patched
+        if !self.zoomed_displays.is_empty() {
+            self.draw(ctx);
+            return;
+        }
+        let state = self.frame_state(&ctx.scene_config);
+        let dirty_rects = match self.last_frame.borrow().as_ref() {
+            Some(last) => last.dirty_rects(&state),
+            None => None,
+        };
+        match dirty_rects {
+            // other contexts have no retained pixmap to patch, and a filtered one can't be patched
+            Some(rects) if rects.is_empty() => {}
+            Some(rects)
+                if matches!(ctx.ctx_type, ContextType::TinySKIA(_))
+                    && ctx.scene_config.post_filters.is_empty() =>
+            {
+                for rect in rects {
+                    self.draw_region(ctx, rect);
+                }
+            }
+            _ => self.draw(ctx),
+        }
+        *self.last_frame.borrow_mut() = Some(state);
+    }
+    // redraw the pixels inside rect of a tiny-skia context
+    fn draw_region(&self, ctx: &mut Context, rect: tiny_skia::IntRect) {
+        let ContextType::TinySKIA(pixmap) = &mut ctx.ctx_type else {
+            return;
+        };
+        let Some(region) = tiny_skia::Pixmap::new(rect.width(), rect.height()) else {
+            return;
+        };
+        let mut region_ctx = Context::new(
+            ContextType::TinySKIA(region),
+            ctx.scene_config.cropped(rect),
+        );
+        self.draw(&mut region_ctx);
+        if let ContextType::TinySKIA(region) = &region_ctx.ctx_type {
+            pixmap.draw_pixmap(
+                rect.x(),
+                rect.y(),
+                region.as_ref(),
+                &tiny_skia::PixmapPaint {
+                    blend_mode: tiny_skia::BlendMode::Source,
+                    ..Default::default()
+                },
+                tiny_skia::Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    pub fn save_png(&self, ctx: &mut Context, file_path: &str) {
+        self.draw(ctx);
 
         match &mut ctx.ctx_type {
             ContextType::TinySKIA(pixmap) => {
@@ -153,12 +1244,206 @@ impl Scene {
         }
     }
 
+    // everything played after this call belongs to the new section
+    pub fn next_section(&mut self, name: &str, skip: bool) {
+        let section = Section {
+            name: name.to_owned(),
+            skip,
+        };
+        self.sections.push(section.clone());
+        self.hooks.section_change(self.hook_time(), section);
+    }
+    pub fn is_skipping(&self) -> bool {
+        self.sections.last().is_some_and(|s| s.skip)
+    }
+    // named pause point, only halts when the preview is enabled and the section is not skipped
+    pub fn pause(&mut self, ctx: &mut Context, name: &str) {
+        if !self.preview.enabled || self.is_skipping() {
+            return;
+        }
+        self.draw(ctx);
+        self.preview.pause(name, ctx);
+    }
+    // indices of the video frames inside render_from..render_to
+    pub fn render_range(&self, framerate: u32) -> std::ops::Range<u64> {
+        let to_frame = |seconds: GMFloat| (seconds.max(0.0) * framerate as GMFloat).round() as u64;
+        to_frame(self.render_from)..self.render_to.map_or(u64::MAX, to_frame)
+    }
+    // the frame about to be rendered, for the hooks
+    pub fn hook_time(&self) -> hooks::HookTime {
+        hooks::HookTime {
+            frame_index: self.frame_index,
+            scene_time: self.time,
+        }
+    }
+    // seconds of video written so far, counting the frames before render_from
+    pub fn video_time(&self, framerate: u32) -> GMFloat {
+        self.frame_index as GMFloat / framerate as GMFloat
+    }
+    // how many of the next count video frames are inside the render range
+    pub fn frames_in_render_range(&self, count: u64, framerate: u32) -> u64 {
+        let range = self.render_range(framerate);
+        let end = self.frame_index + count;
+        end.min(range.end)
+            .saturating_sub(self.frame_index.max(range.start))
+    }
+    // scene time between two video frames
+    pub fn frame_dt(&self, framerate: u32) -> GMFloat {
+        self.playback_speed * self.frame_speed / framerate as GMFloat
+    }
+    // run every updater once, dt is the time since the last frame in seconds
+    pub fn update(&mut self, dt: GMFloat) {
+        self.frame_speed = 1.0;
+        self.time += dt;
+        for m in &self.mobjects {
+            m.borrow_mut().advance_time(dt);
+        }
+        for (m, updater) in &mut self.updaters {
+            updater.update(m.borrow_mut().as_mut(), dt);
+        }
+    }
+
+    // whether frames change over time even when no animation is playing
+    pub fn is_time_dependent(&self) -> bool {
+        !self.updaters.is_empty() || self.mobjects.iter().any(|m| m.borrow().is_time_dependent())
+    }
+
+    // hold the current frame for the given seconds, the frame is only rasterized once
+    // unless there are updaters still moving things around
+    pub fn wait(
+        &mut self,
+        ctx: &mut Context,
+        video_backend: &mut video_backend::VideoBackend,
+        seconds: GMFloat,
+    ) {
+        let total_frame = (seconds * ctx.scene_config.framerate as GMFloat).round() as u32;
+        if total_frame == 0 {
+            return;
+        }
+        let framerate = ctx.scene_config.framerate;
+        let rendered = self.frames_in_render_range(total_frame as u64, framerate);
+        let _span = tracing::info_span!("wait", total_frame, rendered).entered();
+        tracing::info!(frame = self.frame_index, "waiting {seconds}s");
+        if self.is_skipping() || rendered == 0 {
+            if !self.is_skipping() {
+                self.frame_index += total_frame as u64;
+            }
+            let dt = self.frame_dt(framerate);
+            for _ in 0..total_frame {
+                self.update(dt);
+            }
+            return;
+        }
+        let mut writer = SubFrameWriter::new(&ctx.scene_config);
+        if !self.is_time_dependent() {
+            // the updates don't change the frame but keep the scene time running
+            let dt = self.frame_dt(framerate);
+            let first = self.frame_index;
+            let range = self.render_range(framerate);
+            self.update(dt);
+            self.draw(ctx);
+            video_backend.write_repeated_frame(ctx.image_bytes(), writer.repeats(rendered) as u32);
+            for frame_index in first..first + total_frame as u64 {
+                if frame_index != first {
+                    self.update(dt);
+                }
+                if range.contains(&frame_index) {
+                    self.hooks.frame_rendered(hooks::HookTime {
+                        frame_index,
+                        scene_time: self.time,
+                    });
+                }
+            }
+            self.frame_index += total_frame as u64;
+            return;
+        }
+        let sub_frames = writer.sub_frames;
+        let dt = self.frame_dt(framerate) / sub_frames as GMFloat;
+        let range = self.render_range(framerate);
+        for _ in 0..total_frame {
+            let in_range = range.contains(&self.frame_index);
+            for _ in 0..sub_frames {
+                self.update(dt);
+                if in_range {
+                    self.draw_frame(ctx);
+                    writer.add(ctx.image_bytes(), video_backend);
+                }
+            }
+            if in_range {
+                tracing::debug!(frame = self.frame_index, "rendered frame");
+                writer.end_frame(video_backend);
+                self.hooks.frame_rendered(self.hook_time());
+            }
+            self.frame_index += 1;
+        }
+    }
+
+    pub fn add_updater(
+        &mut self,
+        mobject_ref: Rc<RefCell<Box<dyn mobjects::Mobject>>>,
+        updater: Box<dyn updaters::Updater>,
+    ) {
+        self.updaters.push((mobject_ref, updater));
+    }
+    // closure updater, e.g. |m, dt| for simulations that integrate over frame time
+    pub fn add_updater_fn(
+        &mut self,
+        mobject_ref: Rc<RefCell<Box<dyn mobjects::Mobject>>>,
+        updater: impl FnMut(&mut dyn mobjects::Mobject, GMFloat) + 'static,
+    ) {
+        self.add_updater(mobject_ref, Box::new(updater));
+    }
+
     pub fn add(&mut self, mobject: Box<dyn mobjects::Mobject>) {
         self.mobjects.push(Rc::new(RefCell::new(mobject)));
     }
     pub fn add_ref(&mut self, mobject_ref: Rc<RefCell<Box<dyn mobjects::Mobject>>>) {
         self.mobjects.push(mobject_ref.clone());
     }
+    // play the audio file at_time seconds into the video, e.g. at video_time for a sound
+    // effect on the next animation. it is decoded and mixed when the output is finished
+    pub fn add_sound(&mut self, path: &str, at_time: GMFloat) {
+        self.sounds.push(sound::SoundClip::new(path, at_time));
+    }
+    // synthesize the tone starting with the next video frame, e.g. before an animation:
+    //     scene.borrow_mut().play_sound(Tone::new(440.0, 0.2));
+    //     play(&scene, &ctx, &mut video_backend, animation);
+    pub fn play_sound(&mut self, tone: audio::Tone) {
+        self.tones.push((self.frame_index, tone));
+    }
+    // the sounds and tones, placed in the video
+    pub fn audio_timeline(&self, framerate: u32) -> audio::AudioTimeline {
+        let mut timeline = audio::AudioTimeline::from_sounds(&self.sounds);
+        for (frame_index, tone) in &self.tones {
+            let at_time = *frame_index as GMFloat / framerate as GMFloat;
+            timeline.add_samples(tone.samples(sound::SAMPLE_RATE), at_time, 1.0);
+        }
+        timeline
+    }
+    // add the mobject, or mark an added one, to be drawn in screen space
+    pub fn add_fixed_in_frame(&mut self, mobject_ref: Rc<RefCell<Box<dyn mobjects::Mobject>>>) {
+        if !self.mobjects.iter().any(|m| Rc::ptr_eq(m, &mobject_ref)) {
+            self.mobjects.push(mobject_ref.clone());
+        }
+        if !self.is_fixed_in_frame(&mobject_ref) {
+            self.fixed_in_frame.push(mobject_ref);
+        }
+    }
+    // the mobject stays in the scene and moves with the cameras again
+    pub fn unfix_from_frame(&mut self, mobject_ref: &Rc<RefCell<Box<dyn mobjects::Mobject>>>) {
+        self.fixed_in_frame.retain(|m| !Rc::ptr_eq(m, mobject_ref));
+    }
+    pub fn is_fixed_in_frame(&self, mobject_ref: &Rc<RefCell<Box<dyn mobjects::Mobject>>>) -> bool {
+        self.fixed_in_frame
+            .iter()
+            .any(|m| Rc::ptr_eq(m, mobject_ref))
+    }
+    // updaters of the removed mobject are dropped as well
+    pub fn remove(&mut self, mobject_ref: &Rc<RefCell<Box<dyn mobjects::Mobject>>>) {
+        self.mobjects.retain(|m| !Rc::ptr_eq(m, mobject_ref));
+        self.fixed_in_frame.retain(|m| !Rc::ptr_eq(m, mobject_ref));
+        self.updaters.retain(|(m, _)| !Rc::ptr_eq(m, mobject_ref));
+    }
 }
 
 #[test]
@@ -216,6 +1501,581 @@ fn test_rectangle_image() {
     scene.save_png(&mut ctx, "rectangle.png");
 }
 
+#[test]
+fn test_scene_wait() {
+    use mobjects::Rectangle;
+    use video_backend::{BgraRAWBackend, ColorOrder, VideoBackend, VideoBackendType, VideoConfig};
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            framerate: 30,
+            ..Default::default()
+        },
+    );
+    let mut scene = Scene::new();
+    scene.add(Box::new(Rectangle::default()));
+    let video_config = VideoConfig {
+        filename: std::env::temp_dir()
+            .join("gmanim_wait.raw")
+            .to_string_lossy()
+            .into_owned(),
+        framerate: 30,
+        output_width: 160,
+        output_height: 90,
+        color_order: ColorOrder::Rgba,
+    };
+    let _ = std::fs::remove_file(&video_config.filename);
+    let mut video_backend_var = VideoBackend {
+        backend_type: VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+    };
+    scene.wait(&mut ctx, &mut video_backend_var, 0.5);
+    video_backend_var.close().unwrap();
+    let written = std::fs::metadata(&video_config.filename).unwrap().len();
+    assert_eq!(written, 15 * 160 * 90 * 4);
+}
+
+#[test]
+fn test_static_wait_time() {
+    use video_backend::{BufferBackend, VideoBackend, VideoBackendType};
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(16, 9).unwrap()),
+        SceneConfig {
+            output_width: 16,
+            output_height: 9,
+            scale_factor: 1.0,
+            framerate: 60,
+            ..Default::default()
+        },
+    );
+    let mut scene = Scene::new();
+    scene.add(Box::new(mobjects::Rectangle::default()));
+    let times = Rc::new(RefCell::new(vec![]));
+    let recorded = times.clone();
+    scene
+        .hooks
+        .on_frame_rendered(move |time| recorded.borrow_mut().push(time.scene_time));
+    let mut video_backend_var = VideoBackend {
+        backend_type: VideoBackendType::Buffer(BufferBackend::new(|_| {})),
+    };
+    scene.wait(&mut ctx, &mut video_backend_var, 1.0);
+    assert_eq!(scene.frame_index, 60);
+    assert!((scene.time - 1.0).abs() < 1e-4);
+    // every held frame is reported with its own time
+    let times = times.borrow();
+    assert_eq!(times.len(), 60);
+    assert!((times[0] - 1.0 / 60.0).abs() < 1e-5 && (times[59] - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_output_presets() {
+    let default = SceneConfig::default();
+    let hd = SceneConfig::hd_1080p();
+    assert_eq!(
+        (hd.width, hd.height, hd.scale_factor),
+        (default.width, default.height, default.scale_factor)
+    );
+    let vertical = SceneConfig::vertical_1080x1920();
+    assert_eq!(vertical.height, 9.0);
+    assert!((vertical.width - 5.0625).abs() < 1e-4);
+    // the corners of the frame are the corners of the output
+    let (x, y) = vertical.convert_point(vertical.width / 2.0, -vertical.height / 2.0);
+    assert!((x - 1080.0).abs() < 1e-2 && (y - 1920.0).abs() < 1e-2);
+    let square = SceneConfig::square_1080();
+    assert_eq!((square.width, square.scale_factor), (9.0, 120.0));
+}
+
+#[test]
+fn test_supersampling() {
+    use mobjects::SimpleLine;
+    let mut scene = Scene::new();
+    scene.add(Box::new(SimpleLine {
+        p0: nalgebra::Point3::new(-5.0, -3.0, 0.0),
+        p1: nalgebra::Point3::new(5.0, 2.0, 0.0),
+        ..Default::default()
+    }));
+    // distinct values of the green channel, aliased strokes only have background and line color
+    let green_levels = |supersampling: u32| {
+        let mut ctx = Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            SceneConfig {
+                output_width: 160,
+                output_height: 90,
+                scale_factor: 10.0,
+                anti_alias: false,
+                supersampling,
+                ..Default::default()
+            },
+        );
+        scene.draw(&mut ctx);
+        let mut levels: Vec<u8> = ctx.image_bytes().chunks(4).map(|p| p[1]).collect();
+        levels.sort();
+        levels.dedup();
+        levels.len()
+    };
+    assert_eq!(green_levels(1), 2);
+    assert!(green_levels(4) > 2);
+}
+
+#[test]
+fn test_supersampled_pixmap_reused() {
+    let scene = Scene::new();
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            supersampling: 4,
+            ..Default::default()
+        },
+    );
+    let pixels = |ctx: &Context| match ctx.supersampled.as_deref().map(|c| &c.ctx_type) {
+        Some(ContextType::TinySKIA(pixmap)) => (pixmap.width(), pixmap.data().as_ptr()),
+        _ => panic!("no supersampled pixmap"),
+    };
+    scene.draw(&mut ctx);
+    let first = pixels(&ctx);
+    assert_eq!(first.0, 640);
+    scene.draw(&mut ctx);
+    assert_eq!(pixels(&ctx), first);
+    // another factor needs a pixmap of another size
+    ctx.scene_config.supersampling = 2;
+    scene.draw(&mut ctx);
+    assert_eq!(pixels(&ctx).0, 320);
+}
+
+#[test]
+fn test_project_point() {
+    let mut scene_config = SceneConfig {
+        three_d_camera: Some(camera::Camera::perspective_for_frame(16.0, 9.0, 10.0)),
+        ..Default::default()
+    };
+    let p = nalgebra::Point3::new(1.0, 2.0, 0.0);
+    let projected = scene_config.project(&p);
+    let (flat_x, flat_y) = scene_config.convert_point(1.0, 2.0);
+    assert!(
+        (projected.x as GMFloat - flat_x).abs() < 1e-2
+            && (projected.y as GMFloat - flat_y).abs() < 1e-2
+    );
+    assert!((scene_config.pixel_scale_at(&p) - scene_config.pixel_scale()).abs() < 1e-2);
+    // moving towards the camera moves the point away from the center of the frame
+    let projected = scene_config.project(&nalgebra::Point3::new(1.0, 2.0, 5.0));
+    assert!((projected.x as GMFloat - scene_config.convert_point(2.0, 4.0).0).abs() < 1e-2);
+    // without a 3d camera the matrix is the frame camera, z is ignored
+    scene_config.three_d_camera = None;
+    scene_config.camera = camera::FrameCamera::new(nalgebra::Point3::new(1.0, -0.5, 0.0), 2.0, 0.3);
+    let projected = scene_config.project(&nalgebra::Point3::new(3.0, 1.0, 4.0));
+    let (x, y) = scene_config.convert_point(3.0, 1.0);
+    assert!((projected.x as GMFloat - x).abs() < 1e-2 && (projected.y as GMFloat - y).abs() < 1e-2);
+}
+
+#[cfg(feature = "gmfloat_f64")]
+#[test]
+fn test_deep_zoom() {
+    // a pixel is 1e-6 scene units here, below what f32 can tell apart near 1000
+    let mut scene_config = SceneConfig::for_output(1600, 900, 9.0);
+    scene_config.camera =
+        camera::FrameCamera::new(nalgebra::Point3::new(1000.0, 0.0, 0.0), 1e4, 0.0);
+    let steps: Vec<f32> = (0..4)
+        .map(|i| {
+            scene_config
+                .project(&GMPoint::new(1000.0 + i as GMFloat * 1e-5, 0.0, 0.0))
+                .x
+        })
+        .collect();
+    for pair in steps.windows(2) {
+        assert!((pair[1] - pair[0] - 10.0).abs() < 1e-2);
+    }
+}
+
+#[test]
+fn test_depth_sorting() {
+    use mobjects::{polygon::Polygon, DrawConfig, Mobject};
+    let square = |z: GMFloat, color: Color| {
+        let mut polygon = Polygon::new(vec![
+            Point3::new(-1.0, -1.0, z),
+            Point3::new(1.0, -1.0, z),
+            Point3::new(1.0, 1.0, z),
+            Point3::new(-1.0, 1.0, z),
+        ]);
+        polygon.set_draw_config(DrawConfig {
+            color,
+            ..Default::default()
+        });
+        Box::new(polygon)
+    };
+    let mut scene = Scene::new();
+    // the near square is added first, it still covers the far one
+    scene.add(square(1.0, Color::new(0xff, 0, 0, 0xff)));
+    scene.add(square(-1.0, Color::new(0, 0, 0xff, 0xff)));
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            three_d_camera: Some(camera::Camera::perspective_for_frame(16.0, 9.0, 10.0)),
+            ..Default::default()
+        },
+    );
+    scene.draw(&mut ctx);
+    let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+        panic!("not a tiny-skia context");
+    };
+    let center = pixmap.pixel(80, 45).unwrap();
+    assert_eq!((center.red(), center.blue()), (0xff, 0));
+}
+
+#[test]
+fn test_fixed_in_frame() {
+    use mobjects::{polygon::Polygon, DrawConfig, Mobject};
+    let mut title = Polygon::new(vec![
+        Point3::new(-8.0, 4.5, 0.0),
+        Point3::new(-6.0, 4.5, 0.0),
+        Point3::new(-6.0, 2.5, 0.0),
+        Point3::new(-8.0, 2.5, 0.0),
+    ]);
+    title.set_draw_config(DrawConfig {
+        color: Color::new(0xff, 0, 0, 0xff),
+        ..Default::default()
+    });
+    let title: Box<dyn Mobject> = Box::new(title);
+    let title = Rc::new(RefCell::new(title));
+    let mut scene = Scene::new();
+    scene.add_fixed_in_frame(title.clone());
+    let mut camera = camera::Camera::perspective_for_frame(16.0, 9.0, 10.0);
+    camera.rotate_about_origin(1.0);
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            three_d_camera: Some(camera),
+            camera: camera::FrameCamera::new(Point3::new(3.0, 1.0, 0.0), 2.0, 0.5),
+            ..Default::default()
+        },
+    );
+    // the top left corner of the output whatever the cameras do
+    let red_in_corner = |ctx: &mut Context, scene: &Scene| {
+        scene.draw(ctx);
+        let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+            panic!("not a tiny-skia context");
+        };
+        pixmap.pixel(10, 10).unwrap().red() == 0xff
+    };
+    assert!(red_in_corner(&mut ctx, &scene));
+    let rect = ctx
+        .scene_config
+        .fixed_in_frame()
+        .pixel_rect(&**title.borrow());
+    assert_eq!(rect.map(|r| (r.left(), r.top())), Some((0, 0)));
+    scene.unfix_from_frame(&title);
+    assert!(!red_in_corner(&mut ctx, &scene));
+}
+
+#[test]
+fn test_static_frame_cache() {
+    use mobjects::{Rectangle, Transform};
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            ..Default::default()
+        },
+    );
+    let mut scene = Scene::new();
+    let rectangle: Box<dyn mobjects::Mobject> = Box::new(Rectangle::default());
+    let rectangle = Rc::new(RefCell::new(rectangle));
+    scene.add_ref(rectangle.clone());
+    let mark = |ctx: &mut Context| {
+        if let ContextType::TinySKIA(pixmap) = &mut ctx.ctx_type {
+            pixmap.fill(tiny_skia::Color::WHITE);
+        }
+    };
+    let is_marked = |ctx: &Context| ctx.image_bytes()[0] == 0xff;
+    scene.draw_frame(&mut ctx);
+    mark(&mut ctx);
+    // nothing changed, the frame in the context is reused
+    scene.draw_frame(&mut ctx);
+    assert!(is_marked(&ctx));
+    rectangle
+        .borrow_mut()
+        .move_this(nalgebra::Vector3::new(1.0, 0.0, 0.0));
+    // only the old and new area of the rectangle are redrawn
+    scene.draw_frame(&mut ctx);
+    assert!(is_marked(&ctx));
+    let mut full_ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        ctx.scene_config.clone(),
+    );
+    scene.draw(&mut full_ctx);
+    let mut redrawn = 0;
+    for (partial, full) in ctx
+        .image_bytes()
+        .chunks(4)
+        .zip(full_ctx.image_bytes().chunks(4))
+    {
+        if partial != [0xff; 4] {
+            redrawn += 1;
+            assert!(partial.iter().zip(full).all(|(a, b)| a.abs_diff(*b) <= 1));
+        }
+    }
+    assert!(redrawn > 0 && redrawn < 160 * 90 / 4);
+}
+
+#[test]
+fn test_background_change_redraws() {
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(16, 9).unwrap()),
+        SceneConfig {
+            output_width: 16,
+            output_height: 9,
+            scale_factor: 1.0,
+            ..Default::default()
+        },
+    );
+    let scene = Scene::new();
+    scene.draw_frame(&mut ctx);
+    ctx.scene_config.background = background::Background::Color(Color::new(0xff, 0, 0, 0xff));
+    scene.draw_frame(&mut ctx);
+    assert_eq!(ctx.image_bytes()[..4], [0xff, 0, 0, 0xff]);
+}
+
+#[test]
+fn test_dirty_region_redraw() {
+    use mobjects::{dot::Dot, Draw, DrawConfig, Mobject, Rectangle, Transform};
+    // a dot drawn where the elapsed time says, its points stay at the origin
+    struct Ticker {
+        time: GMFloat,
+    }
+    impl Draw for Ticker {
+        fn draw(&self, ctx: &mut Context) {
+            let position = nalgebra::Point3::new(-6.0 + self.time, 3.0, 0.0);
+            Dot::new(
+                position,
+                0.5,
+                Color::new(0, 0, 0xff, 0xff),
+                DrawConfig::default(),
+            )
+            .draw(ctx);
+        }
+    }
+    impl Transform for Ticker {
+        fn transform(&mut self, _: nalgebra::Transform3<GMFloat>) {}
+    }
+    impl Mobject for Ticker {
+        fn get_draw_config(&self) -> DrawConfig {
+            DrawConfig::default()
+        }
+        fn set_draw_config(&mut self, _: DrawConfig) {}
+        fn get_points(&self) -> Vec<GMPoint> {
+            vec![GMPoint::origin()]
+        }
+        fn set_points(&mut self, _: &[GMPoint]) {}
+        fn advance_time(&mut self, dt: GMFloat) {
+            self.time += dt;
+        }
+        fn is_time_dependent(&self) -> bool {
+            true
+        }
+    }
+    let scene_config = SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        ..Default::default()
+    };
+    let new_ctx = || {
+        Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            scene_config.clone(),
+        )
+    };
+    // the frames of draw_frame match a full draw of the same scene
+    let assert_matches_full_draw = |scene: &Scene, ctx: &Context| {
+        let mut full_ctx = new_ctx();
+        scene.draw(&mut full_ctx);
+        assert!(ctx
+            .image_bytes()
+            .iter()
+            .zip(full_ctx.image_bytes())
+            .all(|(a, b)| a.abs_diff(*b) <= 1));
+    };
+    let mut scene = Scene::new();
+    let moved: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let moved = Rc::new(RefCell::new(moved));
+    let restyled: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let restyled = Rc::new(RefCell::new(restyled));
+    restyled
+        .borrow_mut()
+        .move_this(nalgebra::Vector3::new(-4.0, -2.0, 0.0));
+    scene.add_ref(moved.clone());
+    scene.add_ref(restyled.clone());
+    let mut ctx = new_ctx();
+    scene.draw_frame(&mut ctx);
+    moved
+        .borrow_mut()
+        .move_this(nalgebra::Vector3::new(2.0, 1.0, 0.0));
+    let draw_config = restyled.borrow().get_draw_config();
+    restyled.borrow_mut().set_draw_config(DrawConfig {
+        color: Color::new(0xff, 0, 0, 0xff),
+        fill: true,
+        ..draw_config
+    });
+    scene.draw_frame(&mut ctx);
+    assert_matches_full_draw(&scene, &ctx);
+    // the ticker draws away from the rect of its points, only a full redraw removes the old dot
+    let ticker: Box<dyn Mobject> = Box::new(Ticker { time: 0.0 });
+    let ticker = Rc::new(RefCell::new(ticker));
+    scene.add_ref(ticker.clone());
+    scene.draw_frame(&mut ctx);
+    ticker.borrow_mut().advance_time(4.0);
+    scene.draw_frame(&mut ctx);
+    assert_matches_full_draw(&scene, &ctx);
+}
+
+#[test]
+fn test_mobject_signature() {
+    use mobjects::{heatmap::Heatmap, Mobject};
+    let mut heatmap = Heatmap::new(
+        vec![vec![0.0, 1.0]],
+        ColorMap::viridis(),
+        GMPoint::new(-1.0, -1.0, 0.0),
+        GMPoint::new(1.0, 1.0, 0.0),
+    );
+    let signature = Scene::mobject_signature(&heatmap, false);
+    assert_ne!(signature, Scene::mobject_signature(&heatmap, true));
+    // the points stay the same, the colors don't
+    heatmap.values[0][0] = 1.0;
+    assert_ne!(signature, Scene::mobject_signature(&heatmap, false));
+}
+
+#[test]
+fn test_raster_tiles() {
+    use mobjects::{Dot, SimpleLine};
+    let mut scene = Scene::new();
+    scene.add(Box::new(SimpleLine {
+        p0: nalgebra::Point3::new(-5.0, -3.0, 0.0),
+        p1: nalgebra::Point3::new(5.0, 2.0, 0.0),
+        ..Default::default()
+    }));
+    scene.add(Box::new(Dot::default()));
+    let render = |raster_tiles: u32| {
+        let mut ctx = Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            SceneConfig {
+                output_width: 160,
+                output_height: 90,
+                scale_factor: 10.0,
+                raster_tiles,
+                ..Default::default()
+            },
+        );
+        scene.draw(&mut ctx);
+        ctx.image_bytes().to_vec()
+    };
+    let (direct, tiled) = (render(1), render(4));
+    // curves are flattened for tiles, only a few anti-aliased edge pixels differ
+    let different = direct.iter().zip(&tiled).filter(|(a, b)| a != b).count();
+    assert!(different < direct.len() / 1000);
+    assert!(render(7)
+        .iter()
+        .zip(&tiled)
+        .all(|(a, b)| a.abs_diff(*b) <= 1));
+}
+
+#[test]
+fn test_transparent_background() {
+    use mobjects::Rectangle;
+    use video_backend::{
+        ColorOrder, PngSequenceBackend, VideoBackend, VideoBackendType, VideoConfig,
+    };
+    let scene_config = SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        background: background::Background::Transparent,
+        ..Default::default()
+    };
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        scene_config,
+    );
+    let mut scene = Scene::new();
+    let mut rectangle = Rectangle {
+        p0: nalgebra::Point3::new(-2.0, -2.0, 0.0),
+        p1: nalgebra::Point3::new(2.0, -2.0, 0.0),
+        p2: nalgebra::Point3::new(2.0, 2.0, 0.0),
+        p3: nalgebra::Point3::new(-2.0, 2.0, 0.0),
+        ..Default::default()
+    };
+    rectangle.draw_config.color = Color::new(0xff, 0, 0, 0x80);
+    scene.add(Box::new(rectangle));
+    scene.draw(&mut ctx);
+    let video_config = VideoConfig {
+        filename: std::env::temp_dir()
+            .join("gmanim_transparent_{}.png")
+            .to_string_lossy()
+            .into_owned(),
+        framerate: 60,
+        output_width: 160,
+        output_height: 90,
+        color_order: ColorOrder::Rgba,
+    };
+    let backend = PngSequenceBackend::new(&video_config);
+    let path = backend.frame_path(0);
+    let mut video_backend_var = VideoBackend {
+        backend_type: VideoBackendType::PngSequence(backend),
+    };
+    video_backend_var.write_frame(ctx.image_bytes());
+    let frame = tiny_skia::Pixmap::load_png(&path).unwrap();
+    assert_eq!(frame.pixel(0, 0).unwrap().alpha(), 0);
+    // the half transparent stroke keeps its color and alpha through the png
+    let edge = frame.pixel(60, 45).unwrap().demultiply();
+    assert!(edge.red() >= 0xfe && edge.alpha().abs_diff(0x80) <= 1);
+}
+
+#[test]
+fn test_linear_light() {
+    use mobjects::Rectangle;
+    let draw = |linear_light: bool, supersampling: u32| {
+        let scene_config = SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            linear_light,
+            supersampling,
+            ..Default::default()
+        };
+        let mut ctx = Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            scene_config,
+        );
+        let mut scene = Scene::new();
+        let mut rectangle = Rectangle {
+            p0: nalgebra::Point3::new(-2.0, -2.0, 0.0),
+            p1: nalgebra::Point3::new(2.0, -2.0, 0.0),
+            p2: nalgebra::Point3::new(2.0, 2.0, 0.0),
+            p3: nalgebra::Point3::new(-2.0, 2.0, 0.0),
+            ..Default::default()
+        };
+        rectangle.draw_config.color = Color::new(0xff, 0xff, 0xff, 0x80);
+        scene.add(Box::new(rectangle));
+        scene.draw(&mut ctx);
+        ctx.image_bytes()[(45 * 160 + 60) * 4]
+    };
+    // half covering white over black is half the light, which sRGB encodes as 0xbc
+    assert!(draw(false, 1).abs_diff(0x80) <= 1);
+    assert!(draw(true, 1).abs_diff(0xbc) <= 2);
+    assert!(draw(true, 2).abs_diff(0xbc) <= 2);
+}
+
 #[test]
 fn write_frame() {
     use mobjects::Rectangle;
@@ -354,3 +2214,25 @@ fn thread_frame_pass() {
     }
     // thread_handler.join();
 }
+
+#[test]
+fn test_frame_rng() {
+    let mut scene = Scene::new();
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(16, 9).unwrap()),
+        SceneConfig {
+            output_width: 16,
+            output_height: 9,
+            seed: 7,
+            ..Default::default()
+        },
+    );
+    let mut draw = |scene: &Scene| {
+        scene.draw(&mut ctx);
+        ctx.rng.next_u64()
+    };
+    let first = draw(&scene);
+    assert_eq!(first, draw(&scene));
+    scene.frame_index += 1;
+    assert_ne!(first, draw(&scene));
+}
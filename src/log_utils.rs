@@ -18,3 +18,51 @@ pub fn setup_logger() -> Result<(), fern::InitError> {
         .apply()?;
     Ok(())
 }
+
+// how much the render loop reports on stderr, raised by every -v of the cli
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    // errors only
+    Quiet,
+    // warnings, e.g. missing fonts
+    #[default]
+    Normal,
+    // a line per scene and animation
+    Verbose,
+    // spans of every rendered frame with their timing
+    Debug,
+    // encoded frames and the depth of the encoder queue
+    Trace,
+}
+
+impl Verbosity {
+    pub fn level(&self) -> tracing::level_filters::LevelFilter {
+        use tracing::level_filters::LevelFilter;
+        match self {
+            Verbosity::Quiet => LevelFilter::ERROR,
+            Verbosity::Normal => LevelFilter::WARN,
+            Verbosity::Verbose => LevelFilter::INFO,
+            Verbosity::Debug => LevelFilter::DEBUG,
+            Verbosity::Trace => LevelFilter::TRACE,
+        }
+    }
+
+    pub fn more(self) -> Self {
+        match self {
+            Verbosity::Quiet => Verbosity::Normal,
+            Verbosity::Normal => Verbosity::Verbose,
+            Verbosity::Verbose => Verbosity::Debug,
+            Verbosity::Debug | Verbosity::Trace => Verbosity::Trace,
+        }
+    }
+}
+
+// print the tracing events of gmanim, and the log records of it and its dependencies,
+// to stderr. an application with its own subscriber doesn't call it
+pub fn setup_tracing(verbosity: Verbosity) -> Result<(), String> {
+    tracing_subscriber::fmt()
+        .with_max_level(verbosity.level())
+        .with_writer(std::io::stderr)
+        .try_init()
+        .map_err(|e| format!("can't set up tracing: {e}"))
+}
@@ -0,0 +1,383 @@
+use std::fmt::Write;
+
+use tiny_skia::{FillRule, Paint, Path, PathSegment, Point, Shader};
+
+use crate::{mobjects::DrawConfig, Context, ContextType, GMFloat, Scene, SceneConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ShapeKind {
+    Fill(FillRule),
+    Stroke,
+}
+
+// one fill or stroke call, its values already written as lottie json
+struct RecordedShape {
+    kind: ShapeKind,
+    contours: Vec<String>,
+    color: String,
+    opacity: String,
+    width: String,
+}
+
+// the fills and strokes of one frame in output pixels, used by ContextType::Lottie.
+// images, glows and shadows are not recorded
+#[derive(Default)]
+pub struct LottieFrame {
+    shapes: Vec<RecordedShape>,
+}
+
+impl LottieFrame {
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    pub fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule) {
+        // only solid colors are used by the mobjects
+        let Shader::SolidColor(color) = &paint.shader else {
+            return;
+        };
+        self.push(path, ShapeKind::Fill(fill_rule), *color, 0.0);
+    }
+
+    // dashes, caps and joins are not exported, lottie strokes get round ones
+    pub fn stroke_path(&mut self, path: &Path, draw_config: &DrawConfig, scale_factor: GMFloat) {
+        let Shader::SolidColor(color) = draw_config.get_paint().shader else {
+            return;
+        };
+        let width = draw_config.stoke_width * scale_factor;
+        self.push(path, ShapeKind::Stroke, color, width);
+    }
+
+    fn push(&mut self, path: &Path, kind: ShapeKind, color: tiny_skia::Color, width: GMFloat) {
+        self.shapes.push(RecordedShape {
+            kind,
+            contours: contours(path),
+            color: format!(
+                "[{},{},{},1]",
+                number(color.red() as GMFloat),
+                number(color.green() as GMFloat),
+                number(color.blue() as GMFloat)
+            ),
+            opacity: number(color.alpha() as GMFloat * 100.0),
+            width: number(width),
+        });
+    }
+}
+
+// at most three decimals, enough for pixels and colors
+fn number(x: GMFloat) -> String {
+    let x = (x * 1000.0).round() / 1000.0;
+    // no -0 in the document
+    format!("{}", if x == 0.0 { 0.0 } else { x })
+}
+
+fn point(p: Point) -> String {
+    format!("[{},{}]", number(p.x as GMFloat), number(p.y as GMFloat))
+}
+
+// a lottie path per contour: the vertices with their in and out tangents relative to them
+fn contours(path: &Path) -> Vec<String> {
+    #[derive(Default)]
+    struct Contour {
+        vertices: Vec<Point>,
+        in_tangents: Vec<Point>,
+        out_tangents: Vec<Point>,
+        closed: bool,
+    }
+    impl Contour {
+        fn vertex(&mut self, p: Point, in_tangent: Point) {
+            self.vertices.push(p);
+            self.in_tangents.push(in_tangent);
+            self.out_tangents.push(Point::zero());
+        }
+        fn curve(&mut self, p1: Point, p2: Point, p: Point) {
+            if let (Some(&last), Some(out)) = (self.vertices.last(), self.out_tangents.last_mut()) {
+                *out = p1 - last;
+            }
+            self.vertex(p, p2 - p);
+        }
+        // a closing vertex on the start is merged into it
+        fn close(&mut self) {
+            self.closed = true;
+            if self.vertices.len() > 1 && self.vertices.first() == self.vertices.last() {
+                self.vertices.pop();
+                self.out_tangents.pop();
+                self.in_tangents[0] = self.in_tangents.pop().unwrap_or_default();
+            }
+        }
+        fn to_json(&self) -> String {
+            let list = |points: &[Point]| {
+                let points: Vec<_> = points.iter().map(|p| point(*p)).collect();
+                format!("[{}]", points.join(","))
+            };
+            format!(
+                r#"{{"i":{},"o":{},"v":{},"c":{}}}"#,
+                list(&self.in_tangents),
+                list(&self.out_tangents),
+                list(&self.vertices),
+                self.closed
+            )
+        }
+    }
+
+    let mut done = vec![];
+    let mut contour = Contour::default();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                if !contour.vertices.is_empty() {
+                    done.push(std::mem::take(&mut contour));
+                }
+                contour.vertex(p, Point::zero());
+            }
+            PathSegment::LineTo(p) => contour.vertex(p, Point::zero()),
+            PathSegment::QuadTo(p1, p) => {
+                // the cubic with the same curve
+                let start = contour.vertices.last().copied().unwrap_or(p1);
+                let lerp = |a: Point, b: Point| {
+                    Point::from_xy(a.x + (b.x - a.x) * 2.0 / 3.0, a.y + (b.y - a.y) * 2.0 / 3.0)
+                };
+                contour.curve(lerp(start, p1), lerp(p, p1), p);
+            }
+            PathSegment::CubicTo(p1, p2, p) => contour.curve(p1, p2, p),
+            PathSegment::Close => contour.close(),
+        }
+    }
+    if !contour.vertices.is_empty() {
+        done.push(contour);
+    }
+    done.iter().map(Contour::to_json).collect()
+}
+
+const EMPTY_CONTOUR: &str = r#"{"i":[],"o":[],"v":[],"c":false}"#;
+
+// the values of a property per frame, a key is only added when the value changes
+#[derive(Default)]
+struct Keyframes {
+    keys: Vec<(u64, String)>,
+}
+
+impl Keyframes {
+    fn push(&mut self, frame: u64, value: &str) {
+        if self.keys.last().map(|(_, last)| last.as_str()) != Some(value) {
+            self.keys.push((frame, value.to_owned()));
+        }
+    }
+
+    // hold keys, the values are sampled at every frame so nothing is interpolated
+    fn to_json(&self) -> String {
+        match self.keys.as_slice() {
+            [] => r#"{"a":0,"k":0}"#.to_owned(),
+            [(_, value)] => format!(r#"{{"a":0,"k":{value}}}"#),
+            keys => {
+                let keys: Vec<_> = keys
+                    .iter()
+                    .map(|(frame, value)| format!(r#"{{"t":{frame},"s":[{value}],"h":1}}"#))
+                    .collect();
+                format!(r#"{{"a":1,"k":[{}]}}"#, keys.join(","))
+            }
+        }
+    }
+}
+
+// the nth fill or stroke call of consecutive frames
+struct Layer {
+    draw_index: usize,
+    kind: ShapeKind,
+    first_frame: u64,
+    last_frame: u64,
+    contours: Vec<Keyframes>,
+    color: Keyframes,
+    opacity: Keyframes,
+    width: Keyframes,
+}
+
+impl Layer {
+    fn new(draw_index: usize, kind: ShapeKind, frame: u64) -> Self {
+        Self {
+            draw_index,
+            kind,
+            first_frame: frame,
+            last_frame: frame,
+            contours: vec![],
+            color: Keyframes::default(),
+            opacity: Keyframes::default(),
+            width: Keyframes::default(),
+        }
+    }
+
+    fn push(&mut self, frame: u64, shape: &RecordedShape) {
+        self.last_frame = frame;
+        while self.contours.len() < shape.contours.len() {
+            let mut keyframes = Keyframes::default();
+            if frame > self.first_frame {
+                keyframes.push(self.first_frame, EMPTY_CONTOUR);
+            }
+            self.contours.push(keyframes);
+        }
+        for (i, keyframes) in self.contours.iter_mut().enumerate() {
+            let contour = shape.contours.get(i).map_or(EMPTY_CONTOUR, String::as_str);
+            keyframes.push(frame, contour);
+        }
+        self.color.push(frame, &shape.color);
+        self.opacity.push(frame, &shape.opacity);
+        self.width.push(frame, &shape.width);
+    }
+
+    fn to_json(&self, index: usize) -> String {
+        let mut shapes: Vec<_> = self
+            .contours
+            .iter()
+            .map(|contour| format!(r#"{{"ty":"sh","ks":{}}}"#, contour.to_json()))
+            .collect();
+        shapes.push(match self.kind {
+            ShapeKind::Fill(fill_rule) => format!(
+                r#"{{"ty":"fl","c":{},"o":{},"r":{}}}"#,
+                self.color.to_json(),
+                self.opacity.to_json(),
+                if fill_rule == FillRule::EvenOdd { 2 } else { 1 }
+            ),
+            ShapeKind::Stroke => format!(
+                r#"{{"ty":"st","c":{},"o":{},"w":{},"lc":2,"lj":2}}"#,
+                self.color.to_json(),
+                self.opacity.to_json(),
+                self.width.to_json()
+            ),
+        });
+        let static_value = |k: &str| format!(r#"{{"a":0,"k":{k}}}"#);
+        format!(
+            r#"{{"ddd":0,"ind":{},"ty":4,"nm":"shape {}","sr":1,"ks":{{"o":{},"r":{},"p":{},"a":{},"s":{}}},"ao":0,"shapes":[{}],"ip":{},"op":{},"st":0,"bm":0}}"#,
+            index + 1,
+            self.draw_index,
+            static_value("100"),
+            static_value("0"),
+            static_value("[0,0,0]"),
+            static_value("[0,0,0]"),
+            static_value("[100,100,100]"),
+            shapes.join(","),
+            self.first_frame,
+            self.last_frame + 1
+        )
+    }
+}
+
+// collects the rendered frames of a scene as a lottie (bodymovin) animation, see
+// Scene::lottie_export. every fill and stroke call becomes a shape layer, the nth call of
+// a frame continues the layer of the nth call of the frame before while its kind stays
+// the same. paths, colors, opacities and stroke widths are keyframed on every frame
+// they change, so the animation plays like the video without a background
+#[derive(Default)]
+pub struct LottieExport {
+    pub width: u32,
+    pub height: u32,
+    pub framerate: u32,
+    pub frame_count: u64,
+    layers: Vec<Layer>,
+    // the layers of the last frame by draw call
+    active: Vec<usize>,
+}
+
+impl LottieExport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_frame(&mut self, frame: &LottieFrame) {
+        let index = self.frame_count;
+        self.frame_count += 1;
+        let mut active = Vec::with_capacity(frame.shapes.len());
+        for (i, shape) in frame.shapes.iter().enumerate() {
+            let layer = match self.active.get(i) {
+                Some(&layer) if self.layers[layer].kind == shape.kind => layer,
+                _ => {
+                    self.layers.push(Layer::new(i, shape.kind, index));
+                    self.layers.len() - 1
+                }
+            };
+            self.layers[layer].push(index, shape);
+            active.push(layer);
+        }
+        self.active = active;
+    }
+
+    pub fn to_json(&self) -> String {
+        // the first layer is drawn on top
+        let mut order: Vec<usize> = (0..self.layers.len()).collect();
+        order.sort_by_key(|&i| (self.layers[i].draw_index, self.layers[i].first_frame));
+        order.reverse();
+        let mut json = format!(
+            r#"{{"v":"5.7.0","fr":{},"ip":0,"op":{},"w":{},"h":{},"nm":"gmanim","ddd":0,"assets":[],"layers":["#,
+            self.framerate, self.frame_count, self.width, self.height
+        );
+        for (n, &i) in order.iter().enumerate() {
+            if n > 0 {
+                json.push(',');
+            }
+            let _ = write!(json, "{}", self.layers[i].to_json(n));
+        }
+        json.push_str("]}");
+        json
+    }
+
+    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
+        std::fs::write(file_path, self.to_json())
+    }
+}
+
+impl Scene {
+    // called once per rendered frame
+    pub fn export_lottie_frame(&mut self, scene_config: &SceneConfig) {
+        if self.lottie_export.is_none() {
+            return;
+        }
+        let mut ctx = Context::new(
+            ContextType::Lottie(Default::default()),
+            scene_config.clone(),
+        );
+        self.draw(&mut ctx);
+        let (Some(export), ContextType::Lottie(frame)) = (&mut self.lottie_export, &ctx.ctx_type)
+        else {
+            return;
+        };
+        export.width = scene_config.output_width;
+        export.height = scene_config.output_height;
+        export.framerate = scene_config.framerate;
+        export.add_frame(frame);
+    }
+}
+
+#[test]
+fn test_lottie_export() {
+    use crate::mobjects::{Rectangle, Transform};
+
+    let scene_config = SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        framerate: 10,
+        ..Default::default()
+    };
+    let mut scene = Scene::new();
+    scene.lottie_export = Some(LottieExport::new());
+    scene.add(Box::new(Rectangle::default()));
+    for _ in 0..3 {
+        scene.export_lottie_frame(&scene_config);
+    }
+    scene.mobjects[0]
+        .borrow_mut()
+        .shift(nalgebra::Vector3::new(1.0, 0.0, 0.0));
+    scene.export_lottie_frame(&scene_config);
+    let export = scene.lottie_export.as_ref().unwrap();
+    assert_eq!((export.frame_count, export.layers.len()), (4, 1));
+    let json = export.to_json();
+    assert!(json.starts_with(r#"{"v":"5.7.0","fr":10,"ip":0,"op":4,"w":160,"h":90"#));
+    // the unit square from the scene center, held until it moves at frame 3
+    assert!(json.contains(
+        r#"{"t":0,"s":[{"i":[[0,0],[0,0],[0,0],[0,0],[0,0]],"o":[[0,0],[0,0],[0,0],[0,0],[0,0]],"v":[[80,45],[90,45],[90,35],[80,35],[80,45]],"c":false}],"h":1}"#
+    ));
+    assert!(json.contains(r#"{"t":3,"s":[{"i":"#));
+    assert!(json.contains(
+        r#""ty":"st","c":{"a":0,"k":[0.2,0.8,1,1]},"o":{"a":0,"k":100},"w":{"a":0,"k":2.5}"#
+    ));
+    assert!(json.contains(r#""ip":0,"op":4"#));
+}
@@ -1,11 +1,14 @@
-use nalgebra::Vector2;
+use nalgebra::{Rotation3, Vector2, Vector3};
 
 use crate::{
     mobjects::{coordinate_change_x, coordinate_change_y},
-    Color, Context, ContextType, GMFloat,
+    Color, Context, ContextType, GMFloat, GMPoint,
 };
 
 pub mod constants;
+pub mod rng;
+
+pub use rng::Rng;
 
 pub fn bezier_curve(point_list: &[Vector2<GMFloat>], t: GMFloat) -> Vector2<GMFloat> {
     if point_list.len() < 2 {
@@ -99,21 +102,22 @@ fn test_bezier_curve() {
     let mut ctx = Context::default();
     if let ContextType::TinySKIA(pixmap) = &mut ctx.ctx_type {
         pixmap.fill(tiny_skia::Color::from_rgba8(0, 0, 0, 0xff));
+        let scene_config = &ctx.scene_config;
+        let pixel = |p: Vector2<GMFloat>| {
+            (
+                (coordinate_change_x(p.x, scene_config.width) * scene_config.scale_factor) as f32,
+                (coordinate_change_y(p.y, scene_config.height) * scene_config.scale_factor) as f32,
+            )
+        };
         let mut pb = tiny_skia::PathBuilder::new();
-        pb.move_to(
-            coordinate_change_x(p0.x, ctx.scene_config.width) * ctx.scene_config.scale_factor,
-            coordinate_change_y(p0.y, ctx.scene_config.height) * ctx.scene_config.scale_factor,
-        );
+        let (x, y) = pixel(p0);
+        pb.move_to(x, y);
         let mut t = 0.0;
         let delta_t = 1.0 / 20.0;
         while t < 1.0 {
             t += delta_t;
-            let p = bezier_cubic(p0, p1, p2, t);
-            println!("{:?}", p);
-            pb.line_to(
-                coordinate_change_x(p.x, ctx.scene_config.width) * ctx.scene_config.scale_factor,
-                coordinate_change_y(p.y, ctx.scene_config.height) * ctx.scene_config.scale_factor,
-            );
+            let (x, y) = pixel(bezier_cubic(p0, p1, p2, t));
+            pb.line_to(x, y);
         }
 
         let path = pb.finish().unwrap();
@@ -149,3 +153,97 @@ pub fn point2d_to_point3d(p: nalgebra::Point2<GMFloat>) -> nalgebra::Point3<GMFl
 pub fn point3d_to_point2d(p: nalgebra::Point3<GMFloat>) -> nalgebra::Point2<GMFloat> {
     nalgebra::Point2::new(p.x, p.y)
 }
+
+// the part of a polyline between start and end, both given as a fraction (0.0 ~ 1.0) of its length
+pub fn partial_polyline(points: &[GMPoint], start: GMFloat, end: GMFloat) -> Vec<GMPoint> {
+    if points.len() < 2 || end <= start {
+        return vec![];
+    }
+    let total_length: GMFloat = points.windows(2).map(|w| (w[1] - w[0]).norm()).sum();
+    let start_length = start.clamp(0.0, 1.0) * total_length;
+    let end_length = end.clamp(0.0, 1.0) * total_length;
+    let mut result = vec![];
+    let mut walked = 0.0;
+    for w in points.windows(2) {
+        let seg_length = (w[1] - w[0]).norm();
+        let seg_start = walked;
+        let seg_end = walked + seg_length;
+        walked = seg_end;
+        if seg_end <= start_length || seg_start >= end_length || seg_length == 0.0 {
+            continue;
+        }
+        let point_at = |l: GMFloat| w[0] + (w[1] - w[0]) * ((l - seg_start) / seg_length);
+        if result.is_empty() {
+            result.push(point_at(start_length.max(seg_start)));
+        }
+        result.push(point_at(end_length.min(seg_end)));
+    }
+    result
+}
+
+// move from start to end along a circular arc in the xy plane which turns by arc_angle,
+// a positive angle goes counterclockwise and 0.0 is a straight line. z moves linearly
+pub fn path_along_arc(start: GMPoint, end: GMPoint, arc_angle: GMFloat, t: GMFloat) -> GMPoint {
+    if arc_angle.abs() < 1e-4 {
+        return start + (end - start) * t;
+    }
+    let half = Vector3::new(end.x - start.x, end.y - start.y, 0.0) / 2.0;
+    let mut center = start + half;
+    if (arc_angle.abs() - constants::PI).abs() > 1e-4 {
+        center += Vector3::new(-half.y, half.x, 0.0) / (arc_angle / 2.0).tan();
+    }
+    let rotation = Rotation3::from_axis_angle(&Vector3::z_axis(), arc_angle * t);
+    let mut p = center + rotation * (start - center);
+    p.z = start.z + (end.z - start.z) * t;
+    p
+}
+
+// n points spread over the given points by index, so mobjects with different numbers of points can be matched
+pub fn resample_points(points: &[GMPoint], n: usize) -> Vec<GMPoint> {
+    if points.len() == n || points.is_empty() {
+        return points.to_vec();
+    }
+    if n == 1 || points.len() == 1 {
+        return vec![points[0]; n];
+    }
+    (0..n)
+        .map(|i| {
+            let position = i as GMFloat * (points.len() - 1) as GMFloat / (n - 1) as GMFloat;
+            let index = (position.floor() as usize).min(points.len() - 2);
+            let t = position - index as GMFloat;
+            points[index] + (points[index + 1] - points[index]) * t
+        })
+        .collect()
+}
+
+#[test]
+fn test_partial_polyline() {
+    let points = [
+        GMPoint::new(0.0, 0.0, 0.0),
+        GMPoint::new(1.0, 0.0, 0.0),
+        GMPoint::new(1.0, 1.0, 0.0),
+    ];
+    let half = partial_polyline(&points, 0.0, 0.5);
+    assert_eq!(half.len(), 2);
+    assert!((half[1] - points[1]).norm() < 1e-6);
+    let middle = partial_polyline(&points, 0.25, 0.75);
+    assert_eq!(middle.len(), 3);
+    assert!((middle[0] - GMPoint::new(0.5, 0.0, 0.0)).norm() < 1e-6);
+    assert!((middle[2] - GMPoint::new(1.0, 0.5, 0.0)).norm() < 1e-6);
+}
+
+#[test]
+fn test_path_along_arc() {
+    let start = GMPoint::new(1.0, 0.0, 0.0);
+    let end = GMPoint::new(-1.0, 0.0, 0.0);
+    // half a turn counterclockwise about the origin passes the top
+    let middle = path_along_arc(start, end, constants::PI, 0.5);
+    assert!((middle - GMPoint::new(0.0, 1.0, 0.0)).norm() < 1e-5);
+    let quarter = path_along_arc(start, GMPoint::new(0.0, 1.0, 0.0), constants::PI / 2.0, 1.0);
+    assert!((quarter - GMPoint::new(0.0, 1.0, 0.0)).norm() < 1e-5);
+    let straight = path_along_arc(start, end, 0.0, 0.25);
+    assert!((straight - GMPoint::new(0.5, 0.0, 0.0)).norm() < 1e-6);
+    // z is not part of the arc
+    let raised = path_along_arc(start, GMPoint::new(-1.0, 0.0, 2.0), constants::PI, 0.5);
+    assert!((raised - GMPoint::new(0.0, 1.0, 1.0)).norm() < 1e-5);
+}
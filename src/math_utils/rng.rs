@@ -0,0 +1,76 @@
+use crate::{GMFloat, GMPoint};
+
+// splitmix64, small and the same sequence for a seed on every platform and compiler.
+// not for anything security related
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    // an independent generator for key, e.g. one per frame or per mobject,
+    // so values don't depend on how many numbers were taken before
+    pub fn fork(&self, key: u64) -> Rng {
+        let mut rng = Rng::new(self.state ^ key.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        Rng::new(rng.next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in 0..1
+    pub fn next_float(&mut self) -> GMFloat {
+        // 24 bits fit exactly into the mantissa of f32
+        (self.next_u64() >> 40) as GMFloat / (1u64 << 24) as GMFloat
+    }
+
+    // uniform in min..max
+    pub fn range(&mut self, min: GMFloat, max: GMFloat) -> GMFloat {
+        min + (max - min) * self.next_float()
+    }
+
+    // normal distribution with mean 0 and standard deviation 1, box-muller
+    pub fn normal(&mut self) -> GMFloat {
+        let u = 1.0 - self.next_float();
+        let v = self.next_float();
+        (-2.0 * u.ln()).sqrt() * (std::f32::consts::TAU as GMFloat * v).cos()
+    }
+
+    // uniform in the box between the min and max corner
+    pub fn point_in_box(&mut self, min: &GMPoint, max: &GMPoint) -> GMPoint {
+        GMPoint::new(
+            self.range(min.x, max.x),
+            self.range(min.y, max.y),
+            self.range(min.z, max.z),
+        )
+    }
+}
+
+#[test]
+fn test_rng() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    let values: Vec<_> = (0..100).map(|_| a.next_float()).collect();
+    assert!(values.iter().all(|v| (0.0..1.0).contains(v)));
+    assert!(values
+        .iter()
+        .zip((0..100).map(|_| b.next_float()))
+        .all(|(x, y)| *x == y));
+    // fixed output so a change of the sequence shows up
+    assert_eq!(Rng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    assert_ne!(
+        Rng::new(42).fork(1).next_u64(),
+        Rng::new(42).fork(2).next_u64()
+    );
+    let mean = (0..1000).map(|_| a.normal()).sum::<GMFloat>() / 1000.0;
+    assert!(mean.abs() < 0.1);
+}
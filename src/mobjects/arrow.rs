@@ -0,0 +1,127 @@
+use nalgebra::Vector3;
+use tiny_skia::{FillRule, PathBuilder};
+
+use crate::{
+    mobjects::{Draw, DrawConfig, Mobject, Transform},
+    Context, GMFloat, GMPoint,
+};
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arrow {
+    pub start: GMPoint,
+    pub end: GMPoint,
+    pub tip_length: GMFloat,
+    pub draw_config: DrawConfig,
+}
+
+impl Default for Arrow {
+    fn default() -> Self {
+        let draw_config = DrawConfig {
+            stoke_width: 0.05,
+            ..Default::default()
+        };
+        Self {
+            start: GMPoint::origin(),
+            end: GMPoint::new(1.0, 0.0, 0.0),
+            tip_length: 0.25,
+            draw_config,
+        }
+    }
+}
+
+impl Arrow {
+    pub fn new(start: GMPoint, end: GMPoint) -> Self {
+        Self {
+            start,
+            end,
+            ..Default::default()
+        }
+    }
+}
+
+impl Draw for Arrow {
+    fn draw(&self, ctx: &mut Context) {
+        let direction = self.end - self.start;
+        let length = direction.xy().norm();
+        if length <= GMFloat::EPSILON {
+            return;
+        }
+        let direction = direction / length;
+        let normal = Vector3::new(-direction.y, direction.x, 0.0);
+        // the tip never gets longer than the arrow itself
+        let tip_length = self.tip_length.min(length);
+        let tip_base = self.end - direction * tip_length;
+
+        let scene_config = &ctx.scene_config;
+        let to_pixel = |p: GMPoint| scene_config.project(&p);
+        let start = to_pixel(self.start);
+        let shaft_end = to_pixel(tip_base);
+        let tip = [
+            to_pixel(self.end),
+            to_pixel(tip_base + normal * tip_length / 2.0),
+            to_pixel(tip_base - normal * tip_length / 2.0),
+        ];
+        let paint = self.draw_config.get_paint();
+        if length > tip_length {
+            let mut pb = PathBuilder::new();
+            pb.move_to(start.x, start.y);
+            pb.line_to(shaft_end.x, shaft_end.y);
+            let path = pb.finish().unwrap();
+            ctx.stroke_path(&path, &self.draw_config);
+        }
+
+        let mut pb = PathBuilder::new();
+        pb.move_to(tip[0].x, tip[0].y);
+        pb.line_to(tip[1].x, tip[1].y);
+        pb.line_to(tip[2].x, tip[2].y);
+        pb.close();
+        if let Some(path) = pb.finish() {
+            ctx.fill_path(&path, &paint, FillRule::Winding);
+        }
+    }
+}
+
+impl Transform for Arrow {
+    fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {
+        self.start = transform.transform_point(&self.start);
+        self.end = transform.transform_point(&self.end);
+    }
+}
+
+impl Mobject for Arrow {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        vec![self.start, self.end]
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        let &[start, end] = points else {
+            return;
+        };
+        self.start = start;
+        self.end = end;
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::Arrow(self.clone()))
+    }
+}
+
+#[test]
+fn test_draw_arrow() {
+    let mut ctx = Context::default();
+    let mut scene = crate::Scene::new();
+    scene.add(Box::new(Arrow::new(
+        GMPoint::new(-2.0, -1.0, 0.0),
+        GMPoint::new(3.0, 2.0, 0.0),
+    )));
+    scene.save_png(&mut ctx, "arrow.png");
+}
@@ -0,0 +1,150 @@
+use crate::{Context, GMFloat, GMPoint};
+
+use super::{
+    path::{pixel_path, PathElement},
+    Draw, DrawConfig, Mobject, Transform,
+};
+
+// draws mobject only inside of clip, a closed path in scene coordinates.
+// wrap a MobjectGroup to clip several mobjects together.
+// the clip points come after the points of mobject in get_points, so they move with
+// transforms and animations and a changed clip redraws the frame
+pub struct Clipped {
+    pub mobject: Box<dyn Mobject>,
+    pub clip: Vec<PathElement>,
+}
+
+impl Clipped {
+    pub fn new(mobject: Box<dyn Mobject>, clip: Vec<PathElement>) -> Self {
+        Self { mobject, clip }
+    }
+
+    // clipped to the rectangle with the opposite corners min and max
+    pub fn rect(mobject: Box<dyn Mobject>, min: GMPoint, max: GMPoint) -> Self {
+        Self::new(
+            mobject,
+            vec![
+                PathElement::MoveTo(min),
+                PathElement::LineTo(GMPoint::new(max.x, min.y, min.z)),
+                PathElement::LineTo(max),
+                PathElement::LineTo(GMPoint::new(min.x, max.y, max.z)),
+                PathElement::Close,
+            ],
+        )
+    }
+}
+
+impl Draw for Clipped {
+    fn draw(&self, ctx: &mut Context) {
+        // a clip without area hides the mobject
+        let Some(path) = pixel_path(&self.clip, &ctx.scene_config) else {
+            return;
+        };
+        ctx.push_clip(&path);
+        self.mobject.draw(ctx);
+        ctx.pop_clip();
+    }
+}
+
+impl Transform for Clipped {
+    fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {
+        self.mobject.transform(transform);
+        for e in &mut self.clip {
+            let points: Vec<_> = e
+                .get_points()
+                .iter()
+                .map(|p| transform.transform_point(p))
+                .collect();
+            e.set_points(&points);
+        }
+    }
+}
+
+impl Mobject for Clipped {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.mobject.get_draw_config()
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.mobject.set_draw_config(draw_config);
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        let mut points = self.mobject.get_points();
+        points.extend(self.clip.iter().flat_map(|e| e.get_points()));
+        points
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if points.len() != self.get_points().len() {
+            return;
+        }
+        let count = self.mobject.get_points().len();
+        self.mobject.set_points(&points[..count]);
+        let mut offset = count;
+        for e in &mut self.clip {
+            offset += e.set_points(&points[offset..]);
+        }
+    }
+    fn advance_time(&mut self, dt: GMFloat) {
+        self.mobject.advance_time(dt);
+    }
+    fn is_time_dependent(&self) -> bool {
+        self.mobject.is_time_dependent()
+    }
+    fn outline(&self) -> Vec<PathElement> {
+        self.mobject.outline()
+    }
+    // the parts of the clipped mobject, the clip path follows only the whole
+    fn submobjects(&self) -> Vec<&dyn Mobject> {
+        self.mobject.submobjects()
+    }
+    fn submobjects_mut(&mut self) -> Vec<&mut dyn Mobject> {
+        self.mobject.submobjects_mut()
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(Clipped::new(
+            self.mobject.clone_box()?,
+            self.clip.clone(),
+        )))
+    }
+    fn signature(&self, hasher: &mut dyn std::hash::Hasher) {
+        self.mobject.signature(hasher);
+        super::shape_signature(self, hasher);
+    }
+}
+
+#[test]
+fn test_clipped_draw() {
+    use super::Dot;
+    use crate::{Color, ContextType, SceneConfig};
+    let red = Color::new(0xff, 0, 0, 0xff);
+    let dot = Dot::new(
+        GMPoint::origin(),
+        3.0,
+        red,
+        DrawConfig {
+            color: red,
+            ..Default::default()
+        },
+    );
+    // only the right half of the dot is drawn
+    let clipped = Clipped::rect(
+        Box::new(dot),
+        GMPoint::new(0.0, -4.0, 0.0),
+        GMPoint::new(4.0, 4.0, 0.0),
+    );
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            ..Default::default()
+        },
+    );
+    clipped.draw(&mut ctx);
+    let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+        unreachable!()
+    };
+    let red = |x: u32| pixmap.pixel(x, 45).unwrap().red();
+    assert_eq!((red(70), red(90)), (0, 0xff));
+    assert!(ctx.clip.paths.is_empty());
+}
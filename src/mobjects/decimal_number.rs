@@ -0,0 +1,158 @@
+use std::{cell::Cell, rc::Rc};
+
+use nalgebra::Vector3;
+
+use crate::{GMFloat, GMPoint, ValueTracker};
+
+use super::{text::Text, Draw, DrawConfig, Mobject, Transform};
+
+// a number shown as text, it follows the value of its tracker and lays out the digits
+// again whenever the shown string changes
+pub struct DecimalNumber {
+    pub tracker: ValueTracker,
+    pub num_decimal_places: usize,
+    pub position: GMPoint,
+    pub font_size: GMFloat,
+    pub draw_config: DrawConfig,
+    text: Text,
+}
+
+impl DecimalNumber {
+    pub fn new(
+        value: GMFloat,
+        num_decimal_places: usize,
+        position: GMPoint,
+        font_size: GMFloat,
+        draw_config: DrawConfig,
+    ) -> Self {
+        Self::with_tracker(
+            Rc::new(Cell::new(value)),
+            num_decimal_places,
+            position,
+            font_size,
+            draw_config,
+        )
+    }
+
+    // show the value of an existing tracker, e.g. one that is animated by keyframes
+    pub fn with_tracker(
+        tracker: ValueTracker,
+        num_decimal_places: usize,
+        position: GMPoint,
+        font_size: GMFloat,
+        draw_config: DrawConfig,
+    ) -> Self {
+        let text = Text::new(
+            format_decimal(tracker.get(), num_decimal_places),
+            position,
+            font_size,
+            draw_config,
+        );
+        Self {
+            tracker,
+            num_decimal_places,
+            position,
+            font_size,
+            draw_config,
+            text,
+        }
+    }
+
+    pub fn get_value(&self) -> GMFloat {
+        self.tracker.get()
+    }
+
+    pub fn set_value(&mut self, value: GMFloat) {
+        self.tracker.set(value);
+        self.update_text();
+    }
+
+    fn update_text(&mut self) {
+        let text = format_decimal(self.tracker.get(), self.num_decimal_places);
+        if text != self.text.text || self.text.position != self.position {
+            self.text = Text::new(text, self.position, self.font_size, self.draw_config);
+        }
+    }
+}
+
+pub fn format_decimal(value: GMFloat, num_decimal_places: usize) -> String {
+    let text = format!("{:.*}", num_decimal_places, value);
+    // no "-0.00" for values that round to zero
+    match text.strip_prefix('-') {
+        Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => rest.to_owned(),
+        _ => text,
+    }
+}
+
+// only the position follows transforms, the digits are laid out again at the new position
+impl Transform for DecimalNumber {
+    fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {
+        self.position = transform * self.position;
+        self.update_text();
+    }
+}
+
+impl Draw for DecimalNumber {
+    fn draw(&self, ctx: &mut crate::Context) {
+        self.text.draw(ctx);
+    }
+}
+
+impl Mobject for DecimalNumber {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+        self.text.set_draw_config(draw_config);
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.text.get_points()
+    }
+    // the digits are not deformed, the number is moved by the average displacement
+    fn set_points(&mut self, points: &[GMPoint]) {
+        let old_points = self.get_points();
+        if old_points.is_empty() {
+            return;
+        }
+        let displacement = points
+            .iter()
+            .zip(&old_points)
+            .fold(Vector3::zeros(), |acc, (p, o)| acc + (p - o))
+            / old_points.len() as GMFloat;
+        self.position += displacement;
+        self.update_text();
+    }
+    // pick up values written to the tracker by animations or updaters
+    fn advance_time(&mut self, dt: GMFloat) {
+        self.update_text();
+    }
+    // a tracker held elsewhere can be written between frames, so cached pixels may be stale
+    fn is_time_dependent(&self) -> bool {
+        Rc::strong_count(&self.tracker) > 1
+    }
+    fn signature(&self, hasher: &mut dyn std::hash::Hasher) {
+        self.text.signature(hasher);
+    }
+}
+
+#[test]
+fn test_format_decimal() {
+    assert_eq!(format_decimal(1.23456, 2), "1.23");
+    assert_eq!(format_decimal(-0.001, 2), "0.00");
+    assert_eq!(format_decimal(-2.5, 1), "-2.5");
+    assert_eq!(format_decimal(41.6, 0), "42");
+}
+
+#[test]
+fn test_shared_tracker_is_time_dependent() {
+    let mut number = DecimalNumber::new(1.0, 2, GMPoint::origin(), 1.0, DrawConfig::default());
+    assert!(!number.is_time_dependent());
+    let tracker = number.tracker.clone();
+    assert!(number.is_time_dependent());
+    tracker.set(2.0);
+    number.advance_time(0.0);
+    assert_eq!(number.text.text, "2.00");
+    drop(tracker);
+    assert!(!number.is_time_dependent());
+}
@@ -1,11 +1,13 @@
-use nalgebra::Point3;
+use nalgebra::{Point3, Vector3};
 use tiny_skia::{FillRule, LineCap, LineJoin, Paint, PathBuilder, Stroke};
 
 use crate::{
     mobjects::{Draw, DrawConfig, Mobject, Transform},
-    Color, Context, GMFloat,
+    Color, Context, GMFloat, GMPoint,
 };
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dot {
     position: Point3<GMFloat>,
     radius: GMFloat,
@@ -42,36 +44,16 @@ impl Dot {
 
 impl Draw for Dot {
     fn draw(&self, ctx: &mut Context) {
-        match &mut ctx.ctx_type {
-            crate::ContextType::TinySKIA(p) => {
-                println!("Drawing dot");
-                let scale_factor = ctx.scene_config.scale_factor;
-                let mut pb = tiny_skia::PathBuilder::new();
-                let path = PathBuilder::from_circle(
-                    ctx.scene_config.convert_coord_x(self.position.x),
-                    ctx.scene_config.convert_coord_y(self.position.y),
-                    self.radius * scale_factor,
-                )
-                .unwrap();
-
-                let mut stroke = Stroke::default();
-                stroke.width = self.draw_config.stoke_width * scale_factor;
-                stroke.line_cap = LineCap::Round;
-                stroke.line_join = LineJoin::Round;
-                let mut paint = Paint::default();
-                paint.set_color(self.draw_config.color.into());
-                paint.anti_alias = true;
-
-                p.fill_path(
-                    &path,
-                    &paint,
-                    FillRule::Winding,
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
-            }
-            _ => {}
-        }
+        let mut pb = tiny_skia::PathBuilder::new();
+        let center = ctx.scene_config.project(&self.position);
+        let path = PathBuilder::from_circle(
+            center.x,
+            center.y,
+            (self.radius * ctx.scene_config.pixel_scale_at(&self.position)) as f32,
+        )
+        .unwrap();
+        self.draw_config
+            .fill_and_stroke(ctx, &path, FillRule::Winding);
     }
 }
 impl Transform for Dot {
@@ -80,4 +62,41 @@ impl Transform for Dot {
     }
 }
 
-impl Mobject for Dot {}
+impl Mobject for Dot {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        vec![
+            self.position - Vector3::new(self.radius, self.radius, 0.0),
+            self.position + Vector3::new(self.radius, self.radius, 0.0),
+        ]
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        let [p0, p1] = points else {
+            return;
+        };
+        self.position = nalgebra::center(p0, p1);
+        self.radius = ((p1.x - p0.x) / 2.0).abs();
+    }
+    // the circle, counterclockwise from the right
+    fn outline(&self) -> Vec<crate::mobjects::path::PathElement> {
+        let points: Vec<_> = (0..32)
+            .map(|i| {
+                let angle = i as GMFloat / 32.0 * 2.0 * crate::math_utils::constants::PI;
+                self.position + Vector3::new(angle.cos(), angle.sin(), 0.0) * self.radius
+            })
+            .collect();
+        crate::mobjects::path::polyline_elements(&points, true)
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::Dot(self.clone()))
+    }
+}
@@ -35,9 +35,11 @@ fn test_write_svg() {
     let f = Formula {
         formula_text: "pi".to_owned(),
     };
-    let typst_path = "formula.typst";
+    let typst_path = std::env::temp_dir().join("gmanim_formula.typst");
+    let typst_path = typst_path.to_str().unwrap();
     f.write_to_typst(typst_path);
-    let svg_path = "formula.svg";
+    let svg_path = std::env::temp_dir().join("gmanim_formula.svg");
+    let svg_path = svg_path.to_str().unwrap();
     compile_to_svg(typst_path, svg_path);
     assert!(std::path::Path::new(svg_path).exists());
 }
@@ -1,6 +1,6 @@
-use crate::GMFloat;
+use crate::{GMFloat, GMPoint};
 
-use super::{Draw, Mobject};
+use super::{Draw, DrawConfig, Mobject};
 
 pub struct MobjectGroup {
     pub mobjects: Vec<Box<dyn Mobject>>,
@@ -16,10 +16,124 @@ impl super::Transform for MobjectGroup {
 
 impl Draw for MobjectGroup {
     fn draw(&self, ctx: &mut crate::Context) {
+        let mut mobjects: Vec<_> = self.mobjects.iter().collect();
+        ctx.scene_config
+            .sort_back_to_front(&mut mobjects, |m| m.get_center());
+        let stroke_behind = self
+            .mobjects
+            .iter()
+            .any(|m| m.get_draw_config().stroke_behind);
+        ctx.draw_stroke_passes(stroke_behind, |ctx| {
+            for m in &mobjects {
+                m.draw(ctx);
+            }
+        });
+    }
+}
+
+impl Mobject for MobjectGroup {
+    // a group has no style of its own, it reports the first child's one
+    fn get_draw_config(&self) -> DrawConfig {
+        self.mobjects
+            .first()
+            .map(|m| m.get_draw_config())
+            .unwrap_or_default()
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        for m in &mut self.mobjects {
+            m.set_draw_config(draw_config);
+        }
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.mobjects.iter().flat_map(|m| m.get_points()).collect()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if points.len() != self.get_points().len() {
+            return;
+        }
+        let mut offset = 0;
+        for m in &mut self.mobjects {
+            let count = m.get_points().len();
+            m.set_points(&points[offset..offset + count]);
+            offset += count;
+        }
+    }
+    fn outline(&self) -> Vec<super::path::PathElement> {
+        self.mobjects.iter().flat_map(|m| m.outline()).collect()
+    }
+    fn submobjects(&self) -> Vec<&dyn Mobject> {
+        self.mobjects.iter().map(|m| m.as_ref()).collect()
+    }
+    fn submobjects_mut(&mut self) -> Vec<&mut dyn Mobject> {
+        self.mobjects
+            .iter_mut()
+            .map(|m| m.as_mut() as &mut dyn Mobject)
+            .collect()
+    }
+    // only when all children can be copied
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        let mobjects = self
+            .mobjects
+            .iter()
+            .map(|m| m.clone_box())
+            .collect::<Option<_>>()?;
+        Some(Box::new(MobjectGroup { mobjects }))
+    }
+    // only when all children can be saved
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        let mobjects = self
+            .mobjects
+            .iter()
+            .map(|m| m.to_data())
+            .collect::<Option<_>>()?;
+        Some(crate::scene_state::MobjectData::Group { mobjects })
+    }
+    fn signature(&self, hasher: &mut dyn std::hash::Hasher) {
         for m in &self.mobjects {
-            m.draw(ctx);
+            m.signature(hasher);
         }
     }
 }
 
-impl Mobject for MobjectGroup {}
+#[test]
+fn test_stroke_behind() {
+    use super::Dot;
+    use crate::{Color, Context, ContextType, SceneConfig};
+    let draw = |stroke_behind: bool| {
+        let dot = |x: GMFloat, color: Color| -> Box<dyn Mobject> {
+            let draw_config = DrawConfig {
+                color,
+                stoke_width: 0.5,
+                outline: true,
+                stroke_behind,
+                ..Default::default()
+            };
+            Box::new(Dot::new(GMPoint::new(x, 0.0, 0.0), 1.5, color, draw_config))
+        };
+        let group = MobjectGroup {
+            mobjects: vec![
+                dot(-1.0, Color::new(0xff, 0, 0, 0xff)),
+                dot(1.0, Color::new(0, 0, 0xff, 0xff)),
+            ],
+        };
+        let mut ctx = Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            SceneConfig {
+                output_width: 160,
+                output_height: 90,
+                scale_factor: 10.0,
+                ..Default::default()
+            },
+        );
+        group.draw(&mut ctx);
+        let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+            unreachable!()
+        };
+        let p = pixmap.pixel(73, 45).unwrap();
+        (p.red(), p.blue())
+    };
+    // the stroke of the blue dot covers the red fill unless it is drawn behind all fills
+    assert_eq!(draw(false), (0, 0xff));
+    assert_eq!(draw(true), (0xff, 0));
+}
@@ -0,0 +1,161 @@
+use tiny_skia::FillRule;
+
+use crate::{ColorMap, Context, GMFloat, GMPoint};
+
+use super::{Draw, DrawConfig, Mobject, Transform};
+
+// a grid of cells colored by value, values[0] is the top row
+pub struct Heatmap {
+    pub values: Vec<Vec<GMFloat>>,
+    pub color_map: ColorMap,
+    // the values mapped to the two ends of color_map
+    pub range: (GMFloat, GMFloat),
+    // top left, top right, bottom right, bottom left
+    pub corners: [GMPoint; 4],
+    pub draw_config: DrawConfig,
+}
+
+impl Heatmap {
+    // spans the rectangle between the min and max corner, range is taken from the values
+    pub fn new(values: Vec<Vec<GMFloat>>, color_map: ColorMap, min: GMPoint, max: GMPoint) -> Self {
+        let all = values.iter().flatten().copied();
+        let low = all.clone().fold(GMFloat::INFINITY, GMFloat::min);
+        let high = all.fold(GMFloat::NEG_INFINITY, GMFloat::max);
+        Self {
+            values,
+            color_map,
+            range: (low, high),
+            corners: [
+                GMPoint::new(min.x, max.y, min.z),
+                GMPoint::new(max.x, max.y, min.z),
+                GMPoint::new(max.x, min.y, min.z),
+                GMPoint::new(min.x, min.y, min.z),
+            ],
+            draw_config: DrawConfig {
+                outline: false,
+                ..Default::default()
+            },
+        }
+    }
+
+    // u goes left to right and v top to bottom, both in 0..1
+    fn point_at(&self, u: GMFloat, v: GMFloat) -> GMPoint {
+        let [tl, tr, br, bl] = self.corners;
+        let top = tl.coords.lerp(&tr.coords, u);
+        let bottom = bl.coords.lerp(&br.coords, u);
+        GMPoint::from(top.lerp(&bottom, v))
+    }
+}
+
+impl Draw for Heatmap {
+    fn draw(&self, ctx: &mut Context) {
+        let rows = self.values.len();
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        for (i, row) in self.values.iter().enumerate() {
+            let columns = row.len();
+            for (j, value) in row.iter().enumerate() {
+                let (u0, u1) = (
+                    j as GMFloat / columns as GMFloat,
+                    (j + 1) as GMFloat / columns as GMFloat,
+                );
+                let (v0, v1) = (
+                    i as GMFloat / rows as GMFloat,
+                    (i + 1) as GMFloat / rows as GMFloat,
+                );
+                let mut pb = tiny_skia::PathBuilder::new();
+                let p = ctx
+                    .scene_config
+                    .project_with(&projection_matrix, &self.point_at(u0, v0));
+                pb.move_to(p.x, p.y);
+                for (u, v) in [(u1, v0), (u1, v1), (u0, v1)] {
+                    let p = ctx
+                        .scene_config
+                        .project_with(&projection_matrix, &self.point_at(u, v));
+                    pb.line_to(p.x, p.y);
+                }
+                pb.close();
+                if let Some(path) = pb.finish() {
+                    let draw_config = DrawConfig {
+                        color: self.color_map.sample_range(*value, self.range),
+                        ..self.draw_config
+                    };
+                    draw_config.fill_and_stroke(ctx, &path, FillRule::Winding);
+                }
+            }
+        }
+    }
+}
+
+impl Transform for Heatmap {
+    fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {
+        for p in &mut self.corners {
+            *p = transform.transform_point(p);
+        }
+    }
+}
+
+impl Mobject for Heatmap {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.corners.to_vec()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if let Ok(corners) = points.try_into() {
+            self.corners = corners;
+        }
+    }
+    // the cells are colored by the values
+    fn signature(&self, mut hasher: &mut dyn std::hash::Hasher) {
+        use std::hash::Hash;
+        super::shape_signature(self, hasher);
+        for v in self.values.iter().flatten() {
+            v.to_bits().hash(&mut hasher);
+        }
+        format!("{:?} {:?}", self.range, self.color_map).hash(&mut hasher);
+    }
+}
+
+#[test]
+fn test_heatmap() {
+    use crate::{ContextType, SceneConfig};
+    let heatmap = Heatmap::new(
+        vec![vec![0.0, 1.0], vec![2.0, 3.0]],
+        ColorMap::viridis(),
+        GMPoint::new(-1.0, -1.0, 0.0),
+        GMPoint::new(1.0, 1.0, 0.0),
+    );
+    assert_eq!(heatmap.range, (0.0, 3.0));
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            ..Default::default()
+        },
+    );
+    heatmap.draw(&mut ctx);
+    let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+        unreachable!()
+    };
+    // the lowest value is in the top left cell, the highest in the bottom right
+    let top_left = pixmap.pixel(75, 40).unwrap();
+    assert_eq!(
+        (top_left.red(), top_left.green(), top_left.blue()),
+        (0x44, 0x01, 0x54)
+    );
+    let bottom_right = pixmap.pixel(85, 50).unwrap();
+    assert_eq!(
+        (
+            bottom_right.red(),
+            bottom_right.green(),
+            bottom_right.blue()
+        ),
+        (0xfd, 0xe7, 0x25)
+    );
+}
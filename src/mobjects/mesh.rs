@@ -0,0 +1,240 @@
+use nalgebra::Vector3;
+use tiny_skia::FillRule;
+
+use crate::{linear_to_srgb, srgb_to_linear, Color, Context, GMFloat, GMPoint};
+
+use super::{Draw, DrawConfig, Mobject, Transform};
+
+// triangles of a 3d model, drawn back to front like Surface. every face has one color,
+// darkened by the angle between its normal and light
+pub struct Mesh {
+    pub faces: Vec<[GMPoint; 3]>,
+    // one color per face, faces without one use draw_config.color
+    pub face_colors: Vec<Color>,
+    // direction towards the light, None draws the colors unshaded
+    pub light: Option<Vector3<GMFloat>>,
+    // brightness of faces turned away from the light
+    pub ambient: GMFloat,
+    pub draw_config: DrawConfig,
+}
+
+impl Mesh {
+    pub fn new(faces: Vec<[GMPoint; 3]>) -> Self {
+        Self {
+            faces,
+            face_colors: Vec::new(),
+            light: Some(Vector3::new(-1.0, 1.0, 2.0)),
+            ambient: 0.3,
+            draw_config: DrawConfig {
+                stoke_width: 0.0,
+                fill: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    // the meshes of the default scene of a .gltf or .glb file, see gltf_faces
+    #[cfg(feature = "gltf")]
+    pub fn from_gltf(path: &str) -> Result<Self, String> {
+        let (faces, face_colors) = gltf_faces(path)?;
+        Ok(Self {
+            face_colors,
+            ..Self::new(faces)
+        })
+    }
+
+    fn shaded(&self, face: &[GMPoint; 3], color: Color) -> Color {
+        let Some(light) = self.light else {
+            return color;
+        };
+        // faces are lit from both sides, models often have open or flipped ones
+        let normal = (face[1] - face[0]).cross(&(face[2] - face[0]));
+        let brightness = match (normal.try_normalize(0.0), light.try_normalize(0.0)) {
+            (Some(n), Some(l)) => self.ambient + (1.0 - self.ambient) * n.dot(&l).abs(),
+            _ => 1.0,
+        };
+        let channel = |c: u8| {
+            let c = srgb_to_linear(c as f32 / 255.0) * brightness as f32;
+            (linear_to_srgb(c) * 255.0).round() as u8
+        };
+        Color::new(
+            channel(color.r),
+            channel(color.g),
+            channel(color.b),
+            color.a,
+        )
+    }
+}
+
+fn face_center(face: &[GMPoint; 3]) -> GMPoint {
+    GMPoint::from((face[0].coords + face[1].coords + face[2].coords) / 3.0)
+}
+
+// triangles of every mesh in the default scene with the transforms of their nodes applied,
+// and the base color of the material of their primitive or the mean of their vertex colors
+#[cfg(feature = "gltf")]
+pub fn gltf_faces(path: &str) -> Result<(Vec<[GMPoint; 3]>, Vec<Color>), String> {
+    let (document, buffers, _) =
+        gltf::import(path).map_err(|e| format!("can't import {path}: {e}"))?;
+    let scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| format!("{path} has no scene"))?;
+    let mut faces = vec![];
+    let mut colors = vec![];
+    let mut nodes: Vec<_> = scene
+        .nodes()
+        .map(|n| (n, nalgebra::Matrix4::<f32>::identity()))
+        .collect();
+    while let Some((node, parent)) = nodes.pop() {
+        let local = nalgebra::Matrix4::from(node.transform().matrix());
+        let transform: nalgebra::Matrix4<GMFloat> = (parent * local).cast();
+        nodes.extend(node.children().map(|c| (c, parent * local)));
+        let Some(mesh) = node.mesh() else {
+            continue;
+        };
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()].0[..]));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+            let positions: Vec<GMPoint> = positions
+                .map(|[x, y, z]| {
+                    let p = GMPoint::new(x as GMFloat, y as GMFloat, z as GMFloat);
+                    transform.transform_point(&p)
+                })
+                .collect();
+            let vertex_colors: Option<Vec<[f32; 4]>> =
+                reader.read_colors(0).map(|c| c.into_rgba_f32().collect());
+            let indices: Vec<usize> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            // gltf colors are linear
+            let base_color = primitive
+                .material()
+                .pbr_metallic_roughness()
+                .base_color_factor();
+            let to_color = |c: [f32; 4]| {
+                let channel = |c: f32| (linear_to_srgb(c.clamp(0.0, 1.0)) * 255.0).round() as u8;
+                Color::new(
+                    channel(c[0]),
+                    channel(c[1]),
+                    channel(c[2]),
+                    (c[3].clamp(0.0, 1.0) * 255.0).round() as u8,
+                )
+            };
+            for triangle in indices.chunks_exact(3) {
+                let Some(face) = triangle
+                    .iter()
+                    .map(|&i| positions.get(i).copied())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return Err(format!("{path} has an index out of range"));
+                };
+                faces.push([face[0], face[1], face[2]]);
+                let mut color = base_color;
+                if let Some(vertex_colors) = &vertex_colors {
+                    for (channel, value) in color.iter_mut().enumerate() {
+                        let mean = triangle
+                            .iter()
+                            .map(|&i| vertex_colors.get(i).map_or(1.0, |c| c[channel]))
+                            .sum::<f32>()
+                            / 3.0;
+                        *value *= mean;
+                    }
+                }
+                colors.push(to_color(color));
+            }
+        }
+    }
+    Ok((faces, colors))
+}
+
+impl Draw for Mesh {
+    fn draw(&self, ctx: &mut Context) {
+        let mut faces: Vec<_> = self.faces.iter().enumerate().collect();
+        ctx.scene_config
+            .sort_back_to_front(&mut faces, |(_, face)| face_center(face));
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        for (index, face) in faces {
+            let color = self
+                .face_colors
+                .get(index)
+                .copied()
+                .unwrap_or(self.draw_config.color);
+            let draw_config = DrawConfig {
+                color: self.shaded(face, color),
+                ..self.draw_config
+            };
+            let mut pb = tiny_skia::PathBuilder::new();
+            let p = ctx.scene_config.project_with(&projection_matrix, &face[0]);
+            pb.move_to(p.x, p.y);
+            for p in &face[1..] {
+                let p = ctx.scene_config.project_with(&projection_matrix, p);
+                pb.line_to(p.x, p.y);
+            }
+            pb.close();
+            // faces seen edge on have no area
+            if let Some(path) = pb.finish() {
+                draw_config.fill_and_stroke(ctx, &path, FillRule::Winding);
+            }
+        }
+    }
+}
+
+impl Transform for Mesh {
+    fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {
+        for face in &mut self.faces {
+            for p in face.iter_mut() {
+                *p = transform.transform_point(p);
+            }
+        }
+    }
+}
+
+impl Mobject for Mesh {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.faces.iter().flatten().copied().collect()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        for (face, points) in self.faces.iter_mut().zip(points.chunks_exact(3)) {
+            face.copy_from_slice(points);
+        }
+    }
+}
+
+#[test]
+fn test_mesh_shading() {
+    let red = Color::new(0xff, 0, 0, 0xff);
+    let mut mesh = Mesh::new(vec![
+        // facing the light
+        [
+            GMPoint::new(0.0, 0.0, 0.0),
+            GMPoint::new(1.0, 0.0, 0.0),
+            GMPoint::new(0.0, 1.0, 0.0),
+        ],
+        // edge on to the light
+        [
+            GMPoint::new(0.0, 0.0, 0.0),
+            GMPoint::new(1.0, 0.0, 0.0),
+            GMPoint::new(0.0, 0.0, 1.0),
+        ],
+    ]);
+    mesh.light = Some(Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(mesh.shaded(&mesh.faces[0], red).to_hex(), "#ff0000");
+    let dark = mesh.shaded(&mesh.faces[1], red);
+    assert_eq!((dark.g, dark.b, dark.a), (0, 0, 0xff));
+    assert!(dark.r < 0xff && dark.r > 0);
+    mesh.light = None;
+    assert_eq!(mesh.shaded(&mesh.faces[1], red).to_hex(), "#ff0000");
+}
@@ -1,4 +1,111 @@
-pub trait Mobject: Transform + Draw {}
+pub trait Mobject: Transform + Draw {
+    fn get_draw_config(&self) -> DrawConfig;
+    fn set_draw_config(&mut self, draw_config: DrawConfig);
+    // all points that define the shape, including bezier control points
+    fn get_points(&self) -> Vec<GMPoint>;
+    // inverse of get_points, points are given in the same order and number,
+    // a slice of any other length is ignored
+    fn set_points(&mut self, points: &[GMPoint]);
+    // return (min, max) corner, an empty mobject gives a box at the origin
+    fn get_bounding_box(&self) -> (GMPoint, GMPoint) {
+        let points = self.get_points();
+        if points.is_empty() {
+            return (GMPoint::origin(), GMPoint::origin());
+        }
+        let mut min = points[0];
+        let mut max = points[0];
+        for p in &points[1..] {
+            min = min.inf(p);
+            max = max.sup(p);
+        }
+        (min, max)
+    }
+    fn get_center(&self) -> GMPoint {
+        let (min, max) = self.get_bounding_box();
+        nalgebra::center(&min, &max)
+    }
+    // advance internal time dependent state (e.g. ambient camera rotation) by dt seconds,
+    // called once per frame by Scene::update
+    fn advance_time(&mut self, dt: GMFloat) {}
+    // whether advance_time changes anything, a scene with such mobjects can't reuse a still frame
+    fn is_time_dependent(&self) -> bool {
+        false
+    }
+    // a copy with all of its state, see Scene::save_state. None for mobjects that can't be copied
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        None
+    }
+    // a copy for saving the scene, see scene_state. None for mobjects that can't be saved
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        None
+    }
+    // the path the stroke follows, for animations tracing the shape.
+    // by default an open polyline through the points
+    fn outline(&self) -> Vec<path::PathElement> {
+        path::polyline_elements(&self.get_points(), false)
+    }
+    // the parts that can be styled and transformed on their own, e.g. the children of a
+    // group or the glyphs of a text. transforms and set_draw_config of the parent reach
+    // all parts, setting them on a part afterwards changes only that part
+    fn submobjects(&self) -> Vec<&dyn Mobject> {
+        vec![]
+    }
+    fn submobjects_mut(&mut self) -> Vec<&mut dyn Mobject> {
+        vec![]
+    }
+    // for finding a part by name, e.g. the id of an svg path or the chars of a glyph
+    fn name(&self) -> Option<String> {
+        None
+    }
+    // what Scene::draw_frame compares to find the mobjects that changed since the last frame.
+    // mobjects drawing anything besides their points and style, like images, add it
+    fn signature(&self, hasher: &mut dyn std::hash::Hasher) {
+        shape_signature(self, hasher);
+    }
+}
+
+// the points and style of m, the default Mobject::signature
+pub fn shape_signature<M: Mobject + ?Sized>(m: &M, mut hasher: &mut dyn std::hash::Hasher) {
+    use std::hash::Hash;
+    for p in m.get_points() {
+        p.coords.map(|c| c.to_bits()).hash(&mut hasher);
+    }
+    format!("{:?}", m.get_draw_config()).hash(&mut hasher);
+}
+
+impl<'a> dyn Mobject + 'a {
+    // the part at path, each index picks from the submobjects of the part before
+    pub fn submobject(&self, path: &[usize]) -> Option<&dyn Mobject> {
+        let Some((first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        self.submobjects().into_iter().nth(*first)?.submobject(rest)
+    }
+    pub fn submobject_mut(&mut self, path: &[usize]) -> Option<&mut dyn Mobject> {
+        let Some((first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        self.submobjects_mut()
+            .into_iter()
+            .nth(*first)?
+            .submobject_mut(rest)
+    }
+    // the paths of all parts with the given name, depth first
+    pub fn find_submobjects(&self, name: &str) -> Vec<Vec<usize>> {
+        let mut found = vec![];
+        for (i, m) in self.submobjects().into_iter().enumerate() {
+            if m.name().as_deref() == Some(name) {
+                found.push(vec![i]);
+            }
+            for mut path in m.find_submobjects(name) {
+                path.insert(0, i);
+                found.push(path);
+            }
+        }
+        found
+    }
+}
 pub trait MobjectClone: Mobject {
     fn mobject_clone(&self) -> Box<dyn MobjectClone>;
 }
@@ -6,20 +113,31 @@ pub trait MobjectClone: Mobject {
 use std::f32::consts::PI;
 
 use crate::{
-    math_utils::k_for_bezier_arc, Color, Context, ContextType, GMFloat, Scene, SceneConfig,
+    math_utils::k_for_bezier_arc, Color, Context, ContextType, GMFloat, GMPoint, Scene, SceneConfig,
 };
 
 use nalgebra::{point, Point, Point2, Point3, Vector2, Vector3};
-use tiny_skia::{LineCap, LineJoin, Paint, Stroke, StrokeDash};
+use tiny_skia::{BlendMode, LineCap, LineJoin, Paint, Stroke, StrokeDash};
+pub mod arrow;
+pub mod clip;
+pub mod decimal_number;
 pub mod dot;
 pub mod formula;
 pub mod group;
+pub mod heatmap;
+pub mod mesh;
 pub mod path;
 pub mod polygon;
+pub mod surface;
 pub mod svg_shape;
 pub mod text;
 pub mod three_d_viewport;
+pub use arrow::Arrow;
+pub use clip::Clipped;
+pub use decimal_number::DecimalNumber;
 pub use dot::Dot;
+pub use heatmap::Heatmap;
+pub use surface::Surface;
 
 pub trait Transform {
     fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>);
@@ -31,6 +149,34 @@ pub trait Transform {
         let movement_matrix = nalgebra::Matrix4::new_translation(&movement);
         self.transform(nalgebra::Transform::from_matrix_unchecked(movement_matrix));
     }
+    fn shift(&mut self, offset: nalgebra::Vector3<GMFloat>) {
+        self.move_this(offset);
+    }
+    // counterclockwise by angle when looking against axis, a zero axis does nothing
+    fn rotate_about(&mut self, point: GMPoint, axis: nalgebra::Vector3<GMFloat>, angle: GMFloat) {
+        let Some(axis) = nalgebra::Unit::try_new(axis, GMFloat::EPSILON) else {
+            return;
+        };
+        let rotation_matrix = nalgebra::Matrix4::new_rotation_wrt_point(*axis * angle, point);
+        self.transform(nalgebra::Transform::from_matrix_unchecked(rotation_matrix));
+    }
+    fn scale_about(&mut self, point: GMPoint, scale_factor: GMFloat) {
+        let scaling_matrix = nalgebra::Matrix4::new_translation(&point.coords)
+            * nalgebra::Matrix4::new_scaling(scale_factor)
+            * nalgebra::Matrix4::new_translation(&-point.coords);
+        self.transform(nalgebra::Transform::from_matrix_unchecked(scaling_matrix));
+    }
+    // x moves by x_by_y times the height above point, y by y_by_x times the distance
+    // right of it
+    fn shear(&mut self, point: GMPoint, x_by_y: GMFloat, y_by_x: GMFloat) {
+        let mut shear_matrix = nalgebra::Matrix4::identity();
+        shear_matrix[(0, 1)] = x_by_y;
+        shear_matrix[(1, 0)] = y_by_x;
+        let shear_matrix = nalgebra::Matrix4::new_translation(&point.coords)
+            * shear_matrix
+            * nalgebra::Matrix4::new_translation(&-point.coords);
+        self.transform(nalgebra::Transform::from_matrix_unchecked(shear_matrix));
+    }
 }
 
 pub trait Draw {
@@ -39,10 +185,94 @@ pub trait Draw {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DrawConfig {
-    stoke_width: GMFloat,
-    fill: bool,
-    color: Color,
+    pub stoke_width: GMFloat,
+    pub fill: bool,
+    pub color: Color,
+    // multiplied with the alpha of color
+    pub opacity: GMFloat,
+    // (dash length, gap length) in scene unit, None for a solid stroke
+    pub dash: Option<(GMFloat, GMFloat)>,
+    pub dash_offset: GMFloat,
+    // only used by closed shapes, multiplied with opacity for the fill
+    pub fill_opacity: GMFloat,
+    // stroke the outline of closed shapes even when they are filled
+    pub outline: bool,
+    pub glow: Option<Glow>,
+    pub shadow: Option<Shadow>,
+    // draw the stroke under the fill instead of over it, it then also stays behind the fills
+    // of neighbouring shapes of the same group or text, see Context::draw_stroke_passes
+    pub stroke_behind: bool,
+    // how the shape and its glow and shadow are composited onto what is below them
+    #[cfg_attr(feature = "serde", serde(with = "BlendModeDef"))]
+    pub blend_mode: BlendMode,
+    // what stoke_width and the dash lengths are measured in
+    pub stroke_units: StrokeUnits,
+}
+
+// tiny_skia::BlendMode for serde, the variants have to match
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "BlendMode")]
+enum BlendModeDef {
+    Clear,
+    Source,
+    Destination,
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+    Modulate,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Multiply,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrokeUnits {
+    // scene units, the stroke gets thicker when the camera zooms in
+    #[default]
+    World,
+    // pixels of the output video, the stroke looks the same at any zoom
+    Pixels,
+}
+
+// a blurred halo of the shape in color behind it, radius is the blur
+// standard deviation in scene units
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Glow {
+    pub color: Color,
+    pub radius: GMFloat,
+}
+
+// a blurred copy of the shape behind it moved by offset, in scene units
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow {
+    pub color: Color,
+    pub offset: (GMFloat, GMFloat),
+    pub blur: GMFloat,
 }
 
 impl Default for DrawConfig {
@@ -51,10 +281,212 @@ impl Default for DrawConfig {
             stoke_width: 0.25,
             fill: true,
             color: Default::default(),
+            opacity: 1.0,
+            dash: None,
+            dash_offset: 0.0,
+            fill_opacity: 1.0,
+            outline: false,
+            glow: None,
+            shadow: None,
+            stroke_behind: false,
+            blend_mode: BlendMode::SourceOver,
+            stroke_units: StrokeUnits::World,
         }
     }
 }
 
+impl DrawConfig {
+    pub fn get_paint(&self) -> Paint<'static> {
+        let mut color = self.color;
+        color.a = (color.a as GMFloat * self.opacity.clamp(0.0, 1.0)).round() as u8;
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        paint.blend_mode = self.blend_mode;
+        paint
+    }
+
+    pub fn get_fill_paint(&self) -> Paint<'static> {
+        let mut draw_config = *self;
+        draw_config.opacity = self.opacity * self.fill_opacity;
+        draw_config.get_paint()
+    }
+
+    // closed shapes are filled when fill is set, and stroked when not filled or outline is set
+    pub fn fill_and_stroke(
+        &self,
+        ctx: &mut Context,
+        path: &tiny_skia::Path,
+        fill_rule: tiny_skia::FillRule,
+    ) {
+        let stroke = self.outline || !self.fill;
+        let fill = self.fill.then_some(fill_rule);
+        ctx.draw_effects(path, self, fill, stroke);
+        if stroke && self.stroke_behind {
+            ctx.stroke_path_without_effects(path, self);
+        }
+        if let Some(fill_rule) = fill {
+            ctx.fill_path(path, &self.get_fill_paint(), fill_rule);
+        }
+        if stroke && !self.stroke_behind {
+            ctx.stroke_path_without_effects(path, self);
+        }
+    }
+
+    // how far the glow and shadow reach beyond the stroke, in scene units
+    pub fn effect_extent(&self) -> GMFloat {
+        let glow = self.glow.map_or(0.0, |g| 3.0 * g.radius);
+        let shadow = self.shadow.map_or(0.0, |s| {
+            s.offset.0.abs().max(s.offset.1.abs()) + 3.0 * s.blur
+        });
+        glow.max(shadow)
+    }
+
+    // the shadow and glow of the shape as blurred pixmaps at their pixel position,
+    // in drawing order. only the part that blurs into clip_rect is rendered
+    pub fn effect_layers(
+        &self,
+        path: &tiny_skia::Path,
+        fill: Option<tiny_skia::FillRule>,
+        stroke: bool,
+        scale_factor: GMFloat,
+        clip_rect: tiny_skia::IntRect,
+    ) -> Vec<(i32, i32, tiny_skia::Pixmap)> {
+        let shadow = self.shadow.map(|s| {
+            let offset = (s.offset.0 * scale_factor, -s.offset.1 * scale_factor);
+            (s.color, offset, s.blur * scale_factor)
+        });
+        let glow = self
+            .glow
+            .map(|g| (g.color, (0.0, 0.0), g.radius * scale_factor));
+        let mut layers = vec![];
+        for (color, (dx, dy), sigma) in [shadow, glow].into_iter().flatten() {
+            // pixel positions are f32 in tiny_skia
+            let (dx, dy) = (dx as f32, dy as f32);
+            let margin = (self.stoke_width * scale_factor / 2.0 + 3.0 * sigma + 1.0) as f32;
+            let bounds = path.bounds();
+            let Some(rect) = tiny_skia::IntRect::from_ltrb(
+                (bounds.left() + dx - margin).floor() as i32,
+                (bounds.top() + dy - margin).floor() as i32,
+                (bounds.right() + dx + margin).ceil() as i32,
+                (bounds.bottom() + dy + margin).ceil() as i32,
+            )
+            .and_then(|rect| {
+                let blur_margin = (3.0 * sigma).ceil() as i32 + 1;
+                rect.intersect(&tiny_skia::IntRect::from_ltrb(
+                    clip_rect.left() - blur_margin,
+                    clip_rect.top() - blur_margin,
+                    clip_rect.right() + blur_margin,
+                    clip_rect.bottom() + blur_margin,
+                )?)
+            }) else {
+                continue;
+            };
+            let Some(mut layer) = tiny_skia::Pixmap::new(rect.width(), rect.height()) else {
+                continue;
+            };
+            let mut paint = Paint::default();
+            paint.set_color(color.into());
+            let transform =
+                tiny_skia::Transform::from_translate(dx - rect.x() as f32, dy - rect.y() as f32);
+            if let Some(fill_rule) = fill {
+                layer.fill_path(path, &paint, fill_rule, transform, None);
+            }
+            if stroke {
+                layer.stroke_path(
+                    path,
+                    &paint,
+                    &self.get_stroke(scale_factor),
+                    transform,
+                    None,
+                );
+            }
+            crate::filters::gaussian_blur(&mut layer, sigma as f32);
+            layers.push((rect.x(), rect.y(), layer));
+        }
+        layers
+    }
+
+    // the same stroke in scene units for drawing with scene_config,
+    // backends only handle StrokeUnits::World
+    pub fn resolve_stroke_units(&self, scene_config: &SceneConfig) -> DrawConfig {
+        match self.stroke_units {
+            StrokeUnits::World => *self,
+            StrokeUnits::Pixels => {
+                let to_world = scene_config.pixel_density / scene_config.pixel_scale();
+                DrawConfig {
+                    stoke_width: self.stoke_width * to_world,
+                    dash: self
+                        .dash
+                        .map(|(dash, gap)| (dash * to_world, gap * to_world)),
+                    dash_offset: self.dash_offset * to_world,
+                    stroke_units: StrokeUnits::World,
+                    ..*self
+                }
+            }
+        }
+    }
+
+    pub fn get_stroke(&self, scale_factor: GMFloat) -> Stroke {
+        let mut stroke = Stroke {
+            width: (self.stoke_width * scale_factor) as f32,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Default::default()
+        };
+        if let Some((dash_length, gap_length)) = self.dash {
+            stroke.dash = StrokeDash::new(
+                vec![
+                    (dash_length * scale_factor) as f32,
+                    (gap_length * scale_factor) as f32,
+                ],
+                (self.dash_offset * scale_factor) as f32,
+            );
+        }
+        stroke
+    }
+
+    // continuous properties are interpolated, the discrete ones switch at the halfway point
+    pub fn interpolate(&self, other: &DrawConfig, t: GMFloat) -> DrawConfig {
+        let lerp = |a: GMFloat, b: GMFloat| a + (b - a) * t;
+        let discrete = if t < 0.5 { self } else { other };
+        let dash = match (self.dash, other.dash) {
+            (Some(a), Some(b)) => Some((lerp(a.0, b.0), lerp(a.1, b.1))),
+            _ => discrete.dash,
+        };
+        DrawConfig {
+            stoke_width: lerp(self.stoke_width, other.stoke_width),
+            fill: discrete.fill,
+            color: self.color.interpolate(&other.color, t),
+            opacity: lerp(self.opacity, other.opacity),
+            dash,
+            dash_offset: lerp(self.dash_offset, other.dash_offset),
+            fill_opacity: lerp(self.fill_opacity, other.fill_opacity),
+            outline: discrete.outline,
+            glow: match (self.glow, other.glow) {
+                (Some(a), Some(b)) => Some(Glow {
+                    color: a.color.interpolate(&b.color, t),
+                    radius: lerp(a.radius, b.radius),
+                }),
+                _ => discrete.glow,
+            },
+            shadow: match (self.shadow, other.shadow) {
+                (Some(a), Some(b)) => Some(Shadow {
+                    color: a.color.interpolate(&b.color, t),
+                    offset: (lerp(a.offset.0, b.offset.0), lerp(a.offset.1, b.offset.1)),
+                    blur: lerp(a.blur, b.blur),
+                }),
+                _ => discrete.shadow,
+            },
+            stroke_behind: discrete.stroke_behind,
+            stroke_units: discrete.stroke_units,
+            blend_mode: discrete.blend_mode,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rectangle {
     pub p0: Point3<GMFloat>,
     pub p1: Point3<GMFloat>,
@@ -86,56 +518,55 @@ impl Transform for Rectangle {
 
 impl Draw for Rectangle {
     fn draw(self: &Self, ctx: &mut Context) {
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                let scale_factor = ctx.scene_config.scale_factor;
-                let mut pb = tiny_skia::PathBuilder::new();
-                let p0 = (
-                    coordinate_change_x(self.p0[(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.p0[(1)], ctx.scene_config.height) * scale_factor,
-                );
-                let p1 = (
-                    coordinate_change_x(self.p1[(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.p1[(1)], ctx.scene_config.height) * scale_factor,
-                );
-                let p2 = (
-                    coordinate_change_x(self.p2[(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.p2[(1)], ctx.scene_config.height) * scale_factor,
-                );
-                let p3 = (
-                    coordinate_change_x(self.p3[(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.p3[(1)], ctx.scene_config.height) * scale_factor,
-                );
-                pb.move_to(p0.0 as f32, p0.1 as f32);
-                pb.line_to(p1.0 as f32, p1.1 as f32);
-                pb.line_to(p2.0 as f32, p2.1 as f32);
-                pb.line_to(p3.0 as f32, p3.1 as f32);
-                pb.line_to(p0.0 as f32, p0.1 as f32);
-                let path = pb.finish().unwrap();
-
-                let mut stroke = Stroke::default();
-                stroke.width = self.draw_config.stoke_width * scale_factor;
-                stroke.line_cap = LineCap::Round;
-                stroke.line_join = LineJoin::Round;
-                let mut paint = Paint::default();
-                paint.set_color(self.draw_config.color.into());
-
-                paint.anti_alias = true;
-                pixmap.stroke_path(
-                    &path,
-                    &paint,
-                    &stroke,
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
-            }
-            _ => {}
-        }
+        let mut pb = tiny_skia::PathBuilder::new();
+        let p0 = ctx.scene_config.project(&self.p0);
+        let p1 = ctx.scene_config.project(&self.p1);
+        let p2 = ctx.scene_config.project(&self.p2);
+        let p3 = ctx.scene_config.project(&self.p3);
+        pb.move_to(p0.x, p0.y);
+        pb.line_to(p1.x, p1.y);
+        pb.line_to(p2.x, p2.y);
+        pb.line_to(p3.x, p3.y);
+        pb.line_to(p0.x, p0.y);
+        let path = pb.finish().unwrap();
+
+        ctx.stroke_path(&path, &self.draw_config);
     }
 }
 
-impl Mobject for Rectangle {}
+impl Mobject for Rectangle {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        vec![self.p0, self.p1, self.p2, self.p3]
+    }
+    fn outline(&self) -> Vec<path::PathElement> {
+        path::polyline_elements(&self.get_points(), true)
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        let &[p0, p1, p2, p3] = points else {
+            return;
+        };
+        self.p0 = p0;
+        self.p1 = p1;
+        self.p2 = p2;
+        self.p3 = p3;
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::Rectangle(self.clone()))
+    }
+}
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleLine {
     pub p0: Point3<GMFloat>,
     pub p1: Point3<GMFloat>,
@@ -161,44 +592,45 @@ impl Transform for SimpleLine {
 
 impl Draw for SimpleLine {
     fn draw(self: &Self, ctx: &mut Context) {
-        let scale_factor = ctx.scene_config.scale_factor;
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                let mut pb = tiny_skia::PathBuilder::new();
-                let p0 = (
-                    coordinate_change_x(self.p0[(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.p0[(1)], ctx.scene_config.height) * scale_factor,
-                );
-                let p1 = (
-                    coordinate_change_x(self.p1[(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.p1[(1)], ctx.scene_config.height) * scale_factor,
-                );
-                pb.move_to(p0.0 as f32, p0.1 as f32);
-                pb.line_to(p1.0 as f32, p1.1 as f32);
-                let path = pb.finish().unwrap();
-
-                let mut stroke = Stroke::default();
-                stroke.width = self.draw_config.stoke_width * scale_factor;
-                stroke.line_cap = LineCap::Round;
-                stroke.line_join = LineJoin::Round;
-                let mut paint = Paint::default();
-                paint.set_color(self.draw_config.color.into());
-
-                pixmap.stroke_path(
-                    &path,
-                    &paint,
-                    &stroke,
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
-            }
-            _ => {}
-        }
+        let mut pb = tiny_skia::PathBuilder::new();
+        let p0 = ctx.scene_config.project(&self.p0);
+        let p1 = ctx.scene_config.project(&self.p1);
+        pb.move_to(p0.x, p0.y);
+        pb.line_to(p1.x, p1.y);
+        let path = pb.finish().unwrap();
+
+        ctx.stroke_path(&path, &self.draw_config);
     }
 }
 
-impl Mobject for SimpleLine {}
+impl Mobject for SimpleLine {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        vec![self.p0, self.p1]
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        let &[p0, p1] = points else {
+            return;
+        };
+        self.p0 = p0;
+        self.p1 = p1;
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::SimpleLine(self.clone()))
+    }
+}
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PolyLine {
     pub points: Vec<Point3<GMFloat>>,
     pub draw_config: DrawConfig,
@@ -221,6 +653,8 @@ impl Transform for PolyLine {
     }
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arc {
     center_point: Point3<GMFloat>,
     start_angle: GMFloat,
@@ -260,58 +694,34 @@ impl Arc {
 
 impl Draw for Arc {
     fn draw(&self, ctx: &mut Context) {
-        let scale_factor = ctx.scene_config.scale_factor;
-        let scene_width = ctx.scene_config.width;
-        let scene_height = ctx.scene_config.height;
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                for i in 0..(self._segs - 1) {
-                    let mut pb = tiny_skia::PathBuilder::new();
-                    // approximate arc by cubic bezier curve here
-                    let start_angle = self._seg_list[i];
-                    let end_angle = self._seg_list[i + 1];
-                    let k = k_for_bezier_arc((end_angle - start_angle) / 2.0);
-                    let point_0 = self.center_point.xy()
-                        + Vector2::new(end_angle.cos(), end_angle.sin()) * self.radius;
-
-                    let point_3 = self.center_point.xy()
-                        + Vector2::new(start_angle.cos(), start_angle.sin()) * self.radius;
-
-                    let point_1 =
-                        point_0 + Vector2::new(end_angle.sin(), -end_angle.cos()) * k * self.radius;
-                    let point_2 = point_3
-                        + Vector2::new(-start_angle.sin(), start_angle.cos()) * k * self.radius;
-                    pb.move_to(
-                        coordinate_change_x(point_0.x, scene_width) * scale_factor,
-                        coordinate_change_y(point_0.y, scene_height) * scale_factor,
-                    );
-                    pb.cubic_to(
-                        coordinate_change_x(point_1.x, scene_width) * scale_factor,
-                        coordinate_change_y(point_1.y, scene_height) * scale_factor,
-                        coordinate_change_x(point_2.x, scene_width) * scale_factor,
-                        coordinate_change_y(point_2.y, scene_height) * scale_factor,
-                        coordinate_change_x(point_3.x, scene_width) * scale_factor,
-                        coordinate_change_y(point_3.y, scene_height) * scale_factor,
-                    );
-
-                    let path = pb.finish().unwrap();
-                    let mut stroke = Stroke::default();
-                    stroke.width = self.draw_config.stoke_width * scale_factor;
-                    stroke.line_cap = LineCap::Round;
-                    stroke.line_join = LineJoin::Round;
-                    let mut paint = Paint::default();
-                    paint.set_color(self.draw_config.color.into());
-
-                    pixmap.stroke_path(
-                        &path,
-                        &paint,
-                        &stroke,
-                        tiny_skia::Transform::identity(),
-                        None,
-                    );
-                }
-            }
-            _ => {}
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        for i in 0..(self._segs - 1) {
+            let mut pb = tiny_skia::PathBuilder::new();
+            // approximate arc by cubic bezier curve here
+            let start_angle = self._seg_list[i];
+            let end_angle = self._seg_list[i + 1];
+            let k = k_for_bezier_arc((end_angle - start_angle) / 2.0);
+            let point_0 = self.center_point.xy()
+                + Vector2::new(end_angle.cos(), end_angle.sin()) * self.radius;
+
+            let point_3 = self.center_point.xy()
+                + Vector2::new(start_angle.cos(), start_angle.sin()) * self.radius;
+
+            let point_1 =
+                point_0 + Vector2::new(end_angle.sin(), -end_angle.cos()) * k * self.radius;
+            let point_2 =
+                point_3 + Vector2::new(-start_angle.sin(), start_angle.cos()) * k * self.radius;
+            let [p0, p1, p2, p3] = [point_0, point_1, point_2, point_3].map(|p| {
+                ctx.scene_config.project_with(
+                    &projection_matrix,
+                    &Point3::new(p.x, p.y, self.center_point.z),
+                )
+            });
+            pb.move_to(p0.x, p0.y);
+            pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+
+            let path = pb.finish().unwrap();
+            ctx.stroke_path(&path, &self.draw_config);
         }
     }
 }
@@ -320,7 +730,72 @@ impl Transform for Arc {
     fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {}
 }
 
-impl Mobject for Arc {}
+impl Mobject for Arc {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self._seg_list
+            .iter()
+            .map(|angle| {
+                self.center_point + Vector3::new(angle.cos(), angle.sin(), 0.0) * self.radius
+            })
+            .collect()
+    }
+    // the circle between the angles, sampled every PI / 16
+    fn outline(&self) -> Vec<path::PathElement> {
+        let sweep = self.end_angle - self.start_angle;
+        let segments = (sweep.abs() / (PI as GMFloat / 16.0)).ceil().max(1.0) as usize;
+        let points: Vec<_> = (0..=segments)
+            .map(|i| {
+                let angle = self.start_angle + sweep * i as GMFloat / segments as GMFloat;
+                self.center_point + Vector3::new(angle.cos(), angle.sin(), 0.0) * self.radius
+            })
+            .collect();
+        path::polyline_elements(&points, false)
+    }
+    // the angles are kept, center and radius are refit to the points by least squares,
+    // so translation and uniform scaling survive but rotation and shearing are lost
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if points.is_empty() || points.len() != self._seg_list.len() {
+            return;
+        }
+        let n = points.len() as GMFloat;
+        let directions: Vec<Vector3<GMFloat>> = self
+            ._seg_list
+            .iter()
+            .map(|angle| Vector3::new(angle.cos(), angle.sin(), 0.0))
+            .collect();
+        let mean_point = points
+            .iter()
+            .fold(Vector3::zeros(), |acc, p| acc + p.coords)
+            / n;
+        let mean_direction = directions.iter().sum::<Vector3<GMFloat>>() / n;
+        let spread: GMFloat = directions
+            .iter()
+            .map(|u| (u - mean_direction).norm_squared())
+            .sum();
+        if spread > GMFloat::EPSILON {
+            let covariance: GMFloat = points
+                .iter()
+                .zip(&directions)
+                .map(|(p, u)| (p.coords - mean_point).dot(&(u - mean_direction)))
+                .sum();
+            self.radius = covariance / spread;
+        }
+        self.center_point = GMPoint::from(mean_point - mean_direction * self.radius);
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::Arc(self.clone()))
+    }
+}
 
 impl Draw for PolyLine {
     fn draw(self: &Self, ctx: &mut Context) {
@@ -328,48 +803,43 @@ impl Draw for PolyLine {
             return;
         }
 
-        let scale_factor = ctx.scene_config.scale_factor;
-
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                let mut pb = tiny_skia::PathBuilder::new();
-                let p0 = (
-                    coordinate_change_x(self.points[0][(0)], ctx.scene_config.width) * scale_factor,
-                    coordinate_change_y(self.points[0][(1)], ctx.scene_config.height)
-                        * scale_factor,
-                );
-                pb.move_to(p0.0 as f32, p0.1 as f32);
-                for p in self.points[1..].iter() {
-                    let point = (
-                        coordinate_change_x(p[(0)], ctx.scene_config.width) * scale_factor,
-                        coordinate_change_y(p[(1)], ctx.scene_config.height) * scale_factor,
-                    );
-                    pb.line_to(point.0 as f32, point.1 as f32);
-                }
-                let path = pb.finish().unwrap();
-
-                let mut stroke = Stroke::default();
-                stroke.width = self.draw_config.stoke_width * scale_factor;
-                stroke.line_cap = LineCap::Round;
-                stroke.line_join = LineJoin::Round;
-
-                let mut paint = Paint::default();
-                paint.set_color(self.draw_config.color.into());
-
-                pixmap.stroke_path(
-                    &path,
-                    &paint,
-                    &stroke,
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
-            }
-            _ => {}
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        let mut pb = tiny_skia::PathBuilder::new();
+        let p0 = ctx
+            .scene_config
+            .project_with(&projection_matrix, &self.points[0]);
+        pb.move_to(p0.x, p0.y);
+        for p in self.points[1..].iter() {
+            let point = ctx.scene_config.project_with(&projection_matrix, p);
+            pb.line_to(point.x, point.y);
         }
+        let path = pb.finish().unwrap();
+
+        ctx.stroke_path(&path, &self.draw_config);
     }
 }
 
-impl Mobject for PolyLine {}
+impl Mobject for PolyLine {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.points.clone()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        self.points = points.to_vec();
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::PolyLine(self.clone()))
+    }
+}
 
 pub fn rotate_matrix(axis: Vector3<GMFloat>, theta: GMFloat) {
     //assume axis is a unit vector
@@ -393,3 +863,208 @@ fn test_draw_arc() {
     scene.add(Box::new(arc));
     scene.save_png(&mut ctx, "arc.png");
 }
+
+#[test]
+fn test_arc_set_points() {
+    let mut arc = Arc::new(GMPoint::new(1.0, 2.0, 0.0), 0.0, PI as GMFloat, 2.0);
+    let scaled: Vec<GMPoint> = arc
+        .get_points()
+        .iter()
+        .map(|p| GMPoint::new(p.x * 2.0 + 1.0, p.y * 2.0, p.z))
+        .collect();
+    arc.set_points(&scaled);
+    assert!((arc.center_point - GMPoint::new(3.0, 4.0, 0.0)).norm() < 1e-4);
+    assert!((arc.radius - 4.0).abs() < 1e-4);
+    // a slice of the wrong length leaves the arc alone
+    arc.set_points(&scaled[1..]);
+    assert!((arc.radius - 4.0).abs() < 1e-4);
+    let mut line = SimpleLine::default();
+    line.set_points(&[GMPoint::origin()]);
+    assert_eq!(line.get_points().len(), 2);
+}
+
+#[test]
+fn test_glow_and_shadow() {
+    let draw = |draw_config: DrawConfig| {
+        let mut ctx = Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            SceneConfig {
+                output_width: 160,
+                output_height: 90,
+                scale_factor: 10.0,
+                ..Default::default()
+            },
+        );
+        let line = SimpleLine {
+            p0: Point3::new(-3.0, 0.0, 0.0),
+            p1: Point3::new(3.0, 0.0, 0.0),
+            draw_config,
+        };
+        let mut scene = Scene::default();
+        scene.add(Box::new(line));
+        scene.draw(&mut ctx);
+        let ContextType::TinySKIA(pixmap) = ctx.ctx_type else {
+            unreachable!()
+        };
+        pixmap
+    };
+    let plain = draw(DrawConfig::default());
+    let glowing = draw(DrawConfig {
+        glow: Some(Glow {
+            color: Color::new(0xff, 0, 0, 0xff),
+            radius: 0.3,
+        }),
+        shadow: Some(Shadow {
+            color: Color::new(0, 0xff, 0, 0xff),
+            offset: (0.0, -1.0),
+            blur: 0.1,
+        }),
+        ..Default::default()
+    });
+    // the stroke itself is drawn over its effects
+    assert_eq!(plain.pixel(80, 45), glowing.pixel(80, 45));
+    // the halo around the stroke and the shadow below it
+    assert_eq!(plain.pixel(80, 48).unwrap().red(), 0);
+    assert!(glowing.pixel(80, 48).unwrap().red() > 0x20);
+    assert!(glowing.pixel(80, 55).unwrap().green() > 0x80);
+}
+
+#[test]
+fn test_blend_mode() {
+    let mut ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            ..Default::default()
+        },
+    );
+    if let ContextType::TinySKIA(pixmap) = &mut ctx.ctx_type {
+        pixmap.fill(tiny_skia::Color::from_rgba8(0x80, 0x80, 0x80, 0xff));
+    }
+    let red = Color::new(0xff, 0, 0, 0xff);
+    let draw_config = DrawConfig {
+        color: red,
+        blend_mode: BlendMode::Screen,
+        ..Default::default()
+    };
+    Dot::new(Point3::origin(), 2.0, red, draw_config).draw(&mut ctx);
+    let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+        unreachable!()
+    };
+    // screen keeps the gray below in the channels red doesn't cover
+    let p = pixmap.pixel(80, 45).unwrap();
+    assert_eq!((p.red(), p.green(), p.blue()), (0xff, 0x80, 0x80));
+}
+
+#[test]
+fn test_stroke_units() {
+    use crate::camera::FrameCamera;
+    // rows the horizontal line through the frame center covers, with the camera zoomed in twice
+    let covered_rows = |stoke_width: GMFloat, stroke_units: StrokeUnits, supersampling: u32| {
+        let mut scene = Scene::new();
+        scene.add(Box::new(SimpleLine {
+            p0: Point3::new(-8.0, 0.0, 0.0),
+            p1: Point3::new(8.0, 0.0, 0.0),
+            draw_config: DrawConfig {
+                color: Color::new(0xff, 0, 0, 0xff),
+                stoke_width,
+                stroke_units,
+                ..Default::default()
+            },
+        }));
+        let mut ctx = Context::new(
+            ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+            SceneConfig {
+                output_width: 160,
+                output_height: 90,
+                scale_factor: 10.0,
+                camera: FrameCamera::new(Point3::origin(), 2.0, 0.0),
+                supersampling,
+                ..Default::default()
+            },
+        );
+        scene.draw(&mut ctx);
+        let ContextType::TinySKIA(pixmap) = &ctx.ctx_type else {
+            unreachable!()
+        };
+        (0..90)
+            .filter(|&y| pixmap.pixel(80, y).unwrap().red() > 0x80)
+            .count()
+    };
+    assert_eq!(covered_rows(0.4, StrokeUnits::World, 1), 8);
+    assert_eq!(covered_rows(4.0, StrokeUnits::Pixels, 1), 4);
+    assert_eq!(covered_rows(4.0, StrokeUnits::Pixels, 4), 4);
+}
+
+#[test]
+fn test_transform_about_point() {
+    let close = |a: GMPoint, b: GMPoint| (a - b).norm() < 1e-4;
+    let mut line = SimpleLine {
+        p0: Point3::new(1.0, 0.0, 0.0),
+        p1: Point3::new(2.0, 0.0, 0.0),
+        draw_config: DrawConfig::default(),
+    };
+    line.rotate_about(
+        Point3::new(1.0, 0.0, 0.0),
+        Vector3::z(),
+        PI as GMFloat / 2.0,
+    );
+    assert!(
+        close(line.p0, Point3::new(1.0, 0.0, 0.0)) && close(line.p1, Point3::new(1.0, 1.0, 0.0))
+    );
+    line.scale_about(Point3::new(1.0, 1.0, 0.0), 2.0);
+    assert!(close(line.p0, Point3::new(1.0, -1.0, 0.0)));
+    line.shear(Point3::origin(), 1.0, 0.0);
+    assert!(
+        close(line.p0, Point3::new(0.0, -1.0, 0.0)) && close(line.p1, Point3::new(2.0, 1.0, 0.0))
+    );
+    line.shift(Vector3::new(0.0, 1.0, 0.0));
+    line.rotate_about(Point3::origin(), Vector3::zeros(), 1.0);
+    assert!(close(line.p0, Point3::origin()));
+}
+
+#[test]
+fn test_submobjects() {
+    use group::MobjectGroup;
+    let dot = |x: GMFloat| -> Box<dyn Mobject> {
+        Box::new(Dot::new(
+            Point3::new(x, 0.0, 0.0),
+            0.5,
+            Color::default(),
+            DrawConfig::default(),
+        ))
+    };
+    let inner = MobjectGroup {
+        mobjects: vec![dot(1.0), dot(2.0)],
+    };
+    let mut outer: Box<dyn Mobject> = Box::new(MobjectGroup {
+        mobjects: vec![dot(0.0), Box::new(inner)],
+    });
+    assert_eq!(outer.submobjects().len(), 2);
+    assert!(outer.submobject(&[1, 2]).is_none());
+    // styling the parent reaches every part, styling a part afterwards only that part
+    let red = Color::new(0xff, 0, 0, 0xff);
+    outer.set_draw_config(DrawConfig {
+        stoke_width: 0.3,
+        ..Default::default()
+    });
+    let part = outer.submobject_mut(&[1, 1]).unwrap();
+    part.set_draw_config(DrawConfig {
+        color: red,
+        ..part.get_draw_config()
+    });
+    part.shift(Vector3::new(0.0, 1.0, 0.0));
+    let colors: Vec<_> = [&[0][..], &[1, 0], &[1, 1]]
+        .iter()
+        .map(|path| outer.submobject(path).unwrap().get_draw_config())
+        .collect();
+    assert!(colors.iter().all(|c| c.stoke_width == 0.3));
+    let reds: Vec<_> = colors.iter().map(|c| c.color.r == 0xff).collect();
+    assert_eq!(reds, [false, false, true]);
+    assert_eq!(
+        outer.submobject(&[1, 1]).unwrap().get_center(),
+        Point3::new(2.0, 1.0, 0.0)
+    );
+}
@@ -1,6 +1,7 @@
 use crate::GMFloat;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PathElement {
     MoveTo(nalgebra::Point3<GMFloat>),
     LineTo(nalgebra::Point3<GMFloat>),
@@ -13,4 +14,157 @@ pub enum PathElement {
     Close,
 }
 
+impl PathElement {
+    pub fn get_points(&self) -> Vec<nalgebra::Point3<GMFloat>> {
+        match self {
+            PathElement::MoveTo(p) => vec![*p],
+            PathElement::LineTo(p) => vec![*p],
+            PathElement::QuadTo(p1, p2) => vec![*p1, *p2],
+            PathElement::CubicTo(p1, p2, p3) => vec![*p1, *p2, *p3],
+            PathElement::Close => vec![],
+        }
+    }
+    // set points from the front of the slice, return how many points were used
+    pub fn set_points(&mut self, points: &[nalgebra::Point3<GMFloat>]) -> usize {
+        match self {
+            PathElement::MoveTo(p) => {
+                *p = points[0];
+                1
+            }
+            PathElement::LineTo(p) => {
+                *p = points[0];
+                1
+            }
+            PathElement::QuadTo(p1, p2) => {
+                *p1 = points[0];
+                *p2 = points[1];
+                2
+            }
+            PathElement::CubicTo(p1, p2, p3) => {
+                *p1 = points[0];
+                *p2 = points[1];
+                *p3 = points[2];
+                3
+            }
+            PathElement::Close => 0,
+        }
+    }
+}
+
+// a polyline through points, back to the first one when closed
+pub fn polyline_elements(points: &[crate::GMPoint], closed: bool) -> Vec<PathElement> {
+    let mut elements: Vec<_> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| match i {
+            0 => PathElement::MoveTo(*p),
+            _ => PathElement::LineTo(*p),
+        })
+        .collect();
+    if closed && !elements.is_empty() {
+        elements.push(PathElement::Close);
+    }
+    elements
+}
+
+// the subpaths of elements as polylines, every curve is split into segments straight lines.
+// a closed subpath ends at its start
+pub fn flatten(elements: &[PathElement], segments: usize) -> Vec<Vec<crate::GMPoint>> {
+    let mut subpaths = vec![];
+    let mut current: Vec<crate::GMPoint> = vec![];
+    for e in elements {
+        match e {
+            PathElement::MoveTo(p) => {
+                if current.len() > 1 {
+                    subpaths.push(std::mem::take(&mut current));
+                }
+                current = vec![*p];
+            }
+            PathElement::LineTo(p) => current.push(*p),
+            PathElement::QuadTo(p1, p2) => {
+                let p0 = current.last().copied().unwrap_or(*p1);
+                current.extend((1..=segments).map(|i| {
+                    let t = i as GMFloat / segments as GMFloat;
+                    let s = 1.0 - t;
+                    crate::GMPoint::from(
+                        p0.coords * s * s + p1.coords * 2.0 * s * t + p2.coords * t * t,
+                    )
+                }));
+            }
+            PathElement::CubicTo(p1, p2, p3) => {
+                let p0 = current.last().copied().unwrap_or(*p1);
+                current.extend((1..=segments).map(|i| {
+                    let t = i as GMFloat / segments as GMFloat;
+                    let s = 1.0 - t;
+                    crate::GMPoint::from(
+                        p0.coords * s * s * s
+                            + p1.coords * 3.0 * s * s * t
+                            + p2.coords * 3.0 * s * t * t
+                            + p3.coords * t * t * t,
+                    )
+                }));
+            }
+            PathElement::Close => {
+                // the next subpath starts where this one began
+                if let Some(&start) = current.first() {
+                    current.push(start);
+                    subpaths.push(std::mem::replace(&mut current, vec![start]));
+                }
+            }
+        }
+    }
+    if current.len() > 1 {
+        subpaths.push(current);
+    }
+    subpaths
+}
+
+// elements projected to output pixels, None when they don't make a path
+pub fn pixel_path(
+    elements: &[PathElement],
+    scene_config: &crate::SceneConfig,
+) -> Option<tiny_skia::Path> {
+    let projection_matrix = scene_config.projection_matrix();
+    let project = |p: &crate::GMPoint| scene_config.project_with(&projection_matrix, p);
+    let mut pb = tiny_skia::PathBuilder::new();
+    for e in elements {
+        match e {
+            PathElement::MoveTo(p) => {
+                let p = project(p);
+                pb.move_to(p.x, p.y);
+            }
+            PathElement::LineTo(p) => {
+                let p = project(p);
+                pb.line_to(p.x, p.y);
+            }
+            PathElement::QuadTo(p1, p2) => {
+                let (p1, p2) = (project(p1), project(p2));
+                pb.quad_to(p1.x, p1.y, p2.x, p2.y);
+            }
+            PathElement::CubicTo(p1, p2, p3) => {
+                let (p1, p2, p3) = (project(p1), project(p2), project(p3));
+                pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+            }
+            PathElement::Close => pb.close(),
+        }
+    }
+    pb.finish()
+}
+
 pub struct Path {}
+
+#[test]
+fn test_flatten() {
+    use crate::GMPoint;
+    let elements = [
+        PathElement::MoveTo(GMPoint::new(0.0, 0.0, 0.0)),
+        PathElement::QuadTo(GMPoint::new(1.0, 1.0, 0.0), GMPoint::new(2.0, 0.0, 0.0)),
+        PathElement::Close,
+    ];
+    let subpaths = flatten(&elements, 4);
+    assert_eq!(subpaths.len(), 1);
+    // the curve points lie on it, the last one closes the path
+    assert_eq!(subpaths[0].len(), 6);
+    assert!((subpaths[0][2] - GMPoint::new(1.0, 0.5, 0.0)).norm() < 1e-5);
+    assert_eq!(subpaths[0][5], GMPoint::new(0.0, 0.0, 0.0));
+}
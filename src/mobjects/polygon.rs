@@ -5,7 +5,9 @@ use crate::{Color, Context, GMFloat, GMPoint, Scene};
 
 use super::{Draw, DrawConfig, Mobject, Transform};
 
-struct Polygon {
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon {
     vertices: Vec<GMPoint>,
     draw_config: DrawConfig,
 }
@@ -20,36 +22,21 @@ impl Polygon {
 }
 impl Draw for Polygon {
     fn draw(&self, ctx: &mut crate::Context) {
-        match &mut ctx.ctx_type {
-            crate::ContextType::TinySKIA(pixmap) => {
-                let mut pb = tiny_skia::PathBuilder::new();
-                let mut v_list = self.vertices.iter();
-                let start = v_list.next().unwrap();
+        let mut pb = tiny_skia::PathBuilder::new();
+        let mut v_list = self.vertices.iter();
+        let start = v_list.next().unwrap();
 
-                pb.move_to(
-                    ctx.scene_config.convert_coord_x(start.x),
-                    ctx.scene_config.convert_coord_y(start.y),
-                );
-                for p in v_list {
-                    pb.line_to(
-                        ctx.scene_config.convert_coord_x(p.x),
-                        ctx.scene_config.convert_coord_y(p.y),
-                    );
-                }
-                pb.close();
-                let path = pb.finish().unwrap();
-                let mut paint = Paint::default();
-                paint.set_color(self.draw_config.color.into());
-                pixmap.fill_path(
-                    &path,
-                    &paint,
-                    FillRule::EvenOdd,
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
-            }
-            _ => {}
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        let p = ctx.scene_config.project_with(&projection_matrix, start);
+        pb.move_to(p.x, p.y);
+        for p in v_list {
+            let p = ctx.scene_config.project_with(&projection_matrix, p);
+            pb.line_to(p.x, p.y);
         }
+        pb.close();
+        let path = pb.finish().unwrap();
+        self.draw_config
+            .fill_and_stroke(ctx, &path, FillRule::EvenOdd);
     }
 }
 
@@ -61,7 +48,30 @@ impl Transform for Polygon {
     }
 }
 
-impl Mobject for Polygon {}
+impl Mobject for Polygon {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.vertices.clone()
+    }
+    fn outline(&self) -> Vec<super::path::PathElement> {
+        super::path::polyline_elements(&self.vertices, true)
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        self.vertices = points.to_vec();
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::Polygon(self.clone()))
+    }
+}
 
 #[test]
 pub fn test_polygon() {
@@ -0,0 +1,156 @@
+use tiny_skia::FillRule;
+
+use crate::{Color, ColorMap, Context, GMFloat, GMPoint};
+
+use super::{Draw, DrawConfig, Mobject, Transform};
+
+// a mesh of quadrilateral faces, with a 3d camera the faces are drawn back to front
+pub struct Surface {
+    pub faces: Vec<[GMPoint; 4]>,
+    // one color per face, faces without one use draw_config.color
+    pub face_colors: Vec<Color>,
+    pub draw_config: DrawConfig,
+}
+
+impl Surface {
+    pub fn new(faces: Vec<[GMPoint; 4]>) -> Self {
+        Self {
+            faces,
+            face_colors: Vec::new(),
+            draw_config: DrawConfig {
+                stoke_width: 0.02,
+                outline: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    // sample f on a grid of resolution.0 x resolution.1 faces over u_range x v_range
+    pub fn parametric(
+        f: impl Fn(GMFloat, GMFloat) -> GMPoint,
+        u_range: (GMFloat, GMFloat),
+        v_range: (GMFloat, GMFloat),
+        resolution: (usize, usize),
+    ) -> Self {
+        let (u_count, v_count) = (resolution.0.max(1), resolution.1.max(1));
+        let u_at =
+            |i: usize| u_range.0 + (u_range.1 - u_range.0) * i as GMFloat / u_count as GMFloat;
+        let v_at =
+            |j: usize| v_range.0 + (v_range.1 - v_range.0) * j as GMFloat / v_count as GMFloat;
+        let mut faces = Vec::with_capacity(u_count * v_count);
+        for i in 0..u_count {
+            for j in 0..v_count {
+                faces.push([
+                    f(u_at(i), v_at(j)),
+                    f(u_at(i + 1), v_at(j)),
+                    f(u_at(i + 1), v_at(j + 1)),
+                    f(u_at(i), v_at(j + 1)),
+                ]);
+            }
+        }
+        Self::new(faces)
+    }
+
+    // color every face by value(face), the values are stretched over the whole color map
+    pub fn color_by(
+        mut self,
+        color_map: &ColorMap,
+        value: impl Fn(&[GMPoint; 4]) -> GMFloat,
+    ) -> Self {
+        let values: Vec<_> = self.faces.iter().map(value).collect();
+        let min = values.iter().copied().fold(GMFloat::INFINITY, GMFloat::min);
+        let max = values
+            .iter()
+            .copied()
+            .fold(GMFloat::NEG_INFINITY, GMFloat::max);
+        self.face_colors = values
+            .iter()
+            .map(|v| color_map.sample_range(*v, (min, max)))
+            .collect();
+        self
+    }
+
+    // color by the z coordinate of the face center
+    pub fn color_by_height(self, color_map: &ColorMap) -> Self {
+        self.color_by(color_map, |face| face_center(face).z)
+    }
+}
+
+fn face_center(face: &[GMPoint; 4]) -> GMPoint {
+    GMPoint::from((face[0].coords + face[1].coords + face[2].coords + face[3].coords) / 4.0)
+}
+
+impl Draw for Surface {
+    fn draw(&self, ctx: &mut Context) {
+        let mut faces: Vec<_> = self.faces.iter().enumerate().collect();
+        ctx.scene_config
+            .sort_back_to_front(&mut faces, |(_, face)| face_center(face));
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        for (index, face) in faces {
+            let draw_config = DrawConfig {
+                color: self
+                    .face_colors
+                    .get(index)
+                    .copied()
+                    .unwrap_or(self.draw_config.color),
+                ..self.draw_config
+            };
+            let mut pb = tiny_skia::PathBuilder::new();
+            let p = ctx.scene_config.project_with(&projection_matrix, &face[0]);
+            pb.move_to(p.x, p.y);
+            for p in &face[1..] {
+                let p = ctx.scene_config.project_with(&projection_matrix, p);
+                pb.line_to(p.x, p.y);
+            }
+            pb.close();
+            // faces seen edge on have no area
+            if let Some(path) = pb.finish() {
+                draw_config.fill_and_stroke(ctx, &path, FillRule::Winding);
+            }
+        }
+    }
+}
+
+impl Transform for Surface {
+    fn transform(&mut self, transform: nalgebra::Transform3<GMFloat>) {
+        for face in &mut self.faces {
+            for p in face.iter_mut() {
+                *p = transform.transform_point(p);
+            }
+        }
+    }
+}
+
+impl Mobject for Surface {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.faces.iter().flatten().copied().collect()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        for (face, points) in self.faces.iter_mut().zip(points.chunks_exact(4)) {
+            face.copy_from_slice(points);
+        }
+    }
+}
+
+#[test]
+fn test_parametric_surface() {
+    let surface = Surface::parametric(
+        |u, v| GMPoint::new(u, v, u * v),
+        (0.0, 1.0),
+        (0.0, 2.0),
+        (2, 4),
+    );
+    assert_eq!(surface.faces.len(), 8);
+    assert_eq!(surface.faces[0][2], GMPoint::new(0.5, 0.5, 0.25));
+    assert_eq!(surface.faces[7][2], GMPoint::new(1.0, 2.0, 2.0));
+    // the lowest face gets the start of the map and the highest the end
+    let surface = surface.color_by_height(&ColorMap::viridis());
+    assert_eq!(surface.face_colors[0].to_hex(), "#440154");
+    assert_eq!(surface.face_colors[7].to_hex(), "#fde725");
+}
@@ -5,7 +5,7 @@ use usvg::{tiny_skia_path::PathSegment, Group, Node};
 
 use crate::{
     math_utils::{point2d_to_point3d, point3d_to_point2d},
-    Context, ContextType, GMFloat, Scene,
+    Context, ContextType, GMFloat, GMPoint, Scene,
 };
 
 use super::{
@@ -18,6 +18,8 @@ struct SVGPath {
     elements: Vec<PathElement>,
     is_closed: bool,
     draw_config: DrawConfig,
+    // of the svg element, empty when it has none
+    id: String,
 }
 
 impl SVGPath {
@@ -26,6 +28,7 @@ impl SVGPath {
             elements: vec![],
             is_closed: false,
             draw_config: Default::default(),
+            id: String::new(),
         }
     }
     fn move_to_origin(&mut self) {
@@ -95,84 +98,68 @@ impl super::Transform for SVGPath {
 
 impl Draw for SVGPath {
     fn draw(&self, ctx: &mut crate::Context) {
-        let scale_factor = ctx.scene_config.scale_factor;
-        let scene_width = ctx.scene_config.width;
-        let scene_height = ctx.scene_config.height;
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                let mut pb = tiny_skia::PathBuilder::new();
-                for e in &self.elements {
-                    match e {
-                        PathElement::MoveTo(p) => {
-                            pb.move_to(
-                                coordinate_change_x(p.x as f32, ctx.scene_config.width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p.y as f32, ctx.scene_config.height as f32)
-                                    * scale_factor as f32,
-                            );
-                        }
-                        PathElement::LineTo(p) => {
-                            pb.line_to(
-                                coordinate_change_x(p.x as f32, ctx.scene_config.width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p.y as f32, ctx.scene_config.height as f32)
-                                    * scale_factor as f32,
-                            );
-                        }
-                        PathElement::QuadTo(p1, p2) => {
-                            pb.quad_to(
-                                coordinate_change_x(p1.x as f32, ctx.scene_config.width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p1.y as f32, ctx.scene_config.height as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_x(p2.x as f32, ctx.scene_config.width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p2.y as f32, ctx.scene_config.height as f32)
-                                    * scale_factor as f32,
-                            );
-                        }
-                        PathElement::CubicTo(p1, p2, p3) => {
-                            pb.cubic_to(
-                                coordinate_change_x(p1.x as f32, scene_width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p1.y as f32, scene_height as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_x(p2.x as f32, scene_width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p2.y as f32, scene_height as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_x(p3.x as f32, scene_width as f32)
-                                    * scale_factor as f32,
-                                coordinate_change_y(p3.y as f32, scene_height as f32)
-                                    * scale_factor as f32,
-                            );
-                        }
-                        PathElement::Close => {
-                            pb.close();
-                        }
-                    }
+        let scene_config = &ctx.scene_config;
+        let to_pixel = |p: &GMPoint| scene_config.project(p);
+        let mut pb = tiny_skia::PathBuilder::new();
+        for e in &self.elements {
+            match e {
+                PathElement::MoveTo(p) => {
+                    let p = to_pixel(p);
+                    pb.move_to(p.x, p.y);
+                }
+                PathElement::LineTo(p) => {
+                    let p = to_pixel(p);
+                    pb.line_to(p.x, p.y);
+                }
+                PathElement::QuadTo(p1, p2) => {
+                    let (p1, p2) = (to_pixel(p1), to_pixel(p2));
+                    pb.quad_to(p1.x, p1.y, p2.x, p2.y);
+                }
+                PathElement::CubicTo(p1, p2, p3) => {
+                    let (p1, p2, p3) = (to_pixel(p1), to_pixel(p2), to_pixel(p3));
+                    pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+                }
+                PathElement::Close => {
+                    pb.close();
                 }
-                let path = pb.finish().unwrap();
-
-                let mut stroke = tiny_skia::Stroke::default();
-                stroke.width = self.draw_config.stoke_width * scale_factor;
-                stroke.line_cap = tiny_skia::LineCap::Round;
-                let mut paint = tiny_skia::Paint::default();
-                paint.set_color(self.draw_config.color.into());
-                pixmap.fill_path(
-                    &path,
-                    &paint,
-                    Default::default(),
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
             }
-            _ => {}
         }
+        let path = pb.finish().unwrap();
+        self.draw_config
+            .fill_and_stroke(ctx, &path, Default::default());
     }
 }
 
-impl Mobject for SVGPath {}
+impl Mobject for SVGPath {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.elements.iter().flat_map(|e| e.get_points()).collect()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if points.len() != self.get_points().len() {
+            return;
+        }
+        let mut offset = 0;
+        for e in &mut self.elements {
+            offset += e.set_points(&points[offset..]);
+        }
+    }
+    fn name(&self) -> Option<String> {
+        (!self.id.is_empty()).then(|| self.id.clone())
+    }
+    fn outline(&self) -> Vec<PathElement> {
+        let mut elements = self.elements.clone();
+        if self.is_closed && !matches!(elements.last(), Some(PathElement::Close)) {
+            elements.push(PathElement::Close);
+        }
+        elements
+    }
+}
 
 pub fn open_svg_file(svg_filepath: &str) -> MobjectGroup {
     let mut svg_file = fs::File::options()
@@ -195,6 +182,7 @@ pub fn open_svg_file(svg_filepath: &str) -> MobjectGroup {
             Node::Path(path) => {
                 //apply transform
                 let mut svg_path = SVGPath::new();
+                svg_path.id = path.id().to_owned();
                 let transform = node.abs_transform();
                 let path_data = path;
                 for e in path_data.data().segments() {
@@ -1,20 +1,23 @@
-use std::fs;
-use std::io::Read;
+use std::ops::Range;
 
-use log::info;
-use rusttype::{point, Font, Scale};
+use log::warn;
+use rusttype::{point, Scale};
 
+use crate::fonts::{self, FontBook, LoadedFont};
 use crate::log_utils::setup_logger;
 use crate::mobjects::Draw;
-use crate::{log_utils, ContextType, GMFloat};
+use crate::{ContextType, GMFloat, GMPoint};
 use nalgebra::{Point2, Point3, Vector3};
 
 use super::path::PathElement;
 use super::{coordinate_change_x, coordinate_change_y, DrawConfig, Mobject, Transform};
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Text {
     pub text: String,
     glyph_paths: Vec<GlyphPath>,
+    // where the text was laid out, the glyphs follow transforms on their own
     pub position: Point3<GMFloat>,
     pub font_size: GMFloat,
     pub draw_config: DrawConfig,
@@ -43,6 +46,7 @@ impl Transform for PathElement {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum FontConfig {
     Default,
     FontName(String),
@@ -57,9 +61,59 @@ impl Transform for Text {
     }
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct GlyphPath {
     glyph_position: Point2<GMFloat>,
     path_elements: Vec<PathElement>,
+    // the chars the glyph is drawn for, a ligature has several
+    #[cfg_attr(feature = "serde", serde(default))]
+    chars: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    draw_config: DrawConfig,
+    // the bitmap of a color emoji, which has no outline
+    #[cfg_attr(feature = "serde", serde(skip))]
+    raster: Option<GlyphRaster>,
+}
+
+// top left, top right and bottom left corner of the image, relative to the text
+// position like the outlines, so it follows transforms of the text
+#[derive(Clone)]
+struct GlyphRaster {
+    image: tiny_skia::Pixmap,
+    corners: [GMPoint; 3],
+}
+
+impl GlyphRaster {
+    // the png of the strike closest to em pixels per em of a cbdt or sbix font,
+    // origin is the pen position on the baseline in pixels
+    fn new(
+        face: &rustybuzz::Face,
+        glyph_id: u32,
+        em: f32,
+        origin: rusttype::Point<f32>,
+    ) -> Option<Self> {
+        let raster = face.glyph_raster_image(
+            rustybuzz::ttf_parser::GlyphId(glyph_id as u16),
+            em.round().clamp(1.0, u16::MAX as f32) as u16,
+        )?;
+        if raster.format != rustybuzz::ttf_parser::RasterImageFormat::PNG {
+            return None;
+        }
+        let image = tiny_skia::Pixmap::decode_png(raster.data).ok()?;
+        // raster metrics are in pixels of the strike, y is the bottom above the baseline
+        let strike = em / raster.pixels_per_em.max(1) as f32;
+        let left = origin.x + raster.x as f32 * strike;
+        let right = left + raster.width as f32 * strike;
+        let bottom = origin.y - raster.y as f32 * strike;
+        let top = bottom - raster.height as f32 * strike;
+        let corner =
+            |x: f32, y: f32| GMPoint::new(x as GMFloat, -y as GMFloat, 0.0) * SCALE_TEXT_FACTOR;
+        Some(Self {
+            image,
+            corners: [corner(left, top), corner(right, top), corner(left, bottom)],
+        })
+    }
 }
 
 impl Transform for GlyphPath {
@@ -67,6 +121,11 @@ impl Transform for GlyphPath {
         for p in &mut self.path_elements {
             p.transform(transform);
         }
+        if let Some(raster) = &mut self.raster {
+            for p in &mut raster.corners {
+                *p = transform * *p;
+            }
+        }
     }
 }
 
@@ -74,240 +133,239 @@ impl GlyphPath {
     fn new(position: rusttype::Point<f32>) -> Self {
         Self {
             glyph_position: Point2::new(
-                position.x * SCALE_TEXT_FACTOR,
-                -position.y * SCALE_TEXT_FACTOR,
+                position.x as GMFloat * SCALE_TEXT_FACTOR,
+                -position.y as GMFloat * SCALE_TEXT_FACTOR,
             ),
             path_elements: vec![],
+            chars: String::new(),
+            draw_config: DrawConfig::default(),
+            raster: None,
         }
     }
+
+    // a point of the outline, rusttype gives it in pixels relative to the glyph
+    fn outline_point(&self, x: f32, y: f32) -> GMPoint {
+        GMPoint::new(
+            x as GMFloat * SCALE_TEXT_FACTOR + self.glyph_position.x,
+            -y as GMFloat * SCALE_TEXT_FACTOR + self.glyph_position.y,
+            0.0,
+        )
+    }
 }
 
-pub const SCALE_TEXT_FACTOR: f32 = 0.1;
+pub const SCALE_TEXT_FACTOR: GMFloat = 0.1;
 
 impl rusttype::OutlineBuilder for GlyphPath {
     fn move_to(&mut self, x: f32, y: f32) {
-        self.path_elements
-            .push(PathElement::MoveTo(nalgebra::Point3::new(
-                x * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            )))
+        let p = self.outline_point(x, y);
+        self.path_elements.push(PathElement::MoveTo(p))
     }
     fn line_to(&mut self, x: f32, y: f32) {
-        self.path_elements
-            .push(PathElement::LineTo(nalgebra::Point3::new(
-                x * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            )))
+        let p = self.outline_point(x, y);
+        self.path_elements.push(PathElement::LineTo(p))
     }
     fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
-        self.path_elements.push(PathElement::QuadTo(
-            nalgebra::Point3::new(
-                x1 * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y1 * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            ),
-            nalgebra::Point3::new(
-                x * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            ),
-        ))
+        let (p1, p) = (self.outline_point(x1, y1), self.outline_point(x, y));
+        self.path_elements.push(PathElement::QuadTo(p1, p))
     }
     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
-        self.path_elements.push(PathElement::CubicTo(
-            nalgebra::Point3::new(
-                x1 * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y1 * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            ),
-            nalgebra::Point3::new(
-                x2 * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y2 * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            ),
-            nalgebra::Point3::new(
-                x * SCALE_TEXT_FACTOR + self.glyph_position.x,
-                -y * SCALE_TEXT_FACTOR + self.glyph_position.y,
-                0.0,
-            ),
-        ))
+        let (p1, p2) = (self.outline_point(x1, y1), self.outline_point(x2, y2));
+        let p = self.outline_point(x, y);
+        self.path_elements.push(PathElement::CubicTo(p1, p2, p))
     }
     fn close(&mut self) {
         self.path_elements.push(PathElement::Close)
     }
 }
 
+impl GlyphPath {
+    // spaces and color emoji have no outline
+    fn to_path(
+        &self,
+        scene_config: &crate::SceneConfig,
+        projection_matrix: &nalgebra::Matrix4<GMFloat>,
+    ) -> Option<tiny_skia::Path> {
+        let to_pixel = |p: &GMPoint| scene_config.project_with(projection_matrix, p);
+        let mut pb = tiny_skia::PathBuilder::new();
+        for path in &self.path_elements {
+            match path {
+                PathElement::MoveTo(p) => {
+                    let p = to_pixel(p);
+                    pb.move_to(p.x, p.y);
+                }
+                PathElement::LineTo(p) => {
+                    let p = to_pixel(p);
+                    pb.line_to(p.x, p.y);
+                }
+                PathElement::QuadTo(p1, p2) => {
+                    let (p1, p2) = (to_pixel(p1), to_pixel(p2));
+                    pb.quad_to(p1.x, p1.y, p2.x, p2.y);
+                }
+                PathElement::CubicTo(p1, p2, p3) => {
+                    let (p1, p2, p3) = (to_pixel(p1), to_pixel(p2), to_pixel(p3));
+                    pb.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+                }
+                PathElement::Close => {
+                    pb.close();
+                }
+            }
+        }
+        pb.finish()
+    }
+
+    // the bitmap is drawn over the outlines, it has no stroke
+    fn draw_raster(&self, ctx: &mut crate::Context) {
+        if let Some(raster) = &self.raster {
+            if ctx.in_pass(false) {
+                raster.draw(ctx, self.draw_config.opacity);
+            }
+        }
+    }
+}
+
+impl Draw for GlyphPath {
+    fn draw(&self, ctx: &mut crate::Context) {
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        if let Some(path) = self.to_path(&ctx.scene_config, &projection_matrix) {
+            self.draw_config
+                .fill_and_stroke(ctx, &path, Default::default());
+        }
+        self.draw_raster(ctx);
+    }
+}
+
+impl Mobject for GlyphPath {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.path_elements
+            .iter()
+            .flat_map(|e| e.get_points())
+            .collect()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if points.len() != self.get_points().len() {
+            return;
+        }
+        let mut offset = 0;
+        for e in &mut self.path_elements {
+            offset += e.set_points(&points[offset..]);
+        }
+    }
+    fn name(&self) -> Option<String> {
+        Some(self.chars.clone())
+    }
+    fn outline(&self) -> Vec<PathElement> {
+        self.path_elements.clone()
+    }
+    // an emoji has no outline points, its image is told apart by the chars and corners
+    fn signature(&self, mut hasher: &mut dyn std::hash::Hasher) {
+        use std::hash::Hash;
+        super::shape_signature(self, hasher);
+        if let Some(raster) = &self.raster {
+            self.chars.hash(&mut hasher);
+            for p in raster.corners {
+                p.coords.map(|c| c.to_bits()).hash(&mut hasher);
+            }
+        }
+    }
+}
+
 impl Draw for Text {
     fn draw(&self, ctx: &mut crate::Context) {
         if self.text.len() == 0 {
             return; //this is no text to draw
         }
-        let scale_factor = ctx.scene_config.scale_factor;
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                for g in &self.glyph_paths {
-                    let mut pb = tiny_skia::PathBuilder::new();
-                    for path in &g.path_elements {
-                        match path {
-                            PathElement::MoveTo(p) => {
-                                let x = coordinate_change_x(
-                                    p.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y = coordinate_change_y(
-                                    p.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-                                pb.move_to(x, y);
-                            }
-                            PathElement::LineTo(p) => {
-                                let x = coordinate_change_x(
-                                    p.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y = coordinate_change_y(
-                                    p.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-                                pb.line_to(x, y);
-                            }
-                            PathElement::QuadTo(p1, p2) => {
-                                let x1 = coordinate_change_x(
-                                    p1.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y1 = coordinate_change_y(
-                                    p1.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let x2 = coordinate_change_x(
-                                    p2.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y2 = coordinate_change_y(
-                                    p2.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-                                pb.quad_to(x1, y1, x2, y2);
-                            }
-                            PathElement::CubicTo(p1, p2, p3) => {
-                                let x1 = coordinate_change_x(
-                                    p1.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y1 = coordinate_change_y(
-                                    p1.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let x2 = coordinate_change_x(
-                                    p2.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y2 = coordinate_change_y(
-                                    p2.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let x3 = coordinate_change_x(
-                                    p3.x + self.position.x,
-                                    ctx.scene_config.width,
-                                ) as f32
-                                    * scale_factor as f32;
-                                let y3 = coordinate_change_y(
-                                    p3.y + self.position.y,
-                                    ctx.scene_config.height,
-                                ) as f32
-                                    * scale_factor as f32;
-
-                                pb.cubic_to(x1, y1, x2, y2, x3, y3);
-                            }
-                            PathElement::Close => {
-                                pb.close();
-                            }
-                        }
-                    }
-                    let path = pb.finish().unwrap();
-                    let mut stroke = tiny_skia::Stroke::default();
-                    stroke.width = self.draw_config.stoke_width * scale_factor;
-                    stroke.line_cap = tiny_skia::LineCap::Round;
-                    let mut paint = tiny_skia::Paint::default();
-                    paint.set_color(self.draw_config.color.into());
-                    pixmap.fill_path(
-                        &path,
-                        &paint,
-                        Default::default(),
-                        tiny_skia::Transform::identity(),
-                        None,
-                    );
-                }
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        let paths: Vec<_> = self
+            .glyph_paths
+            .iter()
+            .filter_map(|g| {
+                Some((
+                    g.to_path(&ctx.scene_config, &projection_matrix)?,
+                    g.draw_config,
+                ))
+            })
+            .collect();
+        ctx.draw_stroke_passes(self.draw_config.stroke_behind, |ctx| {
+            for (path, draw_config) in &paths {
+                draw_config.fill_and_stroke(ctx, path, Default::default());
             }
-            _ => {}
+        });
+        for g in &self.glyph_paths {
+            g.draw_raster(ctx);
         }
     }
 }
 
+impl GlyphRaster {
+    // the image mapped onto the projected corners, so it is sheared and rotated with the text
+    fn draw(&self, ctx: &mut crate::Context, opacity: GMFloat) {
+        let projection_matrix = ctx.scene_config.projection_matrix();
+        let [top_left, top_right, bottom_left] = self
+            .corners
+            .map(|p| ctx.scene_config.project_with(&projection_matrix, &p));
+        let bottom_right = top_right + (bottom_left - top_left);
+        let mut pb = tiny_skia::PathBuilder::new();
+        pb.move_to(top_left.x, top_left.y);
+        pb.line_to(top_right.x, top_right.y);
+        pb.line_to(bottom_right.x, bottom_right.y);
+        pb.line_to(bottom_left.x, bottom_left.y);
+        pb.close();
+        // a glyph seen edge on has no area
+        let Some(path) = pb.finish() else {
+            return;
+        };
+        let (width, height) = (self.image.width() as f32, self.image.height() as f32);
+        let transform = tiny_skia::Transform::from_row(
+            (top_right.x - top_left.x) / width,
+            (top_right.y - top_left.y) / width,
+            (bottom_left.x - top_left.x) / height,
+            (bottom_left.y - top_left.y) / height,
+            top_left.x,
+            top_left.y,
+        );
+        let paint = tiny_skia::Paint {
+            shader: tiny_skia::Pattern::new(
+                self.image.as_ref(),
+                tiny_skia::SpreadMode::Pad,
+                tiny_skia::FilterQuality::Bicubic,
+                opacity as f32,
+                transform,
+            ),
+            ..Default::default()
+        };
+        ctx.fill_path(&path, &paint, tiny_skia::FillRule::Winding);
+    }
+}
+
 impl Text {
-    fn new(
+    pub fn new(
         text: String,
         position: Point3<GMFloat>,
         font_size: GMFloat,
         draw_config: DrawConfig,
     ) -> Self {
-        let mut glyph_paths = vec![];
-        if text.len() == 0 {
-            info!("text len is 0");
-            return Text {
-                text,
-                glyph_paths,
-                position,
-                font_size,
-                draw_config,
-            };
-        }
-        let mut f = fs::File::open("/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc")
-            .expect("can't open font file"); //replace with some font search
-        let mut font_data_data = vec![];
-        f.read_to_end(&mut font_data_data)
-            .expect("can't read font file");
-
-        let font =
-            Font::try_from_bytes(&font_data_data).expect("failed to parse font file content");
-        let scale = Scale::uniform(font_size as f32);
-        let v_metrics = font.v_metrics(scale);
-        // to see why we make start at (0.0, v_metrics.ascent), take a look at documentation
-        let glyphs: Vec<_> = font
-            .layout(&text, scale, point(0.0, 0.0 + v_metrics.ascent)) // maybe I need some padding here
-            .collect();
+        Self::with_font(text, position, font_size, &FontConfig::Default, draw_config)
+    }
 
-        let img_height = (v_metrics.ascent - v_metrics.descent).ceil() as usize;
-        let (img_width, min_x) = {
-            let min_x = glyphs
-                .first()
-                .map(|g| g.pixel_bounding_box().unwrap().min.x)
-                .unwrap();
-            let max_x = glyphs
-                .last()
-                .map(|g| g.pixel_bounding_box().unwrap().max.x)
-                .unwrap();
-            ((max_x - min_x) as usize, min_x)
-        }; // great, rusttype help me to calculate advance width and Kerning Pair
-        for glyph in glyphs {
-            let mut glyph_path = GlyphPath::new(glyph.position());
-            glyph.build_outline(&mut glyph_path);
-            glyph_paths.push(glyph_path);
+    // glyphs missing from font are taken from the fallback chain of crate::fonts
+    pub fn with_font(
+        text: String,
+        position: Point3<GMFloat>,
+        font_size: GMFloat,
+        font: &FontConfig,
+        draw_config: DrawConfig,
+    ) -> Self {
+        let mut glyph_paths = fonts::with_font_book(|book| layout(book, &text, font_size, font));
+        let offset = nalgebra::Matrix4::new_translation(&position.coords);
+        for g in &mut glyph_paths {
+            g.transform(nalgebra::Transform::from_matrix_unchecked(offset));
+            g.draw_config = draw_config;
         }
         Text {
             text,
@@ -319,7 +377,219 @@ impl Text {
     }
 }
 
-impl Mobject for Text {}
+// marks, joiners and variation selectors are drawn with the font of the char before them
+fn continues_cluster(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036f | 0x0483..=0x0489 | 0x0591..=0x05bd | 0x05bf..=0x05c7
+        | 0x0610..=0x061a | 0x064b..=0x065f | 0x0670 | 0x06d6..=0x06ed
+        | 0x1ab0..=0x1aff | 0x1dc0..=0x1dff | 0x200c | 0x200d | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f | 0xfe20..=0xfe2f | 0x1f3fb..=0x1f3ff | 0xe0020..=0xe007f
+        | 0xe0100..=0xe01ef)
+}
+
+// byte ranges of text with the font their glyphs come from, None for chars no font has
+fn font_runs(
+    book: &mut FontBook,
+    primary: Option<fontdb::ID>,
+    text: &str,
+) -> Vec<(Option<fontdb::ID>, Range<usize>)> {
+    let mut runs: Vec<(Option<fontdb::ID>, Range<usize>)> = vec![];
+    for (i, c) in text.char_indices() {
+        let previous = runs.last().and_then(|(id, _)| *id);
+        let id = match previous {
+            Some(id) if continues_cluster(c) && book.has_glyph(id, c) => Some(id),
+            _ => book.font_for_char(primary, c),
+        };
+        match runs.last_mut() {
+            Some((run_id, range)) if *run_id == id => range.end = i + c.len_utf8(),
+            _ => runs.push((id, i..i + c.len_utf8())),
+        }
+    }
+    runs
+}
+
+// glyphs of a run of one font and direction shaped by rustybuzz, appended at x
+fn shape_run(
+    loaded: &LoadedFont,
+    text: &str,
+    rtl: bool,
+    scale: Scale,
+    ascent: f32,
+    x: &mut f32,
+    glyph_paths: &mut Vec<GlyphPath>,
+) {
+    let Some(face) = rustybuzz::Face::from_slice(&loaded.data, loaded.index) else {
+        return;
+    };
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_direction(if rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+    let output = rustybuzz::shape(&face, &[], buffer);
+    // rusttype scales the height from ascent to descent to the font size
+    let v_metrics = loaded.font.v_metrics_unscaled();
+    let units_to_pixels = scale.y / (v_metrics.ascent - v_metrics.descent);
+    // a glyph covers the chars from its cluster to the next cluster of the run
+    let mut clusters: Vec<usize> = output
+        .glyph_infos()
+        .iter()
+        .map(|i| i.cluster as usize)
+        .collect();
+    clusters.sort_unstable();
+    clusters.dedup();
+    let chars = |cluster: usize| {
+        let end = clusters.iter().find(|&&c| c > cluster).copied();
+        text[cluster..end.unwrap_or(text.len())].to_owned()
+    };
+    for (info, position) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+        let origin = point(
+            *x + position.x_offset as f32 * units_to_pixels,
+            ascent - position.y_offset as f32 * units_to_pixels,
+        );
+        let glyph = loaded
+            .font
+            .glyph(rusttype::GlyphId(info.glyph_id as u16))
+            .scaled(scale)
+            .positioned(origin);
+        let mut glyph_path = GlyphPath::new(glyph.position());
+        glyph.build_outline(&mut glyph_path);
+        glyph_path.chars = chars(info.cluster as usize);
+        if glyph_path.path_elements.is_empty() {
+            let em = units_to_pixels * face.units_per_em() as f32;
+            glyph_path.raster = GlyphRaster::new(&face, info.glyph_id, em, origin);
+        }
+        glyph_paths.push(glyph_path);
+        *x += position.x_advance as f32 * units_to_pixels;
+    }
+}
+
+// one glyph path per glyph in visual order, shaped per run of one font and direction
+// so ligatures, combining marks, emoji sequences and right to left scripts come out
+// right. chars no font has are left out
+fn layout(
+    book: &mut FontBook,
+    text: &str,
+    font_size: GMFloat,
+    font: &FontConfig,
+) -> Vec<GlyphPath> {
+    let scale = Scale::uniform(font_size as f32);
+    let primary = book.primary(font);
+    if primary.is_none() {
+        warn!("no font for text {text:?}");
+    }
+    // the baseline is one ascent of the primary font below the top
+    let ascent = primary
+        .and_then(|id| book.font(id))
+        .map_or(0.0, |f| f.v_metrics(scale).ascent);
+    let mut x = 0.0;
+    let mut glyph_paths = vec![];
+    let bidi = unicode_bidi::BidiInfo::new(text, None);
+    for paragraph in &bidi.paragraphs {
+        let (levels, runs) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        for run in runs {
+            let rtl = levels[run.start].is_rtl();
+            let mut font_runs = font_runs(book, primary, &text[run.clone()]);
+            if rtl {
+                font_runs.reverse();
+            }
+            for (id, range) in font_runs {
+                let Some(loaded) = id.and_then(|id| book.loaded(id)) else {
+                    continue;
+                };
+                let range = run.start + range.start..run.start + range.end;
+                let run_text = text[range].trim_end_matches(['\n', '\r']);
+                shape_run(
+                    loaded,
+                    run_text,
+                    rtl,
+                    scale,
+                    ascent,
+                    &mut x,
+                    &mut glyph_paths,
+                );
+            }
+        }
+    }
+    glyph_paths
+}
+
+impl Text {
+    pub fn glyph_count(&self) -> usize {
+        self.glyph_paths.len()
+    }
+    // the first n glyphs with the same layout, the font is not loaded again.
+    // n counts glyphs like glyph_count, the text keeps the chars of those glyphs
+    pub fn truncated(&self, n: usize) -> Text {
+        let glyph_paths: Vec<GlyphPath> = self.glyph_paths.iter().take(n).cloned().collect();
+        Text {
+            text: glyph_paths.iter().map(|g| g.chars.as_str()).collect(),
+            glyph_paths,
+            position: self.position,
+            font_size: self.font_size,
+            draw_config: self.draw_config,
+        }
+    }
+}
+
+impl Mobject for Text {
+    fn get_draw_config(&self) -> DrawConfig {
+        self.draw_config
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {
+        self.draw_config = draw_config;
+        for g in &mut self.glyph_paths {
+            g.draw_config = draw_config;
+        }
+    }
+    fn get_points(&self) -> Vec<GMPoint> {
+        self.glyph_paths
+            .iter()
+            .flat_map(|g| g.get_points())
+            .collect()
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if points.len() != self.get_points().len() {
+            return;
+        }
+        let mut offset = 0;
+        for g in &mut self.glyph_paths {
+            let count = g.get_points().len();
+            g.set_points(&points[offset..offset + count]);
+            offset += count;
+        }
+    }
+    fn outline(&self) -> Vec<PathElement> {
+        self.glyph_paths.iter().flat_map(|g| g.outline()).collect()
+    }
+    // the glyphs in visual order, named by their chars
+    fn submobjects(&self) -> Vec<&dyn Mobject> {
+        self.glyph_paths.iter().map(|g| g as &dyn Mobject).collect()
+    }
+    fn submobjects_mut(&mut self) -> Vec<&mut dyn Mobject> {
+        self.glyph_paths
+            .iter_mut()
+            .map(|g| g as &mut dyn Mobject)
+            .collect()
+    }
+    fn clone_box(&self) -> Option<Box<dyn Mobject>> {
+        Some(Box::new(self.clone()))
+    }
+    #[cfg(feature = "serde")]
+    fn to_data(&self) -> Option<crate::scene_state::MobjectData> {
+        Some(crate::scene_state::MobjectData::Text(self.clone()))
+    }
+    // the glyphs are styled on their own
+    fn signature(&self, hasher: &mut dyn std::hash::Hasher) {
+        super::shape_signature(self, hasher);
+        for g in &self.glyph_paths {
+            g.signature(hasher);
+        }
+    }
+}
 
 #[test]
 fn test_draw_text() {
@@ -345,3 +615,75 @@ fn test_draw_text() {
         _ => {}
     }
 }
+
+#[test]
+fn test_shaping() {
+    let sans = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+    if !std::path::Path::new(sans).exists() {
+        return;
+    }
+    let mut book = FontBook::new();
+    let font = FontConfig::FontFile(sans.to_owned());
+    // the glyph count and the first point of the outline of the first glyph
+    let mut shape = |text: &str| {
+        let glyphs = layout(&mut book, text, 32.0, &font);
+        let first = glyphs[0].path_elements.iter().find_map(|p| match p {
+            PathElement::MoveTo(p) => Some(*p),
+            _ => None,
+        });
+        (glyphs.len(), first)
+    };
+    // the mark is drawn over the e instead of after it
+    assert_eq!(shape("e\u{301}").0, 1);
+    assert_eq!(shape("abc שלום").0, 8);
+    // hebrew is laid out right to left, the last letter comes first
+    assert_eq!(shape("שלום").1, shape("ם").1);
+    assert_ne!(shape("שלום").1, shape("ש").1);
+}
+
+#[test]
+fn test_glyph_submobjects() {
+    let sans = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+    if !std::path::Path::new(sans).exists() {
+        return;
+    }
+    let font = FontConfig::FontFile(sans.to_owned());
+    let position = Point3::new(1.0, 2.0, 0.0);
+    let text = Text::with_font(
+        "x+y".to_owned(),
+        position,
+        32.0,
+        &font,
+        DrawConfig::default(),
+    );
+    let mut text: Box<dyn Mobject> = Box::new(text);
+    assert_eq!(text.find_submobjects("+"), [[1]]);
+    // the glyphs are placed at the text position
+    let at_origin = Text::with_font(
+        "x+y".to_owned(),
+        Point3::origin(),
+        32.0,
+        &font,
+        DrawConfig::default(),
+    );
+    let offset = text.get_bounding_box().0 - at_origin.get_bounding_box().0;
+    assert!((offset - position.coords).norm() < 1e-4);
+    let plus = text.submobject_mut(&[1]).unwrap();
+    plus.set_draw_config(DrawConfig {
+        color: crate::Color::new(0xff, 0, 0, 0xff),
+        ..plus.get_draw_config()
+    });
+    let colors: Vec<_> = text
+        .submobjects()
+        .iter()
+        .map(|g| g.get_draw_config().color.r)
+        .collect();
+    assert_eq!(
+        colors,
+        [
+            DrawConfig::default().color.r,
+            0xff,
+            DrawConfig::default().color.r
+        ]
+    );
+}
@@ -1,24 +1,26 @@
-use std::f32::INFINITY;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use crate::{math_utils::constants::PI, mobjects::Transform, Color, ContextType};
-use nalgebra::{Isometry2, Matrix2, Point2, Point3, Point4, RealField};
+use nalgebra::{Isometry2, Matrix2, Point2, Point3, Point4, RealField, Vector3};
 use tiny_skia::{Pixmap, PixmapPaint};
 
-use crate::{camera::Camera, GMFloat};
+use crate::{camera::Camera, GMFloat, GMPoint};
 
-use super::Draw;
+use super::{Draw, DrawConfig, Mobject};
 
-struct ThreeDViewport {
+pub struct ThreeDViewport {
     pub position: Point3<GMFloat>,
     pub vp_width: GMFloat,
     pub vp_height: GMFloat,
-    pub camera: Camera,
+    // shared, so the camera can still be controlled after the viewport is added to a scene
+    pub camera: Rc<RefCell<Camera>>,
     pub triangle_list: Vec<Triangle>,
 }
-struct Triangle {
-    p0: Point3<GMFloat>,
-    p1: Point3<GMFloat>,
-    p2: Point3<GMFloat>,
+pub struct Triangle {
+    pub p0: Point3<GMFloat>,
+    pub p1: Point3<GMFloat>,
+    pub p2: Point3<GMFloat>,
 }
 
 impl ThreeDViewport {
@@ -32,10 +34,18 @@ impl ThreeDViewport {
             position,
             vp_width,
             vp_height,
-            camera,
+            camera: Rc::new(RefCell::new(camera)),
             triangle_list: Vec::new(),
         }
     }
+
+    // rotate the camera about the origin with rate radian per second during the following frames
+    pub fn begin_ambient_camera_rotation(&mut self, rate: GMFloat) {
+        self.camera.borrow_mut().begin_ambient_rotation(rate);
+    }
+    pub fn stop_ambient_camera_rotation(&mut self) {
+        self.camera.borrow_mut().stop_ambient_rotation();
+    }
 }
 
 impl Default for ThreeDViewport {
@@ -44,7 +54,7 @@ impl Default for ThreeDViewport {
             position: Point3::origin(),
             vp_width: 16.0,
             vp_height: 9.0,
-            camera: Camera::default(),
+            camera: Rc::new(RefCell::new(Camera::default())),
             triangle_list: Vec::new(),
         }
     }
@@ -59,109 +69,115 @@ impl Transform for ThreeDViewport {
 impl Draw for ThreeDViewport {
     fn draw(&self, ctx: &mut crate::Context) {
         let pixmap_size = (
-            (self.vp_width * ctx.scene_config.scale_factor) as u32,
-            (self.vp_height * ctx.scene_config.scale_factor) as u32,
-        );
-        let target_pix_coord = (
-            ctx.scene_config.convert_coord_y(self.position.x) as i32,
-            ctx.scene_config.convert_coord_y(self.position.y) as i32,
+            (self.vp_width * ctx.scene_config.pixel_scale()) as u32,
+            (self.vp_height * ctx.scene_config.pixel_scale()) as u32,
         );
+        let target_pix_coord = ctx.scene_config.project(&self.position);
+        let target_pix_coord = (target_pix_coord.x as i32, target_pix_coord.y as i32);
         if pixmap_size.0 == 0 {
             return;
         }
         if pixmap_size.1 == 0 {
             return;
         }
-        match &mut ctx.ctx_type {
-            ContextType::TinySKIA(pixmap) => {
-                let mut z_buffer: Vec<Vec<GMFloat>> = (0..pixmap_size.1)
-                    .map(|_| (0..pixmap_size.0).map(|_| -GMFloat::INFINITY).collect())
-                    .collect();
-                let mut new_pixmap = Pixmap::new(pixmap_size.0, pixmap_size.1).unwrap();
-                for t in &self.triangle_list {
-                    let camera_transform = self.camera.get_camera_transform_matrix();
-                    let projection_transform = self.camera.get_projection_transform_matrix();
-                    let m = projection_transform * camera_transform;
-                    let p0_p = m * t.p0.to_homogeneous();
-                    let p1_p = m * t.p1.to_homogeneous();
-                    let p2_p = m * t.p2.to_homogeneous();
-                    let p_x_list = [
-                        (p0_p[0] + 1.0) / 2.0 * pixmap_size.0 as GMFloat,
-                        (p1_p[0] + 1.0) / 2.0 * pixmap_size.0 as GMFloat,
-                        (p2_p[0] + 1.0) / 2.0 * pixmap_size.0 as GMFloat,
-                    ];
-                    let p_y_list = [
-                        (p0_p[1] + 1.0) / 2.0 * pixmap_size.1 as GMFloat,
-                        (p1_p[1] + 1.0) / 2.0 * pixmap_size.1 as GMFloat,
-                        (p2_p[1] + 1.0) / 2.0 * pixmap_size.1 as GMFloat,
-                    ];
-
-                    let x_min = p_x_list
-                        .into_iter()
-                        .map(|x| x as i32)
-                        .min()
-                        .unwrap()
-                        .clone()
-                        .clamp(0, pixmap_size.0 as i32);
-                    let x_max = p_x_list
-                        .into_iter()
-                        .map(|x| x as i32)
-                        .max()
-                        .unwrap()
-                        .clone()
-                        .clamp(0, pixmap_size.0 as i32);
-                    let y_min = p_y_list
-                        .into_iter()
-                        .map(|y| y as i32)
-                        .min()
-                        .unwrap()
-                        .clone()
-                        .clamp(0, pixmap_size.1 as i32);
-                    let y_max = p_y_list
-                        .into_iter()
-                        .map(|y| y as i32)
-                        .max()
-                        .unwrap()
-                        .clone()
-                        .clamp(0, pixmap_size.1 as i32);
-                    for x in x_min..x_max {
-                        for y in y_min..y_max {
-                            let half_width = pixmap_size.0 as GMFloat / 2.0;
-                            let half_height = pixmap_size.1 as GMFloat / 2.0;
-                            let x_r = x as GMFloat / half_width - 1.0;
-                            let y_r = y as GMFloat / half_height - 1.0;
-
-                            if let Some(new_z) = try_triangle_inner_z(
-                                Point3::from_homogeneous(p0_p).unwrap(),
-                                Point3::from_homogeneous(p1_p).unwrap(),
-                                Point3::from_homogeneous(p2_p).unwrap(),
-                                Point2::new(x_r, y_r),
-                            ) {
-                                if z_buffer[y as usize][x as usize] < new_z {
-                                    z_buffer[y as usize][x as usize] = new_z;
-                                    let pix_list = new_pixmap.pixels_mut();
-                                    pix_list[y as usize * pixmap_size.0 as usize + x as usize] =
-                                        tiny_skia::PremultipliedColorU8::from_rgba(
-                                            255, 255, 0, 255,
-                                        )
-                                        .unwrap();
-                                }
-                            }
+        let mut z_buffer: Vec<Vec<GMFloat>> = (0..pixmap_size.1)
+            .map(|_| (0..pixmap_size.0).map(|_| -GMFloat::INFINITY).collect())
+            .collect();
+        let mut new_pixmap = Pixmap::new(pixmap_size.0, pixmap_size.1).unwrap();
+        let camera = self.camera.borrow();
+        for t in &self.triangle_list {
+            let camera_transform = camera.get_camera_transform_matrix();
+            let projection_transform = camera.get_projection_transform_matrix();
+            let m = projection_transform * camera_transform;
+            let p0_p = m * t.p0.to_homogeneous();
+            let p1_p = m * t.p1.to_homogeneous();
+            let p2_p = m * t.p2.to_homogeneous();
+            let p_x_list = [
+                (p0_p[0] + 1.0) / 2.0 * pixmap_size.0 as GMFloat,
+                (p1_p[0] + 1.0) / 2.0 * pixmap_size.0 as GMFloat,
+                (p2_p[0] + 1.0) / 2.0 * pixmap_size.0 as GMFloat,
+            ];
+            let p_y_list = [
+                (p0_p[1] + 1.0) / 2.0 * pixmap_size.1 as GMFloat,
+                (p1_p[1] + 1.0) / 2.0 * pixmap_size.1 as GMFloat,
+                (p2_p[1] + 1.0) / 2.0 * pixmap_size.1 as GMFloat,
+            ];
+
+            let x_min = p_x_list
+                .into_iter()
+                .map(|x| x as i32)
+                .min()
+                .unwrap()
+                .clamp(0, pixmap_size.0 as i32);
+            let x_max = p_x_list
+                .into_iter()
+                .map(|x| x as i32)
+                .max()
+                .unwrap()
+                .clamp(0, pixmap_size.0 as i32);
+            let y_min = p_y_list
+                .into_iter()
+                .map(|y| y as i32)
+                .min()
+                .unwrap()
+                .clamp(0, pixmap_size.1 as i32);
+            let y_max = p_y_list
+                .into_iter()
+                .map(|y| y as i32)
+                .max()
+                .unwrap()
+                .clamp(0, pixmap_size.1 as i32);
+            for x in x_min..x_max {
+                for y in y_min..y_max {
+                    let half_width = pixmap_size.0 as GMFloat / 2.0;
+                    let half_height = pixmap_size.1 as GMFloat / 2.0;
+                    let x_r = x as GMFloat / half_width - 1.0;
+                    let y_r = y as GMFloat / half_height - 1.0;
+
+                    if let Some(new_z) = try_triangle_inner_z(
+                        Point3::from_homogeneous(p0_p).unwrap(),
+                        Point3::from_homogeneous(p1_p).unwrap(),
+                        Point3::from_homogeneous(p2_p).unwrap(),
+                        Point2::new(x_r, y_r),
+                    ) {
+                        if z_buffer[y as usize][x as usize] < new_z {
+                            z_buffer[y as usize][x as usize] = new_z;
+                            let pix_list = new_pixmap.pixels_mut();
+                            pix_list[y as usize * pixmap_size.0 as usize + x as usize] =
+                                tiny_skia::PremultipliedColorU8::from_rgba(255, 255, 0, 255)
+                                    .unwrap();
                         }
                     }
                 }
-                new_pixmap.save_png("out1.png");
-                pixmap.draw_pixmap(
-                    0,
-                    0,
-                    new_pixmap.as_ref(),
-                    &PixmapPaint::default(),
-                    tiny_skia::Transform::identity(),
-                    None,
-                );
             }
-            _ => {}
         }
+        new_pixmap.save_png("out1.png");
+        ctx.draw_pixmap(0, 0, &new_pixmap);
+    }
+}
+
+impl Mobject for ThreeDViewport {
+    // the viewport has no style of its own
+    fn get_draw_config(&self) -> DrawConfig {
+        DrawConfig::default()
+    }
+    fn set_draw_config(&mut self, draw_config: DrawConfig) {}
+    fn get_points(&self) -> Vec<GMPoint> {
+        vec![
+            self.position,
+            self.position + Vector3::new(self.vp_width, self.vp_height, 0.0),
+        ]
+    }
+    fn set_points(&mut self, points: &[GMPoint]) {
+        if let [position, _] = points {
+            self.position = *position;
+        }
+    }
+    fn advance_time(&mut self, dt: GMFloat) {
+        self.camera.borrow_mut().advance_time(dt);
+    }
+    fn is_time_dependent(&self) -> bool {
+        self.camera.borrow().ambient_rotation_rate != 0.0
     }
 }
 
@@ -170,7 +186,7 @@ pub fn test_three_d() {
     let mut ctx = crate::Context::default();
     let mut scene = crate::Scene::new();
     let mut three_d_vp = ThreeDViewport::default();
-    three_d_vp.camera.position = Point3::new(0.0, 0.0, 6.0);
+    three_d_vp.camera.borrow_mut().position = Point3::new(0.0, 0.0, 6.0);
     three_d_vp.triangle_list.push(Triangle {
         p0: Point3::new(0.0, 0.0, 0.0),
         p1: Point3::new(0.5, 0.25, 0.0),
@@ -214,11 +230,7 @@ pub fn try_triangle_inner_z(
     }
 
     let basis_matrix = Matrix2::from_columns(&[v0.xy(), v1.xy()]);
-    let maybe_b_inv = basis_matrix.try_inverse();
-    if maybe_b_inv.is_none() {
-        return None;
-    }
-    let b_inv = maybe_b_inv.unwrap();
+    let b_inv = basis_matrix.try_inverse()?;
     let c = b_inv * p;
     let z = c[0] * v0[2] + c[1] * v1[2];
     Some(z)
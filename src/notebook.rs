@@ -0,0 +1,134 @@
+use std::cell::RefCell;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::cli::{self, Backend, Quality, RenderOptions};
+use crate::svg_export::base64;
+use crate::video_backend::{FfmpegPipeEncoder, VideoBackend};
+use crate::{Context, Scene};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotebookFormat {
+    // a video element with controls
+    Mp4,
+    // an image that loops, larger but shown everywhere
+    Gif,
+}
+
+// small and fast by default, a notebook is for trying things out
+#[derive(Debug, Clone)]
+pub struct NotebookOptions {
+    pub width: u32,
+    pub height: u32,
+    pub framerate: u32,
+    pub format: NotebookFormat,
+}
+
+impl Default for NotebookOptions {
+    fn default() -> Self {
+        Self {
+            width: 640,
+            height: 360,
+            framerate: 15,
+            format: NotebookFormat::Mp4,
+        }
+    }
+}
+
+// evcxr sets this in the process that runs the cells
+pub fn is_evcxr() -> bool {
+    std::env::var_os("EVCXR_IS_RUNTIME").is_some()
+}
+
+// show html as the output of the cell, see the display protocol of evcxr
+pub fn display_html(html: &str) {
+    println!("EVCXR_BEGIN_CONTENT text/html\n{html}\nEVCXR_END_CONTENT");
+}
+
+// the rendered file embedded as a data url
+pub fn html_for(format: NotebookFormat, data: &[u8]) -> String {
+    match format {
+        NotebookFormat::Mp4 => format!(
+            r#"<video controls autoplay loop src="data:video/mp4;base64,{}"></video>"#,
+            base64(data)
+        ),
+        NotebookFormat::Gif => format!(r#"<img src="data:image/gif;base64,{}"/>"#, base64(data)),
+    }
+}
+
+// render the scene to a temporary file like cli::render does and, under evcxr, show it
+// as the output of the cell. the manim %%manim magic for gmanim:
+//     gmanim::notebook::show(&Default::default(), |scene, ctx, video_backend| { ... })?;
+// returns the path of the file
+pub fn show(
+    options: &NotebookOptions,
+    scene_fn: impl FnOnce(&Rc<RefCell<Scene>>, &Rc<RefCell<Context>>, &mut VideoBackend),
+) -> Result<String, String> {
+    static CELL: AtomicUsize = AtomicUsize::new(0);
+    let directory = std::env::temp_dir().join("gmanim_notebook");
+    std::fs::create_dir_all(&directory)
+        .map_err(|e| format!("can't create {}: {e}", directory.display()))?;
+    let name = format!(
+        "cell_{}_{}",
+        std::process::id(),
+        CELL.fetch_add(1, Ordering::Relaxed)
+    );
+    let video = directory.join(format!("{name}.mp4"));
+    let render_options = RenderOptions {
+        scenes: vec![name.clone()],
+        output: Some(video.to_string_lossy().into_owned()),
+        // low quality keeps yuv420p, which browsers play
+        quality: Quality::Low,
+        resolution: Some((options.width, options.height)),
+        framerate: Some(options.framerate),
+        backend: Backend::FfmpegPipe(FfmpegPipeEncoder::Libx264),
+        ..Default::default()
+    };
+    let output = cli::render_with(&render_options, &name, |scene, ctx, video_backend| {
+        scene_fn(scene, ctx, video_backend);
+        Ok(())
+    })?;
+    let output = match options.format {
+        NotebookFormat::Mp4 => output,
+        NotebookFormat::Gif => {
+            let gif = directory.join(format!("{name}.gif"));
+            to_gif(&output, &gif.to_string_lossy(), options.framerate)?;
+            let _ = std::fs::remove_file(&output);
+            gif.to_string_lossy().into_owned()
+        }
+    };
+    if is_evcxr() {
+        let data = std::fs::read(&output).map_err(|e| format!("can't read {output}: {e}"))?;
+        display_html(&html_for(options.format, &data));
+    }
+    Ok(output)
+}
+
+// with a palette made from the video, the default one bands gradients
+fn to_gif(video: &str, gif: &str, framerate: u32) -> Result<(), String> {
+    let status = Command::new("ffmpeg")
+        .args(["-y", "-v", "error", "-i", video, "-vf"])
+        .arg(format!(
+            "fps={framerate},split[a][b];[a]palettegen[p];[b][p]paletteuse"
+        ))
+        .args(["-loop", "0", gif])
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|e| format!("can't run ffmpeg: {e}"))?;
+    if !status.success() {
+        return Err(format!(
+            "can't convert {video} to a gif: ffmpeg exited with {status}"
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_html_for() {
+    assert_eq!(
+        html_for(NotebookFormat::Gif, b"gmanim"),
+        r#"<img src="data:image/gif;base64,Z21hbmlt"/>"#
+    );
+    assert!(html_for(NotebookFormat::Mp4, b"gmanim").starts_with("<video controls"));
+}
@@ -0,0 +1,300 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::animation::{Animation, AnimationConfig};
+use crate::mobjects::Mobject;
+use crate::video_backend::{VideoConfig, VideoSink};
+use crate::{Context, GMFloat, GMPoint, Scene};
+
+type MobjectRef = Rc<RefCell<Box<dyn Mobject>>>;
+
+// a value of the arguments a plugin mobject or animation is made from, as written
+// in a scene file or saved by scene_state
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
+pub enum PluginValue {
+    Bool(bool),
+    Number(GMFloat),
+    Text(String),
+    List(Vec<PluginValue>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct PluginArgs(pub BTreeMap<String, PluginValue>);
+
+impl PluginArgs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, key: &str, value: PluginValue) -> Self {
+        self.0.insert(key.to_owned(), value);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PluginValue> {
+        self.0.get(key)
+    }
+
+    pub fn number(&self, key: &str) -> Result<GMFloat, String> {
+        match self.get(key) {
+            Some(PluginValue::Number(n)) => Ok(*n),
+            Some(_) => Err(format!("{key} is not a number")),
+            None => Err(format!("missing argument {key}")),
+        }
+    }
+
+    pub fn text(&self, key: &str) -> Result<&str, String> {
+        match self.get(key) {
+            Some(PluginValue::Text(t)) => Ok(t),
+            Some(_) => Err(format!("{key} is not a string")),
+            None => Err(format!("missing argument {key}")),
+        }
+    }
+
+    pub fn bool(&self, key: &str) -> Result<bool, String> {
+        match self.get(key) {
+            Some(PluginValue::Bool(b)) => Ok(*b),
+            Some(_) => Err(format!("{key} is not a bool")),
+            None => Err(format!("missing argument {key}")),
+        }
+    }
+
+    // [x, y] or [x, y, z]
+    pub fn point(&self, key: &str) -> Result<GMPoint, String> {
+        let numbers: Option<Vec<GMFloat>> = match self.get(key) {
+            Some(PluginValue::List(list)) => list
+                .iter()
+                .map(|v| match v {
+                    PluginValue::Number(n) => Some(*n),
+                    _ => None,
+                })
+                .collect(),
+            None => return Err(format!("missing argument {key}")),
+            _ => None,
+        };
+        match numbers.as_deref() {
+            Some([x, y]) => Ok(GMPoint::new(*x, *y, 0.0)),
+            Some([x, y, z]) => Ok(GMPoint::new(*x, *y, *z)),
+            _ => Err(format!("{key} is not a point")),
+        }
+    }
+}
+
+pub type MobjectFactory = Rc<dyn Fn(&PluginArgs) -> Result<Box<dyn Mobject>, String>>;
+// the animation of a mobject the scene already holds, like the animations of scene_file
+pub type AnimationFactory = Rc<
+    dyn Fn(
+        Rc<RefCell<Scene>>,
+        Rc<RefCell<Context>>,
+        MobjectRef,
+        AnimationConfig,
+        &PluginArgs,
+    ) -> Result<Box<dyn Animation>, String>,
+>;
+pub type VideoSinkFactory = Rc<dyn Fn(&VideoConfig) -> Box<dyn VideoSink>>;
+
+pub struct VideoSinkEntry {
+    // of the output files, e.g. "gif"
+    pub extension: String,
+    pub factory: VideoSinkFactory,
+}
+
+// what a crate adds to gmanim, registered with register_plugin before rendering
+pub trait Plugin {
+    fn name(&self) -> &str;
+    fn register(&self, registry: &mut PluginRegistry);
+}
+
+// the mobject types, animations and video sinks of the registered plugins by name.
+// the cli renders to a sink with -b <name>, scene files build mobjects and animations
+// with {"plugin": {"kind": <name>, "args": {..}}}, and a mobject whose to_data is
+// MobjectData::Plugin is restored by its kind. a later registration replaces one
+// with the same name
+#[derive(Default)]
+pub struct PluginRegistry {
+    pub plugins: Vec<String>,
+    pub mobjects: BTreeMap<String, MobjectFactory>,
+    pub animations: BTreeMap<String, AnimationFactory>,
+    pub video_sinks: BTreeMap<String, VideoSinkEntry>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, plugin: &dyn Plugin) {
+        plugin.register(self);
+        self.plugins.push(plugin.name().to_owned());
+    }
+
+    pub fn add_mobject(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&PluginArgs) -> Result<Box<dyn Mobject>, String> + 'static,
+    ) -> &mut Self {
+        self.mobjects.insert(name.to_owned(), Rc::new(factory));
+        self
+    }
+
+    pub fn add_animation(
+        &mut self,
+        name: &str,
+        factory: impl Fn(
+                Rc<RefCell<Scene>>,
+                Rc<RefCell<Context>>,
+                MobjectRef,
+                AnimationConfig,
+                &PluginArgs,
+            ) -> Result<Box<dyn Animation>, String>
+            + 'static,
+    ) -> &mut Self {
+        self.animations.insert(name.to_owned(), Rc::new(factory));
+        self
+    }
+
+    pub fn add_video_sink(
+        &mut self,
+        name: &str,
+        extension: &str,
+        factory: impl Fn(&VideoConfig) -> Box<dyn VideoSink> + 'static,
+    ) -> &mut Self {
+        self.video_sinks.insert(
+            name.to_owned(),
+            VideoSinkEntry {
+                extension: extension.to_owned(),
+                factory: Rc::new(factory),
+            },
+        );
+        self
+    }
+
+    // every registered name, for cli --list-plugins
+    pub fn describe(&self) -> String {
+        fn join<'a>(names: impl Iterator<Item = &'a String>) -> String {
+            names.map(String::as_str).collect::<Vec<_>>().join(", ")
+        }
+        format!(
+            "plugins: {}\nmobjects: {}\nanimations: {}\nbackends: {}",
+            join(self.plugins.iter()),
+            join(self.mobjects.keys()),
+            join(self.animations.keys()),
+            join(self.video_sinks.keys())
+        )
+    }
+}
+
+thread_local! {
+    static PLUGINS: RefCell<PluginRegistry> = RefCell::new(PluginRegistry::new());
+}
+
+// the registry the cli, scene files and scene_state look names up in
+pub fn with_plugins<T>(f: impl FnOnce(&mut PluginRegistry) -> T) -> T {
+    PLUGINS.with(|plugins| f(&mut plugins.borrow_mut()))
+}
+
+pub fn register_plugin(plugin: &dyn Plugin) {
+    with_plugins(|plugins| plugins.register(plugin));
+}
+
+// the factories are called outside of the registry, so they may look up other names
+pub fn build_mobject(kind: &str, args: &PluginArgs) -> Result<Box<dyn Mobject>, String> {
+    let factory = with_plugins(|plugins| plugins.mobjects.get(kind).cloned())
+        .ok_or_else(|| format!("no plugin mobject named {kind}"))?;
+    factory(args)
+}
+
+pub fn build_animation(
+    kind: &str,
+    scene: Rc<RefCell<Scene>>,
+    ctx: Rc<RefCell<Context>>,
+    m: MobjectRef,
+    animation_config: AnimationConfig,
+    args: &PluginArgs,
+) -> Result<Box<dyn Animation>, String> {
+    let factory = with_plugins(|plugins| plugins.animations.get(kind).cloned())
+        .ok_or_else(|| format!("no plugin animation named {kind}"))?;
+    factory(scene, ctx, m, animation_config, args)
+}
+
+pub fn video_sink_extension(name: &str) -> Option<String> {
+    with_plugins(|plugins| plugins.video_sinks.get(name).map(|s| s.extension.clone()))
+}
+
+pub fn build_video_sink(name: &str, video_config: &VideoConfig) -> Option<Box<dyn VideoSink>> {
+    let factory = with_plugins(|plugins| plugins.video_sinks.get(name).map(|s| s.factory.clone()))?;
+    Some(factory(video_config))
+}
+
+#[test]
+fn test_plugin_registry() {
+    use crate::mobjects::Dot;
+    use crate::video_backend::ColorOrder;
+
+    struct Dots;
+    struct Counter(Rc<RefCell<usize>>);
+    impl VideoSink for Counter {
+        fn write_frame(&mut self, _: &[u8]) {
+            *self.0.borrow_mut() += 1;
+        }
+    }
+    impl Plugin for Dots {
+        fn name(&self) -> &str {
+            "dots"
+        }
+        fn register(&self, registry: &mut PluginRegistry) {
+            registry.add_mobject("big_dot", |args| {
+                Ok(Box::new(Dot::new(
+                    args.point("at")?,
+                    args.number("radius").unwrap_or(1.0),
+                    Default::default(),
+                    Default::default(),
+                )))
+            });
+        }
+    }
+    register_plugin(&Dots);
+    let frames = Rc::new(RefCell::new(0));
+    let counter = frames.clone();
+    with_plugins(|plugins| {
+        plugins.add_video_sink("count", "count", move |_| {
+            Box::new(Counter(counter.clone()))
+        });
+    });
+    let args = PluginArgs::new().with(
+        "at",
+        PluginValue::List(vec![PluginValue::Number(1.0), PluginValue::Number(2.0)]),
+    );
+    let dot = build_mobject("big_dot", &args).unwrap();
+    assert_eq!(dot.get_center(), GMPoint::new(1.0, 2.0, 0.0));
+    assert!(build_mobject("big_dot", &PluginArgs::new()).is_err());
+    assert!(build_mobject("star", &args).is_err());
+    let video_config = VideoConfig {
+        filename: "out.count".to_owned(),
+        framerate: 10,
+        output_width: 16,
+        output_height: 9,
+        color_order: ColorOrder::Rgba,
+    };
+    let mut sink = build_video_sink("count", &video_config).unwrap();
+    sink.write_frame(&[]);
+    sink.write_frame(&[]);
+    assert_eq!(*frames.borrow(), 2);
+    assert_eq!(video_sink_extension("count").as_deref(), Some("count"));
+    assert!(with_plugins(|p| p.describe()).contains("mobjects: big_dot"));
+    // the cli finds the sink by name
+    let args = ["intro", "-b", "count"].map(str::to_owned);
+    let options = crate::cli::RenderOptions::parse(args).unwrap();
+    assert_eq!(options.output_path("intro"), "intro.count");
+}
@@ -0,0 +1,99 @@
+use std::io::BufRead;
+
+use crate::{Context, ContextType};
+
+// debugging aid for rendering, disabled by default.
+// the latest frame is written to frame_path so an image viewer can keep showing it,
+// rendering halts at pause markers until a line is entered on stdin:
+// an empty line continues, "s" steps to the next frame and keeps stepping until continued
+pub struct Preview {
+    pub enabled: bool,
+    pub frame_path: String,
+    pub stepping: bool,
+    // frames rendered since the last pause marker
+    pub frame_count: u32,
+}
+
+impl Default for Preview {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frame_path: "preview.png".to_owned(),
+            stepping: false,
+            frame_count: 0,
+        }
+    }
+}
+
+impl Preview {
+    pub fn pause(&mut self, name: &str, ctx: &Context) {
+        self.pause_with_input(name, ctx, &mut std::io::stdin().lock());
+    }
+
+    pub fn pause_with_input(&mut self, name: &str, ctx: &Context, input: &mut impl BufRead) {
+        if !self.enabled {
+            return;
+        }
+        self.frame_count = 0;
+        self.save_frame(ctx);
+        println!("paused at {name}, enter to continue, s to step frame by frame");
+        self.stepping = read_step(input);
+    }
+
+    // called after every rendered frame, only halts while stepping
+    pub fn after_frame(&mut self, ctx: &Context) {
+        self.after_frame_with_input(ctx, &mut std::io::stdin().lock());
+    }
+
+    pub fn after_frame_with_input(&mut self, ctx: &Context, input: &mut impl BufRead) {
+        if !self.enabled {
+            return;
+        }
+        self.frame_count += 1;
+        if !self.stepping {
+            return;
+        }
+        self.save_frame(ctx);
+        println!("frame {}, enter to continue, s to step", self.frame_count);
+        self.stepping = read_step(input);
+    }
+
+    fn save_frame(&self, ctx: &Context) {
+        if let ContextType::TinySKIA(pixmap) = &ctx.ctx_type {
+            pixmap.save_png(&self.frame_path);
+        }
+    }
+}
+
+// end of input continues, so a preview never blocks a non interactive run forever
+fn read_step(input: &mut impl BufRead) -> bool {
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(n) if n > 0 => line.trim() == "s",
+        _ => false,
+    }
+}
+
+#[test]
+fn test_preview_stepping() {
+    let ctx = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(16, 9).unwrap()),
+        Default::default(),
+    );
+    let mut preview = Preview {
+        enabled: true,
+        frame_path: "preview_test.png".to_owned(),
+        ..Default::default()
+    };
+    let mut input = std::io::Cursor::new("s\ns\n\n");
+    preview.pause_with_input("start", &ctx, &mut input);
+    assert!(preview.stepping);
+    preview.after_frame_with_input(&ctx, &mut input);
+    assert!(preview.stepping);
+    preview.after_frame_with_input(&ctx, &mut input);
+    assert!(!preview.stepping);
+    // no more input is read once continued
+    preview.after_frame_with_input(&ctx, &mut input);
+    assert_eq!(preview.frame_count, 3);
+    assert_eq!(input.position(), 5);
+}
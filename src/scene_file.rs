@@ -0,0 +1,551 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use nalgebra::Vector3;
+use serde::Deserialize;
+
+use crate::animation::composition::AnimationGroup;
+use crate::animation::creation::DrawBorderThenFill;
+use crate::animation::growing::GrowFromPoint;
+use crate::animation::indication::Indicate;
+use crate::animation::style::SetStyle;
+use crate::animation::{
+    play, rate_functions, seconds_to_frames, Animation, AnimationConfig, SimpleMovement,
+    SimpleRotate,
+};
+use crate::background::Background;
+use crate::math_utils::constants::PI;
+use crate::mobjects::polygon::Polygon;
+use crate::mobjects::text::Text;
+use crate::mobjects::{Arc, Arrow, Dot, DrawConfig, Mobject, SimpleLine, Transform};
+use crate::plugin::{self, PluginArgs};
+use crate::video_backend::VideoBackend;
+use crate::{Color, Context, GMFloat, GMPoint, Scene};
+
+// a scene written as a document instead of rust code, in json, ron or yaml:
+// the mobjects with their style and placement, and a timeline that adds them and
+// animates them by name. the output size and framerate come from the renderer, like
+// for a scene function of cli::SceneRegistry
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SceneFile {
+    // "#rrggbb", the background of the scene config when None
+    pub background: Option<String>,
+    pub mobjects: Vec<MobjectSpec>,
+    pub timeline: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MobjectSpec {
+    // the timeline refers to the mobject by it
+    pub name: String,
+    pub shape: Shape,
+    #[serde(default)]
+    pub style: Style,
+    // placed after the shape is built, first next_to and then shift
+    pub next_to: Option<NextTo>,
+    #[serde(default)]
+    pub shift: (GMFloat, GMFloat),
+}
+
+// positions are scene units from the center of the frame, y up
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shape {
+    Dot {
+        at: (GMFloat, GMFloat),
+        #[serde(default = "default_dot_radius")]
+        radius: GMFloat,
+    },
+    Circle {
+        at: (GMFloat, GMFloat),
+        radius: GMFloat,
+    },
+    // angles in degrees, counterclockwise from the x axis
+    Arc {
+        at: (GMFloat, GMFloat),
+        radius: GMFloat,
+        start_angle: GMFloat,
+        end_angle: GMFloat,
+    },
+    Rectangle {
+        at: (GMFloat, GMFloat),
+        width: GMFloat,
+        height: GMFloat,
+    },
+    Polygon {
+        points: Vec<(GMFloat, GMFloat)>,
+    },
+    Line {
+        from: (GMFloat, GMFloat),
+        to: (GMFloat, GMFloat),
+    },
+    Arrow {
+        from: (GMFloat, GMFloat),
+        to: (GMFloat, GMFloat),
+    },
+    // font_size as for Text::new, at is the start of the baseline
+    Text {
+        text: String,
+        at: (GMFloat, GMFloat),
+        #[serde(default = "default_font_size")]
+        font_size: GMFloat,
+    },
+    // a mobject registered by a plugin, see crate::plugin
+    Plugin {
+        kind: String,
+        #[serde(default)]
+        args: PluginArgs,
+    },
+}
+
+fn default_dot_radius() -> GMFloat {
+    0.08
+}
+
+fn default_font_size() -> GMFloat {
+    48.0
+}
+
+// changes to DrawConfig::default, unset fields keep the default
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Style {
+    // "#rgb", "#rrggbb" or "#rrggbbaa"
+    pub color: Option<String>,
+    pub stroke_width: Option<GMFloat>,
+    pub fill: Option<bool>,
+    pub opacity: Option<GMFloat>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NextTo {
+    pub mobject: String,
+    pub direction: Direction,
+    // the gap between the bounding boxes
+    #[serde(default = "default_buff")]
+    pub buff: GMFloat,
+}
+
+fn default_buff() -> GMFloat {
+    0.25
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    // show the mobjects without an animation
+    Add(Vec<String>),
+    Remove(Vec<String>),
+    // the animations play together for run_time seconds
+    Play {
+        animations: Vec<AnimationSpec>,
+        #[serde(default = "default_run_time")]
+        run_time: GMFloat,
+    },
+    Wait(GMFloat),
+}
+
+fn default_run_time() -> GMFloat {
+    1.0
+}
+
+// create, grow and fade_in add their mobject to the scene, fade_out removes it at the end
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationSpec {
+    Create(String),
+    Grow(String),
+    FadeIn(String),
+    FadeOut(String),
+    Indicate(String),
+    Shift {
+        mobject: String,
+        by: (GMFloat, GMFloat),
+    },
+    // degrees counterclockwise about the center of the mobject
+    Rotate {
+        mobject: String,
+        angle: GMFloat,
+    },
+    // an animation registered by a plugin, see crate::plugin
+    Plugin {
+        kind: String,
+        mobject: String,
+        #[serde(default)]
+        args: PluginArgs,
+    },
+}
+
+impl AnimationSpec {
+    fn mobject(&self) -> &str {
+        match self {
+            AnimationSpec::Create(name)
+            | AnimationSpec::Grow(name)
+            | AnimationSpec::FadeIn(name)
+            | AnimationSpec::FadeOut(name)
+            | AnimationSpec::Indicate(name) => name,
+            AnimationSpec::Shift { mobject, .. }
+            | AnimationSpec::Rotate { mobject, .. }
+            | AnimationSpec::Plugin { mobject, .. } => mobject,
+        }
+    }
+}
+
+type MobjectRef = Rc<RefCell<Box<dyn Mobject>>>;
+
+fn point((x, y): (GMFloat, GMFloat)) -> GMPoint {
+    GMPoint::new(x, y, 0.0)
+}
+
+fn parse_color(hex: &str) -> Result<Color, String> {
+    Color::from_hex(hex).ok_or_else(|| format!("invalid color {hex}"))
+}
+
+impl Style {
+    fn draw_config(&self) -> Result<DrawConfig, String> {
+        let default = DrawConfig::default();
+        Ok(DrawConfig {
+            color: match &self.color {
+                Some(hex) => parse_color(hex)?,
+                None => default.color,
+            },
+            stoke_width: self.stroke_width.unwrap_or(default.stoke_width),
+            fill: self.fill.unwrap_or(default.fill),
+            opacity: self.opacity.unwrap_or(default.opacity),
+            ..default
+        })
+    }
+}
+
+impl Shape {
+    fn build(&self, draw_config: DrawConfig) -> Result<Box<dyn Mobject>, String> {
+        let mut mobject: Box<dyn Mobject> = match self {
+            Shape::Dot { at, radius } => Box::new(Dot::new(
+                point(*at),
+                *radius,
+                draw_config.color,
+                draw_config,
+            )),
+            Shape::Circle { at, radius } => Box::new(Arc::new(point(*at), 0.0, 2.0 * PI, *radius)),
+            Shape::Arc {
+                at,
+                radius,
+                start_angle,
+                end_angle,
+            } => Box::new(Arc::new(
+                point(*at),
+                start_angle.to_radians(),
+                end_angle.to_radians(),
+                *radius,
+            )),
+            Shape::Rectangle { at, width, height } => {
+                let (w, h) = (width / 2.0, height / 2.0);
+                let (x, y) = *at;
+                Box::new(Polygon::new(vec![
+                    point((x - w, y - h)),
+                    point((x + w, y - h)),
+                    point((x + w, y + h)),
+                    point((x - w, y + h)),
+                ]))
+            }
+            Shape::Polygon { points } => {
+                Box::new(Polygon::new(points.iter().copied().map(point).collect()))
+            }
+            Shape::Line { from, to } => Box::new(SimpleLine {
+                p0: point(*from),
+                p1: point(*to),
+                draw_config,
+            }),
+            Shape::Arrow { from, to } => Box::new(Arrow {
+                start: point(*from),
+                end: point(*to),
+                ..Default::default()
+            }),
+            Shape::Text {
+                text,
+                at,
+                font_size,
+            } => Box::new(Text::new(text.clone(), point(*at), *font_size, draw_config)),
+            Shape::Plugin { kind, args } => plugin::build_mobject(kind, args)?,
+        };
+        mobject.set_draw_config(draw_config);
+        Ok(mobject)
+    }
+}
+
+// move m so that its bounding box is buff away from the one of target in direction,
+// centered on it along the other axis
+fn place_next_to(m: &mut dyn Mobject, target: &dyn Mobject, direction: Direction, buff: GMFloat) {
+    let (min, max) = m.get_bounding_box();
+    let (target_min, target_max) = target.get_bounding_box();
+    let center = nalgebra::center(&min, &max);
+    let target_center = nalgebra::center(&target_min, &target_max);
+    let movement = match direction {
+        Direction::Right => {
+            Vector3::new(target_max.x + buff - min.x, target_center.y - center.y, 0.0)
+        }
+        Direction::Left => {
+            Vector3::new(target_min.x - buff - max.x, target_center.y - center.y, 0.0)
+        }
+        Direction::Up => Vector3::new(target_center.x - center.x, target_max.y + buff - min.y, 0.0),
+        Direction::Down => {
+            Vector3::new(target_center.x - center.x, target_min.y - buff - max.y, 0.0)
+        }
+    };
+    m.move_this(movement);
+}
+
+impl SceneFile {
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, String> {
+        ron::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self, String> {
+        serde_yaml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    // the format is chosen by the extension: .json, .ron, .yaml or .yml
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("can't read {}: {e}", path.display()))?;
+        let scene_file = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&text),
+            Some("ron") => Self::from_ron(&text),
+            Some("yaml" | "yml") => Self::from_yaml(&text),
+            _ => {
+                return Err(format!(
+                    "{} is not a json, ron or yaml file",
+                    path.display()
+                ))
+            }
+        };
+        scene_file.map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    // the mobjects by name, placed but not added to a scene
+    pub fn build_mobjects(&self) -> Result<HashMap<String, MobjectRef>, String> {
+        let mut mobjects: HashMap<String, MobjectRef> = HashMap::new();
+        for spec in &self.mobjects {
+            if mobjects.contains_key(&spec.name) {
+                return Err(format!("two mobjects are named {}", spec.name));
+            }
+            let mut m = spec
+                .shape
+                .build(spec.style.draw_config()?)
+                .map_err(|e| format!("{}: {e}", spec.name))?;
+            if let Some(next_to) = &spec.next_to {
+                let target = mobjects
+                    .get(&next_to.mobject)
+                    .ok_or_else(|| format!("next_to refers to {} before it", next_to.mobject))?;
+                place_next_to(
+                    m.as_mut(),
+                    target.borrow().as_ref(),
+                    next_to.direction,
+                    next_to.buff,
+                );
+            }
+            m.move_this(Vector3::new(spec.shift.0, spec.shift.1, 0.0));
+            mobjects.insert(spec.name.clone(), Rc::new(RefCell::new(m)));
+        }
+        // every name of the timeline has to exist before anything is rendered
+        for step in &self.timeline {
+            let names: Vec<&str> = match step {
+                Step::Add(names) | Step::Remove(names) => {
+                    names.iter().map(String::as_str).collect()
+                }
+                Step::Play { animations, .. } => animations.iter().map(|a| a.mobject()).collect(),
+                Step::Wait(_) => vec![],
+            };
+            if let Some(name) = names.iter().find(|name| !mobjects.contains_key(**name)) {
+                return Err(format!(
+                    "the timeline refers to {name}, which is not a mobject"
+                ));
+            }
+        }
+        Ok(mobjects)
+    }
+
+    // build the mobjects and play the timeline into video_backend, like a scene function
+    pub fn play(
+        &self,
+        scene: &Rc<RefCell<Scene>>,
+        ctx: &Rc<RefCell<Context>>,
+        video_backend: &mut VideoBackend,
+    ) -> Result<(), String> {
+        let mobjects = self.build_mobjects()?;
+        if let Some(hex) = &self.background {
+            ctx.borrow_mut().scene_config.background = Background::Color(parse_color(hex)?);
+        }
+        let framerate = ctx.borrow().scene_config.framerate;
+        let add = |m: &MobjectRef| {
+            if !scene.borrow().mobjects.iter().any(|n| Rc::ptr_eq(n, m)) {
+                scene.borrow_mut().add_ref(m.clone());
+            }
+        };
+        for step in &self.timeline {
+            match step {
+                Step::Add(names) => names.iter().for_each(|name| add(&mobjects[name])),
+                Step::Remove(names) => {
+                    for name in names {
+                        scene.borrow_mut().remove(&mobjects[name]);
+                    }
+                }
+                Step::Wait(seconds) => {
+                    scene
+                        .borrow_mut()
+                        .wait(&mut ctx.borrow_mut(), video_backend, *seconds);
+                }
+                Step::Play {
+                    animations,
+                    run_time,
+                } => {
+                    let config = || {
+                        AnimationConfig::from_run_time(*run_time, framerate, rate_functions::smooth)
+                    };
+                    let mut group: Vec<(GMFloat, Box<dyn Animation>)> = vec![];
+                    for spec in animations {
+                        let m = mobjects[spec.mobject()].clone();
+                        let (scene, ctx) = (scene.clone(), ctx.clone());
+                        let animation: Box<dyn Animation> = match spec {
+                            AnimationSpec::Create(_) => {
+                                add(&m);
+                                Box::new(DrawBorderThenFill::new(scene, ctx, m, config()))
+                            }
+                            AnimationSpec::Grow(_) => {
+                                add(&m);
+                                Box::new(GrowFromPoint::from_center(scene, ctx, m, config()))
+                            }
+                            AnimationSpec::FadeIn(_) => {
+                                add(&m);
+                                let target = m.borrow().get_draw_config();
+                                m.borrow_mut().set_draw_config(DrawConfig {
+                                    opacity: 0.0,
+                                    ..target
+                                });
+                                Box::new(SetStyle::to_style(scene, ctx, m, target, config()))
+                            }
+                            AnimationSpec::FadeOut(_) => Box::new(SetStyle::new(
+                                scene,
+                                ctx,
+                                m,
+                                Box::new(|c| DrawConfig { opacity: 0.0, ..c }),
+                                config(),
+                            )),
+                            AnimationSpec::Indicate(_) => Box::new(Indicate::new(
+                                scene,
+                                ctx,
+                                m,
+                                seconds_to_frames(*run_time, framerate).max(1),
+                            )),
+                            AnimationSpec::Shift { by, .. } => Box::new(SimpleMovement {
+                                displacement: Vector3::new(by.0, by.1, 0.0),
+                                scene,
+                                ctx,
+                                m,
+                                animation_config: config(),
+                                last_progress: 0.0,
+                                path_arc: 0.0,
+                            }),
+                            AnimationSpec::Rotate { angle, .. } => {
+                                let point = m.borrow().get_center();
+                                Box::new(SimpleRotate {
+                                    axisangle: Vector3::new(0.0, 0.0, angle.to_radians()),
+                                    point,
+                                    scene,
+                                    ctx,
+                                    m,
+                                    animation_config: config(),
+                                    last_progress: 0.0,
+                                })
+                            }
+                            AnimationSpec::Plugin { kind, args, .. } => {
+                                plugin::build_animation(kind, scene, ctx, m, config(), args)?
+                            }
+                        };
+                        group.push((0.0, animation));
+                    }
+                    play(
+                        scene,
+                        ctx,
+                        video_backend,
+                        AnimationGroup::new(scene.clone(), ctx.clone(), group),
+                    );
+                    for spec in animations {
+                        if let AnimationSpec::FadeOut(name) = spec {
+                            scene.borrow_mut().remove(&mobjects[name]);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_scene_file() {
+    use crate::video_backend::{BufferBackend, VideoBackendType};
+    use crate::{ContextType, SceneConfig};
+    let scene_file = SceneFile::from_json(
+        r##"{
+            "background": "#102030",
+            "mobjects": [
+                {"name": "box", "shape": {"rectangle": {"at": [0, 0], "width": 2, "height": 1}},
+                 "style": {"color": "#ff0000"}},
+                {"name": "dot", "shape": {"dot": {"at": [5, 5], "radius": 0.5}},
+                 "next_to": {"mobject": "box", "direction": "right", "buff": 1}}
+            ],
+            "timeline": [
+                {"add": ["box"]},
+                {"play": {"animations": [{"fade_in": "dot"}, {"shift": {"mobject": "box", "by": [0, 1]}}],
+                          "run_time": 0.5}},
+                {"wait": 0.2}
+            ]
+        }"##,
+    )
+    .unwrap();
+    let mobjects = scene_file.build_mobjects().unwrap();
+    let (min, max) = mobjects["dot"].borrow().get_bounding_box();
+    assert!((min.x - 2.0).abs() < 1e-4 && (max.y - 0.5).abs() < 1e-4);
+    let scene = Rc::new(RefCell::new(Scene::new()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            framerate: 10,
+            ..Default::default()
+        },
+    )));
+    let frames = Rc::new(RefCell::new(0));
+    let counter = frames.clone();
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::Buffer(BufferBackend::new(move |_| {
+            *counter.borrow_mut() += 1
+        })),
+    };
+    scene_file.play(&scene, &ctx, &mut video_backend).unwrap();
+    assert_eq!(*frames.borrow(), 7);
+    assert_eq!(scene.borrow().mobjects.len(), 2);
+    // play builds its own mobjects, the box is the first one added
+    assert!((scene.borrow().mobjects[0].borrow().get_center().y - 1.0).abs() < 1e-4);
+    let missing = SceneFile::from_json(r#"{"timeline": [{"add": ["circle"]}]}"#).unwrap();
+    assert!(missing.build_mobjects().is_err());
+}
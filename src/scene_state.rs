@@ -0,0 +1,146 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::ZoomedDisplay;
+use crate::mobjects::group::MobjectGroup;
+use crate::mobjects::polygon::Polygon;
+use crate::mobjects::text::Text;
+use crate::mobjects::{Arc, Arrow, Dot, Mobject, PolyLine, Rectangle, SimpleLine};
+use crate::plugin::{self, PluginArgs};
+use crate::{GMFloat, Scene, SceneConfig};
+
+// the mobjects of the crate in a serializable form, made by Mobject::to_data
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum MobjectData {
+    Dot(Dot),
+    Rectangle(Rectangle),
+    SimpleLine(SimpleLine),
+    PolyLine(PolyLine),
+    Arc(Arc),
+    Polygon(Polygon),
+    Arrow(Arrow),
+    Text(Text),
+    Group { mobjects: Vec<MobjectData> },
+    // a mobject of a plugin, rebuilt by the mobject factory registered as kind
+    Plugin { kind: String, args: PluginArgs },
+}
+
+impl MobjectData {
+    pub fn into_mobject(self) -> Box<dyn Mobject> {
+        match self {
+            MobjectData::Dot(m) => Box::new(m),
+            MobjectData::Rectangle(m) => Box::new(m),
+            MobjectData::SimpleLine(m) => Box::new(m),
+            MobjectData::PolyLine(m) => Box::new(m),
+            MobjectData::Arc(m) => Box::new(m),
+            MobjectData::Polygon(m) => Box::new(m),
+            MobjectData::Arrow(m) => Box::new(m),
+            MobjectData::Text(m) => Box::new(m),
+            MobjectData::Group { mobjects } => Box::new(MobjectGroup {
+                mobjects: mobjects.into_iter().map(|m| m.into_mobject()).collect(),
+            }),
+            // a state saved with a plugin that isn't registered loads without its mobjects
+            MobjectData::Plugin { kind, args } => plugin::build_mobject(&kind, &args)
+                .unwrap_or_else(|e| {
+                    warn!("{e}");
+                    Box::new(MobjectGroup { mobjects: vec![] })
+                }),
+        }
+    }
+}
+
+// what a scene shows at one moment, to save and reload it or to send it to a render
+// worker. updaters, sections, custom projections, image backgrounds, luts and mobjects
+// without Mobject::to_data are left out
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SceneState {
+    pub scene_config: SceneConfig,
+    // in the order of Scene::mobjects
+    pub mobjects: Vec<MobjectData>,
+    // indices into mobjects, see Scene::add_fixed_in_frame
+    pub fixed_in_frame: Vec<usize>,
+    pub zoomed_displays: Vec<ZoomedDisplay>,
+    pub time: GMFloat,
+    pub playback_speed: GMFloat,
+}
+
+impl SceneState {
+    pub fn capture(scene: &Scene, scene_config: &SceneConfig) -> Self {
+        let mut mobjects = vec![];
+        let mut fixed_in_frame = vec![];
+        for m in &scene.mobjects {
+            let Some(data) = m.borrow().to_data() else {
+                continue;
+            };
+            if scene.is_fixed_in_frame(m) {
+                fixed_in_frame.push(mobjects.len());
+            }
+            mobjects.push(data);
+        }
+        Self {
+            scene_config: scene_config.clone(),
+            mobjects,
+            fixed_in_frame,
+            zoomed_displays: scene.zoomed_displays.clone(),
+            time: scene.time,
+            playback_speed: scene.playback_speed,
+        }
+    }
+
+    // a new scene with the saved mobjects, and the config to draw it with
+    pub fn restore(self) -> (Scene, SceneConfig) {
+        let mut scene = Scene::new();
+        for (i, data) in self.mobjects.into_iter().enumerate() {
+            let m = Rc::new(RefCell::new(data.into_mobject()));
+            if self.fixed_in_frame.contains(&i) {
+                scene.add_fixed_in_frame(m);
+            } else {
+                scene.add_ref(m);
+            }
+        }
+        scene.zoomed_displays = self.zoomed_displays;
+        scene.time = self.time;
+        scene.playback_speed = self.playback_speed;
+        (scene, self.scene_config)
+    }
+}
+
+#[test]
+fn test_scene_state() {
+    use crate::mobjects::DrawConfig;
+    use crate::{Color, GMPoint};
+    let mut scene = Scene::new();
+    let red = Color::new(0xff, 0, 0, 0xff);
+    scene.add(Box::new(Dot::new(
+        GMPoint::new(1.0, 2.0, 0.0),
+        0.5,
+        red,
+        DrawConfig::default(),
+    )));
+    let title: Box<dyn Mobject> = Box::new(Polygon::new(vec![
+        GMPoint::new(0.0, 0.0, 0.0),
+        GMPoint::new(1.0, 0.0, 0.0),
+        GMPoint::new(0.0, 1.0, 0.0),
+    ]));
+    scene.add_fixed_in_frame(Rc::new(RefCell::new(title)));
+    scene.time = 3.0;
+    let scene_config = SceneConfig {
+        framerate: 24,
+        ..Default::default()
+    };
+    let json = serde_json::to_string(&SceneState::capture(&scene, &scene_config)).unwrap();
+    let state: SceneState = serde_json::from_str(&json).unwrap();
+    let (restored, restored_config) = state.restore();
+    assert_eq!(restored_config.framerate, 24);
+    assert_eq!(restored.time, 3.0);
+    assert_eq!(restored.mobjects.len(), 2);
+    assert_eq!(
+        restored.mobjects[0].borrow().get_points(),
+        scene.mobjects[0].borrow().get_points()
+    );
+    assert!(restored.is_fixed_in_frame(&restored.mobjects[1]));
+}
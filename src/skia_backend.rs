@@ -0,0 +1,218 @@
+use skia_safe::{
+    paint, surfaces, AlphaType, BlendMode, Color, Color4f, ColorType, ImageInfo, Paint, PaintStyle,
+    Path, PathEffect, PathFillType, Surface,
+};
+use tiny_skia::{FillRule, PathSegment, Shader};
+
+use crate::{mobjects::DrawConfig, GMFloat};
+
+// raster surface of the full skia library, used by ContextType::Skia.
+// mobjects still build tiny-skia paths which are converted here, the canvas is public
+// for anything tiny-skia can't do (text blobs, image filters)
+pub struct SkiaCanvas {
+    pub surface: Surface,
+    image_info: ImageInfo,
+    // rgba premultiplied like a tiny-skia pixmap, refreshed by finish_frame
+    pixels: Vec<u8>,
+}
+
+impl SkiaCanvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        let image_info = ImageInfo::new(
+            (width as i32, height as i32),
+            ColorType::RGBA8888,
+            AlphaType::Premul,
+            None,
+        );
+        let surface = surfaces::raster(&image_info, None, None).expect("can't create skia surface");
+        Self {
+            surface,
+            image_info,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    pub fn canvas(&mut self) -> &skia_safe::Canvas {
+        self.surface.canvas()
+    }
+
+    // gradients and images are not supported, they clear to Background::base_color
+    pub fn clear(&mut self, color: crate::Color) {
+        self.surface
+            .canvas()
+            .clear(Color::from_argb(color.a, color.r, color.g, color.b));
+    }
+
+    // saves the canvas state, pop_clip restores it
+    pub fn push_clip(&mut self, path: &tiny_skia::Path, anti_alias: bool) {
+        let canvas = self.surface.canvas();
+        canvas.save();
+        canvas.clip_path(&convert_path(path), None, anti_alias);
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.surface.canvas().restore();
+    }
+
+    pub fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        fill_rule: FillRule,
+    ) {
+        let mut path = convert_path(path);
+        path.set_fill_type(match fill_rule {
+            FillRule::Winding => PathFillType::Winding,
+            FillRule::EvenOdd => PathFillType::EvenOdd,
+        });
+        let mut skia_paint = Paint::new(solid_color(paint), None);
+        skia_paint.set_anti_alias(paint.anti_alias);
+        skia_paint.set_blend_mode(blend_mode(paint.blend_mode));
+        skia_paint.set_style(PaintStyle::Fill);
+        self.surface.canvas().draw_path(&path, &skia_paint);
+    }
+
+    pub fn stroke_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        draw_config: &DrawConfig,
+        scale_factor: GMFloat,
+        anti_alias: bool,
+    ) {
+        let path = convert_path(path);
+        let paint = draw_config.get_paint();
+        let mut skia_paint = Paint::new(solid_color(&paint), None);
+        skia_paint.set_anti_alias(anti_alias);
+        skia_paint.set_blend_mode(blend_mode(paint.blend_mode));
+        skia_paint.set_style(PaintStyle::Stroke);
+        skia_paint.set_stroke_width((draw_config.stoke_width * scale_factor) as f32);
+        skia_paint.set_stroke_cap(paint::Cap::Round);
+        skia_paint.set_stroke_join(paint::Join::Round);
+        if let Some((dash_length, gap_length)) = draw_config.dash {
+            skia_paint.set_path_effect(PathEffect::dash(
+                &[
+                    (dash_length * scale_factor) as f32,
+                    (gap_length * scale_factor) as f32,
+                ],
+                (draw_config.dash_offset * scale_factor) as f32,
+            ));
+        }
+        self.surface.canvas().draw_path(&path, &skia_paint);
+    }
+
+    // copy the surface into the byte buffer returned by pixels
+    pub fn finish_frame(&mut self) {
+        let row_bytes = self.image_info.min_row_bytes();
+        let image_info = self.image_info.clone();
+        self.surface
+            .read_pixels(&image_info, &mut self.pixels, row_bytes, (0, 0));
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
+
+fn convert_path(path: &tiny_skia::Path) -> Path {
+    let mut skia_path = Path::new();
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                skia_path.move_to((p.x, p.y));
+            }
+            PathSegment::LineTo(p) => {
+                skia_path.line_to((p.x, p.y));
+            }
+            PathSegment::QuadTo(p1, p) => {
+                skia_path.quad_to((p1.x, p1.y), (p.x, p.y));
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                skia_path.cubic_to((p1.x, p1.y), (p2.x, p2.y), (p.x, p.y));
+            }
+            PathSegment::Close => {
+                skia_path.close();
+            }
+        }
+    }
+    skia_path
+}
+
+fn blend_mode(blend_mode: tiny_skia::BlendMode) -> BlendMode {
+    use tiny_skia::BlendMode as B;
+    match blend_mode {
+        B::Clear => BlendMode::Clear,
+        B::Source => BlendMode::Src,
+        B::Destination => BlendMode::Dst,
+        B::SourceOver => BlendMode::SrcOver,
+        B::DestinationOver => BlendMode::DstOver,
+        B::SourceIn => BlendMode::SrcIn,
+        B::DestinationIn => BlendMode::DstIn,
+        B::SourceOut => BlendMode::SrcOut,
+        B::DestinationOut => BlendMode::DstOut,
+        B::SourceAtop => BlendMode::SrcATop,
+        B::DestinationAtop => BlendMode::DstATop,
+        B::Xor => BlendMode::Xor,
+        B::Plus => BlendMode::Plus,
+        B::Modulate => BlendMode::Modulate,
+        B::Screen => BlendMode::Screen,
+        B::Overlay => BlendMode::Overlay,
+        B::Darken => BlendMode::Darken,
+        B::Lighten => BlendMode::Lighten,
+        B::ColorDodge => BlendMode::ColorDodge,
+        B::ColorBurn => BlendMode::ColorBurn,
+        B::HardLight => BlendMode::HardLight,
+        B::SoftLight => BlendMode::SoftLight,
+        B::Difference => BlendMode::Difference,
+        B::Exclusion => BlendMode::Exclusion,
+        B::Multiply => BlendMode::Multiply,
+        B::Hue => BlendMode::Hue,
+        B::Saturation => BlendMode::Saturation,
+        B::Color => BlendMode::Color,
+        B::Luminosity => BlendMode::Luminosity,
+    }
+}
+
+// only solid colors are used by the mobjects, other shaders are drawn white
+fn solid_color(paint: &tiny_skia::Paint) -> Color4f {
+    match &paint.shader {
+        Shader::SolidColor(color) => {
+            Color4f::new(color.red(), color.green(), color.blue(), color.alpha())
+        }
+        _ => Color4f::new(1.0, 1.0, 1.0, 1.0),
+    }
+}
+
+#[test]
+fn test_skia_context_draw() {
+    use crate::{mobjects::Dot, Color, Context, ContextType, GMPoint, Scene, SceneConfig};
+    let scene_config = SceneConfig {
+        output_width: 160,
+        output_height: 90,
+        scale_factor: 10.0,
+        ..Default::default()
+    };
+    let red = Color::new(0xff, 0, 0, 0xff);
+    let mut scene = Scene::new();
+    scene.add(Box::new(Dot::new(
+        GMPoint::origin(),
+        2.0,
+        red,
+        DrawConfig {
+            color: red,
+            ..Default::default()
+        },
+    )));
+    let mut skia = Context::skia(scene_config.clone());
+    scene.draw(&mut skia);
+    let mut tiny = Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(160, 90).unwrap()),
+        scene_config,
+    );
+    scene.draw(&mut tiny);
+    let pixel =
+        |ctx: &Context, x: usize, y: usize| ctx.image_bytes()[(y * 160 + x) * 4..][..4].to_vec();
+    assert_ne!(pixel(&skia, 80, 45), pixel(&skia, 5, 5));
+    // the background and the dot come out like in a tiny-skia context
+    assert_eq!(pixel(&skia, 5, 5), pixel(&tiny, 5, 5));
+    assert_eq!(pixel(&skia, 80, 45), pixel(&tiny, 80, 45));
+}
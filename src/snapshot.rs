@@ -0,0 +1,193 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use log::warn;
+
+use crate::mobjects::{DrawConfig, Mobject};
+use crate::{GMPoint, Scene};
+
+type MobjectRef = Rc<RefCell<Box<dyn Mobject>>>;
+
+// the style of a mobject and of each of its submobjects
+#[derive(Debug, Clone)]
+struct StyleSnapshot {
+    draw_config: DrawConfig,
+    parts: Vec<StyleSnapshot>,
+}
+
+impl StyleSnapshot {
+    fn new(m: &dyn Mobject) -> Self {
+        Self {
+            draw_config: m.get_draw_config(),
+            parts: m.submobjects().into_iter().map(Self::new).collect(),
+        }
+    }
+
+    // the parent first, its set_draw_config reaches the parts
+    fn apply(&self, m: &mut dyn Mobject) {
+        m.set_draw_config(self.draw_config);
+        for (part, style) in m.submobjects_mut().into_iter().zip(&self.parts) {
+            style.apply(part);
+        }
+    }
+}
+
+// a copy of one mobject, or its points and styles when it can't be copied.
+// the points of its parts are among its own
+pub struct MobjectSnapshot {
+    copy: Option<Box<dyn Mobject>>,
+    points: Vec<GMPoint>,
+    style: StyleSnapshot,
+}
+
+impl Clone for MobjectSnapshot {
+    fn clone(&self) -> Self {
+        Self {
+            copy: self.copy.as_ref().and_then(|m| m.clone_box()),
+            points: self.points.clone(),
+            style: self.style.clone(),
+        }
+    }
+}
+
+impl MobjectSnapshot {
+    pub fn new(m: &dyn Mobject) -> Self {
+        Self {
+            copy: m.clone_box(),
+            points: m.get_points(),
+            style: StyleSnapshot::new(m),
+        }
+    }
+
+    // the copy is swapped in, so content like the string of a text comes back as well.
+    // a mobject without one that was replaced by one with other points, e.g. by a
+    // typewriter animation, keeps its geometry
+    pub fn apply(&self, m: &mut Box<dyn Mobject>) {
+        if let Some(copy) = self.copy.as_ref().and_then(|copy| copy.clone_box()) {
+            *m = copy;
+            return;
+        }
+        if m.get_points().len() == self.points.len() {
+            m.set_points(&self.points);
+        } else {
+            warn!("the mobject changed its shape since the snapshot, restoring its style only");
+        }
+        self.style.apply(m.as_mut());
+    }
+}
+
+// which mobjects a scene shows, with their geometry and styles
+#[derive(Clone)]
+pub struct SceneSnapshot {
+    mobjects: Vec<(MobjectRef, MobjectSnapshot)>,
+    fixed_in_frame: Vec<MobjectRef>,
+}
+
+impl Scene {
+    pub fn snapshot(&self) -> SceneSnapshot {
+        SceneSnapshot {
+            mobjects: self
+                .mobjects
+                .iter()
+                .map(|m| (m.clone(), MobjectSnapshot::new(m.borrow().as_ref())))
+                .collect(),
+            fixed_in_frame: self.fixed_in_frame.clone(),
+        }
+    }
+
+    // mobjects added since the snapshot are removed with their updaters and removed ones
+    // come back, without the updaters remove dropped
+    pub fn restore_snapshot(&mut self, snapshot: &SceneSnapshot) {
+        self.mobjects = snapshot.mobjects.iter().map(|(m, _)| m.clone()).collect();
+        self.fixed_in_frame = snapshot.fixed_in_frame.clone();
+        let mobjects = &self.mobjects;
+        self.updaters
+            .retain(|(m, _)| mobjects.iter().any(|kept| Rc::ptr_eq(kept, m)));
+        for (m, state) in &snapshot.mobjects {
+            state.apply(&mut m.borrow_mut());
+        }
+    }
+
+    // remember the scene for restore, e.g. to show a transformation and then revert it
+    pub fn save_state(&mut self) {
+        self.saved_state = Some(self.snapshot());
+    }
+
+    // back to the last save_state, false when there is none
+    pub fn restore(&mut self) -> bool {
+        let Some(snapshot) = self.saved_state.take() else {
+            return false;
+        };
+        self.restore_snapshot(&snapshot);
+        self.saved_state = Some(snapshot);
+        true
+    }
+}
+
+#[test]
+fn test_save_and_restore() {
+    use crate::mobjects::{Dot, Transform};
+    use crate::Color;
+
+    let mut scene = Scene::new();
+    let dot: MobjectRef = Rc::new(RefCell::new(Box::new(Dot::new(
+        GMPoint::origin(),
+        0.5,
+        Color::default(),
+        DrawConfig::default(),
+    ))));
+    scene.add_ref(dot.clone());
+    assert!(!scene.restore());
+    scene.save_state();
+    dot.borrow_mut()
+        .shift(nalgebra::Vector3::new(2.0, 1.0, 0.0));
+    dot.borrow_mut().set_draw_config(DrawConfig {
+        stoke_width: 0.3,
+        ..Default::default()
+    });
+    scene.remove(&dot);
+    scene.add(Box::new(Dot::new(
+        GMPoint::origin(),
+        1.0,
+        Color::default(),
+        DrawConfig::default(),
+    )));
+    assert!(scene.restore());
+    assert_eq!(scene.mobjects.len(), 1);
+    assert!(Rc::ptr_eq(&scene.mobjects[0], &dot));
+    assert_eq!(dot.borrow().get_center(), GMPoint::origin());
+    assert_eq!(
+        dot.borrow().get_draw_config().stoke_width,
+        DrawConfig::default().stoke_width
+    );
+    // the saved state can be restored again
+    dot.borrow_mut()
+        .shift(nalgebra::Vector3::new(1.0, 0.0, 0.0));
+    assert!(scene.restore());
+    assert_eq!(dot.borrow().get_center(), GMPoint::origin());
+}
+
+#[test]
+fn test_restore_text() {
+    use crate::mobjects::text::Text;
+
+    let mut scene = Scene::new();
+    let text: MobjectRef = Rc::new(RefCell::new(Box::new(Text::new(
+        "a".to_owned(),
+        GMPoint::origin(),
+        32.0,
+        DrawConfig::default(),
+    ))));
+    scene.add_ref(text.clone());
+    scene.save_state();
+    let points = text.borrow().get_points();
+    *text.borrow_mut() = Box::new(Text::new(
+        "abc".to_owned(),
+        GMPoint::origin(),
+        32.0,
+        DrawConfig::default(),
+    ));
+    assert!(scene.restore());
+    assert_eq!(text.borrow().get_points(), points);
+    assert_eq!(text.borrow().submobjects().len(), 1);
+}
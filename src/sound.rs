@@ -0,0 +1,193 @@
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::GMFloat;
+
+// the rate every clip is resampled to, the same as the audio stream of FfmpegBackend
+pub const SAMPLE_RATE: u32 = 44100;
+// samples are interleaved stereo
+pub const CHANNELS: usize = 2;
+
+// an audio file starting at_time seconds into the video, see Scene::add_sound
+#[derive(Debug, Clone)]
+pub struct SoundClip {
+    pub path: String,
+    pub at_time: GMFloat,
+    pub gain: GMFloat,
+}
+
+impl SoundClip {
+    pub fn new(path: &str, at_time: GMFloat) -> Self {
+        Self {
+            path: path.to_owned(),
+            at_time,
+            gain: 1.0,
+        }
+    }
+}
+
+// any format ffmpeg reads, as interleaved stereo f32 samples at sample_rate
+pub fn decode(path: &str, sample_rate: u32) -> Result<Vec<f32>, String> {
+    let output = Command::new("ffmpeg")
+        .args(["-v", "error", "-i", path, "-f", "f32le", "-ac"])
+        .arg(CHANNELS.to_string())
+        .arg("-ar")
+        .arg(sample_rate.to_string())
+        .arg("-")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("can't run ffmpeg: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "can't decode {path}: ffmpeg exited with {}",
+            output.status
+        ));
+    }
+    Ok(output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+// length of an audio file in seconds
+pub fn duration(path: &str) -> Result<GMFloat, String> {
+    let samples = decode(path, SAMPLE_RATE)?;
+    Ok(samples.len() as GMFloat / (SAMPLE_RATE as usize * CHANNELS) as GMFloat)
+}
+
+// add samples into track starting at_time seconds in, the track grows to fit them
+pub fn mix_into(
+    track: &mut Vec<f32>,
+    samples: &[f32],
+    at_time: GMFloat,
+    sample_rate: u32,
+    gain: GMFloat,
+) {
+    let start = (at_time * sample_rate as GMFloat).round() as i64 * CHANNELS as i64;
+    // a clip starting before the track has its beginning cut off
+    let skip = (-start).max(0) as usize;
+    let start = start.max(0) as usize;
+    let Some(samples) = samples.get(skip..) else {
+        return;
+    };
+    if track.len() < start + samples.len() {
+        track.resize(start + samples.len(), 0.0);
+    }
+    for (t, s) in track[start..].iter_mut().zip(samples) {
+        *t += s * gain as f32;
+    }
+}
+
+// the clips mixed into one track, times are moved by -offset e.g. for Scene::render_from
+pub fn mix(clips: &[SoundClip], sample_rate: u32, offset: GMFloat) -> Result<Vec<f32>, String> {
+    let mut track = vec![];
+    for clip in clips {
+        let samples = decode(&clip.path, sample_rate)?;
+        mix_into(
+            &mut track,
+            &samples,
+            clip.at_time - offset,
+            sample_rate,
+            clip.gain,
+        );
+    }
+    for s in &mut track {
+        *s = s.clamp(-1.0, 1.0);
+    }
+    Ok(track)
+}
+
+fn run_with_samples(command: &mut Command, samples: &[f32]) -> Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("can't run ffmpeg: {e}"))?;
+    let mut stdin = child.stdin.take().expect("failed to open stdin");
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    // ffmpeg may stop reading early, its exit status tells what went wrong
+    let _ = stdin.write_all(&bytes);
+    drop(stdin);
+    let mut error = String::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let _ = stderr.read_to_string(&mut error);
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("ffmpeg exited with {status}: {}", error.trim()));
+    }
+    Ok(())
+}
+
+fn raw_input_args(sample_rate: u32) -> Vec<String> {
+    [
+        "-f",
+        "f32le",
+        "-ar",
+        &sample_rate.to_string(),
+        "-ac",
+        &CHANNELS.to_string(),
+        "-i",
+        "-",
+    ]
+    .map(|a| a.to_owned())
+    .to_vec()
+}
+
+// write the track as an audio file, the format follows the extension
+pub fn write_audio(path: &str, track: &[f32], sample_rate: u32) -> Result<(), String> {
+    run_with_samples(
+        Command::new("ffmpeg")
+            .args(["-y", "-v", "error"])
+            .args(raw_input_args(sample_rate))
+            .arg(path),
+        track,
+    )
+}
+
+// replace the audio of the video file with the track, the video stream is copied.
+// the video keeps its length, a longer track is cut and a shorter one ends in silence
+pub fn mux(video_path: &str, track: &[f32], sample_rate: u32) -> Result<(), String> {
+    let path = Path::new(video_path);
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let audio_codec = if extension == "webm" {
+        "libopus"
+    } else {
+        "aac"
+    };
+    let muxed = path.with_extension(format!("audio.{extension}"));
+    run_with_samples(
+        Command::new("ffmpeg")
+            .args(["-y", "-v", "error", "-i", video_path])
+            .args(raw_input_args(sample_rate))
+            .args(["-map", "0:v", "-map", "1:a", "-c:v", "copy", "-c:a"])
+            .arg(audio_codec)
+            .args(["-af", "apad", "-shortest"])
+            .arg(&muxed),
+        track,
+    )?;
+    std::fs::rename(&muxed, path).map_err(|e| format!("can't replace {video_path}: {e}"))
+}
+
+#[test]
+fn test_mix_into() {
+    let mut track = vec![];
+    // one second of a constant stereo clip at a sample rate of 4
+    let clip = vec![0.5; 4 * CHANNELS];
+    mix_into(&mut track, &clip, 1.0, 4, 1.0);
+    assert_eq!(track.len(), 8 * CHANNELS);
+    assert_eq!(track[..4 * CHANNELS], [0.0; 4 * CHANNELS]);
+    assert_eq!(track[4 * CHANNELS..], [0.5; 4 * CHANNELS]);
+
+    // overlapping clips add up, one starting before the track is cut
+    mix_into(&mut track, &clip, -0.5, 4, 0.5);
+    assert_eq!(track[..2 * CHANNELS], [0.25; 2 * CHANNELS]);
+    assert_eq!(track[2 * CHANNELS..4 * CHANNELS], [0.0; 2 * CHANNELS]);
+    mix_into(&mut track, &clip, 1.5, 4, 1.0);
+    assert_eq!(track.len(), 10 * CHANNELS);
+    assert_eq!(track[6 * CHANNELS], 1.0);
+}
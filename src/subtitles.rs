@@ -0,0 +1,94 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::GMFloat;
+
+// a caption shown from start to end seconds of the video, see voiceover::play_voiceover
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    pub start: GMFloat,
+    pub end: GMFloat,
+    pub text: String,
+}
+
+// 01:02:03,456
+fn srt_timestamp(seconds: GMFloat) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02},{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+// the cues as an srt document, times are moved by -offset e.g. for Scene::render_from.
+// cues that are over before the video starts are left out
+pub fn to_srt(cues: &[SubtitleCue], offset: GMFloat) -> String {
+    let mut srt = String::new();
+    let cues = cues.iter().filter(|c| c.end - offset > 0.0);
+    for (i, cue) in cues.enumerate() {
+        srt.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            srt_timestamp(cue.start - offset),
+            srt_timestamp(cue.end - offset),
+            cue.text.trim()
+        ));
+    }
+    srt
+}
+
+// add the srt file as a subtitle track of the video, the other streams are copied
+pub fn mux(video_path: &str, srt_path: &str) -> Result<(), String> {
+    let path = Path::new(video_path);
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
+    let codec = if extension == "webm" {
+        "webvtt"
+    } else {
+        "mov_text"
+    };
+    let muxed = path.with_extension(format!("subtitles.{extension}"));
+    let output = Command::new("ffmpeg")
+        .args(["-y", "-v", "error", "-i", video_path, "-i", srt_path])
+        .args(["-map", "0", "-map", "1", "-c", "copy", "-c:s", codec])
+        .arg(&muxed)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("can't run ffmpeg: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ffmpeg exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    std::fs::rename(&muxed, path).map_err(|e| format!("can't replace {video_path}: {e}"))
+}
+
+#[test]
+fn test_to_srt() {
+    let cues = vec![
+        SubtitleCue {
+            start: 0.5,
+            end: 2.0,
+            text: "a circle".to_owned(),
+        },
+        SubtitleCue {
+            start: 2.0,
+            end: 3723.25,
+            text: "grows into a square\n".to_owned(),
+        },
+    ];
+    assert_eq!(
+        to_srt(&cues, 0.0),
+        "1\n00:00:00,500 --> 00:00:02,000\na circle\n\n\
+         2\n00:00:02,000 --> 01:02:03,250\ngrows into a square\n\n"
+    );
+    // rendering from the second cue on
+    assert_eq!(
+        to_srt(&cues, 2.0),
+        "1\n00:00:00,000 --> 01:02:01,250\ngrows into a square\n\n"
+    );
+}
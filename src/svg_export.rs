@@ -0,0 +1,321 @@
+use std::fmt::Write;
+
+use tiny_skia::{BlendMode, FillRule, Paint, Path, PathSegment, Shader};
+
+use crate::{
+    background::Background, mobjects::DrawConfig, Color, Context, ContextType, GMFloat, Scene,
+    SceneConfig,
+};
+
+// records draw calls as svg elements in output pixel coordinates, used by ContextType::Svg
+pub struct SvgRecorder {
+    pub width: u32,
+    pub height: u32,
+    // the first element of the document, covering the whole viewBox
+    pub background: String,
+    pub elements: Vec<String>,
+    // ids of the clipPath elements of the document
+    clip_count: u32,
+}
+
+impl SvgRecorder {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            background: r#"<rect width="100%" height="100%" fill="black"/>"#.to_owned(),
+            elements: vec![],
+            clip_count: 0,
+        }
+    }
+
+    pub fn set_background(&mut self, background: &Background) {
+        self.background = match background {
+            Background::Color(color) => {
+                format!(
+                    r#"<rect width="100%" height="100%" {}/>"#,
+                    color_attributes("fill", color)
+                )
+            }
+            Background::LinearGradient { start, end, angle } => {
+                let (w, h) = (self.width as GMFloat, self.height as GMFloat);
+                let (sin, cos) = angle.sin_cos();
+                let half_length = (w * cos).abs() / 2.0 + (h * sin).abs() / 2.0;
+                let (dx, dy) = (cos * half_length, -sin * half_length);
+                format!(
+                    r#"<defs><linearGradient id="background" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}"><stop offset="0" stop-color="rgb({},{},{})" stop-opacity="{}"/><stop offset="1" stop-color="rgb({},{},{})" stop-opacity="{}"/></linearGradient></defs><rect width="100%" height="100%" fill="url(#background)"/>"#,
+                    w / 2.0 - dx,
+                    h / 2.0 - dy,
+                    w / 2.0 + dx,
+                    h / 2.0 + dy,
+                    start.r,
+                    start.g,
+                    start.b,
+                    start.a as f32 / 255.0,
+                    end.r,
+                    end.g,
+                    end.b,
+                    end.a as f32 / 255.0
+                )
+            }
+            Background::Image(image) => format!(
+                r#"<image width="100%" height="100%" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+                base64(&image.encode_png().unwrap_or_default())
+            ),
+            Background::Transparent => String::new(),
+        };
+    }
+
+    pub fn clear(&mut self) {
+        self.elements.clear();
+        self.clip_count = 0;
+    }
+
+    // the elements until pop_clip are grouped and clipped by path
+    pub fn push_clip(&mut self, path: &Path) {
+        self.clip_count += 1;
+        self.elements.push(format!(
+            r#"<clipPath id="clip{id}"><path d="{}"/></clipPath><g clip-path="url(#clip{id})">"#,
+            path_data(path),
+            id = self.clip_count
+        ));
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.elements.push("</g>".to_owned());
+    }
+
+    pub fn fill_path(&mut self, path: &Path, paint: &Paint, fill_rule: FillRule) {
+        let fill_rule = match fill_rule {
+            FillRule::Winding => "nonzero",
+            FillRule::EvenOdd => "evenodd",
+        };
+        self.elements.push(format!(
+            r#"<path d="{}" {} fill-rule="{}" stroke="none"/>"#,
+            path_data(path),
+            paint_attributes("fill", paint),
+            fill_rule
+        ));
+    }
+
+    // strokes follow DrawConfig::get_stroke, round caps and joins
+    pub fn stroke_path(&mut self, path: &Path, draw_config: &DrawConfig, scale_factor: GMFloat) {
+        let mut element = format!(
+            r#"<path d="{}" fill="none" {} stroke-width="{}" stroke-linecap="round" stroke-linejoin="round""#,
+            path_data(path),
+            paint_attributes("stroke", &draw_config.get_paint()),
+            draw_config.stoke_width * scale_factor,
+        );
+        if let Some((dash_length, gap_length)) = draw_config.dash {
+            let _ = write!(
+                element,
+                r#" stroke-dasharray="{} {}" stroke-dashoffset="{}""#,
+                dash_length * scale_factor,
+                gap_length * scale_factor,
+                draw_config.dash_offset * scale_factor
+            );
+        }
+        element.push_str("/>");
+        self.elements.push(element);
+    }
+
+    // a standalone svg document filled with the background color of the scene
+    pub fn to_svg_string(&self) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.width,
+            h = self.height
+        );
+        svg.push('\n');
+        svg.push_str(&self.background);
+        svg.push('\n');
+        for e in &self.elements {
+            svg.push_str(e);
+            svg.push('\n');
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save(&self, file_path: &str) -> std::io::Result<()> {
+        std::fs::write(file_path, self.to_svg_string())
+    }
+}
+
+fn path_data(path: &Path) -> String {
+    let mut d = String::new();
+    for segment in path.segments() {
+        let _ = match segment {
+            PathSegment::MoveTo(p) => write!(d, "M{} {} ", p.x, p.y),
+            PathSegment::LineTo(p) => write!(d, "L{} {} ", p.x, p.y),
+            PathSegment::QuadTo(p1, p) => write!(d, "Q{} {} {} {} ", p1.x, p1.y, p.x, p.y),
+            PathSegment::CubicTo(p1, p2, p) => {
+                write!(d, "C{} {} {} {} {} {} ", p1.x, p1.y, p2.x, p2.y, p.x, p.y)
+            }
+            PathSegment::Close => write!(d, "Z "),
+        };
+    }
+    d.trim_end().to_owned()
+}
+
+fn color_attributes(kind: &str, color: &Color) -> String {
+    format!(
+        r#"{kind}="rgb({},{},{})" {kind}-opacity="{}""#,
+        color.r,
+        color.g,
+        color.b,
+        color.a as f32 / 255.0
+    )
+}
+
+pub(crate) fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(TABLE[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// only solid colors are used by the mobjects
+fn paint_attributes(kind: &str, paint: &Paint) -> String {
+    let mut attributes = match &paint.shader {
+        Shader::SolidColor(color) => {
+            let color = color.to_color_u8();
+            format!(
+                r#"{kind}="rgb({},{},{})" {kind}-opacity="{}""#,
+                color.red(),
+                color.green(),
+                color.blue(),
+                color.alpha() as f32 / 255.0
+            )
+        }
+        _ => format!(r#"{kind}="white""#),
+    };
+    if let Some(mode) = css_blend_mode(paint.blend_mode) {
+        let _ = write!(attributes, r#" style="mix-blend-mode:{mode}""#);
+    }
+    attributes
+}
+
+// the separable and non-separable blend modes, the porter-duff ones have no css equivalent
+fn css_blend_mode(blend_mode: BlendMode) -> Option<&'static str> {
+    Some(match blend_mode {
+        BlendMode::Plus => "plus-lighter",
+        BlendMode::Screen => "screen",
+        BlendMode::Overlay => "overlay",
+        BlendMode::Darken => "darken",
+        BlendMode::Lighten => "lighten",
+        BlendMode::ColorDodge => "color-dodge",
+        BlendMode::ColorBurn => "color-burn",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::SoftLight => "soft-light",
+        BlendMode::Difference => "difference",
+        BlendMode::Exclusion => "exclusion",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Hue => "hue",
+        BlendMode::Saturation => "saturation",
+        BlendMode::Color => "color",
+        BlendMode::Luminosity => "luminosity",
+        _ => return None,
+    })
+}
+
+// dump every nth rendered frame as a numbered svg file into directory, see Scene::svg_export
+pub struct SvgExport {
+    pub directory: String,
+    pub every_nth_frame: u32,
+    pub frame_count: u32,
+}
+
+impl SvgExport {
+    pub fn new(directory: &str, every_nth_frame: u32) -> Self {
+        Self {
+            directory: directory.to_owned(),
+            every_nth_frame: every_nth_frame.max(1),
+            frame_count: 0,
+        }
+    }
+}
+
+impl Scene {
+    // draw the current state of the scene into a standalone svg file
+    pub fn save_svg(&self, scene_config: &SceneConfig, file_path: &str) -> std::io::Result<()> {
+        let mut ctx = Context::new(
+            ContextType::Svg(SvgRecorder::new(
+                scene_config.output_width,
+                scene_config.output_height,
+            )),
+            scene_config.clone(),
+        );
+        self.draw(&mut ctx);
+        match &ctx.ctx_type {
+            ContextType::Svg(recorder) => recorder.save(file_path),
+            _ => Ok(()),
+        }
+    }
+
+    // called once per rendered frame
+    pub fn export_svg_frame(&mut self, scene_config: &SceneConfig) {
+        let Some(svg_export) = &mut self.svg_export else {
+            return;
+        };
+        let frame = svg_export.frame_count;
+        svg_export.frame_count += 1;
+        if frame % svg_export.every_nth_frame != 0 {
+            return;
+        }
+        let file_path = format!("{}/frame_{:06}.svg", svg_export.directory, frame);
+        let _ = self.save_svg(scene_config, &file_path);
+    }
+}
+
+#[test]
+fn test_svg_recorder() {
+    use crate::mobjects::{DrawConfig, Rectangle};
+    let mut scene = Scene::new();
+    scene.add(Box::new(Rectangle {
+        draw_config: DrawConfig {
+            dash: Some((0.2, 0.1)),
+            ..Default::default()
+        },
+        ..Default::default()
+    }));
+    let mut ctx = Context::new(
+        ContextType::Svg(SvgRecorder::new(160, 90)),
+        SceneConfig {
+            output_width: 160,
+            output_height: 90,
+            scale_factor: 10.0,
+            ..Default::default()
+        },
+    );
+    scene.draw(&mut ctx);
+    let ContextType::Svg(recorder) = &ctx.ctx_type else {
+        panic!("not an svg context");
+    };
+    assert_eq!(recorder.elements.len(), 1);
+    let svg = recorder.to_svg_string();
+    assert!(svg.contains(r#"viewBox="0 0 160 90""#));
+    // the unit square from the scene center to the upper right
+    assert!(svg.contains(r#"d="M80 45 L90 45 L90 35 L80 35 L80 45""#));
+    assert!(svg.contains(r#"stroke-dasharray="2 1" stroke-dashoffset="0""#));
+    assert!(
+        svg.contains(r#"<rect width="100%" height="100%" fill="rgb(0,0,0)" fill-opacity="1"/>"#)
+    );
+    assert_eq!(base64(b"gmanim"), "Z21hbmlt");
+    assert_eq!(base64(b"svg"), "c3Zn");
+    assert_eq!(base64(b"sv"), "c3Y=");
+}
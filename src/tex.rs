@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::mobjects::group::MobjectGroup;
+use crate::mobjects::svg_shape::open_svg_file;
+
+// the document a tex string is compiled in, the body is put between
+// \begin{document} and \end{document}
+#[derive(Debug, Clone, PartialEq)]
+pub struct TexTemplate {
+    // "latex", or "xelatex" for system fonts through fontspec
+    pub compiler: String,
+    pub documentclass: String,
+    pub preamble: String,
+}
+
+impl Default for TexTemplate {
+    fn default() -> Self {
+        Self {
+            compiler: "latex".to_owned(),
+            documentclass: r"\documentclass[preview]{standalone}".to_owned(),
+            preamble: [
+                r"\usepackage[english]{babel}",
+                r"\usepackage{amsmath}",
+                r"\usepackage{amssymb}",
+            ]
+            .join("\n"),
+        }
+    }
+}
+
+impl TexTemplate {
+    pub fn add_to_preamble(mut self, line: &str) -> Self {
+        self.preamble.push('\n');
+        self.preamble.push_str(line);
+        self
+    }
+
+    // a font package like "libertine" or "mathpazo" for latex
+    pub fn with_font_package(self, package: &str) -> Self {
+        self.add_to_preamble(&format!(r"\usepackage{{{package}}}"))
+    }
+
+    // any installed font by name, switches to xelatex
+    pub fn with_system_font(mut self, font: &str) -> Self {
+        self.compiler = "xelatex".to_owned();
+        self.add_to_preamble(r"\usepackage{fontspec}")
+            .add_to_preamble(&format!(r"\setmainfont{{{font}}}"))
+    }
+
+    pub fn document(&self, body: &str) -> String {
+        format!(
+            "{}\n{}\n\\begin{{document}}\n{}\n\\end{{document}}\n",
+            self.documentclass, self.preamble, body
+        )
+    }
+
+    // body for a display math expression like MathTex
+    pub fn math_body(expression: &str) -> String {
+        format!("\\begin{{align*}}\n{expression}\n\\end{{align*}}")
+    }
+
+    // latex writes .dvi, xelatex .xdv, both read by dvisvgm
+    fn intermediate_extension(&self) -> &'static str {
+        if self.compiler == "xelatex" {
+            "xdv"
+        } else {
+            "dvi"
+        }
+    }
+}
+
+// svg files of compiled tex named by the hash of the compiler and document,
+// so the same string is compiled once across renders
+#[derive(Debug, Clone)]
+pub struct TexCache {
+    pub dir: PathBuf,
+}
+
+impl Default for TexCache {
+    fn default() -> Self {
+        Self::new(std::env::temp_dir().join("gmanim_tex"))
+    }
+}
+
+impl TexCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn key(template: &TexTemplate, body: &str) -> String {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        template.compiler.hash(&mut hasher);
+        template.document(body).hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    pub fn svg_path(&self, template: &TexTemplate, body: &str) -> PathBuf {
+        self.dir.join(format!("{}.svg", Self::key(template, body)))
+    }
+
+    // the svg of body, compiled with the template when it isn't in the cache yet
+    pub fn compile(&self, template: &TexTemplate, body: &str) -> Result<PathBuf, String> {
+        let svg_path = self.svg_path(template, body);
+        if svg_path.exists() {
+            return Ok(svg_path);
+        }
+        fs::create_dir_all(&self.dir).map_err(|e| format!("can't create {:?}: {e}", self.dir))?;
+        let key = Self::key(template, body);
+        let tex_path = self.dir.join(format!("{key}.tex"));
+        fs::write(&tex_path, template.document(body))
+            .map_err(|e| format!("can't write {tex_path:?}: {e}"))?;
+
+        run(Command::new(&template.compiler)
+            .arg("-interaction=batchmode")
+            .arg("-halt-on-error")
+            .arg(format!("-output-directory={}", self.dir.display()))
+            .args(if template.compiler == "xelatex" {
+                &["-no-pdf"][..]
+            } else {
+                &[]
+            })
+            .arg(&tex_path))
+        .map_err(|e| {
+            let log = self.dir.join(format!("{key}.log"));
+            format!("{e}, see {}", log.display())
+        })?;
+
+        // --no-fonts writes glyphs as paths, which is what open_svg_file reads
+        let dvi_path = self
+            .dir
+            .join(format!("{key}.{}", template.intermediate_extension()));
+        run(Command::new("dvisvgm")
+            .arg("--no-fonts")
+            .arg("--verbosity=0")
+            .arg(format!("--output={}", svg_path.display()))
+            .arg(&dvi_path))?;
+        if !svg_path.exists() {
+            return Err(format!("dvisvgm didn't write {}", svg_path.display()));
+        }
+        Ok(svg_path)
+    }
+
+    pub fn clear(&self) -> std::io::Result<()> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("can't run {program}: {e}"))?;
+    if !status.success() {
+        return Err(format!("{program} exited with {status}"));
+    }
+    Ok(())
+}
+
+pub fn tex(body: &str, template: &TexTemplate, cache: &TexCache) -> Result<MobjectGroup, String> {
+    let svg_path = cache.compile(template, body)?;
+    Ok(open_svg_file(&svg_path.to_string_lossy()))
+}
+
+pub fn math_tex(
+    expression: &str,
+    template: &TexTemplate,
+    cache: &TexCache,
+) -> Result<MobjectGroup, String> {
+    tex(&TexTemplate::math_body(expression), template, cache)
+}
+
+#[test]
+fn test_tex_cache() {
+    let cache = TexCache::new(std::env::temp_dir().join("gmanim_tex_test"));
+    cache.clear().unwrap();
+    let template = TexTemplate::default();
+    let body = TexTemplate::math_body(r"e^{i\pi} + 1 = 0");
+    let fonts = template.clone().with_font_package("libertine");
+    assert_ne!(
+        TexCache::key(&template, &body),
+        TexCache::key(&fonts, &body)
+    );
+    assert_eq!(
+        TexCache::key(&template, &body),
+        TexCache::key(&TexTemplate::default(), &body)
+    );
+
+    // a cached svg is used without running latex
+    let svg_path = cache.svg_path(&template, &body);
+    fs::create_dir_all(&cache.dir).unwrap();
+    fs::write(
+        &svg_path,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><path d="M0 0 L10 0 L10 10 Z"/></svg>"#,
+    )
+    .unwrap();
+    assert_eq!(cache.compile(&template, &body).unwrap(), svg_path);
+    let group = tex(&body, &template, &cache).unwrap();
+    assert_eq!(group.mobjects.len(), 1);
+    cache.clear().unwrap();
+}
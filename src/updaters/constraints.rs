@@ -0,0 +1,76 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::Vector3;
+
+use crate::{
+    animation::growing::GrowAnchor,
+    mobjects::{Dot, Mobject, Rectangle},
+    GMFloat, GMPoint, Scene,
+};
+
+use super::Updater;
+
+// keep a mobject (e.g. a label) next to the target every frame, also while the target is animated.
+// direction picks the edge of the target like GrowAnchor::Edge, the opposite edge of the mobject
+// is placed there with buff in between. a zero direction keeps both centers together
+pub struct Follow {
+    pub target: Rc<RefCell<Box<dyn Mobject>>>,
+    pub direction: Vector3<GMFloat>,
+    pub buff: GMFloat,
+}
+
+impl Follow {
+    pub fn new(
+        target: Rc<RefCell<Box<dyn Mobject>>>,
+        direction: Vector3<GMFloat>,
+        buff: GMFloat,
+    ) -> Self {
+        Self {
+            target,
+            direction,
+            buff,
+        }
+    }
+}
+
+impl Updater for Follow {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat) {
+        let target_point =
+            GrowAnchor::Edge(self.direction).get_point(self.target.borrow().as_ref());
+        let anchor = GrowAnchor::Edge(-self.direction).get_point(m);
+        m.move_this(target_point + self.direction * self.buff - anchor);
+    }
+}
+
+#[test]
+fn test_follow() {
+    let mut scene = Scene::new();
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    let label: Box<dyn Mobject> = Box::new(Dot::new(
+        GMPoint::new(5.0, 5.0, 0.0),
+        0.1,
+        Default::default(),
+        Default::default(),
+    ));
+    let label_ref = Rc::new(RefCell::new(label));
+    scene.add_ref(rectangle_ref.clone());
+    scene.add_ref(label_ref.clone());
+    scene.add_updater(
+        label_ref.clone(),
+        Box::new(Follow::new(
+            rectangle_ref.clone(),
+            Vector3::new(0.0, 1.0, 0.0),
+            0.2,
+        )),
+    );
+    scene.update(1.0 / 60.0);
+    let center = label_ref.borrow().get_center();
+    assert!((center - GMPoint::new(0.5, 1.3, 0.0)).norm() < 1e-5);
+    rectangle_ref
+        .borrow_mut()
+        .move_this(Vector3::new(-2.0, 1.0, 0.0));
+    scene.update(1.0 / 60.0);
+    let center = label_ref.borrow().get_center();
+    assert!((center - GMPoint::new(-1.5, 2.3, 0.0)).norm() < 1e-5);
+}
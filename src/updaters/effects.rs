@@ -0,0 +1,114 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    animation::indication::{bounding_outline, CircumscribeShape},
+    mobjects::{DrawConfig, Mobject, PolyLine, Rectangle},
+    Color, GMFloat, GMPoint, Scene,
+};
+
+use super::Updater;
+
+// a dashed outline flowing around the target while its color cycles through colors.
+// it keeps following the target, so it can stay on while other animations move it
+pub struct AnimatedBoundary {
+    pub target: Rc<RefCell<Box<dyn Mobject>>>,
+    pub colors: Vec<Color>,
+    // full color cycles per second
+    pub cycle_rate: GMFloat,
+    // scene units per second the dashes move along the outline
+    pub flow_speed: GMFloat,
+    pub buff: GMFloat,
+    pub draw_config: DrawConfig,
+    time: GMFloat,
+}
+
+impl AnimatedBoundary {
+    pub fn new(target: Rc<RefCell<Box<dyn Mobject>>>) -> Self {
+        let draw_config = DrawConfig {
+            fill: false,
+            stoke_width: 0.04,
+            dash: Some((0.3, 0.15)),
+            ..Default::default()
+        };
+        Self {
+            target,
+            colors: vec![
+                Color::new(0x29, 0xab, 0xca, 0xff),
+                Color::new(0x9c, 0xdc, 0xeb, 0xff),
+                Color::new(0x23, 0x6b, 0x8e, 0xff),
+                Color::new(0x73, 0x6c, 0x99, 0xff),
+            ],
+            cycle_rate: 0.5,
+            flow_speed: 1.0,
+            buff: 0.1,
+            draw_config,
+            time: 0.0,
+        }
+    }
+
+    // add the boundary to the scene and drive it by this updater,
+    // remove the returned mobject from the scene to stop the effect
+    pub fn add_to(self, scene: &mut Scene) -> Rc<RefCell<Box<dyn Mobject>>> {
+        let boundary: Box<dyn Mobject> = Box::new(PolyLine {
+            points: bounding_outline(
+                self.target.borrow().as_ref(),
+                CircumscribeShape::Rectangle,
+                self.buff,
+            ),
+            draw_config: self.draw_config,
+        });
+        let boundary = Rc::new(RefCell::new(boundary));
+        scene.add_ref(boundary.clone());
+        scene.add_updater(boundary.clone(), Box::new(self));
+        boundary
+    }
+
+    fn color_at(&self, time: GMFloat) -> Color {
+        if self.colors.is_empty() {
+            return self.draw_config.color;
+        }
+        let position = (time * self.cycle_rate).rem_euclid(1.0) * self.colors.len() as GMFloat;
+        let index = position.floor() as usize % self.colors.len();
+        let next = (index + 1) % self.colors.len();
+        self.colors[index].interpolate(&self.colors[next], position.fract())
+    }
+}
+
+impl Updater for AnimatedBoundary {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat) {
+        self.time += dt;
+        let outline = bounding_outline(
+            self.target.borrow().as_ref(),
+            CircumscribeShape::Rectangle,
+            self.buff,
+        );
+        m.set_points(&outline);
+        let mut draw_config = self.draw_config;
+        draw_config.color = self.color_at(self.time);
+        draw_config.dash_offset = -self.time * self.flow_speed;
+        m.set_draw_config(draw_config);
+    }
+}
+
+#[test]
+fn test_animated_boundary() {
+    let mut scene = Scene::new();
+    let rectangle: Box<dyn Mobject> = Box::new(Rectangle::default());
+    let rectangle_ref = Rc::new(RefCell::new(rectangle));
+    scene.add_ref(rectangle_ref.clone());
+    let boundary = AnimatedBoundary::new(rectangle_ref.clone()).add_to(&mut scene);
+    scene.update(0.5);
+    let first_color = boundary.borrow().get_draw_config().color;
+    // the boundary follows the target
+    rectangle_ref
+        .borrow_mut()
+        .move_this(nalgebra::Vector3::new(2.0, 0.0, 0.0));
+    scene.update(0.5);
+    let (min, _) = boundary.borrow().get_bounding_box();
+    assert!((min - GMPoint::new(1.9, -0.1, 0.0)).norm() < 1e-5);
+    let draw_config = boundary.borrow().get_draw_config();
+    assert!((draw_config.dash_offset + 1.0).abs() < 1e-5);
+    assert_ne!(draw_config.color.r, first_color.r);
+    scene.remove(&boundary);
+    assert!(scene.updaters.is_empty());
+}
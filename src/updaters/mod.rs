@@ -0,0 +1,48 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    mobjects::{Dot, Mobject, Transform},
+    GMFloat, GMPoint, Scene,
+};
+
+pub mod constraints;
+pub mod effects;
+pub mod physics;
+
+// called once for every rendered frame with the time since the last frame in seconds
+pub trait Updater {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat);
+}
+
+impl<F: FnMut(&mut dyn Mobject, GMFloat)> Updater for F {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat) {
+        self(m, dt)
+    }
+}
+
+#[test]
+fn test_fn_updater_framerate_independent() {
+    // one full orbit around the origin per 2 seconds, the result must not depend on framerate
+    let orbit = |framerate: u32| -> GMPoint {
+        let mut scene = Scene::new();
+        let dot: Box<dyn Mobject> = Box::new(Dot::default());
+        let dot_ref = Rc::new(RefCell::new(dot));
+        scene.add_ref(dot_ref.clone());
+        let mut angle: GMFloat = 0.0;
+        scene.add_updater_fn(dot_ref.clone(), move |m, dt| {
+            angle += std::f32::consts::PI as GMFloat * dt;
+            let target = GMPoint::new(angle.cos(), angle.sin(), 0.0);
+            m.move_this(target - m.get_center());
+        });
+        for _ in 0..framerate {
+            scene.update(1.0 / framerate as GMFloat);
+        }
+        assert!((scene.time - 1.0).abs() < 1e-4);
+        let center = dot_ref.borrow().get_center();
+        center
+    };
+    let slow = orbit(24);
+    let fast = orbit(60);
+    assert!((slow - fast).norm() < 1e-3);
+    assert!((fast - GMPoint::new(-1.0, 0.0, 0.0)).norm() < 1e-3);
+}
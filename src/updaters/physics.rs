@@ -0,0 +1,132 @@
+use std::{cell::RefCell, rc::Rc};
+
+use nalgebra::Vector3;
+
+use crate::{
+    mobjects::{Dot, Mobject, Transform},
+    GMFloat, GMPoint,
+};
+
+use super::Updater;
+
+// all physics updaters move the center of the mobject and integrate with semi-implicit euler
+
+pub enum SpringTarget {
+    Point(GMPoint),
+    // follow the center of another mobject, it must not be the mobject being updated
+    Mobject(Rc<RefCell<Box<dyn Mobject>>>),
+}
+
+impl SpringTarget {
+    fn get_point(&self) -> GMPoint {
+        match self {
+            SpringTarget::Point(p) => *p,
+            SpringTarget::Mobject(m) => m.borrow().get_center(),
+        }
+    }
+}
+
+// a damped spring pulling the mobject toward the target, with damping_ratio 1.0
+// it settles as fast as possible without overshooting, less than 1.0 gives a bounce
+pub struct Spring {
+    pub target: SpringTarget,
+    pub stiffness: GMFloat,
+    pub damping_ratio: GMFloat,
+    pub velocity: Vector3<GMFloat>,
+}
+
+impl Spring {
+    pub fn new(target: SpringTarget) -> Self {
+        Self {
+            target,
+            stiffness: 100.0,
+            damping_ratio: 1.0,
+            velocity: Vector3::zeros(),
+        }
+    }
+}
+
+impl Updater for Spring {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat) {
+        let center = m.get_center();
+        let damping = 2.0 * self.damping_ratio * self.stiffness.sqrt();
+        let acceleration =
+            (self.target.get_point() - center) * self.stiffness - self.velocity * damping;
+        self.velocity += acceleration * dt;
+        m.move_this(self.velocity * dt);
+    }
+}
+
+// keep moving with the given velocity, slowed down by linear drag
+pub struct Damping {
+    pub velocity: Vector3<GMFloat>,
+    // fraction of the velocity lost per second
+    pub drag: GMFloat,
+}
+
+impl Updater for Damping {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat) {
+        self.velocity *= (1.0 - self.drag * dt).max(0.0);
+        m.move_this(self.velocity * dt);
+    }
+}
+
+// constant acceleration, optionally bouncing on a horizontal floor
+pub struct Gravity {
+    pub acceleration: Vector3<GMFloat>,
+    pub velocity: Vector3<GMFloat>,
+    // y coordinate the bottom of the mobject bounces on
+    pub floor: Option<GMFloat>,
+    // fraction of the speed kept after a bounce
+    pub restitution: GMFloat,
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self {
+            acceleration: Vector3::new(0.0, -9.8, 0.0),
+            velocity: Vector3::zeros(),
+            floor: None,
+            restitution: 0.8,
+        }
+    }
+}
+
+impl Updater for Gravity {
+    fn update(&mut self, m: &mut dyn Mobject, dt: GMFloat) {
+        self.velocity += self.acceleration * dt;
+        m.move_this(self.velocity * dt);
+        if let Some(floor) = self.floor {
+            let (min, _) = m.get_bounding_box();
+            if min.y < floor {
+                m.move_this(Vector3::new(0.0, floor - min.y, 0.0));
+                if self.velocity.y < 0.0 {
+                    self.velocity.y = -self.velocity.y * self.restitution;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_spring_settle() {
+    let mut dot: Box<dyn Mobject> = Box::new(Dot::default());
+    let mut spring = Spring::new(SpringTarget::Point(GMPoint::new(2.0, 1.0, 0.0)));
+    for _ in 0..600 {
+        spring.update(dot.as_mut(), 1.0 / 60.0);
+    }
+    assert!((dot.get_center() - GMPoint::new(2.0, 1.0, 0.0)).norm() < 1e-3);
+}
+
+#[test]
+fn test_gravity_floor() {
+    let mut dot: Box<dyn Mobject> = Box::new(Dot::default());
+    let mut gravity = Gravity {
+        floor: Some(-1.0),
+        ..Default::default()
+    };
+    for _ in 0..600 {
+        gravity.update(dot.as_mut(), 1.0 / 60.0);
+        assert!(dot.get_bounding_box().0.y >= -1.0 - 1e-4);
+    }
+}
@@ -9,35 +9,83 @@ use ffmpeg_next::{ChannelLayout, StreamMut};
 use ffmpeg_next::codec::encoder::{Audio, Video};
 use ffmpeg_next::format::context::Output;
 use ffmpeg_next::software::scaling;
+use log::warn;
 use yuv::rgba_to_yuv420;
 
-use crate::video_backend::VideoConfig;
+use crate::audio::AudioTimeline;
+use crate::sound::{self, CHANNELS, SAMPLE_RATE};
+use crate::video_backend::{
+    stream_format, FfmpegConfig, FfmpegPipeEncoder, ProResProfile, RealTime, VideoConfig,
+};
+use crate::GMFloat;
 pub struct FfmpegBackend {
     v_enc: Video,
-    a_enc: Audio,
+    // None for webm, which has no aac
+    a_enc: Option<Audio>,
     octx: Output,
     v_stream_idx: usize,
-    a_stream_idx: usize,
-    // scaler: scaling::context::Context,
+    a_stream_idx: Option<usize>,
+    // the pixel format of the encoder, yuv420p is converted by do_scale and the others by scaler
+    pix_fmt: Pixel,
+    // the encoder keeps the alpha channel, frames are demultiplied before they are converted
+    alpha: bool,
+    scaler: Option<scaling::context::Context>,
     frame_count: u64,
+    framerate: u32,
+    // interleaved stereo at sound::SAMPLE_RATE from add_audio_track, encoded along the frames
+    audio: Vec<f32>,
+    // samples per channel sent to the audio encoder
+    audio_sent: usize,
+    // mixed into the audio when finishing
+    audio_timeline: Option<AudioTimeline>,
+    // set for a live output, see video_backend::stream_format
+    real_time: Option<RealTime>,
 }
 
 impl FfmpegBackend {
     pub fn new(video_config: &VideoConfig) -> Self {
+        Self::with_config(
+            video_config,
+            &FfmpegConfig {
+                ffmpeg_encoder: FfmpegPipeEncoder::Libx264,
+            },
+        )
+    }
+
+    // libx264, prores_ks and libvpx-vp9 get their options, other encoders of the pipe backend
+    // are opened with their defaults and yuv420p, nv12 for hevc_qsv. the encoders with
+    // FfmpegPipeEncoder::has_alpha keep the alpha channel, for Background::Transparent. an
+    // rtmp:// or srt:// filename streams live, libx264 is tuned for latency and the frames are
+    // sent in real time
+    pub fn with_config(video_config: &VideoConfig, ffmpeg_config: &FfmpegConfig) -> Self {
         ffmpeg_next::init().unwrap();
 
         #[cfg(not(test))]
         ffmpeg_next::log::set_level(ffmpeg_next::log::Level::Quiet);
 
-        let mut octx = ffmpeg_next::format::output(&video_config.filename).unwrap();
+        let live = stream_format(&video_config.filename);
+        let mut octx = match live {
+            Some(format) => {
+                ffmpeg_next::format::network::init();
+                ffmpeg_next::format::output_as(&video_config.filename, format)
+            }
+            None => ffmpeg_next::format::output(&video_config.filename),
+        }
+        .unwrap_or_else(|e| panic!("can't open {}: {e}", video_config.filename));
         let global_header = octx
             .format()
             .flags()
             .contains(ffmpeg_next::format::Flags::GLOBAL_HEADER);
 
         // video codec settings
-        let v_codec = ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::H264)
-            .expect("H.264 encoder not found");
+        let encoder = ffmpeg_config.ffmpeg_encoder;
+        let v_codec = match encoder {
+            FfmpegPipeEncoder::Libx264 => ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::H264),
+            _ => ffmpeg_next::encoder::find_by_name(encoder.get_encoder_name()),
+        }
+        .unwrap_or_else(|| panic!("{} encoder not found", encoder.get_encoder_name()));
+        let alpha = encoder.has_alpha();
+        let pix_fmt = encoder_pix_fmt(encoder);
 
         let mut v_stream = octx.add_stream(v_codec).unwrap();
         let v_stream_idx = v_stream.index();
@@ -47,7 +95,7 @@ impl FfmpegBackend {
 
         v_enc.set_width(video_config.output_width);
         v_enc.set_height(video_config.output_height);
-        v_enc.set_format(Pixel::YUV420P);
+        v_enc.set_format(pix_fmt);
         v_enc.set_time_base((1, video_config.framerate as i32));
         v_enc.set_gop(12);
 
@@ -56,35 +104,57 @@ impl FfmpegBackend {
         }
 
         let mut v_opts = Dictionary::new();
-        v_opts.set("preset", "ultrafast");
-        v_opts.set("tune", "fastdecode");
+        match encoder {
+            FfmpegPipeEncoder::Libx264 if live.is_some() => {
+                v_opts.set("preset", "veryfast");
+                v_opts.set("tune", "zerolatency");
+            }
+            FfmpegPipeEncoder::Libx264 => {
+                v_opts.set("preset", "ultrafast");
+                v_opts.set("tune", "fastdecode");
+            }
+            FfmpegPipeEncoder::ProRes(profile) => {
+                v_opts.set("profile", profile.name());
+                v_opts.set("vendor", "apl0");
+                if alpha {
+                    v_opts.set("alpha_bits", "16");
+                }
+            }
+            FfmpegPipeEncoder::Vp9 { .. } => {
+                v_opts.set("crf", "30");
+                v_opts.set("b", "0");
+                v_opts.set("deadline", "realtime");
+                v_opts.set("cpu-used", "8");
+                v_opts.set("row-mt", "1");
+            }
+            _ => {}
+        }
 
         let v_enc = v_enc
             .open_as_with(v_codec, v_opts)
-            .expect("Failed to open libx264");
+            .unwrap_or_else(|e| panic!("Failed to open {}: {e}", encoder.get_encoder_name()));
         v_stream.set_parameters(&v_enc);
 
-        // audio codec settings
-        let a_codec = ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::AAC).unwrap();
-        let mut a_stream = octx.add_stream(a_codec).unwrap();
-        let a_stream_idx = a_stream.index();
-
-        let mut a_enc_ctx = ffmpeg_next::codec::context::Context::new();
-        let mut a_enc = a_enc_ctx.encoder().audio().unwrap();
+        let scaler = (pix_fmt != Pixel::YUV420P).then(|| {
+            scaling::context::Context::get(
+                Pixel::RGBA,
+                video_config.output_width,
+                video_config.output_height,
+                pix_fmt,
+                video_config.output_width,
+                video_config.output_height,
+                scaling::flag::Flags::BILINEAR,
+            )
+            .expect("Failed to create the scaler")
+        });
 
-        a_enc.set_format(ffmpeg_next::format::Sample::F32(
-            ffmpeg_next::format::sample::Type::Planar,
-        ));
-        a_enc.set_rate(44100);
-        a_enc.set_channel_layout(ChannelLayout::STEREO);
-        a_enc.set_time_base((1, 44100));
-
-        if global_header {
-            a_enc.set_flags(ffmpeg_next::codec::Flags::GLOBAL_HEADER);
-        }
-
-        let a_enc = a_enc.open_as(a_codec).unwrap();
-        a_stream.set_parameters(&a_enc);
+        // audio codec settings
+        let (a_enc, a_stream_idx) = if octx.format().name() == "webm" {
+            (None, None)
+        } else {
+            let (a_enc, a_stream_idx) = add_audio_stream(&mut octx, global_header);
+            (Some(a_enc), Some(a_stream_idx))
+        };
 
         octx.write_header().unwrap();
 
@@ -94,12 +164,24 @@ impl FfmpegBackend {
             a_enc,
             v_stream_idx,
             a_stream_idx,
-            // scaler,
+            pix_fmt,
+            alpha,
+            scaler,
             frame_count: 0,
+            framerate: video_config.framerate,
+            audio: vec![],
+            audio_sent: 0,
+            audio_timeline: None,
+            real_time: live.map(|_| RealTime::new(video_config.framerate)),
         }
     }
 
     pub fn write_frame(&mut self, frame_data: &[u8]) {
+        self.write_repeated_frame(frame_data, 1);
+    }
+
+    // the rgba -> yuv conversion is done only once, then the same frame is sent with increasing pts
+    pub fn write_repeated_frame(&mut self, frame_data: &[u8], count: u32) {
         let width = self.v_enc.width();
         let height = self.v_enc.height();
         let mut input_frame = ffmpeg_next::util::frame::video::Video::empty();
@@ -109,6 +191,14 @@ impl FfmpegBackend {
 
         let stride = (self.v_enc.width() * 4) as usize;
 
+        // the frames are premultiplied, the yuva formats are not
+        let demultiplied;
+        let frame_data = if self.alpha {
+            demultiplied = super::demultiply(frame_data);
+            &demultiplied
+        } else {
+            frame_data
+        };
         unsafe {
             let mut data = input_frame.data_mut(0);
             data.copy_from_slice(frame_data); // assume no padding needed
@@ -116,29 +206,134 @@ impl FfmpegBackend {
 
         let mut output_frame = ffmpeg_next::util::frame::video::Video::empty();
         unsafe {
-            output_frame.alloc(Pixel::YUV420P, width, height);
+            output_frame.alloc(self.pix_fmt, width, height);
+        }
+        match &mut self.scaler {
+            Some(scaler) => scaler.run(&input_frame, &mut output_frame).unwrap(),
+            None => do_scale(&input_frame, &mut output_frame),
         }
-        do_scale(&input_frame, &mut output_frame);
-        // self.scaler.run(&input_frame, &mut output_frame).unwrap(); // TODO: need measure time here
         let d = output_frame.data(0);
 
-        output_frame.set_pts(Some(self.frame_count as i64));
-        self.frame_count += 1;
+        for _ in 0..count {
+            if let Some(real_time) = &mut self.real_time {
+                real_time.wait();
+            }
+            output_frame.set_pts(Some(self.frame_count as i64));
+            self.frame_count += 1;
 
-        self.send_frame(&output_frame);
+            self.send_frame(&output_frame);
+        }
     }
 
     fn send_frame(&mut self, frame: &ffmpeg_next::util::frame::video::Video) {
         self.v_enc.send_frame(frame).unwrap();
         self.write_video_packet();
+        // the audio up to the end of the frame, so the muxer can interleave the streams
+        self.encode_audio(self.video_samples(), false);
     }
 
     pub fn finish(&mut self) {
         self.v_enc.send_eof().unwrap();
         self.write_video_packet();
+        if let Some(timeline) = self.audio_timeline.take() {
+            match timeline.mix_with(0.0, |path| {
+                decode_audio(path).map_err(|e| format!("can't decode {path}: {e}"))
+            }) {
+                Ok(track) => sound::mix_into(&mut self.audio, &track, 0.0, SAMPLE_RATE, 1.0),
+                Err(e) => warn!("the audio timeline is left out: {e}"),
+            }
+        }
+        if !self.audio.is_empty() {
+            self.encode_audio(self.video_samples(), true);
+            if let Some(a_enc) = &mut self.a_enc {
+                a_enc.send_eof().unwrap();
+            }
+            self.write_audio_packet();
+        }
         self.octx.write_trailer().unwrap();
     }
 
+    // mix an audio file in any format ffmpeg reads into the audio stream, starting offset
+    // seconds into the video. call it before the frames, samples before the frames already
+    // written are dropped. the audio is cut at the end of the video
+    pub fn add_audio_track(&mut self, path: &str, offset: GMFloat) -> Result<(), String> {
+        if self.a_enc.is_none() {
+            return Err(format!("{} has no audio stream", self.octx.format().name()));
+        }
+        let samples = decode_audio(path).map_err(|e| format!("can't decode {path}: {e}"))?;
+        sound::mix_into(&mut self.audio, &samples, offset, SAMPLE_RATE, 1.0);
+        Ok(())
+    }
+
+    // the clips of the timeline go into the audio stream when finishing, over the tracks of
+    // add_audio_track. the audio of those already sent with the frames stays as it is
+    pub fn set_audio_timeline(&mut self, timeline: AudioTimeline) -> Result<(), String> {
+        if self.a_enc.is_none() {
+            return Err(format!("{} has no audio stream", self.octx.format().name()));
+        }
+        self.audio_timeline = Some(timeline);
+        Ok(())
+    }
+
+    // the samples per channel covered by the frames written so far
+    fn video_samples(&self) -> usize {
+        (self.frame_count * SAMPLE_RATE as u64 / self.framerate.max(1) as u64) as usize
+    }
+
+    // whole encoder frames of the track up to end samples per channel, with pad the last
+    // frame reaching end is filled with silence
+    fn encode_audio(&mut self, end: usize, pad: bool) {
+        let Some(a_enc) = &mut self.a_enc else {
+            return;
+        };
+        let track_end = self.audio.len() / CHANNELS;
+        let end = end.min(track_end);
+        let frame_size = match a_enc.frame_size() as usize {
+            0 => 1024,
+            size => size,
+        };
+        let mut encoded = false;
+        while self.audio_sent + if pad { 1 } else { frame_size } <= end {
+            let mut frame = ffmpeg_next::util::frame::Audio::new(
+                ffmpeg_next::format::Sample::F32(ffmpeg_next::format::sample::Type::Planar),
+                frame_size,
+                ChannelLayout::STEREO,
+            );
+            frame.set_rate(SAMPLE_RATE);
+            for channel in 0..CHANNELS {
+                for (i, s) in frame.plane_mut::<f32>(channel).iter_mut().enumerate() {
+                    let index = self.audio_sent + i;
+                    *s = match index < end {
+                        true => self.audio[index * CHANNELS + channel].clamp(-1.0, 1.0),
+                        false => 0.0,
+                    };
+                }
+            }
+            frame.set_pts(Some(self.audio_sent as i64));
+            a_enc.send_frame(&frame).unwrap();
+            self.audio_sent += frame_size;
+            encoded = true;
+        }
+        if encoded {
+            self.write_audio_packet();
+        }
+    }
+
+    fn write_audio_packet(&mut self) {
+        let (Some(a_enc), Some(a_stream_idx)) = (&mut self.a_enc, self.a_stream_idx) else {
+            return;
+        };
+        let mut packet = ffmpeg_next::Packet::empty();
+        while a_enc.receive_packet(&mut packet).is_ok() {
+            packet.set_stream(a_stream_idx);
+            packet.rescale_ts(
+                a_enc.time_base(),
+                self.octx.stream(a_stream_idx).unwrap().time_base(),
+            );
+            packet.write_interleaved(&mut self.octx).unwrap();
+        }
+    }
+
     // before call this function, send_frame to encoder first
     fn write_video_packet(&mut self) {
         loop {
@@ -163,6 +358,89 @@ impl FfmpegBackend {
     }
 }
 
+// the first audio stream of a file as interleaved stereo f32 at sound::SAMPLE_RATE
+fn decode_audio(path: &str) -> Result<Vec<f32>, ffmpeg_next::Error> {
+    use ffmpeg_next::software::resampling;
+
+    // the resampled frames are packed, their one plane holds the interleaved samples
+    fn receive_samples(
+        decoder: &mut ffmpeg_next::decoder::Audio,
+        resampler: &mut resampling::Context,
+        samples: &mut Vec<f32>,
+    ) -> Result<(), ffmpeg_next::Error> {
+        let mut decoded = ffmpeg_next::util::frame::Audio::empty();
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            let mut resampled = ffmpeg_next::util::frame::Audio::empty();
+            resampler.run(&decoded, &mut resampled)?;
+            push_packed(&resampled, samples);
+        }
+        Ok(())
+    }
+    fn push_packed(frame: &ffmpeg_next::util::frame::Audio, samples: &mut Vec<f32>) {
+        let bytes = &frame.data(0)[..frame.samples() * CHANNELS * 4];
+        samples.extend(
+            bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]])),
+        );
+    }
+
+    let mut ictx = ffmpeg_next::format::input(&path)?;
+    let stream = ictx
+        .streams()
+        .best(ffmpeg_next::media::Type::Audio)
+        .ok_or(ffmpeg_next::Error::StreamNotFound)?;
+    let index = stream.index();
+    let mut decoder = ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .audio()?;
+    let mut resampler = decoder.resampler(
+        ffmpeg_next::format::Sample::F32(ffmpeg_next::format::sample::Type::Packed),
+        ChannelLayout::STEREO,
+        SAMPLE_RATE,
+    )?;
+    let mut samples = vec![];
+    for (stream, packet) in ictx.packets() {
+        if stream.index() == index {
+            decoder.send_packet(&packet)?;
+            receive_samples(&mut decoder, &mut resampler, &mut samples)?;
+        }
+    }
+    decoder.send_eof()?;
+    receive_samples(&mut decoder, &mut resampler, &mut samples)?;
+    // the samples the resampler still holds
+    let mut rest = ffmpeg_next::util::frame::Audio::empty();
+    while resampler.flush(&mut rest)?.is_some() && rest.samples() > 0 {
+        push_packed(&rest, &mut samples);
+    }
+    Ok(samples)
+}
+
+// the aac stream, 44.1 khz stereo
+fn add_audio_stream(octx: &mut Output, global_header: bool) -> (Audio, usize) {
+    let a_codec = ffmpeg_next::encoder::find(ffmpeg_next::codec::Id::AAC).unwrap();
+    let mut a_stream = octx.add_stream(a_codec).unwrap();
+    let a_stream_idx = a_stream.index();
+
+    let mut a_enc_ctx = ffmpeg_next::codec::context::Context::new();
+    let mut a_enc = a_enc_ctx.encoder().audio().unwrap();
+
+    a_enc.set_format(ffmpeg_next::format::Sample::F32(
+        ffmpeg_next::format::sample::Type::Planar,
+    ));
+    a_enc.set_rate(44100);
+    a_enc.set_channel_layout(ChannelLayout::STEREO);
+    a_enc.set_time_base((1, 44100));
+
+    if global_header {
+        a_enc.set_flags(ffmpeg_next::codec::Flags::GLOBAL_HEADER);
+    }
+
+    let a_enc = a_enc.open_as(a_codec).unwrap();
+    a_stream.set_parameters(&a_enc);
+    (a_enc, a_stream_idx)
+}
+
 fn do_scale(
     input_frame: &ffmpeg_next::util::frame::Video,
     output_frame: &mut ffmpeg_next::util::frame::Video,
@@ -210,6 +488,76 @@ fn do_scale(
     );
 }
 
+// the format frames are converted to for the encoder, with an alpha plane for the encoders
+// that keep the alpha channel
+fn encoder_pix_fmt(encoder: FfmpegPipeEncoder) -> Pixel {
+    match encoder {
+        FfmpegPipeEncoder::ProRes(ProResProfile::P4444) => Pixel::YUVA444P10LE,
+        FfmpegPipeEncoder::ProRes(_) => Pixel::YUV422P10LE,
+        FfmpegPipeEncoder::Vp9 { alpha: true } => Pixel::YUVA420P,
+        FfmpegPipeEncoder::HevcQsv => Pixel::NV12,
+        _ => Pixel::YUV420P,
+    }
+}
+
+#[test]
+fn test_encoder_pix_fmt() {
+    assert_eq!(
+        encoder_pix_fmt(FfmpegPipeEncoder::Vp9 { alpha: true }),
+        Pixel::YUVA420P
+    );
+    assert_eq!(
+        encoder_pix_fmt(FfmpegPipeEncoder::Vp9 { alpha: false }),
+        Pixel::YUV420P
+    );
+    assert_eq!(
+        encoder_pix_fmt(FfmpegPipeEncoder::ProRes(ProResProfile::P4444)),
+        Pixel::YUVA444P10LE
+    );
+    assert_eq!(
+        encoder_pix_fmt(FfmpegPipeEncoder::ProRes(ProResProfile::Hq)),
+        Pixel::YUV422P10LE
+    );
+}
+
+#[test]
+fn test_add_audio_track() {
+    // a second of a 440hz tone under half a second of video
+    let tone: Vec<f32> = (0..SAMPLE_RATE as usize)
+        .flat_map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let s = (t * 440.0 * std::f32::consts::TAU).sin() * 0.5;
+            [s, s]
+        })
+        .collect();
+    let wav = std::env::temp_dir().join("gmanim_test_tone.wav");
+    let wav = wav.to_str().unwrap();
+    // needs the ffmpeg executable to write the input
+    if sound::write_audio(wav, &tone, SAMPLE_RATE).is_err() {
+        return;
+    }
+    let video_path = std::env::temp_dir().join("gmanim_test_audio_track.mp4");
+    let video_config = VideoConfig {
+        filename: video_path.to_str().unwrap().to_owned(),
+        framerate: 30,
+        output_width: 64,
+        output_height: 64,
+        color_order: super::ColorOrder::Rgba,
+    };
+    let mut backend = FfmpegBackend::new(&video_config);
+    backend.add_audio_track(wav, 0.0).unwrap();
+    backend.write_repeated_frame(&vec![0xff; 64 * 64 * 4], 15);
+    backend.finish();
+    let input = ffmpeg_next::format::input(&video_config.filename).unwrap();
+    let audio = input
+        .streams()
+        .best(ffmpeg_next::media::Type::Audio)
+        .expect("no audio stream");
+    // the track is cut at the end of the video
+    let seconds = audio.duration() as f64 * f64::from(audio.time_base());
+    assert!((seconds - 0.5).abs() < 0.1, "{seconds}s of audio");
+}
+
 #[test]
 fn test_bench_ffmpeg_alloc() {
     const S: usize = 1000_0;
@@ -4,22 +4,40 @@ use std::sync::mpsc::{self, channel, Receiver, Sender};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread::{self, JoinHandle};
 
+#[cfg(feature = "ffmpeg")]
 use crate::video_backend::ffmpeg::FfmpegBackend;
+#[cfg(feature = "ffmpeg")]
 pub mod ffmpeg;
 
 const BLOCK_SIZE: usize = 240;
 pub enum VideoBackendType {
     FfmpegPipe(FfmpegPipeBackend),
+    #[cfg(feature = "ffmpeg")]
     Ffmpeg(FfmpegBackend),
     BgraRAW(BgraRAWBackend),
+    PngSequence(PngSequenceBackend),
+    // no file, every frame is handed to the application, see crate::embed
+    Buffer(BufferBackend),
+    // an output registered by a plugin, see crate::plugin
+    Plugin(Box<dyn VideoSink>),
     Gstreamer,
 }
 
+// where the frames of a plugin backend go, in the color order of the video config
+pub trait VideoSink {
+    fn write_frame(&mut self, frame_data: &[u8]);
+    // called once after the last frame
+    fn close(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 pub struct VideoBackend {
     pub backend_type: VideoBackendType,
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ColorOrder {
     Bgra,
     Rgba,
@@ -39,6 +57,7 @@ impl Display for ColorOrder {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VideoConfig {
     pub filename: String,
     pub framerate: u32,
@@ -47,22 +66,117 @@ pub struct VideoConfig {
     pub color_order: ColorOrder,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FfmpegPipeEncoder {
     Libx264,
     Libx265,
     HevcNvenc,
     HevcVaapi,
+    // intel quick sync and apple's videotoolbox
+    HevcQsv,
+    HevcVideoToolbox,
+    // av1 for webm, aom is the reference encoder and svt the much faster one
+    LibaomAv1,
+    SvtAv1,
+    // prores_ks, for editors. the 4444 profile and vp9 with alpha keep the alpha channel,
+    // for rendering with Background::Transparent
+    ProRes(ProResProfile),
+    Vp9 { alpha: bool },
+}
+
+// the profiles of prores_ks from the smallest files to the best quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProResProfile {
+    Proxy,
+    Lt,
+    Standard,
+    Hq,
+    P4444,
+}
+
+impl ProResProfile {
+    // the -profile:v value of prores_ks
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Proxy => "proxy",
+            Self::Lt => "lt",
+            Self::Standard => "standard",
+            Self::Hq => "hq",
+            Self::P4444 => "4444",
+        }
+    }
+
+    // also 422 for standard
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "422" => Some(Self::Standard),
+            _ => [Self::Proxy, Self::Lt, Self::Standard, Self::Hq, Self::P4444]
+                .into_iter()
+                .find(|p| p.name() == name),
+        }
+    }
+
+    // the 422 profiles drop the alpha channel and half of the color resolution
+    pub fn pix_fmt(&self) -> &'static str {
+        match self {
+            Self::P4444 => "yuva444p10le",
+            _ => "yuv422p10le",
+        }
+    }
 }
 
 impl FfmpegPipeEncoder {
-    fn get_encoder_name(&self) -> &'static str {
+    pub fn get_encoder_name(&self) -> &'static str {
         match self {
             Self::Libx264 => "libx264",
             Self::Libx265 => "libx265",
             Self::HevcNvenc => "hevc_nvenc",
             Self::HevcVaapi => "hevc_vaapi",
+            Self::HevcQsv => "hevc_qsv",
+            Self::HevcVideoToolbox => "hevc_videotoolbox",
+            Self::LibaomAv1 => "libaom-av1",
+            Self::SvtAv1 => "libsvtav1",
+            Self::ProRes(_) => "prores_ks",
+            Self::Vp9 { .. } => "libvpx-vp9",
         }
     }
+
+    // the ffmpeg name, e.g. "libx264". prores_ks is the 4444 profile, libvpx-vp9 is opaque
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Libx264,
+            Self::Libx265,
+            Self::HevcNvenc,
+            Self::HevcVaapi,
+            Self::HevcQsv,
+            Self::HevcVideoToolbox,
+            Self::LibaomAv1,
+            Self::SvtAv1,
+            Self::ProRes(ProResProfile::P4444),
+            Self::Vp9 { alpha: false },
+        ]
+        .into_iter()
+        .find(|e| e.get_encoder_name() == name)
+    }
+
+    pub fn has_alpha(&self) -> bool {
+        matches!(
+            self,
+            Self::ProRes(ProResProfile::P4444) | Self::Vp9 { alpha: true }
+        )
+    }
+
+    pub fn is_hardware(&self) -> bool {
+        matches!(
+            self,
+            Self::HevcNvenc | Self::HevcVaapi | Self::HevcQsv | Self::HevcVideoToolbox
+        )
+    }
+
+    // written into webm files, the others into mp4 or mov
+    pub fn is_webm(&self) -> bool {
+        matches!(self, Self::Vp9 { .. } | Self::LibaomAv1 | Self::SvtAv1)
+    }
 }
 
 pub struct FfmpegPipeConfig {
@@ -71,9 +185,119 @@ pub struct FfmpegPipeConfig {
 
 pub struct FfmpegPipeBackend {
     child: std::process::Child,
-    stdin: std::process::ChildStdin,
+    // None after close
+    stdin: Option<std::process::ChildStdin>,
+    // the encoder keeps the alpha channel, frames are demultiplied before they are sent
+    alpha: bool,
+    // set for a live output, see stream_format
+    real_time: Option<RealTime>,
+}
+
+// the hardware encoders in the order they are tried by detect_best_encoder, the ones of
+// other platforms are skipped
+const HARDWARE_ENCODERS: [FfmpegPipeEncoder; 4] = [
+    FfmpegPipeEncoder::HevcNvenc,
+    FfmpegPipeEncoder::HevcVideoToolbox,
+    FfmpegPipeEncoder::HevcQsv,
+    FfmpegPipeEncoder::HevcVaapi,
+];
+
+// the fastest encoder that works here: the hardware encoders are tried with a test encode of
+// a few frames through the ffmpeg executable, libx264 when none works. probed once a process
+pub fn detect_best_encoder() -> FfmpegPipeEncoder {
+    static BEST: std::sync::OnceLock<FfmpegPipeEncoder> = std::sync::OnceLock::new();
+    *BEST.get_or_init(|| best_encoder(probe_encoder))
+}
+
+// the first hardware encoder of this platform that probe accepts, libx264 otherwise
+fn best_encoder(mut probe: impl FnMut(FfmpegPipeEncoder) -> bool) -> FfmpegPipeEncoder {
+    HARDWARE_ENCODERS
+        .into_iter()
+        .filter(|encoder| match encoder {
+            FfmpegPipeEncoder::HevcVideoToolbox => cfg!(target_os = "macos"),
+            FfmpegPipeEncoder::HevcVaapi => cfg!(target_os = "linux"),
+            _ => !cfg!(target_os = "macos"),
+        })
+        .find(|encoder| probe(*encoder))
+        .unwrap_or(FfmpegPipeEncoder::Libx264)
+}
+
+// a driver or device missing makes ffmpeg fail to open the encoder
+fn probe_encoder(encoder: FfmpegPipeEncoder) -> bool {
+    let mut args: Vec<String> = [
+        "-v",
+        "error",
+        "-f",
+        "lavfi",
+        "-i",
+        "color=black:s=256x256:r=30",
+        "-frames:v",
+        "5",
+    ]
+    .map(|a| a.to_owned())
+    .to_vec();
+    FfmpegPipeOutputOptionBuilder {
+        high_quality: false,
+        encoder,
+        live: false,
+    }
+    .build_option(&mut args);
+    args.extend(["-f", "null", "-"].map(|a| a.to_owned()));
+    let status = std::process::Command::new("ffmpeg")
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    let works = status.is_ok_and(|s| s.success());
+    tracing::debug!(
+        encoder = encoder.get_encoder_name(),
+        works,
+        "probed encoder"
+    );
+    works
 }
 
+// the container of a live output: rtmp:// and rtmps:// carry flv, srt:// mpeg-ts.
+// None for files
+pub fn stream_format(filename: &str) -> Option<&'static str> {
+    match filename.split_once("://") {
+        Some(("rtmp" | "rtmps", _)) => Some("flv"),
+        Some(("srt", _)) => Some("mpegts"),
+        _ => None,
+    }
+}
+
+// holds back the frames of a live output until they are due, a server expects them as fast
+// as they are played
+pub struct RealTime {
+    framerate: u32,
+    start: Option<std::time::Instant>,
+    frames: u64,
+}
+
+impl RealTime {
+    pub fn new(framerate: u32) -> Self {
+        Self {
+            framerate: framerate.max(1),
+            start: None,
+            frames: 0,
+        }
+    }
+
+    // sleep until the next frame is due, the first one is sent right away
+    pub fn wait(&mut self) {
+        let start = *self.start.get_or_insert_with(std::time::Instant::now);
+        let due =
+            start + std::time::Duration::from_secs_f64(self.frames as f64 / self.framerate as f64);
+        if let Some(delay) = due.checked_duration_since(std::time::Instant::now()) {
+            thread::sleep(delay);
+        }
+        self.frames += 1;
+    }
+}
+
+// see FfmpegBackend::with_config
 pub struct FfmpegConfig {
     pub ffmpeg_encoder: FfmpegPipeEncoder,
 }
@@ -82,6 +306,34 @@ pub struct BgraRAWBackend {
     file: std::fs::File,
 }
 
+// every frame as a png file, filename is a pattern like "frames/frame_{}.png",
+// {} is replaced with the frame index padded to 6 digits. needs no ffmpeg
+pub struct PngSequenceBackend {
+    filename: String,
+    width: u32,
+    height: u32,
+    color_order: ColorOrder,
+    frame_count: u64,
+    // the converted pixels, kept between frames
+    buffer: Vec<u8>,
+}
+
+// receives the premultiplied pixels of a frame
+pub type FrameCallback = Box<dyn FnMut(&[u8])>;
+
+// calls on_frame with the premultiplied pixels of every frame
+pub struct BufferBackend {
+    on_frame: FrameCallback,
+}
+
+impl BufferBackend {
+    pub fn new(on_frame: impl FnMut(&[u8]) + 'static) -> Self {
+        Self {
+            on_frame: Box::new(on_frame),
+        }
+    }
+}
+
 pub enum FrameMessage {
     Frame,
     End,
@@ -99,12 +351,44 @@ pub enum VideoBackendState {
 }
 
 impl VideoBackend {
+    // for the tracing events of the backend
+    pub fn name(&self) -> &'static str {
+        match &self.backend_type {
+            VideoBackendType::FfmpegPipe(_) => "ffmpeg_pipe",
+            #[cfg(feature = "ffmpeg")]
+            VideoBackendType::Ffmpeg(_) => "ffmpeg",
+            VideoBackendType::BgraRAW(_) => "raw",
+            VideoBackendType::PngSequence(_) => "png",
+            VideoBackendType::Buffer(_) => "buffer",
+            VideoBackendType::Plugin(_) => "plugin",
+            VideoBackendType::Gstreamer => "gstreamer",
+        }
+    }
+
     pub fn write_frame(&mut self, frame_data: &[u8]) {
+        let _span = tracing::trace_span!("encode_frame", backend = self.name()).entered();
+        let start = std::time::Instant::now();
+        self.write_frame_to_backend(frame_data);
+        tracing::trace!(elapsed = ?start.elapsed(), "encoded frame");
+    }
+
+    fn write_frame_to_backend(&mut self, frame_data: &[u8]) {
         match &mut self.backend_type {
             VideoBackendType::FfmpegPipe(f) => {
                 use std::io::Write;
-                f.stdin.write_all(frame_data);
+                let Some(stdin) = &mut f.stdin else {
+                    return;
+                };
+                if let Some(real_time) = &mut f.real_time {
+                    real_time.wait();
+                }
+                if f.alpha {
+                    stdin.write_all(&demultiply(frame_data));
+                } else {
+                    stdin.write_all(frame_data);
+                }
             }
+            #[cfg(feature = "ffmpeg")]
             VideoBackendType::Ffmpeg(f) => {
                 f.write_frame(frame_data);
             }
@@ -112,17 +396,67 @@ impl VideoBackend {
                 use std::io::Write;
                 f.file.write_all(frame_data);
             }
+            VideoBackendType::PngSequence(f) => {
+                f.write_frame(frame_data);
+            }
+            VideoBackendType::Buffer(f) => {
+                (f.on_frame)(frame_data);
+            }
+            VideoBackendType::Plugin(f) => {
+                f.write_frame(frame_data);
+            }
             _ => {}
         }
     }
 
-    pub fn close(&mut self) {
+    // write the same frame count times, backends that encode frames
+    // only have to convert it once
+    pub fn write_repeated_frame(&mut self, frame_data: &[u8], count: u32) {
         match &mut self.backend_type {
+            #[cfg(feature = "ffmpeg")]
+            VideoBackendType::Ffmpeg(f) => {
+                let _span = tracing::trace_span!("encode_frame", backend = "ffmpeg").entered();
+                f.write_repeated_frame(frame_data, count);
+                tracing::trace!(count, "encoded repeated frame");
+            }
+            _ => {
+                for _ in 0..count {
+                    self.write_frame(frame_data);
+                }
+            }
+        }
+    }
+
+    // finish the output file, for the ffmpeg pipe this waits until ffmpeg has exited
+    pub fn close(&mut self) -> std::io::Result<()> {
+        match &mut self.backend_type {
+            VideoBackendType::FfmpegPipe(f) => {
+                // ffmpeg stops reading when its input is closed
+                let Some(stdin) = f.stdin.take() else {
+                    return Ok(());
+                };
+                drop(stdin);
+                let status = f.child.wait()?;
+                if !status.success() {
+                    return Err(std::io::Error::other(format!(
+                        "ffmpeg exited with {status}"
+                    )));
+                }
+            }
+            #[cfg(feature = "ffmpeg")]
             VideoBackendType::Ffmpeg(f) => {
                 f.finish();
             }
+            VideoBackendType::BgraRAW(f) => {
+                use std::io::Write;
+                f.file.flush()?;
+            }
+            VideoBackendType::Plugin(f) => {
+                f.close()?;
+            }
             _ => {}
         }
+        Ok(())
     }
 
     pub fn write_frame_background(
@@ -137,13 +471,17 @@ impl VideoBackend {
             {
                 let mut queue_guard = queue.lock().unwrap();
                 data = queue_guard.pop_front();
+                tracing::trace!(
+                    queue_depth = queue_guard.len(),
+                    "took a frame from the queue"
+                );
             }
             if data.is_none() {
                 {
                     let mut state_guard = state.lock().unwrap();
                     *state_guard = VideoBackendState::Sleeping;
                 }
-                println!("sleeping!");
+                tracing::trace!("encoder thread waiting for frames");
                 match rx.recv() {
                     Ok(f) => match f {
                         FrameMessage::Frame => {}
@@ -159,13 +497,15 @@ impl VideoBackend {
             } else {
                 self.write_frame(&data.unwrap());
             }
-            println!("write takes: {:?}", now.elapsed());
+            tracing::trace!(elapsed = ?now.elapsed(), "encoder thread wrote a frame");
         }
     }
 }
 struct FfmpegPipeOutputOptionBuilder {
     high_quality: bool,
     encoder: FfmpegPipeEncoder,
+    // streaming to a server, see stream_format
+    live: bool,
 }
 
 impl FfmpegPipeOutputOptionBuilder {
@@ -209,6 +549,21 @@ impl FfmpegPipeOutputOptionBuilder {
                     vec!["-preset", "p1"]
                 }
             }
+            FfmpegPipeEncoder::HevcQsv => {
+                if self.high_quality {
+                    vec!["-preset", "veryslow"]
+                } else {
+                    vec!["-preset", "veryfast"]
+                }
+            }
+            // videotoolbox has no presets
+            FfmpegPipeEncoder::HevcVideoToolbox => {
+                if self.high_quality {
+                    vec![]
+                } else {
+                    vec!["-realtime", "1"]
+                }
+            }
             _ => {
                 if self.high_quality {
                     vec!["-preset", "veryslow"]
@@ -217,9 +572,85 @@ impl FfmpegPipeOutputOptionBuilder {
                 }
             }
         };
+        match self.encoder {
+            // apl0 marks the file as written by apple's encoder, some editors check for it
+            FfmpegPipeEncoder::ProRes(profile) => {
+                quality_options = vec![
+                    "-profile:v",
+                    profile.name(),
+                    "-pix_fmt",
+                    profile.pix_fmt(),
+                    "-vendor",
+                    "apl0",
+                ];
+                if profile == ProResProfile::P4444 {
+                    quality_options.extend(["-alpha_bits", "16"]);
+                }
+                if !self.high_quality {
+                    quality_options.extend(["-qscale:v", "11"]);
+                }
+            }
+            // without row-mt and cpu-used vp9 and aom encode a few frames per second at most
+            FfmpegPipeEncoder::Vp9 { alpha } => {
+                let pix_fmt = if alpha { "yuva420p" } else { "yuv420p" };
+                quality_options = vec!["-pix_fmt", pix_fmt, "-b:v", "0", "-row-mt", "1"];
+                if self.high_quality {
+                    quality_options.extend(["-crf", "15", "-deadline", "good", "-cpu-used", "2"]);
+                } else {
+                    quality_options.extend([
+                        "-crf",
+                        "35",
+                        "-deadline",
+                        "realtime",
+                        "-cpu-used",
+                        "8",
+                    ]);
+                }
+            }
+            // svt only takes 4:2:0, aom gets the same for players without 4:4:4 av1
+            FfmpegPipeEncoder::LibaomAv1 => {
+                quality_options = vec!["-pix_fmt", "yuv420p", "-b:v", "0", "-row-mt", "1"];
+                if self.high_quality {
+                    quality_options.extend(["-crf", "20", "-cpu-used", "4"]);
+                } else {
+                    quality_options.extend(["-crf", "40", "-cpu-used", "8", "-usage", "realtime"]);
+                }
+            }
+            FfmpegPipeEncoder::SvtAv1 => {
+                quality_options = vec!["-pix_fmt", "yuv420p"];
+                if self.high_quality {
+                    quality_options.extend(["-crf", "24", "-preset", "4"]);
+                } else {
+                    quality_options.extend(["-crf", "40", "-preset", "10"]);
+                }
+            }
+            _ => {}
+        }
+        // a live stream is encoded in real time and every frame is sent right away
+        if self.live {
+            match self.encoder {
+                FfmpegPipeEncoder::Libx264 | FfmpegPipeEncoder::Libx265 => {
+                    quality_options = vec!["-preset", "veryfast", "-tune", "zerolatency"];
+                }
+                FfmpegPipeEncoder::HevcNvenc => {
+                    quality_options = vec!["-preset", "p1", "-tune", "ll"];
+                }
+                _ => {}
+            }
+        }
         //vaapi only support "vaapi" pix_fmt
-        if !matches!(self.encoder, FfmpegPipeEncoder::HevcVaapi) {
-            if self.high_quality {
+        if !matches!(
+            self.encoder,
+            FfmpegPipeEncoder::HevcVaapi | FfmpegPipeEncoder::ProRes(_)
+        ) && !self.encoder.is_webm()
+        {
+            // players of live streams expect 4:2:0, quick sync and videotoolbox only take it
+            if self.encoder == FfmpegPipeEncoder::HevcQsv {
+                quality_options.extend(["-pix_fmt", "nv12"]);
+            } else if self.high_quality
+                && !self.live
+                && self.encoder != FfmpegPipeEncoder::HevcVideoToolbox
+            {
                 quality_options.extend(["-pix_fmt", "yuv444p"]);
             } else {
                 quality_options.extend(["-pix_fmt", "yuv420p"]);
@@ -236,6 +667,7 @@ impl FfmpegPipeBackend {
         high_profile: bool,
     ) -> Self {
         let encoder_name = encoder_config.get_encoder_name();
+        let alpha = encoder_config.has_alpha();
 
         let mut args = vec![
             "-y".to_string(),
@@ -253,13 +685,24 @@ impl FfmpegPipeBackend {
             "-i".to_string(),
             "-".to_string(),
         ];
+        let live = stream_format(&video_config.filename);
         let encoder_option_builder = FfmpegPipeOutputOptionBuilder {
             high_quality: high_profile,
             encoder: encoder_config,
+            live: live.is_some(),
         };
 
         encoder_option_builder.build_option(&mut args);
 
+        if let Some(format) = live {
+            // a keyframe every 2 seconds, as the ingest servers ask for
+            args.extend([
+                "-g".to_string(),
+                (2 * video_config.framerate).to_string(),
+                "-f".to_string(),
+                format.to_string(),
+            ]);
+        }
         args.push(video_config.filename.to_string());
 
         let mut c = std::process::Command::new("ffmpeg")
@@ -272,11 +715,104 @@ impl FfmpegPipeBackend {
         let mut stdin = c.stdin.take().expect("failed to open stdin");
         Self {
             child: c,
-            stdin: stdin,
+            stdin: Some(stdin),
+            alpha,
+            real_time: live.map(|_| RealTime::new(video_config.framerate)),
         }
     }
 }
 
+// how many frames VideoBackendController holds before they reach the backend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryBudget {
+    // 0 streams every frame straight to the backend
+    pub max_frames: usize,
+    pub max_bytes: usize,
+    pub overflow: Overflow,
+}
+
+// what the controller does with the held frames when the budget is exceeded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    // encode them now, rendering waits for the encoder
+    Throttle,
+    // append them to a temporary raw file that is encoded by end
+    Spill,
+}
+
+impl Default for MemoryBudget {
+    // a gigabyte, about 30 frames of 4k
+    fn default() -> Self {
+        Self {
+            max_frames: 4 * BLOCK_SIZE,
+            max_bytes: 1 << 30,
+            overflow: Overflow::Throttle,
+        }
+    }
+}
+
+impl MemoryBudget {
+    pub fn streaming() -> Self {
+        Self {
+            max_frames: 0,
+            ..Default::default()
+        }
+    }
+}
+
+// frames written as their length and data, read back in order by drain
+struct SpillFile {
+    path: std::path::PathBuf,
+    file: std::io::BufWriter<std::fs::File>,
+    frames: usize,
+}
+
+impl SpillFile {
+    fn create() -> std::io::Result<Self> {
+        static COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "gmanim_spill_{}_{}.raw",
+            std::process::id(),
+            COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        let file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        Ok(Self {
+            path,
+            file,
+            frames: 0,
+        })
+    }
+
+    fn write(&mut self, frame: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+        self.file.write_all(&(frame.len() as u64).to_le_bytes())?;
+        self.file.write_all(frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn drain(mut self, video_backend: &mut VideoBackend) -> std::io::Result<()> {
+        use std::io::{Read, Write};
+        self.file.flush()?;
+        let mut file = std::io::BufReader::new(std::fs::File::open(&self.path)?);
+        let mut frame = vec![];
+        for _ in 0..self.frames {
+            let mut len = [0; 8];
+            file.read_exact(&mut len)?;
+            frame.resize(u64::from_le_bytes(len) as usize, 0);
+            file.read_exact(&mut frame)?;
+            video_backend.write_frame(&frame);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
 // the intent of backend controller is to seperate framge generation and video encoding
 // we use a backgroud thread to push frame data to the ffmpeg pipe
 // TODO: make send frame zero copy
@@ -287,10 +823,22 @@ pub struct VideoBackendController {
     block_queue: Arc<Mutex<VecDeque<Vec<Vec<u8>>>>>,
     sender: Sender<FrameMessage>,
     block: Option<Vec<Vec<u8>>>,
+    // opened with the default player of the system once end has written it, see open_in_player
+    pub open_when_done: Option<String>,
+    pub memory_budget: MemoryBudget,
+    // the frames and bytes in block_queue and block
+    held_frames: usize,
+    held_bytes: usize,
+    // frames spilled past the budget, they come before the held ones
+    spill: Option<SpillFile>,
 }
 
 impl VideoBackendController {
     pub fn new(video_backend: VideoBackend) -> Self {
+        Self::with_budget(video_backend, MemoryBudget::default())
+    }
+
+    pub fn with_budget(video_backend: VideoBackend, memory_budget: MemoryBudget) -> Self {
         let video_backend_ref = Arc::new(Mutex::new(video_backend));
 
         let block_queue = Arc::new(Mutex::new(VecDeque::<Vec<Vec<u8>>>::new()));
@@ -331,24 +879,123 @@ impl VideoBackendController {
             block_queue,
             sender,
             block,
+            open_when_done: None,
+            memory_budget,
+            held_frames: 0,
+            held_bytes: 0,
+            spill: None,
         }
     }
     pub fn write_frame(&mut self, frame: Vec<u8>) {
-        self.block.as_mut().unwrap().push(frame.to_owned());
+        self.held_frames += 1;
+        self.held_bytes += frame.len();
+        self.block.as_mut().unwrap().push(frame);
         if self.block.as_ref().unwrap().len() == BLOCK_SIZE {
-            self.block_queue
-                .lock()
-                .unwrap()
-                .push_back(self.block.replace(Vec::new()).unwrap());
+            let mut queue = self.block_queue.lock().unwrap();
+            queue.push_back(self.block.replace(Vec::new()).unwrap());
+            tracing::debug!(queue_depth = queue.len(), "queued a block of frames");
+            drop(queue);
             self.sender.send(FrameMessage::Frame);
         }
+        if self.held_frames > self.memory_budget.max_frames
+            || self.held_bytes > self.memory_budget.max_bytes
+        {
+            self.release_held_frames();
+        }
+    }
+
+    // the held frames in order, the queued blocks before the open one
+    fn take_held_frames(&mut self) -> Vec<Vec<u8>> {
+        let mut queue = self.block_queue.lock().unwrap();
+        queue.extend(self.block.replace(Vec::new()));
+        self.held_frames = 0;
+        self.held_bytes = 0;
+        queue.drain(..).flatten().collect()
+    }
+
+    fn release_held_frames(&mut self) {
+        let mut frames = self.take_held_frames();
+        if self.memory_budget.overflow == Overflow::Spill {
+            tracing::debug!(
+                frames = frames.len(),
+                "spilling frames past the memory budget"
+            );
+            if self.spill.is_none() {
+                match SpillFile::create() {
+                    Ok(spill) => self.spill = Some(spill),
+                    Err(e) => log::warn!("can't create a spill file, encoding instead: {e}"),
+                }
+            }
+            if let Some(spill) = &mut self.spill {
+                let mut written = 0;
+                for frame in &frames {
+                    if let Err(e) = spill.write(frame) {
+                        log::warn!("can't write the spill file, encoding instead: {e}");
+                        break;
+                    }
+                    written += 1;
+                }
+                if written == frames.len() {
+                    return;
+                }
+                frames.drain(..written);
+            }
+        }
+        tracing::debug!(
+            frames = frames.len(),
+            "encoding frames past the memory budget"
+        );
+        let mut video_backend = self.video_backend.lock().unwrap();
+        if let Some(spill) = self.spill.take() {
+            if let Err(e) = spill.drain(&mut video_backend) {
+                log::error!("can't read back the spill file: {e}");
+            }
+        }
+        for frame in &frames {
+            video_backend.write_frame(frame);
+        }
     }
-    pub fn end(self) {
+
+    // write the frames still queued and close the backend
+    pub fn end(mut self) -> std::io::Result<()> {
         self.sender.send(FrameMessage::End);
         // self.background_thread_handler.join();
+        let frames = self.take_held_frames();
+        let mut video_backend = self.video_backend.lock().unwrap();
+        if let Some(spill) = self.spill.take() {
+            spill.drain(&mut video_backend)?;
+        }
+        for frame in frames {
+            video_backend.write_frame(&frame);
+        }
+        video_backend.close()?;
+        if let Some(path) = &self.open_when_done {
+            open_in_player(path)?;
+        }
+        Ok(())
     }
 }
 
+// open path with the default application of the system without waiting for it
+pub fn open_in_player(path: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        // the empty argument is the window title of start
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command
+        .arg(path)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
 impl BgraRAWBackend {
     pub fn new(video_config: &VideoConfig) -> Self {
         let file = std::fs::OpenOptions::new()
@@ -359,3 +1006,360 @@ impl BgraRAWBackend {
         Self { file }
     }
 }
+
+impl PngSequenceBackend {
+    pub fn new(video_config: &VideoConfig) -> Self {
+        Self {
+            filename: video_config.filename.clone(),
+            width: video_config.output_width,
+            height: video_config.output_height,
+            color_order: video_config.color_order,
+            frame_count: 0,
+            buffer: vec![],
+        }
+    }
+
+    // frame_000000.png, frame_000001.png, .. in directory, which is created
+    pub fn in_directory(
+        directory: impl AsRef<std::path::Path>,
+        video_config: &VideoConfig,
+    ) -> std::io::Result<Self> {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory)?;
+        Ok(Self::new(&VideoConfig {
+            filename: directory
+                .join("frame_{}.png")
+                .to_string_lossy()
+                .into_owned(),
+            ..video_config.clone()
+        }))
+    }
+
+    // the index goes where {} is in the filename, without one it is put before the
+    // extension so the frames don't overwrite each other
+    pub fn frame_path(&self, index: u64) -> String {
+        let index = format!("{:06}", index);
+        if self.filename.contains("{}") {
+            return self.filename.replace("{}", &index);
+        }
+        let path = std::path::Path::new(&self.filename);
+        match path.extension() {
+            Some(extension) => format!(
+                "{}_{index}.{}",
+                path.with_extension("").to_string_lossy(),
+                extension.to_string_lossy()
+            ),
+            None => format!("{}_{index}", self.filename),
+        }
+    }
+
+    pub fn write_frame(&mut self, frame_data: &[u8]) {
+        let path = self.frame_path(self.frame_count);
+        self.frame_count += 1;
+        if let Err(e) = self.encode(frame_data, &path) {
+            log::error!("failed to write {}: {}", path, e);
+        }
+    }
+
+    // png stores straight rgba. rgba frames without translucent pixels are the same
+    // premultiplied and are encoded as they are, others are converted into the buffer
+    fn encode(&mut self, frame_data: &[u8], path: &str) -> Result<(), String> {
+        if frame_data.len() != self.width as usize * self.height as usize * 4 {
+            return Err(format!(
+                "the frame has {} bytes, not {}x{} pixels",
+                frame_data.len(),
+                self.width,
+                self.height
+            ));
+        }
+        let bgra = matches!(self.color_order, ColorOrder::Bgra);
+        let pixels = if !bgra
+            && frame_data
+                .chunks_exact(4)
+                .all(|p| p[3] == 0 || p[3] == 0xff)
+        {
+            frame_data
+        } else {
+            self.buffer.clear();
+            self.buffer.extend_from_slice(frame_data);
+            for p in self.buffer.chunks_exact_mut(4) {
+                if bgra {
+                    p.swap(0, 2);
+                }
+                demultiply_pixel(p);
+            }
+            &self.buffer
+        };
+        let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(pixels).map_err(|e| e.to_string())?;
+        writer.finish().map_err(|e| e.to_string())
+    }
+}
+
+// the contexts draw premultiplied colors, encoders with an alpha channel expect straight ones.
+// works for rgba and bgra, the alpha is the last byte of both
+pub fn demultiply(frame_data: &[u8]) -> Vec<u8> {
+    let mut data = frame_data.to_vec();
+    data.chunks_exact_mut(4).for_each(demultiply_pixel);
+    data
+}
+
+fn demultiply_pixel(p: &mut [u8]) {
+    let a = p[3] as u32;
+    if a == 0 || a == 0xff {
+        return;
+    }
+    for c in &mut p[..3] {
+        *c = ((*c as u32 * 0xff + a / 2) / a).min(0xff) as u8;
+    }
+}
+
+#[test]
+fn test_encoder_options() {
+    let options = |encoder, high_quality, live| {
+        let mut args = vec![];
+        FfmpegPipeOutputOptionBuilder {
+            high_quality,
+            encoder,
+            live,
+        }
+        .build_option(&mut args);
+        args.join(" ")
+    };
+    let args = |encoder, high_quality| options(encoder, high_quality, false);
+    assert!(args(FfmpegPipeEncoder::Vp9 { alpha: false }, true).contains("-row-mt 1"));
+    assert!(args(FfmpegPipeEncoder::LibaomAv1, false).contains("-cpu-used 8"));
+    let svt = args(FfmpegPipeEncoder::SvtAv1, true);
+    assert!(svt.contains("-vcodec libsvtav1") && svt.contains("-preset 4"));
+    assert_eq!(svt.matches("-pix_fmt").count(), 1);
+    assert_eq!(
+        FfmpegPipeEncoder::from_name("libaom-av1"),
+        Some(FfmpegPipeEncoder::LibaomAv1)
+    );
+    let hq = args(FfmpegPipeEncoder::ProRes(ProResProfile::Hq), true);
+    assert!(hq.contains("-profile:v hq -pix_fmt yuv422p10le") && !hq.contains("alpha_bits"));
+    assert_eq!(
+        ProResProfile::from_name("422"),
+        Some(ProResProfile::Standard)
+    );
+    let live = options(FfmpegPipeEncoder::Libx264, true, true);
+    assert!(live.contains("-preset veryfast -tune zerolatency -pix_fmt yuv420p"));
+    let qsv = args(FfmpegPipeEncoder::HevcQsv, true);
+    assert!(qsv.contains("-vcodec hevc_qsv -preset veryslow -pix_fmt nv12"));
+    assert!(args(FfmpegPipeEncoder::HevcVideoToolbox, true).ends_with("-pix_fmt yuv420p"));
+    assert!(HARDWARE_ENCODERS.iter().all(|e| e.is_hardware()));
+}
+
+#[test]
+fn test_prores_profiles() {
+    let profiles = [
+        ProResProfile::Proxy,
+        ProResProfile::Lt,
+        ProResProfile::Standard,
+        ProResProfile::Hq,
+        ProResProfile::P4444,
+    ];
+    for profile in profiles {
+        assert_eq!(ProResProfile::from_name(profile.name()), Some(profile));
+    }
+    assert_eq!(ProResProfile::from_name("xq"), None);
+    let mut args = vec![];
+    FfmpegPipeOutputOptionBuilder {
+        high_quality: false,
+        encoder: FfmpegPipeEncoder::ProRes(ProResProfile::Proxy),
+        live: false,
+    }
+    .build_option(&mut args);
+    // prores_ks has no presets, low quality renders get a coarser quantizer instead
+    let args = args.join(" ");
+    assert!(args.contains("-vcodec prores_ks -profile:v proxy") && args.ends_with("-qscale:v 11"));
+    assert!(!args.contains("-preset"));
+}
+
+#[test]
+fn test_alpha_pix_fmt() {
+    let args = |encoder| {
+        let mut args = vec![];
+        FfmpegPipeOutputOptionBuilder {
+            high_quality: false,
+            encoder,
+            live: false,
+        }
+        .build_option(&mut args);
+        args.join(" ")
+    };
+    let transparent = FfmpegPipeEncoder::Vp9 { alpha: true };
+    assert!(transparent.has_alpha() && args(transparent).contains("-pix_fmt yuva420p"));
+    // an opaque vp9 video has no alpha plane to encode
+    let opaque = FfmpegPipeEncoder::Vp9 { alpha: false };
+    assert!(!opaque.has_alpha() && args(opaque).contains("-pix_fmt yuv420p"));
+    let p4444 = FfmpegPipeEncoder::ProRes(ProResProfile::P4444);
+    assert!(p4444.has_alpha());
+    assert!(args(p4444).contains("-pix_fmt yuva444p10le -vendor apl0 -alpha_bits 16"));
+    assert!(!FfmpegPipeEncoder::ProRes(ProResProfile::Lt).has_alpha());
+    assert!(!FfmpegPipeEncoder::Libx264.has_alpha());
+}
+
+#[test]
+fn test_stream_format() {
+    assert_eq!(stream_format("rtmp://localhost/live/key"), Some("flv"));
+    assert_eq!(
+        stream_format("rtmps://live.example.com/app/key"),
+        Some("flv")
+    );
+    assert_eq!(stream_format("srt://127.0.0.1:9000"), Some("mpegts"));
+    assert_eq!(stream_format("out/scene.mp4"), None);
+    assert_eq!(stream_format("file://out/scene.mp4"), None);
+}
+
+#[test]
+fn test_real_time() {
+    let mut real_time = RealTime::new(50);
+    let start = std::time::Instant::now();
+    real_time.wait();
+    assert!(start.elapsed() < std::time::Duration::from_millis(20));
+    // frames 1 to 3 are due 20ms apart
+    for _ in 0..3 {
+        real_time.wait();
+    }
+    assert!(start.elapsed() >= std::time::Duration::from_millis(60));
+    // a frame that is already late goes out right away
+    thread::sleep(std::time::Duration::from_millis(100));
+    let late = std::time::Instant::now();
+    real_time.wait();
+    assert!(late.elapsed() < std::time::Duration::from_millis(20));
+}
+
+#[test]
+fn test_best_encoder_fallback() {
+    let mut probed = vec![];
+    let best = best_encoder(|encoder| {
+        probed.push(encoder);
+        false
+    });
+    assert_eq!(best, FfmpegPipeEncoder::Libx264);
+    assert!(!probed.is_empty() && probed.iter().all(|e| e.is_hardware()));
+    assert_eq!(
+        probed.contains(&FfmpegPipeEncoder::HevcVideoToolbox),
+        cfg!(target_os = "macos")
+    );
+    // the first encoder that works is taken
+    let last = *probed.last().unwrap();
+    assert_eq!(best_encoder(|encoder| encoder == last), last);
+    assert_eq!(best_encoder(|_| true), probed[0]);
+}
+
+#[test]
+fn test_demultiply() {
+    let frame = [0x40, 0x20, 0x00, 0x80, 0x10, 0x20, 0x30, 0xff, 0, 0, 0, 0];
+    assert_eq!(
+        demultiply(&frame),
+        [0x80, 0x40, 0x00, 0x80, 0x10, 0x20, 0x30, 0xff, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn test_png_sequence() {
+    let directory = std::env::temp_dir().join(format!("gmanim_png_{}", std::process::id()));
+    let video_config = VideoConfig {
+        filename: String::new(),
+        framerate: 60,
+        output_width: 2,
+        output_height: 1,
+        color_order: ColorOrder::Bgra,
+    };
+    let mut backend = PngSequenceBackend::in_directory(&directory, &video_config).unwrap();
+    let path = backend.frame_path(1);
+    assert!(path.ends_with("frame_000001.png"));
+    backend.write_frame(&[0xff, 0, 0, 0xff, 0, 0, 0, 0]);
+    // a half transparent red in the buffer of the first frame
+    backend.write_frame(&[0, 0, 0x80, 0x80, 0x10, 0x20, 0x30, 0xff]);
+    let frame = tiny_skia::Pixmap::load_png(&path).unwrap();
+    let pixel = frame.pixel(0, 0).unwrap().demultiply();
+    assert_eq!((pixel.red(), pixel.blue(), pixel.alpha()), (0xff, 0, 0x80));
+    let pixel = frame.pixel(1, 0).unwrap();
+    assert_eq!(
+        (pixel.red(), pixel.green(), pixel.blue()),
+        (0x30, 0x20, 0x10)
+    );
+    let first = tiny_skia::Pixmap::load_png(backend.frame_path(0)).unwrap();
+    assert_eq!(first.pixel(0, 0).unwrap().blue(), 0xff);
+    let _ = std::fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn test_png_frame_path() {
+    let video_config = |filename: &str| VideoConfig {
+        filename: filename.to_owned(),
+        framerate: 60,
+        output_width: 2,
+        output_height: 1,
+        color_order: ColorOrder::Bgra,
+    };
+    let backend = PngSequenceBackend::new(&video_config("out/intro.png"));
+    assert_eq!(backend.frame_path(3), "out/intro_000003.png");
+    let backend = PngSequenceBackend::new(&video_config("out/intro"));
+    assert_eq!(backend.frame_path(3), "out/intro_000003");
+}
+
+#[test]
+fn test_controller_end() {
+    let video_config = VideoConfig {
+        filename: std::env::temp_dir()
+            .join("gmanim_controller.raw")
+            .to_string_lossy()
+            .into_owned(),
+        framerate: 30,
+        output_width: 4,
+        output_height: 2,
+        color_order: ColorOrder::Rgba,
+    };
+    let _ = std::fs::remove_file(&video_config.filename);
+    let mut controller = VideoBackendController::new(VideoBackend {
+        backend_type: VideoBackendType::BgraRAW(BgraRAWBackend::new(&video_config)),
+    });
+    // more than a block, the last one is only partly filled
+    for _ in 0..BLOCK_SIZE + 10 {
+        controller.write_frame(vec![0xff; 4 * 2 * 4]);
+    }
+    controller.end().unwrap();
+    let written = std::fs::metadata(&video_config.filename).unwrap().len();
+    assert_eq!(written, (BLOCK_SIZE as u64 + 10) * 4 * 2 * 4);
+}
+
+#[test]
+fn test_memory_budget() {
+    let frames = Arc::new(Mutex::new(vec![]));
+    let budget = |overflow| MemoryBudget {
+        max_frames: 3,
+        max_bytes: 1 << 20,
+        overflow,
+    };
+    for overflow in [Overflow::Throttle, Overflow::Spill] {
+        frames.lock().unwrap().clear();
+        let written = frames.clone();
+        let mut controller = VideoBackendController::with_budget(
+            VideoBackend {
+                backend_type: VideoBackendType::Buffer(BufferBackend::new(move |data| {
+                    written.lock().unwrap().push(data[0])
+                })),
+            },
+            budget(overflow),
+        );
+        for i in 0..10 {
+            controller.write_frame(vec![i; 4]);
+            assert!(controller.held_frames <= 3);
+        }
+        // throttling encodes every fourth frame, spilling keeps them for end
+        let encoded = frames.lock().unwrap().len();
+        assert_eq!(encoded, if overflow == Overflow::Throttle { 8 } else { 0 });
+        let spill = controller.spill.as_ref().map(|s| s.path.clone());
+        controller.end().unwrap();
+        assert_eq!(*frames.lock().unwrap(), (0..10).collect::<Vec<u8>>());
+        assert!(spill.is_none_or(|path| !path.exists()));
+    }
+}
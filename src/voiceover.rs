@@ -0,0 +1,204 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+use crate::animation::composition::{Stretch, Succession};
+use crate::animation::{play, seconds_to_frames, Animation};
+use crate::subtitles::SubtitleCue;
+use crate::video_backend::VideoBackend;
+use crate::{sound, Context, GMFloat, Scene};
+
+// turns the text of a voiceover into an audio file, returns its path
+pub trait SpeechService {
+    fn speak(&self, text: &str) -> Result<String, String>;
+}
+
+// a text to speech program like espeak-ng, piper or a script calling a web service.
+// {text} and {output} in args are replaced with the text and the path to write to,
+// e.g. espeak-ng -w {output} {text}. the same text is spoken once across renders
+#[derive(Debug, Clone)]
+pub struct CommandSpeech {
+    pub program: String,
+    pub args: Vec<String>,
+    // the extension of the files the program writes
+    pub extension: String,
+    pub cache_dir: PathBuf,
+}
+
+impl CommandSpeech {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_owned(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            extension: "wav".to_owned(),
+            cache_dir: std::env::temp_dir().join("gmanim_voiceover"),
+        }
+    }
+
+    fn output_path(&self, text: &str) -> PathBuf {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.program.hash(&mut hasher);
+        self.args.hash(&mut hasher);
+        text.hash(&mut hasher);
+        self.cache_dir
+            .join(format!("{:016x}.{}", hasher.finish(), self.extension))
+    }
+}
+
+impl SpeechService for CommandSpeech {
+    fn speak(&self, text: &str) -> Result<String, String> {
+        let path = self.output_path(text);
+        let output = path.to_string_lossy().into_owned();
+        if path.exists() {
+            return Ok(output);
+        }
+        std::fs::create_dir_all(&self.cache_dir)
+            .map_err(|e| format!("can't create {:?}: {e}", self.cache_dir))?;
+        let args = self
+            .args
+            .iter()
+            .map(|a| a.replace("{text}", text).replace("{output}", &output));
+        let status = Command::new(&self.program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("can't run {}: {e}", self.program))?;
+        if !status.success() || !path.exists() {
+            // no half written file in the cache
+            let _ = std::fs::remove_file(&path);
+            return Err(format!("{} exited with {status}", self.program));
+        }
+        Ok(output)
+    }
+}
+
+// a recorded or synthesized clip narrating a block of animations
+#[derive(Debug, Clone)]
+pub struct Voiceover {
+    pub path: String,
+    // what is said, for subtitles
+    pub text: Option<String>,
+    // seconds
+    pub duration: GMFloat,
+}
+
+impl Voiceover {
+    pub fn recorded(path: &str) -> Result<Self, String> {
+        Ok(Self {
+            path: path.to_owned(),
+            text: None,
+            duration: sound::duration(path)?,
+        })
+    }
+
+    pub fn synthesize(service: &dyn SpeechService, text: &str) -> Result<Self, String> {
+        let path = service.speak(text)?;
+        Ok(Self {
+            text: Some(text.to_owned()),
+            ..Self::recorded(&path)?
+        })
+    }
+}
+
+// play the animations one after another while the voiceover is spoken. they are
+// sped up or slowed down together to last as long as the clip, which starts with the
+// first frame of the block and is mixed into the output by cli::render, its text
+// becomes a subtitle cue. without animations the scene waits until the clip is over
+pub fn play_voiceover(
+    scene: &Rc<RefCell<Scene>>,
+    ctx: &Rc<RefCell<Context>>,
+    video_backend: &mut VideoBackend,
+    voiceover: &Voiceover,
+    animations: Vec<Box<dyn Animation>>,
+) {
+    let framerate = ctx.borrow().scene_config.framerate;
+    let at_time = scene.borrow().video_time(framerate);
+    scene.borrow_mut().add_sound(&voiceover.path, at_time);
+    if let Some(text) = &voiceover.text {
+        scene.borrow_mut().subtitles.push(SubtitleCue {
+            start: at_time,
+            end: at_time + voiceover.duration,
+            text: text.clone(),
+        });
+    }
+    if animations.is_empty() {
+        scene
+            .borrow_mut()
+            .wait(&mut ctx.borrow_mut(), video_backend, voiceover.duration);
+        return;
+    }
+    let block = Succession::new(scene.clone(), ctx.clone(), animations);
+    let total_frame = seconds_to_frames(voiceover.duration, framerate).max(1);
+    play(
+        scene,
+        ctx,
+        video_backend,
+        Stretch::new(scene.clone(), ctx.clone(), Box::new(block), total_frame),
+    );
+}
+
+#[test]
+fn test_play_voiceover() {
+    use crate::animation::{AnimationConfig, SimpleMovement};
+    use crate::mobjects::{Dot, Mobject};
+    use crate::video_backend::{BufferBackend, VideoBackendType};
+    use crate::{ContextType, GMPoint, SceneConfig};
+
+    let scene = Rc::new(RefCell::new(Scene::new()));
+    let ctx = Rc::new(RefCell::new(Context::new(
+        ContextType::TinySKIA(tiny_skia::Pixmap::new(16, 9).unwrap()),
+        SceneConfig::for_output(16, 9, 9.0),
+    )));
+    let framerate = ctx.borrow().scene_config.framerate;
+    let dot: Box<dyn Mobject> = Box::new(Dot::new(
+        GMPoint::origin(),
+        0.5,
+        Default::default(),
+        Default::default(),
+    ));
+    let dot = Rc::new(RefCell::new(dot));
+    scene.borrow_mut().add_ref(dot.clone());
+    let frames = Rc::new(RefCell::new(0));
+    let mut video_backend = VideoBackend {
+        backend_type: VideoBackendType::Buffer(BufferBackend::new({
+            let frames = frames.clone();
+            move |_| *frames.borrow_mut() += 1
+        })),
+    };
+    let movement = SimpleMovement {
+        displacement: nalgebra::Vector3::new(2.0, 0.0, 0.0),
+        scene: scene.clone(),
+        ctx: ctx.clone(),
+        m: dot.clone(),
+        animation_config: AnimationConfig::from_run_time(0.5, framerate, |x| x),
+        last_progress: 0.0,
+        path_arc: 0.0,
+    };
+    let voiceover = Voiceover {
+        path: "intro.wav".to_owned(),
+        text: Some("a dot moves to the right".to_owned()),
+        duration: 2.0,
+    };
+    // half a second of animation is stretched over the two seconds of the clip
+    play_voiceover(
+        &scene,
+        &ctx,
+        &mut video_backend,
+        &voiceover,
+        vec![Box::new(movement)],
+    );
+    assert_eq!(*frames.borrow(), 2 * framerate);
+    assert_eq!(dot.borrow().get_points()[0].x, 1.5);
+    // the next clip starts where this one ended
+    play_voiceover(&scene, &ctx, &mut video_backend, &voiceover, vec![]);
+    let sounds = &scene.borrow().sounds;
+    assert_eq!(sounds.len(), 2);
+    assert_eq!(sounds[0].at_time, 0.0);
+    assert_eq!(sounds[1].at_time, 2.0);
+    assert_eq!(scene.borrow().subtitles[1].start, 2.0);
+    assert_eq!(scene.borrow().subtitles[1].end, 4.0);
+}
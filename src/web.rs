@@ -0,0 +1,100 @@
+use wasm_bindgen::{Clamped, JsCast, JsValue};
+use web_sys::{
+    CanvasRenderingContext2d, HtmlCanvasElement, ImageData, OffscreenCanvas,
+    OffscreenCanvasRenderingContext2d,
+};
+
+use crate::embed::FrameRenderer;
+use crate::video_backend::demultiply;
+use crate::{GMFloat, SceneConfig};
+
+// the 2d context frames are put into, an html canvas on the page
+// or an offscreen canvas for rendering in a web worker
+#[derive(Debug, Clone)]
+pub enum CanvasTarget {
+    Canvas(CanvasRenderingContext2d),
+    Offscreen(OffscreenCanvasRenderingContext2d),
+}
+
+impl CanvasTarget {
+    pub fn from_canvas(canvas: &HtmlCanvasElement) -> Result<Self, JsValue> {
+        let ctx = canvas
+            .get_context("2d")?
+            .ok_or("canvas has no 2d context")?;
+        Ok(Self::Canvas(ctx.dyn_into()?))
+    }
+
+    pub fn from_offscreen(canvas: &OffscreenCanvas) -> Result<Self, JsValue> {
+        let ctx = canvas
+            .get_context("2d")?
+            .ok_or("canvas has no 2d context")?;
+        Ok(Self::Offscreen(ctx.dyn_into()?))
+    }
+
+    // draw a frame of width x height premultiplied rgba pixels
+    // at the top left corner of the canvas
+    pub fn put_frame(&self, frame: &[u8], width: u32, height: u32) -> Result<(), JsValue> {
+        let pixels =
+            image_data_pixels(frame, width, height).ok_or("frame doesn't match the size")?;
+        let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), width, height)?;
+        match self {
+            CanvasTarget::Canvas(ctx) => ctx.put_image_data(&image, 0.0, 0.0),
+            CanvasTarget::Offscreen(ctx) => ctx.put_image_data(&image, 0.0, 0.0),
+        }
+    }
+}
+
+// the straight alpha rgba image data wants, None when frame is not width x height pixels
+fn image_data_pixels(frame: &[u8], width: u32, height: u32) -> Option<Vec<u8>> {
+    (frame.len() == width as usize * height as usize * 4).then(|| demultiply(frame))
+}
+
+// a scene running in the browser. the page calls tick from requestAnimationFrame
+// with the seconds since the last call, the scene moves through its updaters
+// and value trackers like with FrameRenderer::render_into.
+// wasm32 has no system fonts, Text draws nothing until a font is bundled with
+// fonts::with_font_book(|book| book.register_font_data(include_bytes!(..).to_vec()))
+pub struct CanvasPlayer {
+    pub renderer: FrameRenderer,
+    pub target: CanvasTarget,
+    buffer: Vec<u8>,
+}
+
+impl CanvasPlayer {
+    // None for an empty output size
+    pub fn new(scene_config: SceneConfig, target: CanvasTarget) -> Option<Self> {
+        let renderer = FrameRenderer::new(scene_config)?;
+        let buffer = vec![0; renderer.frame_len()];
+        Some(Self {
+            renderer,
+            target,
+            buffer,
+        })
+    }
+
+    pub fn tick(&mut self, dt: GMFloat) -> Result<(), JsValue> {
+        self.renderer.render_into(dt, &mut self.buffer);
+        self.show(None)
+    }
+
+    // put a frame of FrameRenderer::frames on the canvas, None shows the last tick again
+    pub fn show(&self, frame: Option<&[u8]>) -> Result<(), JsValue> {
+        let (width, height) = {
+            let scene_config = &self.renderer.ctx.borrow().scene_config;
+            (scene_config.output_width, scene_config.output_height)
+        };
+        self.target
+            .put_frame(frame.unwrap_or(&self.buffer), width, height)
+    }
+}
+
+#[test]
+fn test_image_data_pixels() {
+    // half transparent red next to a transparent pixel, premultiplied
+    let frame = [0x80, 0, 0, 0x80, 0, 0, 0, 0];
+    assert_eq!(
+        image_data_pixels(&frame, 2, 1),
+        Some(vec![0xff, 0, 0, 0x80, 0, 0, 0, 0])
+    );
+    assert_eq!(image_data_pixels(&frame, 2, 2), None);
+}